@@ -0,0 +1,399 @@
+use std::ops::Range;
+
+use lapce_xi_rope::{compare::RopeScanner, find::is_multiline_regex, Rope, RopeDelta};
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    buffer::rope_text::{RopeText, RopeTextRef},
+    word::{get_char_property, CharClassification},
+};
+
+/// Searches a [`Rope`] for a pattern and keeps the matches up to date as the text is edited.
+///
+/// Patterns are matched literally by default, or as a regular expression when
+/// [`set_regex`](Find::set_regex) is enabled. After changing the query, call
+/// [`search`](Find::search) (or one of the update functions) to find the matches again.
+#[derive(Clone, Debug, Default)]
+pub struct Find {
+    pattern: String,
+    case_sensitive: bool,
+    whole_words: bool,
+    is_regex: bool,
+    /// The compiled query, `None` if the pattern is empty or isn't a valid regex
+    regex: Option<Regex>,
+    error: Option<String>,
+    /// Sorted, non-overlapping and non-empty matches in `text`
+    matches: Vec<Range<usize>>,
+    /// The text the matches were found in. `None` if the query changed since the last search.
+    text: Option<Rope>,
+}
+
+impl Find {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn set_pattern(&mut self, pattern: impl Into<String>) {
+        let pattern = pattern.into();
+        if pattern != self.pattern {
+            self.pattern = pattern;
+            self.compile();
+        }
+    }
+
+    pub fn case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        if case_sensitive != self.case_sensitive {
+            self.case_sensitive = case_sensitive;
+            self.compile();
+        }
+    }
+
+    pub fn whole_words(&self) -> bool {
+        self.whole_words
+    }
+
+    /// Only match the pattern when it isn't surrounded by other word characters.
+    pub fn set_whole_words(&mut self, whole_words: bool) {
+        if whole_words != self.whole_words {
+            self.whole_words = whole_words;
+            self.text = None;
+        }
+    }
+
+    pub fn is_regex(&self) -> bool {
+        self.is_regex
+    }
+
+    pub fn set_regex(&mut self, is_regex: bool) {
+        if is_regex != self.is_regex {
+            self.is_regex = is_regex;
+            self.compile();
+        }
+    }
+
+    /// The error message if the pattern is not a valid regular expression.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The matches found by the last search, sorted by offset.
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// The matches which overlap with `range`.
+    pub fn matches_in(&self, range: Range<usize>) -> &[Range<usize>] {
+        let start = self.matches.partition_point(|m| m.end <= range.start);
+        let end = self.matches.partition_point(|m| m.start < range.end);
+        &self.matches[start..end.max(start)]
+    }
+
+    /// The index of the match which exactly covers `range`.
+    pub fn match_index(&self, range: Range<usize>) -> Option<usize> {
+        self.matches
+            .binary_search_by_key(&range.start, |m| m.start)
+            .ok()
+            .filter(|&idx| self.matches[idx] == range)
+    }
+
+    /// The first match starting at or after `offset` when searching forward, or the last match
+    /// starting before `offset` when searching backward. Wraps around the end of the text.
+    pub fn next_match(&self, offset: usize, forward: bool) -> Option<Range<usize>> {
+        if forward {
+            let idx = self.matches.partition_point(|m| m.start < offset);
+            self.matches.get(idx).or_else(|| self.matches.first())
+        } else {
+            let idx = self.matches.partition_point(|m| m.start < offset);
+            idx.checked_sub(1)
+                .and_then(|idx| self.matches.get(idx))
+                .or_else(|| self.matches.last())
+        }
+        .cloned()
+    }
+
+    /// The text that should replace the match at `range`. For regular expressions, `$name` and
+    /// `${1}` style references in `replacement` are expanded with the captured groups.
+    ///
+    /// Returns `None` if the query doesn't match exactly `range` in `text`.
+    pub fn replacement(
+        &self,
+        text: &Rope,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Option<String> {
+        let regex = self.regex.as_ref()?;
+        // Match in the same haystack the matches are searched in, so anchors and word boundaries
+        // see the text around the match.
+        let region = if self.is_multiline() {
+            0..text.len()
+        } else {
+            line_region(text, range.start, range.end)
+        };
+        let haystack = text.slice_to_cow(region.clone());
+        let captures = regex.captures_at(&haystack, range.start - region.start)?;
+        let m = captures.get(0)?;
+        if region.start + m.start() != range.start || region.start + m.end() != range.end {
+            return None;
+        }
+        if !self.is_regex {
+            return Some(replacement.to_string());
+        }
+        let mut dst = String::new();
+        captures.expand(replacement, &mut dst);
+        Some(dst)
+    }
+
+    /// Finds all the matches in `text`.
+    pub fn search(&mut self, text: &Rope) {
+        self.matches = self.search_range(text, 0..text.len());
+        self.text = Some(text.clone());
+    }
+
+    /// Updates the matches after `delta` was applied, producing `text`.
+    ///
+    /// Only the lines touched by the edit are searched again, matches elsewhere are moved
+    /// along with the text.
+    pub fn update(&mut self, text: &Rope, delta: &RopeDelta) {
+        if self.text.is_none() {
+            self.search(text);
+            return;
+        }
+        let (iv, new_len) = delta.summary();
+        self.update_range(text, iv.start(), iv.end(), iv.start() + new_len);
+    }
+
+    /// Updates the matches for the new `text` when the delta that produced it isn't known.
+    ///
+    /// The changed range is found by comparing `text` with the text of the last search.
+    pub fn update_text(&mut self, text: &Rope) {
+        let Some(old_text) = &self.text else {
+            self.search(text);
+            return;
+        };
+        if old_text.ptr_eq(text) {
+            return;
+        }
+        let (prefix, suffix) = RopeScanner::new(old_text, text).find_min_diff_range();
+        let old_end = old_text.len() - suffix;
+        // When the text is unchanged the prefix and suffix cover the whole text.
+        let start = prefix.min(old_end);
+        self.update_range(text, start, old_end, (text.len() - suffix).max(start));
+    }
+
+    /// Moves the matches after the edit replacing `start..old_end` with `start..new_end`, then
+    /// searches the edited lines.
+    fn update_range(&mut self, text: &Rope, start: usize, old_end: usize, new_end: usize) {
+        if self.regex.is_none() || self.is_multiline() {
+            self.search(text);
+            return;
+        }
+
+        let Range {
+            start: region_start,
+            end: region_end,
+        } = line_region(text, start, new_end);
+
+        let mut matches = Vec::with_capacity(self.matches.len());
+        for m in self.matches.drain(..) {
+            let m = if m.end <= start {
+                m
+            } else if m.start >= old_end {
+                m.start + new_end - old_end..m.end + new_end - old_end
+            } else {
+                continue;
+            };
+            if m.end <= region_start || m.start >= region_end {
+                matches.push(m);
+            }
+        }
+
+        let idx = matches.partition_point(|m| m.start < region_start);
+        let found = self.search_range(text, region_start..region_end);
+        matches.splice(idx..idx, found);
+
+        self.matches = matches;
+        self.text = Some(text.clone());
+    }
+
+    fn search_range(&self, text: &Rope, range: Range<usize>) -> Vec<Range<usize>> {
+        let Some(regex) = &self.regex else {
+            return Vec::new();
+        };
+        let offset = range.start;
+        let haystack = text.slice_to_cow(range);
+        regex
+            .find_iter(&haystack)
+            .map(|m| offset + m.start()..offset + m.end())
+            .filter(|m| !m.is_empty())
+            .filter(|m| !self.whole_words || is_whole_word(text, m))
+            .collect()
+    }
+
+    fn is_multiline(&self) -> bool {
+        if self.is_regex {
+            is_multiline_regex(&self.pattern)
+        } else {
+            self.pattern.contains('\n')
+        }
+    }
+
+    fn compile(&mut self) {
+        self.text = None;
+        self.matches.clear();
+        self.error = None;
+        self.regex = None;
+        if self.pattern.is_empty() {
+            return;
+        }
+
+        let pattern = if self.is_regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        match RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .multi_line(true)
+            .build()
+        {
+            Ok(regex) => self.regex = Some(regex),
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+}
+
+/// The range of the lines from the one containing `start` up to the one containing `end`,
+/// including the line break.
+fn line_region(text: &Rope, start: usize, end: usize) -> Range<usize> {
+    let rope_text = RopeTextRef::new(text);
+    let region_start = rope_text.offset_of_line(rope_text.line_of_offset(start));
+    let region_end = rope_text.offset_of_line(rope_text.line_of_offset(end) + 1);
+    region_start..region_end
+}
+
+fn is_word_char(c: char) -> bool {
+    get_char_property(c) == CharClassification::Other
+}
+
+fn is_whole_word(text: &Rope, range: &Range<usize>) -> bool {
+    let before = text
+        .prev_codepoint_offset(range.start)
+        .and_then(|offset| text.slice_to_cow(offset..range.start).chars().next());
+    let after = text
+        .next_codepoint_offset(range.end)
+        .and_then(|offset| text.slice_to_cow(range.end..offset).chars().next());
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+#[cfg(test)]
+// Matches are compared with slices of ranges, which includes ones with a single range.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use lapce_xi_rope::{DeltaBuilder, Rope};
+
+    use super::Find;
+
+    fn find(pattern: &str) -> Find {
+        let mut find = Find::new();
+        find.set_pattern(pattern);
+        find
+    }
+
+    #[test]
+    fn literal_and_case() {
+        let text = Rope::from("Foo foo f.o FOO");
+        let mut find = find("foo");
+        find.search(&text);
+        assert_eq!(find.matches(), &[0..3, 4..7, 12..15]);
+
+        find.set_case_sensitive(true);
+        find.search(&text);
+        assert_eq!(find.matches(), &[4..7]);
+
+        // Literal patterns don't treat `.` as a wildcard
+        find.set_pattern("f.o");
+        find.search(&text);
+        assert_eq!(find.matches(), &[8..11]);
+    }
+
+    #[test]
+    fn whole_words() {
+        let text = Rope::from("cat concat cat_ cat.");
+        let mut find = find("cat");
+        find.set_whole_words(true);
+        find.search(&text);
+        assert_eq!(find.matches(), &[0..3, 16..19]);
+    }
+
+    #[test]
+    fn regex_and_replacement() {
+        let text = Rope::from("a1 b22\nc333");
+        let mut find = find(r"([a-z])(\d+)");
+        find.set_regex(true);
+        find.search(&text);
+        assert_eq!(find.matches(), &[0..2, 3..6, 7..11]);
+        assert_eq!(
+            find.replacement(&text, 3..6, "$2$1").as_deref(),
+            Some("22b")
+        );
+        assert_eq!(find.replacement(&text, 4..6, "$2$1"), None);
+
+        // Anchors see the text around the match
+        find.set_pattern(r"^(\w)\d+$");
+        find.search(&text);
+        assert_eq!(find.matches(), &[7..11]);
+        assert_eq!(
+            find.replacement(&text, 7..11, "${1}!").as_deref(),
+            Some("c!")
+        );
+
+        find.set_pattern("(");
+        assert!(find.error().is_some());
+        find.search(&text);
+        assert!(find.matches().is_empty());
+    }
+
+    #[test]
+    fn navigation_wraps() {
+        let text = Rope::from("ab ab ab");
+        let mut find = find("ab");
+        find.search(&text);
+        assert_eq!(find.next_match(1, true), Some(3..5));
+        assert_eq!(find.next_match(7, true), Some(0..2));
+        assert_eq!(find.next_match(3, false), Some(0..2));
+        assert_eq!(find.next_match(0, false), Some(6..8));
+        assert_eq!(find.matches_in(4..7), &[3..5, 6..8]);
+    }
+
+    #[test]
+    fn incremental_update() {
+        let text = Rope::from("foo\nbar\nfoo bar\nfoo");
+        let mut find = find("foo");
+        find.search(&text);
+        assert_eq!(find.matches(), &[0..3, 8..11, 16..19]);
+
+        // Insert "foo" in the middle of "bar" on the second line
+        let mut builder = DeltaBuilder::new(text.len());
+        builder.replace(5..5, Rope::from("foo"));
+        let delta = builder.build();
+        let text = delta.apply(&text);
+        find.update(&text, &delta);
+        assert_eq!(find.matches(), &[0..3, 5..8, 11..14, 19..22]);
+
+        // Break the match on the third line without a delta
+        let new_text = Rope::from("foo\nbfooar\nfo bar\nfoo");
+        find.update_text(&new_text);
+        let mut full = find.clone();
+        full.search(&new_text);
+        assert_eq!(find.matches(), full.matches());
+        assert_eq!(find.matches(), &[0..3, 5..8, 18..21]);
+    }
+}
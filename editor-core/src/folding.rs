@@ -0,0 +1,380 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use lapce_xi_rope::Rope;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A region of lines that can be collapsed.
+/// The start line stays visible when the region is folded, while the lines after it up to and
+/// including the end line are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FoldingRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl FoldingRange {
+    pub fn new(start_line: usize, end_line: usize) -> Self {
+        Self {
+            start_line,
+            end_line,
+        }
+    }
+
+    /// The lines which are hidden when the range is folded.
+    pub fn hidden_lines(&self) -> Range<usize> {
+        self.start_line + 1..self.end_line + 1
+    }
+
+    /// Whether `line` is within the range, including the start line.
+    pub fn contains(&self, line: usize) -> bool {
+        self.start_line <= line && line <= self.end_line
+    }
+}
+
+/// The foldable regions of a document and which of them are currently folded.
+#[derive(Debug, Clone, Default)]
+pub struct Folding {
+    /// Sorted by start line, with at most one range per start line
+    ranges: Vec<FoldingRange>,
+    /// The start lines of the folded ranges
+    folded: BTreeSet<usize>,
+    /// Sorted, non-overlapping ranges of hidden lines
+    hidden: Vec<Range<usize>>,
+}
+
+impl Folding {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[FoldingRange] {
+        &self.ranges
+    }
+
+    /// Replace the foldable ranges. Folded ranges which no longer exist are unfolded.
+    /// When several ranges start on the same line, only the largest is kept.
+    pub fn set_ranges(&mut self, mut ranges: Vec<FoldingRange>) {
+        ranges.retain(|range| range.end_line > range.start_line);
+        ranges.sort_by(|a, b| {
+            a.start_line
+                .cmp(&b.start_line)
+                .then(b.end_line.cmp(&a.end_line))
+        });
+        ranges.dedup_by_key(|range| range.start_line);
+        self.ranges = ranges;
+        self.folded
+            .retain(|line| self.ranges.iter().any(|r| r.start_line == *line));
+        self.update_hidden();
+    }
+
+    /// The range that starts on `line`.
+    pub fn range_at(&self, line: usize) -> Option<FoldingRange> {
+        self.ranges
+            .binary_search_by_key(&line, |range| range.start_line)
+            .ok()
+            .map(|idx| self.ranges[idx])
+    }
+
+    /// The smallest range that contains `line`.
+    pub fn range_containing(&self, line: usize) -> Option<FoldingRange> {
+        self.ranges
+            .iter()
+            .take_while(|range| range.start_line <= line)
+            .filter(|range| range.contains(line))
+            .min_by_key(|range| range.end_line - range.start_line)
+            .copied()
+    }
+
+    pub fn is_folded(&self, start_line: usize) -> bool {
+        self.folded.contains(&start_line)
+    }
+
+    /// The folded range that starts on `line`, which is where the placeholder for the hidden
+    /// lines is shown.
+    pub fn folded_range_at(&self, line: usize) -> Option<FoldingRange> {
+        if self.is_folded(line) {
+            self.range_at(line)
+        } else {
+            None
+        }
+    }
+
+    pub fn folded_ranges(&self) -> impl Iterator<Item = FoldingRange> + '_ {
+        self.folded.iter().filter_map(|line| self.range_at(*line))
+    }
+
+    /// Fold the range that starts on `line`, or otherwise the smallest unfolded range that
+    /// contains it.
+    /// Returns the range that was folded.
+    pub fn fold(&mut self, line: usize) -> Option<FoldingRange> {
+        let range = self
+            .range_at(line)
+            .filter(|range| !self.is_folded(range.start_line))
+            .or_else(|| {
+                self.ranges
+                    .iter()
+                    .take_while(|range| range.start_line <= line)
+                    .filter(|range| range.contains(line) && !self.is_folded(range.start_line))
+                    .min_by_key(|range| range.end_line - range.start_line)
+                    .copied()
+            })?;
+        self.folded.insert(range.start_line);
+        self.update_hidden();
+        Some(range)
+    }
+
+    /// Unfold the range that starts on `line`, as well as any folded ranges that hide `line`.
+    /// Returns whether anything was unfolded.
+    pub fn unfold(&mut self, line: usize) -> bool {
+        let ranges = &self.ranges;
+        let len = self.folded.len();
+        self.folded.retain(|start| {
+            let Some(range) = ranges
+                .binary_search_by_key(start, |range| range.start_line)
+                .ok()
+                .map(|idx| ranges[idx])
+            else {
+                return false;
+            };
+            range.start_line != line && !range.hidden_lines().contains(&line)
+        });
+        let changed = self.folded.len() != len;
+        if changed {
+            self.update_hidden();
+        }
+        changed
+    }
+
+    /// Unfold the range starting on `line` if it is folded, otherwise fold it.
+    pub fn toggle(&mut self, line: usize) {
+        if self.is_folded(line) {
+            self.unfold(line);
+        } else {
+            self.fold(line);
+        }
+    }
+
+    pub fn fold_all(&mut self) {
+        self.folded = self.ranges.iter().map(|range| range.start_line).collect();
+        self.update_hidden();
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folded.clear();
+        self.hidden.clear();
+    }
+
+    /// Whether any lines are hidden by a folded range.
+    pub fn has_hidden(&self) -> bool {
+        !self.hidden.is_empty()
+    }
+
+    /// Whether the line is hidden by a folded range.
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.hidden_range(line).is_some()
+    }
+
+    /// The range of consecutive hidden lines that `line` is in.
+    pub fn hidden_range(&self, line: usize) -> Option<Range<usize>> {
+        let idx = self.hidden.partition_point(|hidden| hidden.end <= line);
+        self.hidden
+            .get(idx)
+            .filter(|hidden| hidden.contains(&line))
+            .cloned()
+    }
+
+    /// The closest line to `line` which is not hidden, searching forward or backward.
+    /// When searching forward past the end of the hidden lines, `None` is returned if there are
+    /// no more lines, in which case the caller should search backward.
+    pub fn visible_line(&self, line: usize, forward: bool, last_line: usize) -> Option<usize> {
+        match self.hidden_range(line) {
+            None => Some(line),
+            Some(hidden) if forward => Some(hidden.end).filter(|line| *line <= last_line),
+            // The line before the hidden lines is the start of the folded range, which is visible
+            Some(hidden) => hidden.start.checked_sub(1),
+        }
+    }
+
+    /// Update the line numbers after an edit replaced `inval_count` lines starting at
+    /// `start_line` with `new_count` lines.
+    /// Folded ranges which started on one of the replaced lines, other than the first, are
+    /// unfolded.
+    pub fn update_lines(&mut self, start_line: usize, inval_count: usize, new_count: usize) {
+        if inval_count == new_count {
+            return;
+        }
+        let old_end = start_line + inval_count;
+        let shift = |line: usize| {
+            if line >= old_end {
+                Some(line + new_count - inval_count)
+            } else if line > start_line && line < old_end {
+                None
+            } else {
+                Some(line)
+            }
+        };
+
+        self.folded = self.folded.iter().filter_map(|line| shift(*line)).collect();
+        let ranges = self
+            .ranges
+            .iter()
+            .filter_map(|range| {
+                let range_start = shift(range.start_line)?;
+                let range_end = if range.end_line >= old_end {
+                    range.end_line + new_count - inval_count
+                } else {
+                    // The end was on one of the replaced lines
+                    range.end_line.min(start_line + new_count.saturating_sub(1))
+                };
+                Some(FoldingRange::new(range_start, range_end))
+            })
+            .collect();
+        self.set_ranges(ranges);
+    }
+
+    fn update_hidden(&mut self) {
+        let mut hidden: Vec<Range<usize>> = Vec::new();
+        for range in self.folded_ranges() {
+            let lines = range.hidden_lines();
+            match hidden.last_mut() {
+                Some(last) if lines.start <= last.end => last.end = last.end.max(lines.end),
+                _ => hidden.push(lines),
+            }
+        }
+        self.hidden = hidden;
+    }
+}
+
+/// Find foldable ranges from the indentation of the text.
+/// A range starts on each line that is followed by lines with a larger indentation, and covers
+/// those lines. Blank lines at the end of a range are left out of it.
+pub fn indent_folding_ranges(text: &Rope, tab_width: usize) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    // The lines which could start a range, with their indentation
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut last_non_blank = 0;
+
+    for (line, content) in text.lines(..).enumerate() {
+        let Some(indent) = indent_width(&content, tab_width) else {
+            continue;
+        };
+
+        while let Some(&(start, start_indent)) = stack.last() {
+            if start_indent < indent {
+                break;
+            }
+            stack.pop();
+            if last_non_blank > start {
+                ranges.push(FoldingRange::new(start, last_non_blank));
+            }
+        }
+
+        stack.push((line, indent));
+        last_non_blank = line;
+    }
+
+    for (start, _) in stack.into_iter().rev() {
+        if last_non_blank > start {
+            ranges.push(FoldingRange::new(start, last_non_blank));
+        }
+    }
+
+    ranges.sort();
+    ranges
+}
+
+/// The width of the indentation of the line, or `None` if the line is blank.
+fn indent_width(line: &str, tab_width: usize) -> Option<usize> {
+    let tab_width = tab_width.max(1);
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += tab_width - width % tab_width,
+            c if c.is_whitespace() => {}
+            _ => return Some(width),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use lapce_xi_rope::Rope;
+
+    use super::{indent_folding_ranges, Folding, FoldingRange};
+
+    #[test]
+    fn indent_ranges() {
+        let text =
+            Rope::from("fn a() {\n    b();\n    if c {\n        d();\n\n    }\n}\n\nfn e() {}");
+        assert_eq!(
+            indent_folding_ranges(&text, 4),
+            vec![FoldingRange::new(0, 5), FoldingRange::new(2, 3)]
+        );
+
+        let text = Rope::from("a:\n\tb\n\t\tc\n");
+        assert_eq!(
+            indent_folding_ranges(&text, 4),
+            vec![FoldingRange::new(0, 2), FoldingRange::new(1, 2)]
+        );
+        // A tab width of zero is treated as one
+        assert_eq!(
+            indent_folding_ranges(&text, 0),
+            vec![FoldingRange::new(0, 2), FoldingRange::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn fold_and_unfold() {
+        let mut folding = Folding::new();
+        folding.set_ranges(vec![
+            FoldingRange::new(2, 3),
+            FoldingRange::new(0, 5),
+            FoldingRange::new(7, 7),
+        ]);
+        assert_eq!(folding.ranges().len(), 2);
+
+        // Folding inside a range folds the smallest range containing the line
+        assert_eq!(folding.fold(3), Some(FoldingRange::new(2, 3)));
+        assert!(folding.is_hidden(3));
+        assert!(!folding.is_hidden(2));
+        assert_eq!(folding.fold(3), Some(FoldingRange::new(0, 5)));
+        assert_eq!(folding.hidden_range(4), Some(1..6));
+        assert_eq!(folding.visible_line(3, true, 10), Some(6));
+        assert_eq!(folding.visible_line(3, false, 10), Some(0));
+        assert_eq!(folding.visible_line(3, true, 5), None);
+
+        // Unfolding a hidden line unfolds every range hiding it
+        assert!(folding.unfold(3));
+        assert!(!folding.has_hidden());
+
+        folding.toggle(0);
+        assert!(folding.is_folded(0));
+        folding.toggle(0);
+        assert!(!folding.is_folded(0));
+    }
+
+    #[test]
+    fn update_lines() {
+        let mut folding = Folding::new();
+        folding.set_ranges(vec![FoldingRange::new(2, 4), FoldingRange::new(8, 9)]);
+        folding.fold_all();
+
+        // Insert two lines after line 5
+        folding.update_lines(5, 1, 3);
+        assert_eq!(
+            folding.ranges(),
+            &[FoldingRange::new(2, 4), FoldingRange::new(10, 11)]
+        );
+        assert!(folding.is_folded(10));
+        assert!(folding.is_hidden(11));
+
+        // Delete lines 2 to 4, keeping the start line of the first range
+        folding.update_lines(2, 3, 1);
+        assert_eq!(folding.ranges(), &[FoldingRange::new(8, 9)]);
+        assert!(folding.is_folded(8));
+    }
+}
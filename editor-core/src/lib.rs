@@ -4,6 +4,8 @@ pub mod chars;
 pub mod command;
 pub mod cursor;
 pub mod editor;
+pub mod find;
+pub mod folding;
 pub mod indent;
 pub mod line_ending;
 pub mod mode;
@@ -1,7 +1,7 @@
 use floem::{
     keyboard::{Key, ModifiersState, NamedKey},
     peniko::Color,
-    reactive::{provide_context, use_context},
+    reactive::{provide_context, use_context, with_scope, Scope},
     view::View,
     views::{empty, label, v_stack, Decorators},
 };
@@ -16,14 +16,18 @@ fn context_container<V: View + 'static>(
     name: String,
     view_fn: impl Fn() -> V,
 ) -> impl View {
-    provide_context(color);
+    // Contexts are provided in a child scope so that they only apply to this subtree
+    let cx = Scope::current().create_child();
+    with_scope(cx, move || {
+        provide_context(color);
 
-    v_stack((colored_label(name), view_fn())).style(move |s| {
-        s.padding(10)
-            .border(1)
-            .border_color(color)
-            .gap(0, 5)
-            .items_center()
+        v_stack((colored_label(name), view_fn())).style(move |s| {
+            s.padding(10)
+                .border(1)
+                .border_color(color)
+                .gap(0, 5)
+                .items_center()
+        })
     })
 }
 
@@ -1,9 +1,11 @@
 use std::any::{Any, TypeId};
 
-use crate::runtime::RUNTIME;
+use crate::{id::Id, runtime::RUNTIME};
 
 /// Try to retrieve a stored Context value in the reactive system.
-/// You can store a Context value anywhere, and retrieve it from anywhere afterwards.
+/// The value is looked up in the current Scope and then in each of its parent Scopes, so the
+/// closest value provided above the current Scope is returned. Values provided in the root Scope,
+/// which is current outside of any [with_scope](crate::with_scope), are visible from every Scope.
 ///
 /// # Example
 /// In a parent component:
@@ -26,16 +28,29 @@ where
     let ty = TypeId::of::<T>();
     RUNTIME.with(|runtime| {
         let contexts = runtime.contexts.borrow();
-        let context = contexts
-            .get(&ty)
-            .and_then(|val| val.downcast_ref::<T>())
-            .cloned();
-        context
+        let parents = runtime.parents.borrow();
+        let get = |scope: &Id| {
+            contexts
+                .get(scope)
+                .and_then(|contexts| contexts.get(&ty))
+                .and_then(|val| val.downcast_ref::<T>())
+        };
+
+        let mut scope = Some(*runtime.current_scope.borrow());
+        while let Some(id) = scope {
+            if let Some(context) = get(&id) {
+                return Some(context.clone());
+            }
+            scope = parents.get(&id).copied();
+        }
+
+        get(&runtime.root_scope).cloned()
     })
 }
 
-/// Sets a context value to be stored in the reactive system.
-/// The stored context value can be retrieved from anywhere by using [use_context](use_context)
+/// Sets a context value to be stored in the current Scope.
+/// The stored context value can be retrieved from this Scope and its child Scopes by using
+/// [use_context](use_context), and is dropped when the Scope is disposed.
 ///
 /// # Example
 /// In a parent component:
@@ -58,7 +73,14 @@ where
     let id = value.type_id();
 
     RUNTIME.with(|runtime| {
+        let scope = *runtime.current_scope.borrow();
         let mut contexts = runtime.contexts.borrow_mut();
-        contexts.insert(id, Box::new(value) as Box<dyn Any>);
+        let prev = contexts
+            .entry(scope)
+            .or_default()
+            .insert(id, Box::new(value) as Box<dyn Any>);
+        // Drop the replaced value outside of the borrow
+        drop(contexts);
+        drop(prev);
     });
 }
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, fmt::Write, panic::Location};
+
+use crate::{id::Id, runtime::RUNTIME};
+
+/// What a node of the [`ReactiveGraph`] is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Signal,
+    Memo,
+    Effect,
+    Scope,
+}
+
+impl NodeKind {
+    /// The name of the kind, like `"Signal"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeKind::Signal => "Signal",
+            NodeKind::Memo => "Memo",
+            NodeKind::Effect => "Effect",
+            NodeKind::Scope => "Scope",
+        }
+    }
+}
+
+/// What the Runtime records about a Signal, Effect or Scope while debug tracking is enabled
+pub(crate) struct NodeInfo {
+    kind: NodeKind,
+    location: &'static Location<'static>,
+    type_name: Option<&'static str>,
+    run_count: u64,
+    /// The Signals which have changed since the Effect last ran
+    pending_causes: Vec<Id>,
+    /// The Signals which caused the last run of the Effect
+    caused_by: Vec<Id>,
+}
+
+/// Record where Signals, Memos, Effects and Scopes are created and how often Effects run, so
+/// they show up in [`reactive_graph`]. Only what is created while this is enabled is tracked.
+///
+/// It's enabled by default in debug builds.
+pub fn set_debug_tracking(enabled: bool) {
+    RUNTIME.with(|runtime| runtime.debug_enabled.set(enabled));
+}
+
+pub(crate) fn register(
+    id: Id,
+    kind: NodeKind,
+    location: &'static Location<'static>,
+    type_name: Option<&'static str>,
+) {
+    RUNTIME.with(|runtime| {
+        if !runtime.debug_enabled.get() {
+            return;
+        }
+        runtime.debug.borrow_mut().insert(
+            id,
+            NodeInfo {
+                kind,
+                location,
+                type_name,
+                run_count: 0,
+                pending_causes: Vec::new(),
+                caused_by: Vec::new(),
+            },
+        );
+    });
+}
+
+pub(crate) fn set_kind(id: Id, kind: NodeKind) {
+    RUNTIME.with(|runtime| {
+        if let Some(info) = runtime.debug.borrow_mut().get_mut(&id) {
+            info.kind = kind;
+        }
+    });
+}
+
+/// Forget about a node once it's gone. Effects are disposed before each run, so they are only
+/// forgotten when `effect` is true, which is once they are dropped.
+pub(crate) fn remove(id: Id, effect: bool) {
+    let _ = RUNTIME.try_with(|runtime| {
+        let mut debug = runtime.debug.borrow_mut();
+        if debug
+            .get(&id)
+            .is_some_and(|info| (info.kind == NodeKind::Effect) == effect)
+        {
+            debug.remove(&id);
+        }
+    });
+}
+
+/// Record that `signal` changed, which will cause `effect` to run
+pub(crate) fn add_cause(effect: Id, signal: Id) {
+    RUNTIME.with(|runtime| {
+        if !runtime.debug_enabled.get() {
+            return;
+        }
+        if let Some(info) = runtime.debug.borrow_mut().get_mut(&effect) {
+            if !info.pending_causes.contains(&signal) {
+                info.pending_causes.push(signal);
+            }
+        }
+    });
+}
+
+pub(crate) fn record_run(effect: Id) {
+    RUNTIME.with(|runtime| {
+        if !runtime.debug_enabled.get() {
+            return;
+        }
+        if let Some(info) = runtime.debug.borrow_mut().get_mut(&effect) {
+            info.run_count += 1;
+            info.caused_by = std::mem::take(&mut info.pending_causes);
+        }
+    });
+}
+
+/// A Signal, Memo, Effect or Scope in a [`ReactiveGraph`]
+#[derive(Clone, Debug)]
+pub struct GraphNode {
+    pub id: u64,
+    pub kind: NodeKind,
+    /// Where it was created
+    pub location: &'static Location<'static>,
+    /// The type of the value of a Signal or Memo
+    pub type_name: Option<&'static str>,
+    /// The Scope or Effect that owns it, which disposes it when it is disposed or re-runs
+    pub owner: Option<u64>,
+    /// How many times an Effect has run, including its first run
+    pub run_count: u64,
+    /// The Effects subscribed to a Signal or Memo
+    pub subscribers: Vec<u64>,
+    /// The Signals and Memos an Effect read in its last run
+    pub dependencies: Vec<u64>,
+    /// The Signals and Memos whose changes caused the last run of an Effect
+    pub caused_by: Vec<u64>,
+}
+
+impl GraphNode {
+    fn label(&self) -> String {
+        let mut label = format!("{} #{}", self.kind.name(), self.id);
+        if let Some(type_name) = self.type_name {
+            let _ = write!(label, ": {type_name}");
+        }
+        if self.kind == NodeKind::Effect {
+            let _ = write!(label, " ({} runs)", self.run_count);
+        }
+        let _ = write!(label, "\n{}", self.location);
+        label
+    }
+}
+
+/// A snapshot of the live Signals, Memos, Effects and Scopes of the current thread which were
+/// created while debug tracking was enabled, see [`set_debug_tracking`].
+#[derive(Clone, Debug, Default)]
+pub struct ReactiveGraph {
+    /// The nodes ordered by when they were created
+    pub nodes: Vec<GraphNode>,
+}
+
+/// Take a snapshot of the reactive graph of the current thread
+pub fn reactive_graph() -> ReactiveGraph {
+    RUNTIME.with(|runtime| {
+        let debug = runtime.debug.borrow();
+        let signals = runtime.signals.borrow();
+
+        let mut owners = HashMap::new();
+        for (owner, children) in runtime.children.borrow().iter() {
+            for child in children {
+                owners.insert(*child, *owner);
+            }
+        }
+
+        let mut nodes: Vec<GraphNode> = debug
+            .iter()
+            .map(|(id, info)| {
+                // Effects and Scopes also have a Signal with their Id, which is used to track them
+                let subscribers = match info.kind {
+                    NodeKind::Signal | NodeKind::Memo => signals
+                        .get(id)
+                        .map(|signal| {
+                            let mut subscribers: Vec<u64> =
+                                signal.subscribers.borrow().keys().map(Id::raw).collect();
+                            subscribers.sort_unstable();
+                            subscribers
+                        })
+                        .unwrap_or_default(),
+                    NodeKind::Effect | NodeKind::Scope => Vec::new(),
+                };
+                GraphNode {
+                    id: id.raw(),
+                    kind: info.kind,
+                    location: info.location,
+                    type_name: info.type_name,
+                    owner: owners.get(id).map(Id::raw),
+                    run_count: info.run_count,
+                    subscribers,
+                    dependencies: Vec::new(),
+                    caused_by: info.caused_by.iter().map(Id::raw).collect(),
+                }
+            })
+            .collect();
+        nodes.sort_unstable_by_key(|node| node.id);
+
+        let mut dependencies: HashMap<u64, Vec<u64>> = HashMap::new();
+        for node in &nodes {
+            for subscriber in &node.subscribers {
+                dependencies.entry(*subscriber).or_default().push(node.id);
+            }
+        }
+        for node in &mut nodes {
+            if let Some(dependencies) = dependencies.remove(&node.id) {
+                node.dependencies = dependencies;
+            }
+        }
+
+        ReactiveGraph { nodes }
+    })
+}
+
+impl ReactiveGraph {
+    pub fn node(&self, id: u64) -> Option<&GraphNode> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|i| &self.nodes[i])
+    }
+
+    /// The nodes of this snapshot which weren't in the `earlier` one.
+    ///
+    /// To find leaks, take a snapshot, do something that should clean up after itself, like
+    /// opening and closing a view, and check what is left over.
+    pub fn added_since(&self, earlier: &ReactiveGraph) -> Vec<&GraphNode> {
+        self.nodes
+            .iter()
+            .filter(|node| earlier.node(node.id).is_none())
+            .collect()
+    }
+
+    /// Export the graph in the Graphviz DOT format. Solid edges go from Signals to the Effects
+    /// subscribed to them, and dashed edges from owners to what they own.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactive {\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Signal => "ellipse",
+                NodeKind::Memo => "doubleoctagon",
+                NodeKind::Effect => "box",
+                NodeKind::Scope => "folder",
+            };
+            let _ = writeln!(
+                dot,
+                "    n{} [label=\"{}\", shape={shape}];",
+                node.id,
+                escape_dot(&node.label()),
+            );
+        }
+        for node in &self.nodes {
+            for subscriber in &node.subscribers {
+                if self.node(*subscriber).is_some() {
+                    let _ = writeln!(dot, "    n{} -> n{subscriber};", node.id);
+                }
+            }
+            if let Some(owner) = node.owner.filter(|owner| self.node(*owner).is_some()) {
+                let _ = writeln!(dot, "    n{owner} -> n{} [style=dashed];", node.id);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Export the graph as JSON, an object with a `nodes` array
+    pub fn to_json(&self) -> String {
+        fn ids(ids: &[u64]) -> String {
+            let ids: Vec<String> = ids.iter().map(u64::to_string).collect();
+            format!("[{}]", ids.join(","))
+        }
+
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{{\"id\":{},\"kind\":\"{}\",\"location\":\"{}\",\"type\":{},\"owner\":{},\
+                     \"run_count\":{},\"subscribers\":{},\"dependencies\":{},\"caused_by\":{}}}",
+                    node.id,
+                    node.kind.name(),
+                    escape_json(&node.location.to_string()),
+                    node.type_name
+                        .map(|name| format!("\"{}\"", escape_json(name)))
+                        .unwrap_or_else(|| "null".to_string()),
+                    node.owner
+                        .map(|owner| owner.to_string())
+                        .unwrap_or_else(|| "null".to_string()),
+                    node.run_count,
+                    ids(&node.subscribers),
+                    ids(&node.dependencies),
+                    ids(&node.caused_by),
+                )
+            })
+            .collect();
+        format!("{{\"nodes\":[{}]}}", nodes.join(","))
+    }
+}
+
+/// Escape a string for a quoted DOT string. Newlines become `\n`, which centers the line.
+fn escape_dot(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a string for a quoted JSON string
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
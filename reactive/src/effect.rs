@@ -1,6 +1,7 @@
-use std::{any::Any, cell::RefCell, collections::HashSet, mem, rc::Rc};
+use std::{any::Any, cell::RefCell, collections::HashSet, mem, panic::Location, rc::Rc};
 
 use crate::{
+    debug::{self, NodeKind},
     id::Id,
     runtime::RUNTIME,
     scope::{with_scope, Scope},
@@ -31,6 +32,7 @@ where
 {
     fn drop(&mut self) {
         self.id.dispose();
+        debug::remove(self.id, true);
     }
 }
 
@@ -41,6 +43,7 @@ where
 /// subscribed in that run. And when these Signals update, it will rerun the function.
 /// And the effect re-tracks the signals in each run, so that it will only be re-run
 /// by the Signals that actually ran in the last effect run.
+#[track_caller]
 pub fn create_effect<T>(f: impl Fn(Option<T>) -> T + 'static)
 where
     T: Any + 'static,
@@ -53,6 +56,7 @@ where
         observers: RefCell::new(HashSet::default()),
     });
     id.set_scope();
+    debug::register(id, NodeKind::Effect, Location::caller(), None);
 
     run_initial_effect(effect);
 }
@@ -76,11 +80,13 @@ where
 {
     fn drop(&mut self) {
         self.id.dispose();
+        debug::remove(self.id, true);
     }
 }
 
 /// Create an effect updater that runs `on_change` when any signals `compute` subscribes to
 /// changes. `compute` is immediately run and its return value is returned from `create_updater`.
+#[track_caller]
 pub fn create_updater<R>(compute: impl Fn() -> R + 'static, on_change: impl Fn(R) + 'static) -> R
 where
     R: 'static,
@@ -90,6 +96,7 @@ where
 
 /// Create an effect updater that runs `on_change` when any signals `compute` subscribes to
 /// changes. `compute` is immediately run and its return value is returned from `create_updater`.
+#[track_caller]
 pub fn create_stateful_updater<T, R>(
     compute: impl Fn(Option<T>) -> (R, T) + 'static,
     on_change: impl Fn(R, T) -> T + 'static,
@@ -107,6 +114,7 @@ where
         observers: RefCell::new(HashSet::default()),
     });
     id.set_scope();
+    debug::register(id, NodeKind::Effect, Location::caller(), None);
 
     run_initial_updater_effect(effect)
 }
@@ -144,6 +152,7 @@ pub fn batch<T>(f: impl FnOnce() -> T) -> T {
 
 pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
     let effect_id = effect.id();
+    debug::record_run(effect_id);
 
     RUNTIME.with(|runtime| {
         *runtime.current_effect.borrow_mut() = Some(effect.clone());
@@ -160,9 +169,10 @@ pub(crate) fn run_initial_effect(effect: Rc<dyn EffectTrait>) {
 
 pub(crate) fn run_effect(effect: Rc<dyn EffectTrait>) {
     let effect_id = effect.id();
-    effect_id.dispose();
+    effect_id.dispose_owned();
 
     observer_clean_up(&effect);
+    debug::record_run(effect_id);
 
     RUNTIME.with(|runtime| {
         *runtime.current_effect.borrow_mut() = Some(effect.clone());
@@ -185,6 +195,7 @@ where
     U: Fn(I, T) -> T + 'static,
 {
     let effect_id = effect.id();
+    debug::record_run(effect_id);
 
     let result = RUNTIME.with(|runtime| {
         *runtime.current_effect.borrow_mut() = Some(effect.clone());
@@ -1,6 +1,6 @@
 use std::sync::atomic::AtomicU64;
 
-use crate::{effect::observer_clean_up, runtime::RUNTIME, signal::Signal};
+use crate::{debug, effect::observer_clean_up, runtime::RUNTIME, signal::Signal};
 
 /// An internal id which can reference a Signal/Effect/Scope.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
@@ -13,6 +13,10 @@ impl Id {
         Id(COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
     }
 
+    pub(crate) fn raw(&self) -> u64 {
+        self.0
+    }
+
     /// Try to get the Signal that links with this Id
     pub(crate) fn signal(&self) -> Option<Signal> {
         RUNTIME.with(|runtime| runtime.signals.borrow().get(self).cloned())
@@ -26,22 +30,38 @@ impl Id {
     /// Make this Id a child of the current Scope
     pub(crate) fn set_scope(&self) {
         RUNTIME.with(|runtime| {
-            let scope = runtime.current_scope.borrow();
+            let scope = *runtime.current_scope.borrow();
             let mut children = runtime.children.borrow_mut();
-            let children = children.entry(*scope).or_default();
+            let children = children.entry(scope).or_default();
             children.insert(*self);
+            // Effects and memos run with their own Id as the current Scope, so the parent is
+            // needed to look up contexts from them.
+            runtime.parents.borrow_mut().insert(*self, scope);
         });
     }
 
     /// Dispose the relevant resources that's linking to this Id, and the all the children
     /// and grandchildren.
     pub(crate) fn dispose(&self) {
-        if let Ok((children, signal)) = RUNTIME.try_with(|runtime| {
+        let _ = RUNTIME.try_with(|runtime| runtime.parents.borrow_mut().remove(self));
+        self.dispose_owned();
+    }
+
+    /// Dispose the children and contexts of this Id, but keep it in its Scope. Effects do this
+    /// before running again.
+    pub(crate) fn dispose_owned(&self) {
+        if let Ok((children, signal, contexts)) = RUNTIME.try_with(|runtime| {
             (
                 runtime.children.borrow_mut().remove(self),
                 runtime.signals.borrow_mut().remove(self),
+                runtime.contexts.borrow_mut().remove(self),
             )
         }) {
+            // Drop the context values outside of the borrow, in case dropping them touches the
+            // runtime
+            drop(contexts);
+            debug::remove(*self, false);
+
             if let Some(children) = children {
                 for child in children {
                     child.dispose();
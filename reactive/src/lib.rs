@@ -1,15 +1,31 @@
 mod context;
+mod debug;
 mod effect;
 mod id;
 mod memo;
+mod resource;
 mod runtime;
 mod scope;
 mod signal;
+mod store;
+mod sync_signal;
 mod trigger;
 
 pub use context::{provide_context, use_context};
+pub use debug::{reactive_graph, set_debug_tracking, GraphNode, NodeKind, ReactiveGraph};
 pub use effect::{batch, create_effect, create_stateful_updater, create_updater, untrack};
 pub use memo::{create_memo, Memo};
+pub use resource::{
+    create_background_resource, create_resource, set_ext_trigger_handler, ExtTriggerHandler,
+    Resource,
+};
 pub use scope::{as_child_of_current_scope, with_scope, Scope};
 pub use signal::{create_rw_signal, create_signal, ReadSignal, RwSignal, WriteSignal};
+pub use store::{
+    create_store_map, create_store_vec, Store, StoreDiff, StoreItem, StoreMap, StoreVec,
+};
+pub use sync_signal::{
+    create_sync_signal, set_timer_handler, SendReadSignal, SendWriteSignal, SyncSignal,
+    TimerHandler,
+};
 pub use trigger::{create_trigger, Trigger};
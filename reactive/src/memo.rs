@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
 use crate::{
+    debug::{self, NodeKind},
     effect::create_effect,
     scope::Scope,
     signal::{create_signal, ReadSignal},
@@ -72,12 +73,14 @@ impl<T> Memo<T> {
 
 /// Create a Memo which takes the computed value of the given function, and triggers
 /// the reactive system when the computed value is different with the last computed value.
+#[track_caller]
 pub fn create_memo<T>(f: impl Fn(Option<&T>) -> T + 'static) -> Memo<T>
 where
     T: PartialEq + 'static,
 {
     let cx = Scope::current();
     let (getter, setter) = create_signal(None::<T>);
+    debug::set_kind(getter.id, NodeKind::Memo);
 
     create_effect(move |_| {
         cx.track();
@@ -0,0 +1,258 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{
+    effect::{batch, create_effect, untrack},
+    runtime::RUNTIME,
+    signal::{create_rw_signal, ReadSignal, RwSignal},
+    trigger::{create_trigger, Trigger},
+};
+
+/// Delivers a [`Trigger`] from any thread to the thread that owns the reactive runtime, which
+/// should then call [`Trigger::notify`].
+pub type ExtTriggerHandler = Arc<dyn Fn(Trigger) + Send + Sync>;
+
+/// Set how the wakeups of [`Resource`] futures and writes to a
+/// [`SyncSignal`](crate::SyncSignal) from other threads get back to the current thread.
+/// Floem sets this to send the trigger through its `ExtEventHandler`, so it only needs to be set
+/// when using the reactive runtime on its own, such as in tests.
+pub fn set_ext_trigger_handler(handler: impl Fn(Trigger) + Send + Sync + 'static) {
+    RUNTIME.with(|runtime| {
+        *runtime.ext_trigger_handler.borrow_mut() = Some(Arc::new(handler));
+    });
+}
+
+/// Data loaded asynchronously whenever the source it depends on changes.
+/// Created with [`create_resource`] or [`create_background_resource`].
+pub struct Resource<T: 'static, E: 'static> {
+    loading: RwSignal<bool>,
+    value: RwSignal<Option<T>>,
+    error: RwSignal<Option<E>>,
+    refetch: Trigger,
+}
+
+impl<T, E> Copy for Resource<T, E> {}
+
+impl<T, E> Clone for Resource<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E> Resource<T, E> {
+    /// Whether a fetch is in progress
+    pub fn loading(&self) -> ReadSignal<bool> {
+        self.loading.read_only()
+    }
+
+    /// The value from the last successful fetch.
+    /// The value is kept while a new fetch is loading, or if it fails.
+    pub fn ready(&self) -> ReadSignal<Option<T>> {
+        self.value.read_only()
+    }
+
+    /// The error from the last fetch, if it failed
+    pub fn error(&self) -> ReadSignal<Option<E>> {
+        self.error.read_only()
+    }
+
+    /// Fetch again with the current source, cancelling any fetch that is in progress
+    pub fn refetch(&self) {
+        self.refetch.notify();
+    }
+}
+
+/// Create a [`Resource`] which runs the future returned by `fetcher` whenever the signals read by
+/// `source` change. When the source changes again before the future finishes, the future is
+/// dropped so that its result is never used.
+///
+/// The future is polled on the current thread. When it is woken from another thread, it is polled
+/// again once the trigger given to the [`ExtTriggerHandler`] is notified on this thread.
+#[track_caller]
+pub fn create_resource<S, T, E, Fut>(
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Fut + 'static,
+) -> Resource<T, E>
+where
+    S: 'static,
+    T: Any + 'static,
+    E: Any + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    let resource = Resource {
+        loading: create_rw_signal(false),
+        value: create_rw_signal(None),
+        error: create_rw_signal(None),
+        refetch: create_trigger(),
+    };
+
+    let poll_trigger = create_trigger();
+    let woken = Arc::new(AtomicBool::new(false));
+    let handler = RUNTIME.with(|runtime| runtime.ext_trigger_handler.borrow().clone());
+    let waker = Waker::from(Arc::new(ResourceWaker {
+        trigger: poll_trigger,
+        handler,
+        woken: woken.clone(),
+    }));
+
+    let task: Rc<RefCell<Option<Pin<Box<Fut>>>>> = Rc::new(RefCell::new(None));
+    // Incremented for each fetch, so that stale futures are recognized
+    let generation = Rc::new(Cell::new(0u64));
+
+    let poll = {
+        let task = task.clone();
+        let generation = generation.clone();
+        move || loop {
+            let Some(mut fut) = task.borrow_mut().take() else {
+                return;
+            };
+            let current = generation.get();
+
+            woken.store(false, Ordering::SeqCst);
+            let mut cx = Context::from_waker(&waker);
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(result) => {
+                    if generation.get() == current {
+                        batch(|| {
+                            resource.loading.set(false);
+                            match result {
+                                Ok(value) => {
+                                    resource.value.set(Some(value));
+                                    resource.error.set(None);
+                                }
+                                Err(error) => resource.error.set(Some(error)),
+                            }
+                        });
+                    }
+                    return;
+                }
+                Poll::Pending => {
+                    if generation.get() != current {
+                        // A new fetch started while polling
+                        return;
+                    }
+                    *task.borrow_mut() = Some(fut);
+                    // Poll again straight away if it was woken from within `poll`
+                    if !woken.load(Ordering::SeqCst) {
+                        return;
+                    }
+                }
+            }
+        }
+    };
+    let poll = Rc::new(poll);
+
+    {
+        let poll = poll.clone();
+        create_effect(move |_| {
+            poll_trigger.track();
+            untrack(|| poll());
+        });
+    }
+
+    create_effect(move |_| {
+        resource.refetch.track();
+        let input = source();
+        untrack(|| {
+            let fut = fetcher(input);
+            generation.set(generation.get() + 1);
+            // Dropping the previous future cancels it
+            let prev = task.borrow_mut().replace(Box::pin(fut));
+            drop(prev);
+            resource.loading.set(true);
+            poll();
+        });
+    });
+
+    resource
+}
+
+/// Create a [`Resource`] which runs `fetcher` on a new thread whenever the signals read by
+/// `source` change. Results from a thread whose source has since changed are discarded, though
+/// the thread itself runs to completion.
+#[track_caller]
+pub fn create_background_resource<S, T, E>(
+    source: impl Fn() -> S + 'static,
+    fetcher: impl Fn(S) -> Result<T, E> + Send + Sync + 'static,
+) -> Resource<T, E>
+where
+    S: Send + 'static,
+    T: Any + Send + 'static,
+    E: Any + Send + 'static,
+{
+    let fetcher = Arc::new(fetcher);
+    create_resource(source, move |input| {
+        let fetcher = fetcher.clone();
+        background(move || fetcher(input))
+    })
+}
+
+struct ResourceWaker {
+    trigger: Trigger,
+    handler: Option<ExtTriggerHandler>,
+    woken: Arc<AtomicBool>,
+}
+
+impl Wake for ResourceWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        if let Some(handler) = &self.handler {
+            handler(self.trigger);
+        }
+    }
+}
+
+struct BackgroundState<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// Run `f` on a new thread, returning a future that resolves to its result.
+fn background<T: Send + 'static>(
+    f: impl FnOnce() -> T + Send + 'static,
+) -> impl Future<Output = T> {
+    let state = Arc::new(Mutex::new(BackgroundState {
+        result: None,
+        waker: None,
+    }));
+
+    {
+        let state = state.clone();
+        std::thread::spawn(move || {
+            let result = f();
+            let waker = {
+                let mut state = state.lock().unwrap();
+                state.result = Some(result);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+    }
+
+    std::future::poll_fn(move |cx| {
+        let mut state = state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    })
+}
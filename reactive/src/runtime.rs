@@ -8,11 +8,17 @@ use std::{
 use smallvec::SmallVec;
 
 use crate::{
+    debug::NodeInfo,
     effect::{run_effect, EffectTrait},
     id::Id,
+    resource::ExtTriggerHandler,
     signal::Signal,
+    sync_signal::TimerHandler,
 };
 
+/// The context values provided in a Scope, by their type
+pub(crate) type Contexts = HashMap<TypeId, Box<dyn Any>>;
+
 thread_local! {
     pub(crate) static RUNTIME: Runtime = Runtime::new();
 }
@@ -22,11 +28,22 @@ thread_local! {
 pub(crate) struct Runtime {
     pub(crate) current_effect: RefCell<Option<Rc<dyn EffectTrait>>>,
     pub(crate) current_scope: RefCell<Id>,
+    /// The Scope that is current when nothing else is, whose contexts are visible everywhere
+    pub(crate) root_scope: Id,
     pub(crate) children: RefCell<HashMap<Id, HashSet<Id>>>,
+    /// The Scope each child Scope, signal and effect was created in
+    pub(crate) parents: RefCell<HashMap<Id, Id>>,
     pub(crate) signals: RefCell<HashMap<Id, Signal>>,
-    pub(crate) contexts: RefCell<HashMap<TypeId, Box<dyn Any>>>,
+    /// The context values provided in each Scope
+    pub(crate) contexts: RefCell<HashMap<Id, Contexts>>,
     pub(crate) batching: Cell<bool>,
     pub(crate) pending_effects: RefCell<SmallVec<[Rc<dyn EffectTrait>; 10]>>,
+    pub(crate) ext_trigger_handler: RefCell<Option<ExtTriggerHandler>>,
+    pub(crate) timer_handler: RefCell<Option<TimerHandler>>,
+    pub(crate) debug_enabled: Cell<bool>,
+    /// What is known about the Signals, Effects and Scopes created while debug tracking was
+    /// enabled
+    pub(crate) debug: RefCell<HashMap<Id, NodeInfo>>,
 }
 
 impl Default for Runtime {
@@ -37,14 +54,21 @@ impl Default for Runtime {
 
 impl Runtime {
     pub(crate) fn new() -> Self {
+        let root_scope = Id::next();
         Self {
             current_effect: RefCell::new(None),
-            current_scope: RefCell::new(Id::next()),
+            current_scope: RefCell::new(root_scope),
+            root_scope,
             children: RefCell::new(HashMap::new()),
+            parents: Default::default(),
             signals: Default::default(),
             contexts: Default::default(),
             batching: Cell::new(false),
             pending_effects: RefCell::new(SmallVec::new()),
+            ext_trigger_handler: RefCell::new(None),
+            timer_handler: RefCell::new(None),
+            debug_enabled: Cell::new(cfg!(debug_assertions)),
+            debug: Default::default(),
         }
     }
 
@@ -1,7 +1,9 @@
-use std::{any::Any, cell::RefCell, collections::HashMap, fmt, rc::Rc};
+use std::{any::Any, cell::RefCell, collections::HashMap, fmt, panic::Location, rc::Rc};
 
 use crate::{
+    context::provide_context,
     create_effect,
+    debug::{self, NodeKind},
     id::Id,
     memo::{create_memo, Memo},
     runtime::RUNTIME,
@@ -17,6 +19,7 @@ use crate::{
 pub struct Scope(pub(crate) Id);
 
 impl Default for Scope {
+    #[track_caller]
     fn default() -> Self {
         Self::new()
     }
@@ -32,8 +35,11 @@ impl fmt::Debug for Scope {
 
 impl Scope {
     /// Create a new Scope that isn't a child or parent of any scope
+    #[track_caller]
     pub fn new() -> Self {
-        Self(Id::next())
+        let id = Id::next();
+        debug::register(id, NodeKind::Scope, Location::caller(), None);
+        Self(id)
     }
 
     /// The current Scope in the Runtime. Any Signal/Effect/Memo created with
@@ -43,51 +49,93 @@ impl Scope {
     }
 
     /// Create a child Scope of this Scope
+    #[track_caller]
     pub fn create_child(&self) -> Scope {
+        self.create_child_at(Location::caller())
+    }
+
+    fn create_child_at(&self, location: &'static Location<'static>) -> Scope {
         let child = Id::next();
         RUNTIME.with(|runtime| {
             let mut children = runtime.children.borrow_mut();
             let children = children.entry(self.0).or_default();
             children.insert(child);
+            runtime.parents.borrow_mut().insert(child, self.0);
         });
+        debug::register(child, NodeKind::Scope, location, None);
         Scope(child)
     }
 
+    /// The Scope this Scope was created from with [`Scope::create_child`]
+    pub fn parent(&self) -> Option<Scope> {
+        RUNTIME.with(|runtime| runtime.parents.borrow().get(&self.0).copied().map(Scope))
+    }
+
+    /// Provide a context value in this Scope, which is visible to this Scope and its children.
+    /// See [provide_context](crate::provide_context)
+    pub fn provide_context<T>(self, value: T)
+    where
+        T: Clone + 'static,
+    {
+        with_scope(self, || provide_context(value))
+    }
+
     /// Create a new Signal under this Scope
+    #[track_caller]
     pub fn create_signal<T>(self, value: T) -> (ReadSignal<T>, WriteSignal<T>)
     where
         T: Any + 'static,
     {
-        with_scope(self, || create_signal(value))
+        // Not using `with_scope` so that the caller is tracked as where the Signal is created
+        let _guard = self.enter();
+        create_signal(value)
     }
 
     /// Create a RwSignal under this Scope
+    #[track_caller]
     pub fn create_rw_signal<T>(self, value: T) -> RwSignal<T>
     where
         T: Any + 'static,
     {
-        with_scope(self, || create_rw_signal(value))
+        let _guard = self.enter();
+        create_rw_signal(value)
     }
 
     /// Create a Memo under this Scope
+    #[track_caller]
     pub fn create_memo<T>(self, f: impl Fn(Option<&T>) -> T + 'static) -> Memo<T>
     where
         T: PartialEq + 'static,
     {
-        with_scope(self, || create_memo(f))
+        let _guard = self.enter();
+        create_memo(f)
     }
 
     /// Create a Trigger under this Scope
+    #[track_caller]
     pub fn create_trigger(self) -> Trigger {
-        with_scope(self, create_trigger)
+        let _guard = self.enter();
+        create_trigger()
     }
 
     /// Create effect under this Scope
+    #[track_caller]
     pub fn create_effect<T>(self, f: impl Fn(Option<T>) -> T + 'static)
     where
         T: Any + 'static,
     {
-        with_scope(self, || create_effect(f))
+        let _guard = self.enter();
+        create_effect(f)
+    }
+
+    /// Make this the current Scope until the guard is dropped
+    fn enter(self) -> ScopeGuard {
+        RUNTIME.with(|runtime| {
+            let mut current_scope = runtime.current_scope.borrow_mut();
+            let prev_scope = *current_scope;
+            *current_scope = self.0;
+            ScopeGuard(prev_scope)
+        })
     }
 
     /// This is normally used in create_effect, and it will bind the effect's lifetime
@@ -114,6 +162,17 @@ impl Scope {
     }
 }
 
+/// Restores the previous Scope when dropped
+struct ScopeGuard(Id);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let _ = RUNTIME.try_with(|runtime| {
+            *runtime.current_scope.borrow_mut() = self.0;
+        });
+    }
+}
+
 /// Runs the given code with the given Scope
 pub fn with_scope<T>(scope: Scope, f: impl FnOnce() -> T) -> T
 where
@@ -137,13 +196,15 @@ where
 
 /// Wrap the closure so that whenever the closure runs, it will be under a child Scope
 /// of the current Scope
+#[track_caller]
 pub fn as_child_of_current_scope<T, U>(f: impl Fn(T) -> U + 'static) -> impl Fn(T) -> (U, Scope)
 where
     T: 'static,
 {
     let current_scope = Scope::current();
+    let location = Location::caller();
     move |t| {
-        let scope = current_scope.create_child();
+        let scope = current_scope.create_child_at(location);
         let prev_scope = RUNTIME.with(|runtime| {
             let mut current_scope = runtime.current_scope.borrow_mut();
             let prev_scope = *current_scope;
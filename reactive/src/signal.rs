@@ -1,6 +1,10 @@
-use std::{any::Any, cell::RefCell, collections::HashMap, fmt, marker::PhantomData, rc::Rc};
+use std::{
+    any::Any, cell::RefCell, collections::HashMap, fmt, marker::PhantomData, panic::Location,
+    rc::Rc,
+};
 
 use crate::{
+    debug::{self, NodeKind},
     effect::{run_effect, EffectTrait},
     id::Id,
     runtime::RUNTIME,
@@ -124,6 +128,7 @@ impl<T> RwSignal<T> {
     }
 }
 impl<T: 'static> RwSignal<T> {
+    #[track_caller]
     pub fn new(value: T) -> Self {
         create_rw_signal(value)
     }
@@ -164,6 +169,7 @@ impl<T: Clone> RwSignal<T> {
 /// Accessing the signal value in an Effect will make the Effect subscribe
 /// to the value change of the Signal. And whenever the signal value changes,
 /// it will trigger an effect run.
+#[track_caller]
 pub fn create_rw_signal<T>(value: T) -> RwSignal<T>
 where
     T: Any + 'static,
@@ -176,6 +182,12 @@ where
     };
     id.add_signal(signal);
     id.set_scope();
+    debug::register(
+        id,
+        NodeKind::Signal,
+        Location::caller(),
+        Some(std::any::type_name::<T>()),
+    );
     RwSignal {
         id,
         ty: PhantomData,
@@ -314,6 +326,7 @@ impl<T> WriteSignal<T> {
 /// Accessing the signal value in an Effect will make the Effect subscribe
 /// to the value change of the Signal. And whenever the signal value changes,
 /// it will trigger an effect run.
+#[track_caller]
 pub fn create_signal<T>(value: T) -> (ReadSignal<T>, WriteSignal<T>)
 where
     T: Any + 'static,
@@ -326,6 +339,12 @@ where
     };
     id.add_signal(signal);
     id.set_scope();
+    debug::register(
+        id,
+        NodeKind::Signal,
+        Location::caller(),
+        Some(std::any::type_name::<T>()),
+    );
     (
         ReadSignal {
             id,
@@ -356,6 +375,7 @@ impl Signal {
         if RUNTIME.with(|r| r.batching.get()) {
             RUNTIME.with(|r| {
                 for (_, subscriber) in self.subscribers() {
+                    debug::add_cause(subscriber.id(), self.id);
                     r.add_pending_effect(subscriber);
                 }
             });
@@ -363,6 +383,7 @@ impl Signal {
         }
 
         for (_, subscriber) in self.subscribers() {
+            debug::add_cause(subscriber.id(), self.id);
             run_effect(subscriber);
         }
     }
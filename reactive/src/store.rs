@@ -0,0 +1,629 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use smallvec::SmallVec;
+
+use crate::{
+    effect::batch,
+    scope::{with_scope, Scope},
+    signal::{create_rw_signal, RwSignal},
+};
+
+/// The number of structural changes a [`StoreVec`] remembers for [`StoreVec::diffs_since`].
+const DIFF_HISTORY: usize = 128;
+
+/// A value which can be split into separate signals, so that changing one part of it only
+/// re-runs the effects that read that part.
+///
+/// Structs implement this with the [`store!`](crate::store!) macro, which creates a handle with a
+/// signal for each field. Plain values like `String` or `u32` use a single `RwSignal` as their
+/// handle.
+pub trait Store: Sized + 'static {
+    /// The signals holding the parts of the value
+    type Handle: Copy + 'static;
+
+    /// Create the signals for the value in the current Scope
+    fn into_handle(self) -> Self::Handle;
+
+    /// Read the value out of its signals, without subscribing to them
+    fn from_handle(handle: &Self::Handle) -> Self;
+
+    /// Read the value out of its signals, subscribing to each of them
+    fn get(handle: &Self::Handle) -> Self;
+
+    /// Set each part of the value
+    fn set(handle: &Self::Handle, value: Self);
+}
+
+macro_rules! impl_signal_store {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Store for $ty {
+                type Handle = RwSignal<$ty>;
+
+                fn into_handle(self) -> Self::Handle {
+                    create_rw_signal(self)
+                }
+
+                fn from_handle(handle: &Self::Handle) -> Self {
+                    handle.get_untracked()
+                }
+
+                fn get(handle: &Self::Handle) -> Self {
+                    handle.get()
+                }
+
+                fn set(handle: &Self::Handle, value: Self) {
+                    handle.set(value);
+                }
+            }
+        )*
+    };
+}
+
+impl_signal_store!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, String,
+);
+
+/// Define a struct along with a handle that has a signal for each of its fields, and implement
+/// [`Store`] for it.
+///
+/// Fields marked with `#[store]` hold another [`Store`] value and get its handle instead of a
+/// single signal, so their own fields can be changed separately too. `#[store]` has to be the
+/// first attribute of the field.
+///
+/// ```rust
+/// use floem_reactive::{create_effect, store, Store};
+///
+/// store! {
+///     #[derive(Clone, Debug)]
+///     pub struct Owner as OwnerStore {
+///         pub name: String,
+///     }
+/// }
+///
+/// store! {
+///     #[derive(Clone, Debug)]
+///     pub struct Todo as TodoStore {
+///         pub title: String,
+///         pub done: bool,
+///         #[store]
+///         pub owner: Owner,
+///     }
+/// }
+///
+/// let todo = Todo {
+///     title: "Write docs".to_string(),
+///     done: false,
+///     owner: Owner {
+///         name: "Ada".to_string(),
+///     },
+/// }
+/// .into_handle();
+/// create_effect(move |_| {
+///     // Only re-runs when the title changes
+///     println!("{}", todo.title.get());
+/// });
+/// todo.done.set(true);
+/// todo.owner.name.set("Grace".to_string());
+/// ```
+#[macro_export]
+macro_rules! store {
+    // Collect the fields one at a time, noting which of them are nested stores
+    (@fields $head:tt [$($done:tt)*]) => {
+        $crate::store!(@define $head $($done)*);
+    };
+    (@fields $head:tt [$($done:tt)*] $($rest:tt)+) => {
+        $crate::store!(@attrs $head [$($done)*] [] signal $($rest)+);
+    };
+    (@attrs $head:tt $done:tt [$($attrs:tt)*] $kind:ident #[store] $($rest:tt)*) => {
+        $crate::store!(@attrs $head $done [$($attrs)*] nested $($rest)*);
+    };
+    (@attrs $head:tt $done:tt [$($attrs:tt)*] $kind:ident #[$meta:meta] $($rest:tt)*) => {
+        $crate::store!(@attrs $head $done [$($attrs)* #[$meta]] $kind $($rest)*);
+    };
+    (
+        @attrs $head:tt [$($done:tt)*] [$($attrs:tt)*] $kind:ident
+        $field_vis:vis $field:ident : $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::store!(
+            @fields $head [$($done)* {[$($attrs)*] [$field_vis] $field [$ty] $kind}] $($($rest)*)?
+        );
+    };
+
+    (@handle signal $ty:ty) => { $crate::RwSignal<$ty> };
+    (@handle nested $ty:ty) => { <$ty as $crate::Store>::Handle };
+    (@into_handle signal $ty:ty, $value:expr) => { $crate::create_rw_signal($value) };
+    (@into_handle nested $ty:ty, $value:expr) => { <$ty as $crate::Store>::into_handle($value) };
+    (@from_handle signal $ty:ty, $handle:expr) => { $handle.get_untracked() };
+    (@from_handle nested $ty:ty, $handle:expr) => { <$ty as $crate::Store>::from_handle(&$handle) };
+    (@get signal $ty:ty, $handle:expr) => { $handle.get() };
+    (@get nested $ty:ty, $handle:expr) => { <$ty as $crate::Store>::get(&$handle) };
+    (@set signal $ty:ty, $handle:expr, $value:expr) => { $handle.set($value) };
+    (@set nested $ty:ty, $handle:expr, $value:expr) => {
+        <$ty as $crate::Store>::set(&$handle, $value)
+    };
+
+    (
+        @define [$(#[$meta:meta])* [$vis:vis] $name:ident $store:ident]
+        $({[$(#[$field_meta:meta])*] [$field_vis:vis] $field:ident [$ty:ty] $kind:ident})*
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty,)*
+        }
+
+        #[doc = concat!("The signals for each field of [`", stringify!($name), "`].")]
+        #[derive(Clone, Copy)]
+        $vis struct $store {
+            $($field_vis $field: $crate::store!(@handle $kind $ty),)*
+        }
+
+        impl $crate::Store for $name {
+            type Handle = $store;
+
+            fn into_handle(self) -> Self::Handle {
+                $store {
+                    $($field: $crate::store!(@into_handle $kind $ty, self.$field),)*
+                }
+            }
+
+            fn from_handle(handle: &Self::Handle) -> Self {
+                $name {
+                    $($field: $crate::store!(@from_handle $kind $ty, handle.$field),)*
+                }
+            }
+
+            fn get(handle: &Self::Handle) -> Self {
+                $name {
+                    $($field: $crate::store!(@get $kind $ty, handle.$field),)*
+                }
+            }
+
+            fn set(handle: &Self::Handle, value: Self) {
+                $crate::batch(|| {
+                    $($crate::store!(@set $kind $ty, handle.$field, value.$field);)*
+                });
+            }
+        }
+
+        impl $store {
+            /// Read every field, subscribing to each of them
+            #[allow(dead_code)]
+            pub fn get(&self) -> $name {
+                <$name as $crate::Store>::get(self)
+            }
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident as $store:ident { $($fields:tt)* }
+    ) => {
+        $crate::store!(@fields [$(#[$meta])* [$vis] $name $store] [] $($fields)*);
+    };
+}
+
+/// An item of a [`StoreVec`] or [`StoreMap`].
+#[derive(Debug, Clone, Copy)]
+pub struct StoreItem<K, H> {
+    pub key: K,
+    pub handle: H,
+    /// The Scope holding the signals of the handle
+    scope: Scope,
+}
+
+/// A structural change to a [`StoreVec`]. Changes to the values of items are made through their
+/// signals and are not part of the diff.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreDiff<K, H> {
+    Insert {
+        at: usize,
+        key: K,
+        handle: H,
+    },
+    Remove {
+        at: usize,
+        key: K,
+    },
+    /// Carries the key and handle of the item like `Insert`, so consumers that only show part of
+    /// the items can show one that is moved into view
+    Move {
+        from: usize,
+        to: usize,
+        key: K,
+        handle: H,
+    },
+    Clear,
+}
+
+struct DiffEntry<K, H> {
+    /// The version of the store after the change
+    version: u64,
+    diff: StoreDiff<K, H>,
+    /// The Scopes of removed items, which are kept alive until the change leaves the history so
+    /// that consumers that are catching up can still read them
+    removed: SmallVec<[Scope; 1]>,
+}
+
+struct StoreVecState<K, H> {
+    items: im::Vector<StoreItem<K, H>>,
+    version: u64,
+    history: VecDeque<DiffEntry<K, H>>,
+}
+
+impl<K, H> StoreVecState<K, H> {
+    fn record(&mut self, diff: StoreDiff<K, H>, removed: SmallVec<[Scope; 1]>) {
+        self.version += 1;
+        self.history.push_back(DiffEntry {
+            version: self.version,
+            diff,
+            removed,
+        });
+        while self.history.len() > DIFF_HISTORY {
+            if let Some(entry) = self.history.pop_front() {
+                for scope in entry.removed {
+                    scope.dispose();
+                }
+            }
+        }
+    }
+}
+
+/// A reactive list where each item has its own signals, created from a [`Store`] value.
+/// The list itself is only notified when items are added, removed or moved, and it keeps a
+/// history of those changes as keyed diffs, see [`StoreVec::diffs_since`].
+pub struct StoreVec<K: 'static, T: Store> {
+    state: RwSignal<StoreVecState<K, T::Handle>>,
+    scope: Scope,
+}
+
+impl<K, T: Store> Copy for StoreVec<K, T> {}
+
+impl<K, T: Store> Clone for StoreVec<K, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Create a [`StoreVec`] in the current Scope from items with a key identifying each of them.
+pub fn create_store_vec<K, T>(items: impl IntoIterator<Item = (K, T)>) -> StoreVec<K, T>
+where
+    K: Clone + PartialEq + 'static,
+    T: Store,
+{
+    let scope = Scope::current().create_child();
+    let items = items
+        .into_iter()
+        .map(|(key, value)| new_item(scope, key, value))
+        .collect();
+    let state = scope.create_rw_signal(StoreVecState {
+        items,
+        version: 0,
+        history: VecDeque::new(),
+    });
+    StoreVec { state, scope }
+}
+
+fn new_item<K, T: Store>(scope: Scope, key: K, value: T) -> StoreItem<K, T::Handle> {
+    let scope = scope.create_child();
+    let handle = with_scope(scope, || value.into_handle());
+    StoreItem { key, handle, scope }
+}
+
+impl<K, T> StoreVec<K, T>
+where
+    K: Clone + PartialEq + 'static,
+    T: Store,
+{
+    /// Subscribe to structural changes of the list
+    pub fn track(&self) {
+        self.state.track();
+    }
+
+    /// The number of changes made to the structure of the list, subscribing to it
+    pub fn version(&self) -> u64 {
+        self.state.with(|state| state.version)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.with(|state| state.items.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The signals of the item at `index`, subscribing to the structure of the list
+    pub fn get(&self, index: usize) -> Option<T::Handle> {
+        self.state
+            .with(|state| state.items.get(index).map(|item| item.handle))
+    }
+
+    /// The signals of the first item with the key, subscribing to the structure of the list
+    pub fn get_by_key(&self, key: &K) -> Option<T::Handle> {
+        self.state.with(|state| {
+            state
+                .items
+                .iter()
+                .find(|item| item.key == *key)
+                .map(|item| item.handle)
+        })
+    }
+
+    /// The index of the first item with the key, subscribing to the structure of the list
+    pub fn position(&self, key: &K) -> Option<usize> {
+        self.state
+            .with(|state| state.items.iter().position(|item| item.key == *key))
+    }
+
+    /// Apply a closure to the items, subscribing to the structure of the list
+    pub fn with<O>(&self, f: impl FnOnce(&im::Vector<StoreItem<K, T::Handle>>) -> O) -> O {
+        self.state.with(|state| f(&state.items))
+    }
+
+    pub fn with_untracked<O>(
+        &self,
+        f: impl FnOnce(&im::Vector<StoreItem<K, T::Handle>>) -> O,
+    ) -> O {
+        self.state.with_untracked(|state| f(&state.items))
+    }
+
+    /// Read the values out of the signals of every item, without subscribing to them
+    pub fn get_untracked(&self) -> Vec<T> {
+        self.with_untracked(|items| {
+            items
+                .iter()
+                .map(|item| T::from_handle(&item.handle))
+                .collect()
+        })
+    }
+
+    /// The structural changes made after the given version, or `None` if they are no longer all
+    /// in the history, in which case the consumer should rebuild from the current items.
+    /// This does not subscribe to the list.
+    pub fn diffs_since(&self, version: u64) -> Option<Vec<StoreDiff<K, T::Handle>>> {
+        self.state.with_untracked(|state| {
+            if version > state.version {
+                return None;
+            }
+            if version == state.version {
+                return Some(Vec::new());
+            }
+            let first = state.history.front()?;
+            if first.version > version + 1 {
+                return None;
+            }
+            Some(
+                state
+                    .history
+                    .iter()
+                    .filter(|entry| entry.version > version)
+                    .map(|entry| entry.diff.clone())
+                    .collect(),
+            )
+        })
+    }
+
+    pub fn push(&self, key: K, value: T) {
+        let len = self.state.with_untracked(|state| state.items.len());
+        self.insert(len, key, value);
+    }
+
+    /// Insert an item at `index`, which is clamped to the length of the list
+    pub fn insert(&self, index: usize, key: K, value: T) {
+        let item = new_item(self.scope, key, value);
+        self.state.update(|state| {
+            let at = index.min(state.items.len());
+            state.items.insert(at, item.clone());
+            state.record(
+                StoreDiff::Insert {
+                    at,
+                    key: item.key,
+                    handle: item.handle,
+                },
+                SmallVec::new(),
+            );
+        });
+    }
+
+    /// Remove the item at `index`, returning its value
+    pub fn remove(&self, index: usize) -> Option<T> {
+        if index >= self.state.with_untracked(|state| state.items.len()) {
+            return None;
+        }
+
+        let mut value = None;
+        self.state.update(|state| {
+            let item = state.items.remove(index);
+            value = Some(T::from_handle(&item.handle));
+            state.record(
+                StoreDiff::Remove {
+                    at: index,
+                    key: item.key,
+                },
+                SmallVec::from_elem(item.scope, 1),
+            );
+        });
+        value
+    }
+
+    /// Remove the first item with the key, returning its value
+    pub fn remove_key(&self, key: &K) -> Option<T> {
+        let index = self
+            .state
+            .with_untracked(|state| state.items.iter().position(|item| item.key == *key))?;
+        self.remove(index)
+    }
+
+    /// Move the item at `from` so that it ends up at `to`
+    pub fn move_item(&self, from: usize, to: usize) {
+        let len = self.state.with_untracked(|state| state.items.len());
+        if from >= len || to >= len || from == to {
+            return;
+        }
+
+        self.state.update(|state| {
+            let item = state.items.remove(from);
+            let diff = StoreDiff::Move {
+                from,
+                to,
+                key: item.key.clone(),
+                handle: item.handle,
+            };
+            state.items.insert(to, item);
+            state.record(diff, SmallVec::new());
+        });
+    }
+
+    pub fn clear(&self) {
+        if self.state.with_untracked(|state| state.items.is_empty()) {
+            return;
+        }
+
+        self.state.update(|state| {
+            let removed = std::mem::take(&mut state.items)
+                .into_iter()
+                .map(|item| item.scope)
+                .collect();
+            state.record(StoreDiff::Clear, removed);
+        });
+    }
+
+    /// Replace all of the items
+    pub fn set_all(&self, items: impl IntoIterator<Item = (K, T)>) {
+        batch(|| {
+            self.clear();
+            for (key, value) in items {
+                self.push(key, value);
+            }
+        });
+    }
+
+    /// Set the value of the item at `index` through its signals, without changing the structure
+    /// of the list. Returns false if there is no item at `index`.
+    pub fn set(&self, index: usize, value: T) -> bool {
+        let handle = self
+            .state
+            .with_untracked(|state| state.items.get(index).map(|item| item.handle));
+        match handle {
+            Some(handle) => {
+                T::set(&handle, value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A reactive map where each value has its own signals, created from a [`Store`] value.
+/// The map itself is only notified when keys are added or removed.
+pub struct StoreMap<K: 'static, T: Store> {
+    items: RwSignal<im::HashMap<K, StoreItem<K, T::Handle>>>,
+    scope: Scope,
+}
+
+impl<K, T: Store> Copy for StoreMap<K, T> {}
+
+impl<K, T: Store> Clone for StoreMap<K, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Create a [`StoreMap`] in the current Scope.
+pub fn create_store_map<K, T>(items: impl IntoIterator<Item = (K, T)>) -> StoreMap<K, T>
+where
+    K: Clone + Eq + Hash + 'static,
+    T: Store,
+{
+    let scope = Scope::current().create_child();
+    let items = items
+        .into_iter()
+        .map(|(key, value)| (key.clone(), new_item(scope, key, value)))
+        .collect();
+    StoreMap {
+        items: scope.create_rw_signal(items),
+        scope,
+    }
+}
+
+impl<K, T> StoreMap<K, T>
+where
+    K: Clone + Eq + Hash + 'static,
+    T: Store,
+{
+    /// Subscribe to keys being added or removed
+    pub fn track(&self) {
+        self.items.track();
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.with(|items| items.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.items.with(|items| items.contains_key(key))
+    }
+
+    /// The signals of the value for the key, subscribing to the keys of the map
+    pub fn get(&self, key: &K) -> Option<T::Handle> {
+        self.items
+            .with(|items| items.get(key).map(|item| item.handle))
+    }
+
+    pub fn keys(&self) -> Vec<K> {
+        self.items.with(|items| items.keys().cloned().collect())
+    }
+
+    /// Apply a closure to the items, subscribing to the keys of the map
+    pub fn with<O>(&self, f: impl FnOnce(&im::HashMap<K, StoreItem<K, T::Handle>>) -> O) -> O {
+        self.items.with(f)
+    }
+
+    /// Insert a value. If the key already exists then the value is set through the existing
+    /// signals, and only the subscribers of those signals are notified.
+    pub fn insert(&self, key: K, value: T) {
+        let handle = self
+            .items
+            .with_untracked(|items| items.get(&key).map(|item| item.handle));
+        match handle {
+            Some(handle) => T::set(&handle, value),
+            None => {
+                let item = new_item(self.scope, key.clone(), value);
+                self.items.update(|items| {
+                    items.insert(key, item);
+                });
+            }
+        }
+    }
+
+    /// Remove the value for the key, returning it
+    pub fn remove(&self, key: &K) -> Option<T> {
+        if !self.items.with_untracked(|items| items.contains_key(key)) {
+            return None;
+        }
+
+        let mut item = None;
+        self.items.update(|items| item = items.remove(key));
+        let item = item?;
+        let value = T::from_handle(&item.handle);
+        item.scope.dispose();
+        Some(value)
+    }
+
+    pub fn clear(&self) {
+        if self.items.with_untracked(|items| items.is_empty()) {
+            return;
+        }
+
+        let mut removed = im::HashMap::new();
+        self.items.update(|items| removed = std::mem::take(items));
+        for item in removed.values() {
+            item.scope.dispose();
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex, MutexGuard},
+    thread::ThreadId,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    effect::{create_effect, untrack},
+    resource::ExtTriggerHandler,
+    runtime::RUNTIME,
+    signal::{create_rw_signal, ReadSignal, RwSignal},
+    trigger::{create_trigger, Trigger},
+};
+
+struct SyncState<T> {
+    /// The latest value, which is ahead of the Signal while an update is pending
+    value: T,
+    /// Whether `value` has changed since it was last applied to the Signal
+    dirty: bool,
+    /// Whether the trigger has been sent from another thread and the write it's for hasn't been
+    /// applied yet
+    scheduled: bool,
+    last_applied: Option<Instant>,
+    throttle: Option<Duration>,
+}
+
+/// Notifies a [`Trigger`] once a duration has passed, on the thread the handler was set on.
+pub type TimerHandler = Rc<dyn Fn(Duration, Trigger)>;
+
+/// Set how a throttled [`SyncSignal`] created on the current thread waits until it can apply the
+/// next write from another thread. Floem sets this to notify the trigger from an `exec_after`
+/// timer. Without it, writes are applied as soon as their trigger is handled.
+pub fn set_timer_handler(handler: impl Fn(Duration, Trigger) + 'static) {
+    RUNTIME.with(|runtime| {
+        *runtime.timer_handler.borrow_mut() = Some(Rc::new(handler));
+    });
+}
+
+struct Shared<T> {
+    state: Mutex<SyncState<T>>,
+    trigger: Trigger,
+    handler: Option<ExtTriggerHandler>,
+    owner: ThreadId,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, SyncState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get the trigger to the thread owning the Signal, unless it's already on its way
+    fn schedule(&self, mut state: MutexGuard<'_, SyncState<T>>) {
+        state.dirty = true;
+        if std::thread::current().id() == self.owner {
+            // Writes on the owning thread aren't throttled, and take any pending write with them
+            state.scheduled = false;
+            drop(state);
+            self.trigger.notify();
+            return;
+        }
+        if state.scheduled {
+            return;
+        }
+        let Some(handler) = self.handler.clone() else {
+            return;
+        };
+        state.scheduled = true;
+        drop(state);
+        handler(self.trigger);
+    }
+}
+
+impl<T> SyncState<T> {
+    /// How long a write from another thread has to wait before it can be applied
+    fn throttle_delay(&self) -> Duration {
+        self.throttle
+            .zip(self.last_applied)
+            .map(|(throttle, last)| throttle.saturating_sub(last.elapsed()))
+            .unwrap_or_default()
+    }
+}
+
+/// A Signal which can be written from other threads through a [`SendWriteSignal`].
+///
+/// Writes from other threads are applied on the thread that created the Signal once the trigger
+/// given to the [`ExtTriggerHandler`] is notified there, and writes made before that are
+/// coalesced so that effects only run for the latest value.
+pub struct SyncSignal<T: 'static> {
+    signal: RwSignal<T>,
+    shared: RwSignal<Arc<Shared<T>>>,
+}
+
+impl<T> Copy for SyncSignal<T> {}
+
+impl<T> Clone for SyncSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// A handle to a [`SyncSignal`] which can be sent to and written from any thread.
+pub struct SendWriteSignal<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for SendWriteSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// A read only handle to a [`SyncSignal`] which can be sent to other threads, to read a snapshot
+/// of its latest value.
+pub struct SendReadSignal<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for SendReadSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// Create a [`SyncSignal`]. Use [`SyncSignal::write_handle`] to get a handle for other threads.
+#[track_caller]
+pub fn create_sync_signal<T>(value: T) -> SyncSignal<T>
+where
+    T: Clone + Send + 'static,
+{
+    let handler = RUNTIME.with(|runtime| runtime.ext_trigger_handler.borrow().clone());
+    let signal = create_rw_signal(value.clone());
+    let trigger = create_trigger();
+    let shared = Arc::new(Shared {
+        state: Mutex::new(SyncState {
+            value,
+            dirty: false,
+            scheduled: false,
+            last_applied: None,
+            throttle: None,
+        }),
+        trigger,
+        handler,
+        owner: std::thread::current().id(),
+    });
+
+    {
+        let shared = shared.clone();
+        create_effect(move |_| {
+            trigger.track();
+            let value = {
+                let mut state = shared.lock();
+                if !state.dirty {
+                    state.scheduled = false;
+                    return;
+                }
+                if state.scheduled {
+                    let delay = state.throttle_delay();
+                    let timer = RUNTIME.with(|runtime| runtime.timer_handler.borrow().clone());
+                    if let Some(timer) = timer.filter(|_| !delay.is_zero()) {
+                        drop(state);
+                        timer(delay, trigger);
+                        return;
+                    }
+                }
+                state.scheduled = false;
+                state.dirty = false;
+                state.last_applied = Some(Instant::now());
+                state.value.clone()
+            };
+            untrack(|| signal.set(value));
+        });
+    }
+
+    SyncSignal {
+        signal,
+        shared: create_rw_signal(shared),
+    }
+}
+
+impl<T: Clone + Send + 'static> SyncSignal<T> {
+    /// Apply writes from other threads at most once per `interval`. Writes made in between are
+    /// coalesced into the next update, which waits on the thread owning the Signal through the
+    /// handler set with [`set_timer_handler`].
+    pub fn throttle(self, interval: Duration) -> Self {
+        self.shared
+            .with_untracked(|shared| shared.lock().throttle = Some(interval));
+        self
+    }
+
+    /// A handle that can be sent to other threads to write or read the value
+    pub fn write_handle(&self) -> SendWriteSignal<T> {
+        SendWriteSignal {
+            shared: self.shared.get_untracked(),
+        }
+    }
+
+    /// A read only handle that can be sent to other threads
+    pub fn read_handle(&self) -> SendReadSignal<T> {
+        SendReadSignal {
+            shared: self.shared.get_untracked(),
+        }
+    }
+
+    /// Clones and returns the current value, and subscribes to the current running effect
+    pub fn get(&self) -> T {
+        self.signal.get()
+    }
+
+    /// Clones and returns the current value, but it doesn't subscribe to the current running
+    /// effect.
+    pub fn get_untracked(&self) -> T {
+        self.signal.get_untracked()
+    }
+
+    /// Applies a closure to the current value, and subscribes to the current running effect
+    pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        self.signal.with(f)
+    }
+
+    /// Applies a closure to the current value, but it doesn't subscribe to the current running
+    /// effect.
+    pub fn with_untracked<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        self.signal.with_untracked(f)
+    }
+
+    /// Sets the new value and triggers effect run. Any pending write from another thread is
+    /// overwritten.
+    pub fn set(&self, new_value: T) {
+        self.update(|value| *value = new_value);
+    }
+
+    /// Update the value with the given function and triggers effect run
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let value = self.shared.with_untracked(|shared| {
+            let mut state = shared.lock();
+            f(&mut state.value);
+            state.dirty = false;
+            state.value.clone()
+        });
+        self.signal.set(value);
+    }
+
+    /// A getter for the value on this thread
+    pub fn read_only(&self) -> ReadSignal<T> {
+        self.signal.read_only()
+    }
+}
+
+impl<T: Clone + Send + 'static> SendWriteSignal<T> {
+    /// Set the value, which is applied to the Signal on its own thread
+    pub fn set(&self, new_value: T) {
+        self.update(|value| *value = new_value);
+    }
+
+    /// Update the value, which is applied to the Signal on its own thread. The function is
+    /// given the latest value, including writes which haven't been applied yet.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut state = self.shared.lock();
+        f(&mut state.value);
+        self.shared.schedule(state);
+    }
+
+    /// A snapshot of the latest value
+    pub fn get(&self) -> T {
+        self.shared.lock().value.clone()
+    }
+
+    /// Applies a closure to the latest value. The value is locked while the closure runs, so it
+    /// should be short.
+    pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        f(&self.shared.lock().value)
+    }
+}
+
+impl<T: Clone + Send + 'static> SendReadSignal<T> {
+    /// A snapshot of the latest value
+    pub fn get(&self) -> T {
+        self.shared.lock().value.clone()
+    }
+
+    /// Applies a closure to the latest value. The value is locked while the closure runs, so it
+    /// should be short.
+    pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        f(&self.shared.lock().value)
+    }
+}
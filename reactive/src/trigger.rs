@@ -23,11 +23,13 @@ impl Trigger {
     }
 
     #[allow(clippy::new_without_default)]
+    #[track_caller]
     pub fn new() -> Self {
         create_trigger()
     }
 }
 
+#[track_caller]
 pub fn create_trigger() -> Trigger {
     Trigger {
         signal: create_rw_signal(()),
@@ -0,0 +1,107 @@
+use std::{cell::Cell, rc::Rc};
+
+use floem_reactive::{
+    create_effect, create_memo, create_rw_signal, provide_context, use_context, with_scope, Scope,
+};
+
+#[test]
+fn child_scopes_see_parent_contexts() {
+    let parent = Scope::new();
+    parent.provide_context(1u32);
+    parent.provide_context("parent");
+
+    let child = parent.create_child();
+    child.provide_context(2u32);
+
+    with_scope(child, || {
+        assert_eq!(use_context::<u32>(), Some(2));
+        // Types which the child doesn't provide come from the parent
+        assert_eq!(use_context::<&str>(), Some("parent"));
+    });
+    with_scope(parent, || {
+        assert_eq!(use_context::<u32>(), Some(1));
+    });
+}
+
+#[test]
+fn sibling_scopes_are_separate() {
+    let parent = Scope::new();
+    let a = parent.create_child();
+    let b = parent.create_child();
+
+    with_scope(a, || provide_context(String::from("a")));
+    with_scope(b, || provide_context(String::from("b")));
+
+    assert_eq!(
+        with_scope(a, use_context::<String>),
+        Some(String::from("a"))
+    );
+    assert_eq!(
+        with_scope(b, use_context::<String>),
+        Some(String::from("b"))
+    );
+    assert_eq!(with_scope(parent, use_context::<String>), None);
+}
+
+#[test]
+fn dispose_removes_contexts() {
+    let parent = Scope::new();
+    let child = parent.create_child();
+    child.provide_context(5i64);
+    let grandchild = child.create_child();
+    assert_eq!(with_scope(grandchild, use_context::<i64>), Some(5));
+
+    parent.dispose();
+    assert_eq!(with_scope(child, use_context::<i64>), None);
+    assert_eq!(with_scope(grandchild, use_context::<i64>), None);
+    assert!(grandchild.parent().is_none());
+}
+
+#[test]
+fn root_contexts_are_global() {
+    provide_context(7u8);
+    // A Scope without a parent still sees the contexts of the root Scope
+    assert_eq!(with_scope(Scope::new(), use_context::<u8>), Some(7));
+}
+
+#[test]
+fn effects_see_scope_contexts() {
+    let scope = Scope::new();
+    scope.provide_context(42u16);
+    let seen = Rc::new(Cell::new(None));
+    let trigger = create_rw_signal(0);
+
+    with_scope(scope, || {
+        let seen = seen.clone();
+        create_effect(move |_| {
+            trigger.get();
+            seen.set(use_context::<u16>());
+        });
+    });
+    assert_eq!(seen.get(), Some(42));
+
+    // Also when the effect runs again outside of the Scope
+    seen.set(None);
+    trigger.set(1);
+    assert_eq!(seen.get(), Some(42));
+
+    let memo = with_scope(scope, || create_memo(move |_| use_context::<u16>()));
+    assert_eq!(memo.get(), Some(42));
+}
+
+#[test]
+fn effects_in_child_scopes_see_parent_contexts() {
+    let parent = Scope::new();
+    parent.provide_context("parent");
+    let child = parent.create_child();
+    child.provide_context(3i16);
+    let seen = Rc::new(Cell::new(None));
+
+    with_scope(child, || {
+        let seen = seen.clone();
+        create_effect(move |_| {
+            seen.set(use_context::<&str>().zip(use_context::<i16>()));
+        });
+    });
+    assert_eq!(seen.get(), Some(("parent", 3)));
+}
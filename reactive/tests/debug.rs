@@ -0,0 +1,110 @@
+use floem_reactive::{
+    batch, create_effect, create_rw_signal, reactive_graph, set_debug_tracking, NodeKind, Scope,
+};
+
+#[test]
+fn records_creation_sites_and_edges() {
+    set_debug_tracking(true);
+    let scope = Scope::new();
+    let line = line!() + 1;
+    let signal = scope.create_rw_signal(1u8);
+    scope.create_effect(move |_| {
+        signal.get();
+    });
+
+    let graph = reactive_graph();
+    let signal_node = graph
+        .nodes
+        .iter()
+        .find(|node| node.type_name == Some("u8"))
+        .unwrap();
+    assert_eq!(signal_node.kind, NodeKind::Signal);
+    assert_eq!(signal_node.location.file(), file!());
+    assert_eq!(signal_node.location.line(), line);
+    assert_eq!(signal_node.subscribers.len(), 1);
+
+    let effect = graph.node(signal_node.subscribers[0]).unwrap();
+    assert_eq!(effect.kind, NodeKind::Effect);
+    assert_eq!(effect.location.line(), line + 1);
+    assert_eq!(effect.dependencies, vec![signal_node.id]);
+    assert_eq!(effect.run_count, 1);
+
+    // Both are owned by the scope
+    let scope_node = graph.node(signal_node.owner.unwrap()).unwrap();
+    assert_eq!(scope_node.kind, NodeKind::Scope);
+    assert_eq!(effect.owner, Some(scope_node.id));
+
+    let dot = graph.to_dot();
+    assert!(dot.contains(&format!("n{} -> n{};", signal_node.id, effect.id)));
+    // The location goes on its own line of the label
+    assert!(dot.contains(&format!("\\n{}\"", signal_node.location)));
+    let json = graph.to_json();
+    assert!(json.contains(&format!("\"subscribers\":[{}]", effect.id)));
+}
+
+#[test]
+fn records_what_caused_a_run() {
+    set_debug_tracking(true);
+    let a = create_rw_signal(1u16);
+    let b = create_rw_signal(1u32);
+    let c = create_rw_signal(1u64);
+    create_effect(move |_| {
+        a.get();
+        b.get();
+        c.get();
+    });
+
+    a.set(2);
+    batch(|| {
+        b.set(2);
+        c.set(2);
+    });
+
+    let graph = reactive_graph();
+    let id_of = |type_name| {
+        graph
+            .nodes
+            .iter()
+            .find(|node| node.type_name == Some(type_name))
+            .unwrap()
+            .id
+    };
+    let a_node = graph.node(id_of("u16")).unwrap();
+    let effect = graph.node(a_node.subscribers[0]).unwrap();
+    assert_eq!(effect.run_count, 3);
+    let mut caused_by = effect.caused_by.clone();
+    caused_by.sort();
+    assert_eq!(caused_by, vec![id_of("u32"), id_of("u64")]);
+}
+
+#[test]
+fn memos_and_leaks() {
+    set_debug_tracking(true);
+    let before = reactive_graph();
+
+    let scope = Scope::new();
+    let signal = scope.create_rw_signal(1i8);
+    let memo = scope.create_memo(move |_| signal.get() * 2);
+    assert_eq!(memo.get_untracked(), 2);
+
+    let graph = reactive_graph();
+    let added = graph.added_since(&before);
+    assert!(added
+        .iter()
+        .any(|node| node.kind == NodeKind::Memo
+            && node.type_name == Some("core::option::Option<i8>")));
+    assert!(added.iter().any(|node| node.kind == NodeKind::Effect));
+
+    // Nothing is left over once the scope is disposed
+    scope.dispose();
+    assert!(reactive_graph().added_since(&before).is_empty());
+}
+
+#[test]
+fn disabled_tracking() {
+    set_debug_tracking(false);
+    let before = reactive_graph();
+    let signal = create_rw_signal(0);
+    create_effect(move |_| signal.get());
+    assert!(reactive_graph().added_since(&before).is_empty());
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{
+    create_background_resource, create_resource, create_rw_signal, set_ext_trigger_handler, Trigger,
+};
+
+/// A minimal local executor: wakeups queue the resource's trigger, which `run` notifies on the
+/// test thread.
+#[derive(Clone, Default)]
+struct LocalExecutor {
+    queue: Arc<Mutex<VecDeque<Trigger>>>,
+}
+
+impl LocalExecutor {
+    fn install() -> Self {
+        let executor = Self::default();
+        let queue = executor.queue.clone();
+        set_ext_trigger_handler(move |trigger| queue.lock().unwrap().push_back(trigger));
+        executor
+    }
+
+    fn run(&self) {
+        while let Some(trigger) = { self.queue.lock().unwrap().pop_front() } {
+            trigger.notify();
+        }
+    }
+
+    /// Run until `done` returns true, waiting for wakeups from other threads.
+    fn run_until(&self, done: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !done() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            self.run();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+type GateState = (Option<Result<u32, String>>, Option<Waker>);
+
+/// A future which is completed by the test.
+#[derive(Clone, Default)]
+struct Gate {
+    state: Arc<Mutex<GateState>>,
+}
+
+impl Gate {
+    fn complete(&self, result: Result<u32, String>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.0 = Some(result);
+            state.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Gate {
+    type Output = Result<u32, String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.0.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[test]
+fn ready_future() {
+    let _executor = LocalExecutor::install();
+    let source = create_rw_signal(1);
+    let resource = create_resource(move || source.get(), |n| async move { Ok::<_, ()>(n * 2) });
+
+    assert!(!resource.loading().get());
+    assert_eq!(resource.ready().get(), Some(2));
+
+    source.set(5);
+    assert_eq!(resource.ready().get(), Some(10));
+}
+
+#[test]
+fn loading_and_errors() {
+    let executor = LocalExecutor::install();
+    let gates: Arc<Mutex<Vec<Gate>>> = Default::default();
+    let source = create_rw_signal(0);
+    let resource = create_resource(move || source.get(), {
+        let gates = gates.clone();
+        move |_| {
+            let gate = Gate::default();
+            gates.lock().unwrap().push(gate.clone());
+            gate
+        }
+    });
+
+    assert!(resource.loading().get());
+    assert_eq!(resource.ready().get(), None);
+
+    gates.lock().unwrap()[0].complete(Ok(3));
+    executor.run();
+    assert!(!resource.loading().get());
+    assert_eq!(resource.ready().get(), Some(3));
+
+    resource.refetch();
+    assert!(resource.loading().get());
+    gates.lock().unwrap()[1].complete(Err("failed".to_string()));
+    executor.run();
+    assert!(!resource.loading().get());
+    assert_eq!(resource.error().get(), Some("failed".to_string()));
+    // The last value is kept
+    assert_eq!(resource.ready().get(), Some(3));
+}
+
+#[test]
+fn stale_results_are_cancelled() {
+    let executor = LocalExecutor::install();
+    let gates: Arc<Mutex<Vec<Gate>>> = Default::default();
+    let source = create_rw_signal(0);
+    let resource = create_resource(move || source.get(), {
+        let gates = gates.clone();
+        move |_| {
+            let gate = Gate::default();
+            gates.lock().unwrap().push(gate.clone());
+            gate
+        }
+    });
+
+    source.set(1);
+    let (first, second) = {
+        let gates = gates.lock().unwrap();
+        (gates[0].clone(), gates[1].clone())
+    };
+
+    second.complete(Ok(2));
+    executor.run();
+    assert_eq!(resource.ready().get(), Some(2));
+
+    // The first fetch finishing later doesn't overwrite the newer result
+    first.complete(Ok(1));
+    executor.run();
+    assert_eq!(resource.ready().get(), Some(2));
+    assert!(!resource.loading().get());
+}
+
+#[test]
+fn background_resource() {
+    let executor = LocalExecutor::install();
+    let source = create_rw_signal(String::from("floem"));
+    let resource = create_background_resource(
+        move || source.get(),
+        |name| {
+            std::thread::sleep(Duration::from_millis(5));
+            Ok::<_, ()>(name.len())
+        },
+    );
+
+    assert!(resource.loading().get());
+    executor.run_until(|| !resource.loading().get());
+    assert_eq!(resource.ready().get(), Some(5));
+
+    source.set(String::from("reactive"));
+    executor.run_until(|| !resource.loading().get());
+    assert_eq!(resource.ready().get(), Some(8));
+}
@@ -0,0 +1,182 @@
+use std::{cell::Cell, rc::Rc};
+
+use floem_reactive::{create_effect, create_store_map, create_store_vec, store, Store, StoreDiff};
+
+store! {
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Todo as TodoStore {
+        pub title: String,
+        pub done: bool,
+    }
+}
+
+fn todo(title: &str) -> Todo {
+    Todo {
+        title: title.to_string(),
+        done: false,
+    }
+}
+
+fn count_runs(f: impl Fn() + 'static) -> Rc<Cell<usize>> {
+    let count = Rc::new(Cell::new(0));
+    create_effect({
+        let count = count.clone();
+        move |_| {
+            f();
+            count.set(count.get() + 1);
+        }
+    });
+    count
+}
+
+#[test]
+fn fields_are_separate_signals() {
+    let todo = todo("a").into_handle();
+    let title_runs = count_runs(move || {
+        todo.title.track();
+    });
+    let done_runs = count_runs(move || {
+        todo.done.track();
+    });
+
+    todo.done.set(true);
+    assert_eq!(title_runs.get(), 1);
+    assert_eq!(done_runs.get(), 2);
+
+    Todo::set(&todo, self::todo("b"));
+    assert_eq!(title_runs.get(), 2);
+    assert_eq!(todo.get(), self::todo("b"));
+}
+
+store! {
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Project as ProjectStore {
+        /// Each field of a nested store gets its own signal too
+        #[store]
+        pub lead: Todo,
+        pub name: String,
+    }
+}
+
+#[test]
+fn nested_stores() {
+    let project = Project {
+        lead: todo("a"),
+        name: "p".to_string(),
+    }
+    .into_handle();
+    let title_runs = count_runs(move || {
+        project.lead.title.track();
+    });
+    let project_runs = count_runs(move || {
+        project.get();
+    });
+
+    project.lead.done.set(true);
+    assert_eq!(title_runs.get(), 1);
+    assert_eq!(project_runs.get(), 2);
+
+    let value = Project {
+        lead: todo("b"),
+        name: "q".to_string(),
+    };
+    Project::set(&project, value.clone());
+    assert_eq!(title_runs.get(), 2);
+    assert_eq!(project_runs.get(), 3);
+    assert_eq!(Project::from_handle(&project), value);
+}
+
+#[test]
+fn vec_structure_and_items() {
+    let todos = create_store_vec([(1, todo("a")), (2, todo("b"))]);
+    let structure_runs = count_runs(move || todos.track());
+    let first = todos.get(0).unwrap();
+    let first_runs = count_runs(move || {
+        first.done.track();
+    });
+
+    // Changing an item doesn't notify the list
+    first.done.set(true);
+    assert!(todos.set(1, todo("c")));
+    assert_eq!(structure_runs.get(), 1);
+    assert_eq!(first_runs.get(), 2);
+
+    todos.push(3, todo("d"));
+    todos.move_item(2, 0);
+    assert_eq!(structure_runs.get(), 3);
+    assert_eq!(
+        todos.with_untracked(|items| items.iter().map(|item| item.key).collect::<Vec<_>>()),
+        vec![3, 1, 2]
+    );
+
+    assert_eq!(todos.remove_key(&1).map(|todo| todo.done), Some(true));
+    assert_eq!(todos.position(&2), Some(1));
+    assert_eq!(
+        todos
+            .get_untracked()
+            .into_iter()
+            .map(|todo| todo.title)
+            .collect::<Vec<_>>(),
+        vec!["d", "c"]
+    );
+}
+
+#[test]
+fn vec_diffs() {
+    let list = create_store_vec([("a", 1u32), ("b", 2)]);
+    let version = list.version();
+
+    list.insert(1, "c", 3);
+    list.remove(0);
+    list.move_item(0, 1);
+    let handle = list.get_by_key(&"c").unwrap();
+
+    let diffs = list.diffs_since(version).unwrap();
+    assert_eq!(
+        diffs,
+        vec![
+            StoreDiff::Insert {
+                at: 1,
+                key: "c",
+                handle,
+            },
+            StoreDiff::Remove { at: 0, key: "a" },
+            StoreDiff::Move {
+                from: 0,
+                to: 1,
+                key: "c",
+                handle,
+            },
+        ]
+    );
+    assert_eq!(list.diffs_since(list.version()), Some(Vec::new()));
+
+    // Old versions are forgotten once enough changes are made
+    for i in 0..200 {
+        list.push("x", i);
+    }
+    assert_eq!(list.diffs_since(version), None);
+    list.clear();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn map_values() {
+    let map = create_store_map([("a", 1u32)]);
+    let keys_runs = count_runs(move || map.track());
+    let a = map.get(&"a").unwrap();
+    let a_runs = count_runs(move || {
+        a.track();
+    });
+
+    // Inserting an existing key sets its signal
+    map.insert("a", 2);
+    assert_eq!(a.get_untracked(), 2);
+    assert_eq!(keys_runs.get(), 1);
+    assert_eq!(a_runs.get(), 2);
+
+    map.insert("b", 3);
+    assert_eq!(keys_runs.get(), 2);
+    assert_eq!(map.remove(&"a"), Some(2));
+    assert_eq!(map.keys(), vec!["b"]);
+}
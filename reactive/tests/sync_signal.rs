@@ -0,0 +1,136 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{
+    create_effect, create_sync_signal, set_ext_trigger_handler, set_timer_handler, Trigger,
+};
+
+/// Queues the triggers sent from other threads and the timers requested on the test thread, to be
+/// notified on the test thread.
+#[derive(Clone, Default)]
+struct TriggerQueue {
+    queue: Arc<Mutex<VecDeque<Trigger>>>,
+    timers: Rc<RefCell<Vec<(Instant, Trigger)>>>,
+}
+
+impl TriggerQueue {
+    fn install() -> Self {
+        let triggers = Self::default();
+        let queue = triggers.queue.clone();
+        set_ext_trigger_handler(move |trigger| queue.lock().unwrap().push_back(trigger));
+        let timers = triggers.timers.clone();
+        set_timer_handler(move |delay, trigger| {
+            timers.borrow_mut().push((Instant::now() + delay, trigger));
+        });
+        triggers
+    }
+
+    fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    fn timers(&self) -> usize {
+        self.timers.borrow().len()
+    }
+
+    /// Notify the triggers sent so far and the timers which are due
+    fn run(&self) {
+        while let Some(trigger) = { self.queue.lock().unwrap().pop_front() } {
+            trigger.notify();
+        }
+        let now = Instant::now();
+        let due: Vec<_> = {
+            let mut timers = self.timers.borrow_mut();
+            let (due, pending) = timers.drain(..).partition(|(deadline, _)| *deadline <= now);
+            *timers = pending;
+            due
+        };
+        for (_, trigger) in due {
+            trigger.notify();
+        }
+    }
+}
+
+#[test]
+fn writes_from_threads_are_coalesced() {
+    let triggers = TriggerQueue::install();
+    let signal = create_sync_signal(0);
+    let runs = Rc::new(Cell::new(0));
+    {
+        let runs = runs.clone();
+        create_effect(move |_| {
+            signal.get();
+            runs.set(runs.get() + 1);
+        });
+    }
+
+    let writer = signal.write_handle();
+    std::thread::spawn(move || {
+        for i in 1..=100 {
+            writer.update(|value| *value += 1);
+            assert_eq!(writer.get(), i);
+        }
+    })
+    .join()
+    .unwrap();
+
+    // Nothing is applied until the trigger is handled, and it's only sent once
+    assert_eq!(signal.get_untracked(), 0);
+    assert_eq!(triggers.len(), 1);
+
+    triggers.run();
+    assert_eq!(signal.get_untracked(), 100);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn writes_on_the_owning_thread() {
+    let _triggers = TriggerQueue::install();
+    let signal = create_sync_signal(String::from("a"));
+    let reader = signal.read_handle();
+
+    signal.set(String::from("b"));
+    assert_eq!(reader.get(), "b");
+
+    // A write handle used on the owning thread applies straight away
+    signal.write_handle().set(String::from("c"));
+    assert_eq!(signal.get_untracked(), "c");
+    assert_eq!(reader.with(|value| value.len()), 1);
+}
+
+#[test]
+fn throttled_writes() {
+    let triggers = TriggerQueue::install();
+    let signal = create_sync_signal(0).throttle(Duration::from_millis(50));
+    let writer = signal.write_handle();
+
+    let write = |value| {
+        let writer = writer.clone();
+        std::thread::spawn(move || writer.set(value))
+            .join()
+            .unwrap();
+    };
+
+    write(1);
+    triggers.run();
+    assert_eq!(signal.get_untracked(), 1);
+
+    // The next update is held back by a timer on this thread until the interval has passed
+    let start = Instant::now();
+    write(2);
+    write(3);
+    triggers.run();
+    assert_eq!(signal.get_untracked(), 1);
+    assert_eq!(triggers.timers(), 1);
+    while signal.get_untracked() != 3 {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        triggers.run();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(start.elapsed() >= Duration::from_millis(40));
+}
@@ -31,6 +31,16 @@ pub trait Renderer {
 
     fn clear_clip(&mut self);
 
+    /// Start painting into a new layer. Everything painted until the matching
+    /// [`Renderer::pop_layer`] is composited as a group with `opacity`.
+    ///
+    /// Layers can be nested. Renderers which can't paint offscreen may approximate this by
+    /// fading everything painted in the layer individually.
+    fn push_layer(&mut self, opacity: f32);
+
+    /// Composite the most recently pushed layer onto the layer below it.
+    fn pop_layer(&mut self);
+
     /// Stroke a [`Shape`].
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64);
 
@@ -45,6 +55,14 @@ pub trait Renderer {
     /// (even for right-to-left text).
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>);
 
+    /// Draw a [`TextLayout`], painting all glyphs with `brush` instead of their own colors.
+    fn draw_text_with_brush<'b>(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<Point>,
+        brush: impl Into<BrushRef<'b>>,
+    );
+
     fn draw_svg<'b>(&mut self, svg: Svg<'b>, rect: Rect, brush: Option<impl Into<BrushRef<'b>>>);
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect);
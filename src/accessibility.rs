@@ -0,0 +1,332 @@
+//! # Accessibility
+//!
+//! Views describe themselves to assistive technologies with an [`Accessibility`] value, set with
+//! [`Decorators::accessibility`](crate::views::Decorators::accessibility). The built-in widgets
+//! like [`button`](crate::widgets::button) and [`checkbox`](crate::widgets::checkbox) already
+//! do this.
+//!
+//! While accessibility is active for a window, an [AccessKit](https://accesskit.dev)
+//! [`TreeUpdate`] is built after every frame from the views that have accessibility info.
+//! Views without it are left out of the tree and their children are attached to the closest
+//! ancestor which has it. Action requests from assistive technologies are turned back into the
+//! events a user would have produced, so a [`Action::Default`] on a button is delivered as a
+//! click.
+//!
+//! ```
+//! use floem::accessibility::Role;
+//! use floem::views::{label, Decorators};
+//!
+//! label(|| "Volume").accessibility(|a| a.role(Role::Label).name("Volume"));
+//! ```
+
+pub(crate) mod adapter;
+
+use std::rc::Rc;
+
+pub use accesskit::{Action, ActionData, ActionRequest, Checked, NodeId, Role, Tree, TreeUpdate};
+use accesskit::{NodeBuilder, NodeClassSet};
+
+use crate::{context::AppState, event::EventListener, id::Id, view::Widget};
+
+/// A numeric value with its range, like the position of a slider.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericValue {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: Option<f64>,
+}
+
+/// Describes a view to assistive technologies.
+#[derive(Clone)]
+pub struct Accessibility {
+    pub role: Role,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub value: Option<String>,
+    pub numeric_value: Option<NumericValue>,
+    pub checked: Option<bool>,
+    pub expanded: Option<bool>,
+    pub selected: Option<bool>,
+    pub read_only: bool,
+    pub(crate) on_set_value: Option<Rc<dyn Fn(String)>>,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            role: Role::GenericContainer,
+            name: None,
+            description: None,
+            value: None,
+            numeric_value: None,
+            checked: None,
+            expanded: None,
+            selected: None,
+            read_only: false,
+            on_set_value: None,
+        }
+    }
+}
+
+impl Accessibility {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// The label read out for the view.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// The textual value, like the contents of a text input.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// A numeric value between `min` and `max`. Views with a numeric value get the
+    /// `Increment` and `Decrement` actions, which are delivered as right and left arrow key
+    /// presses.
+    pub fn numeric_value(mut self, value: f64, min: f64, max: f64) -> Self {
+        self.numeric_value = Some(NumericValue {
+            value,
+            min,
+            max,
+            step: self.numeric_value.and_then(|v| v.step),
+        });
+        self
+    }
+
+    /// The amount the numeric value changes by for each increment or decrement.
+    pub fn step(mut self, step: f64) -> Self {
+        let value = self.numeric_value.get_or_insert(NumericValue {
+            value: 0.0,
+            min: 0.0,
+            max: 0.0,
+            step: None,
+        });
+        value.step = Some(step);
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Handles the `SetValue` action, which assistive technologies use to replace the
+    /// textual value.
+    pub fn on_set_value(mut self, on_set_value: impl Fn(String) + 'static) -> Self {
+        self.on_set_value = Some(Rc::new(on_set_value));
+        self
+    }
+}
+
+/// The tree of a window before its views have been collected, containing only the window node.
+pub(crate) fn initial_tree_update(root_id: Id, title: Option<&str>) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    let mut window = NodeBuilder::new(Role::Window);
+    if let Some(title) = title {
+        window.set_name(title);
+    }
+    TreeUpdate {
+        nodes: vec![(node_id(root_id), window.build(&mut classes))],
+        tree: Some(Tree::new(node_id(root_id))),
+        focus: node_id(root_id),
+    }
+}
+
+pub(crate) fn node_id(id: Id) -> NodeId {
+    NodeId(id.to_raw())
+}
+
+/// Builds the full accessibility tree for the view tree under `root`, which becomes the window
+/// node.
+pub(crate) fn build_tree_update(
+    root: &dyn Widget,
+    app_state: &AppState,
+    scale: f64,
+    title: Option<&str>,
+) -> TreeUpdate {
+    let mut classes = NodeClassSet::new();
+    let mut nodes = Vec::new();
+
+    let root_id = root.view_data().id();
+    let mut children = Vec::new();
+    root.for_each_child(&mut |child| {
+        collect_nodes(
+            child,
+            app_state,
+            scale,
+            &mut classes,
+            &mut nodes,
+            &mut children,
+        );
+        false
+    });
+    let mut window = NodeBuilder::new(Role::Window);
+    if let Some(title) = title {
+        window.set_name(title);
+    }
+    window.set_children(children);
+    nodes.push((node_id(root_id), window.build(&mut classes)));
+
+    let focus = app_state
+        .focus
+        .filter(|id| has_node(app_state, *id))
+        .unwrap_or(root_id);
+
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(node_id(root_id))),
+        focus: node_id(focus),
+    }
+}
+
+fn has_node(app_state: &AppState, id: Id) -> bool {
+    app_state
+        .view_states
+        .get(&id)
+        .is_some_and(|state| state.accessibility.is_some())
+        && !app_state.is_hidden_recursive(id)
+}
+
+/// Adds the node for `view` to `nodes` and its id to `parent_children`. Views without
+/// accessibility info add their children to `parent_children` instead.
+fn collect_nodes(
+    view: &dyn Widget,
+    app_state: &AppState,
+    scale: f64,
+    classes: &mut NodeClassSet,
+    nodes: &mut Vec<(NodeId, accesskit::Node)>,
+    parent_children: &mut Vec<NodeId>,
+) {
+    let id = view.view_data().id();
+    if app_state.is_hidden(id) {
+        return;
+    }
+    let Some(state) = app_state.view_states.get(&id) else {
+        return;
+    };
+
+    let Some(info) = state.accessibility.as_ref() else {
+        view.for_each_child(&mut |child| {
+            collect_nodes(child, app_state, scale, classes, nodes, parent_children);
+            false
+        });
+        return;
+    };
+
+    let mut children = Vec::new();
+    view.for_each_child(&mut |child| {
+        collect_nodes(child, app_state, scale, classes, nodes, &mut children);
+        false
+    });
+
+    let mut node = NodeBuilder::new(info.role);
+    let rect = state.layout_rect.scale_from_origin(scale);
+    node.set_bounds(accesskit::Rect {
+        x0: rect.x0,
+        y0: rect.y0,
+        x1: rect.x1,
+        y1: rect.y1,
+    });
+    if let Some(name) = &info.name {
+        node.set_name(name.as_str());
+    }
+    if let Some(description) = &info.description {
+        node.set_description(description.as_str());
+    }
+    if let Some(value) = &info.value {
+        node.set_value(value.as_str());
+    }
+    if let Some(numeric) = info.numeric_value {
+        node.set_numeric_value(numeric.value);
+        node.set_min_numeric_value(numeric.min);
+        node.set_max_numeric_value(numeric.max);
+        if let Some(step) = numeric.step {
+            node.set_numeric_value_step(step);
+        }
+    }
+    if let Some(checked) = info.checked {
+        node.set_checked(if checked {
+            Checked::True
+        } else {
+            Checked::False
+        });
+    }
+    if let Some(expanded) = info.expanded {
+        node.set_expanded(expanded);
+    }
+    if let Some(selected) = info.selected {
+        node.set_selected(selected);
+    }
+    if info.read_only {
+        node.set_read_only();
+    }
+
+    if app_state.is_disabled(&id) {
+        node.set_disabled();
+    } else {
+        if app_state.keyboard_navigable.contains(&id) {
+            node.add_action(Action::Focus);
+        }
+        if state.event_listeners.contains_key(&EventListener::Click) || info.role == Role::ComboBox
+        {
+            node.add_action(Action::Default);
+        }
+        if info.expanded.is_some() {
+            node.add_action(Action::Expand);
+            node.add_action(Action::Collapse);
+        }
+        if info.numeric_value.is_some() && !info.read_only {
+            node.add_action(Action::Increment);
+            node.add_action(Action::Decrement);
+        }
+        if info.on_set_value.is_some() && !info.read_only {
+            node.add_action(Action::SetValue);
+        }
+    }
+    node.add_action(Action::ScrollIntoView);
+    node.set_children(children);
+
+    let node_id = node_id(id);
+    nodes.push((node_id, node.build(classes)));
+    parent_children.push(node_id);
+}
+
+/// Finds the view with the accessibility node `node_id`.
+pub(crate) fn view_for_node(app_state: &AppState, node_id: NodeId) -> Option<Id> {
+    app_state
+        .view_states
+        .keys()
+        .find(|id| id.to_raw() == node_id.0)
+        .copied()
+}
@@ -0,0 +1,243 @@
+//! Connects a window to the platform accessibility API.
+//!
+//! This mirrors `accesskit_winit`, which can't be used directly as it depends on upstream winit
+//! instead of `floem-winit`.
+
+use accesskit::{ActionHandler, ActionRequest, TreeUpdate};
+use floem_winit::window::{Window, WindowId};
+
+use crate::app::{Application, UserEvent};
+
+/// Forwards action requests from assistive technologies to the event loop, as the platform
+/// adapter may call the handler on any thread.
+struct ProxyActionHandler {
+    window_id: WindowId,
+}
+
+impl ActionHandler for ProxyActionHandler {
+    fn do_action(&mut self, request: ActionRequest) {
+        let window_id = self.window_id;
+        Application::with_event_loop_proxy(|proxy| {
+            let _ = proxy.send_event(UserEvent::AccessibilityAction { window_id, request });
+        });
+    }
+}
+
+/// The platform accessibility adapter of a window.
+pub(crate) struct Adapter {
+    adapter: platform::Adapter,
+}
+
+impl Adapter {
+    /// Creates the adapter for `window`. This has to happen before the window is shown for the
+    /// first time.
+    ///
+    /// `initial` is handed out when an assistive technology first asks for the tree. The window
+    /// is then asked to repaint, which builds the full tree.
+    pub(crate) fn new(window: &Window, initial: TreeUpdate) -> Self {
+        let window_id = window.id();
+        let source = move || {
+            Application::with_event_loop_proxy(|proxy| {
+                let _ = proxy.send_event(UserEvent::AccessibilityActivated { window_id });
+            });
+            initial
+        };
+        let action_handler = Box::new(ProxyActionHandler { window_id });
+        Self {
+            adapter: platform::Adapter::new(window, source, action_handler),
+        }
+    }
+
+    /// Calls `build` and sends the new tree to the platform, but only while an assistive
+    /// technology is using the adapter.
+    pub(crate) fn update_if_active(&self, build: impl FnOnce() -> TreeUpdate) {
+        self.adapter.update_if_active(build);
+    }
+
+    pub(crate) fn focused(&self, focused: bool) {
+        self.adapter.focused(focused);
+    }
+
+    /// Tells the platform where the window is after it was moved or resized.
+    pub(crate) fn window_bounds_changed(&self, window: &Window) {
+        self.adapter.window_bounds_changed(window);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use accesskit::{ActionHandler, TreeUpdate};
+    use accesskit_windows::{SubclassingAdapter, HWND};
+    use floem_winit::window::Window;
+    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+
+    pub(super) struct Adapter {
+        adapter: SubclassingAdapter,
+    }
+
+    impl Adapter {
+        pub(super) fn new(
+            window: &Window,
+            source: impl 'static + FnOnce() -> TreeUpdate,
+            action_handler: Box<dyn ActionHandler + Send>,
+        ) -> Self {
+            let hwnd = match window.raw_window_handle() {
+                RawWindowHandle::Win32(handle) => handle.hwnd as isize,
+                _ => unreachable!(),
+            };
+            Self {
+                adapter: SubclassingAdapter::new(HWND(hwnd), source, action_handler),
+            }
+        }
+
+        pub(super) fn update_if_active(&self, build: impl FnOnce() -> TreeUpdate) {
+            if let Some(events) = self.adapter.update_if_active(build) {
+                events.raise();
+            }
+        }
+
+        pub(super) fn focused(&self, _focused: bool) {}
+
+        pub(super) fn window_bounds_changed(&self, _window: &Window) {}
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use accesskit::{ActionHandler, TreeUpdate};
+    use accesskit_macos::SubclassingAdapter;
+    use floem_winit::window::Window;
+    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+
+    pub(super) struct Adapter {
+        adapter: SubclassingAdapter,
+    }
+
+    impl Adapter {
+        pub(super) fn new(
+            window: &Window,
+            source: impl 'static + FnOnce() -> TreeUpdate,
+            action_handler: Box<dyn ActionHandler + Send>,
+        ) -> Self {
+            let view = match window.raw_window_handle() {
+                RawWindowHandle::AppKit(handle) => handle.ns_view,
+                _ => unreachable!(),
+            };
+            // SAFETY: The view belongs to `window`, which is owned by the same `WindowHandle` as
+            // the adapter.
+            let adapter = unsafe { SubclassingAdapter::new(view, source, action_handler) };
+            Self { adapter }
+        }
+
+        pub(super) fn update_if_active(&self, build: impl FnOnce() -> TreeUpdate) {
+            if let Some(events) = self.adapter.update_if_active(build) {
+                events.raise();
+            }
+        }
+
+        pub(super) fn focused(&self, focused: bool) {
+            if let Some(events) = self.adapter.update_view_focus_state(focused) {
+                events.raise();
+            }
+        }
+
+        pub(super) fn window_bounds_changed(&self, _window: &Window) {}
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod platform {
+    use accesskit::{ActionHandler, Rect, TreeUpdate};
+    use floem_winit::window::Window;
+
+    pub(super) struct Adapter {
+        /// `None` if there's no accessibility bus to talk to.
+        adapter: Option<accesskit_unix::Adapter>,
+    }
+
+    impl Adapter {
+        pub(super) fn new(
+            _window: &Window,
+            source: impl 'static + FnOnce() -> TreeUpdate,
+            action_handler: Box<dyn ActionHandler + Send>,
+        ) -> Self {
+            Self {
+                adapter: accesskit_unix::Adapter::new(source, false, action_handler),
+            }
+        }
+
+        /// The Unix adapter can't tell whether an assistive technology is listening, so the tree
+        /// is built whenever the accessibility bus is available.
+        pub(super) fn update_if_active(&self, build: impl FnOnce() -> TreeUpdate) {
+            if let Some(adapter) = &self.adapter {
+                adapter.update(build());
+            }
+        }
+
+        pub(super) fn focused(&self, focused: bool) {
+            if let Some(adapter) = &self.adapter {
+                adapter.update_window_focus_state(focused);
+            }
+        }
+
+        pub(super) fn window_bounds_changed(&self, window: &Window) {
+            let Some(adapter) = &self.adapter else {
+                return;
+            };
+            let outer_position: (f64, f64) = window
+                .outer_position()
+                .unwrap_or_default()
+                .cast::<f64>()
+                .into();
+            let outer_size: (f64, f64) = window.outer_size().cast::<f64>().into();
+            let inner_position: (f64, f64) = window
+                .inner_position()
+                .unwrap_or_default()
+                .cast::<f64>()
+                .into();
+            let inner_size: (f64, f64) = window.inner_size().cast::<f64>().into();
+            adapter.set_root_window_bounds(
+                Rect::from_origin_size(outer_position, outer_size),
+                Rect::from_origin_size(inner_position, inner_size),
+            );
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+mod platform {
+    use accesskit::{ActionHandler, TreeUpdate};
+    use floem_winit::window::Window;
+
+    pub(super) struct Adapter;
+
+    impl Adapter {
+        pub(super) fn new(
+            _window: &Window,
+            _source: impl 'static + FnOnce() -> TreeUpdate,
+            _action_handler: Box<dyn ActionHandler + Send>,
+        ) -> Self {
+            Self
+        }
+
+        pub(super) fn update_if_active(&self, _build: impl FnOnce() -> TreeUpdate) {}
+
+        pub(super) fn focused(&self, _focused: bool) {}
+
+        pub(super) fn window_bounds_changed(&self, _window: &Window) {}
+    }
+}
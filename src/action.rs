@@ -10,6 +10,7 @@ use kurbo::{Point, Size, Vec2};
 
 use crate::{
     app::{add_app_update_event, AppUpdateEvent},
+    clock,
     ext_event::create_ext_action,
     file::{FileDialogOptions, FileInfo},
     id::Id,
@@ -94,7 +95,7 @@ pub fn exec_after(duration: Duration, action: impl FnOnce(TimerToken) + 'static)
     };
 
     let token = TimerToken::next();
-    let deadline = Instant::now() + duration;
+    let deadline = clock::now() + duration;
     add_app_update_event(AppUpdateEvent::RequestTimer {
         timer: Timer {
             token,
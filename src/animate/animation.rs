@@ -1,10 +1,17 @@
-use crate::style::{Background, BorderColor, BorderRadius, TextColor};
+use crate::{
+    clock,
+    style::{
+        Background, BorderColor, BorderRadius, Height, Opacity, Rotate, ScaleX, ScaleY, Style,
+        StyleKey, StyleKeyInfo, StyleProp, TextColor, TranslateX, TranslateY, Width,
+    },
+    unit::PxPct,
+};
 
 use super::{
-    anim_val::AnimValue, AnimId, AnimPropKind, AnimState, AnimStateKind, AnimatedProp, Easing,
-    EasingFn, EasingMode,
+    anim_val::AnimValue, keyframe::apply_keyframes, AnimId, AnimPropKind, AnimState, AnimStateKind,
+    AnimatedProp, Easing, EasingFn, EasingMode, KeyFrame, Spring,
 };
-use std::{borrow::BorrowMut, collections::HashMap, rc::Rc, time::Duration, time::Instant};
+use std::{collections::HashMap, rc::Rc, time::Duration};
 
 use floem_peniko::Color;
 use floem_reactive::create_effect;
@@ -21,6 +28,10 @@ pub struct Animation {
     /// How many times the animation has been repeated so far
     pub(crate) repeat_count: usize,
     pub(crate) animated_props: HashMap<AnimPropKind, AnimatedProp>,
+    pub(crate) keyframes: Vec<KeyFrame>,
+    pub(crate) spring: Option<Spring>,
+    /// The velocity each prop started with, as a fraction of the distance it's moving per second
+    pub(crate) velocities: HashMap<AnimPropKind, f64>,
     pub(crate) on_create_listener: Option<Rc<dyn Fn(AnimId) + 'static>>,
 }
 
@@ -53,6 +64,9 @@ pub fn animation() -> Animation {
         repeat_mode: RepeatMode::Times(1),
         repeat_count: 0,
         animated_props: HashMap::new(),
+        keyframes: Vec::new(),
+        spring: None,
+        velocities: HashMap::new(),
         on_create_listener: None,
     }
 }
@@ -68,6 +82,8 @@ pub enum AnimUpdateMsg {
     Resume(AnimId),
     Start(AnimId),
     Stop(AnimId),
+    /// The [`Timeline`](super::Timeline) driving the animation has moved
+    Seek(AnimId),
 }
 
 #[derive(Clone, Debug)]
@@ -127,9 +143,57 @@ impl Animation {
         self
     }
 
-    // pub fn scale(self, scale: f64) -> Self {
-    //     todo!()
-    // }
+    pub fn scale(self, scale_fn: impl Fn() -> f64 + 'static) -> Self {
+        create_effect(move |_| {
+            let scale = scale_fn();
+
+            self.id.update_style_prop(ScaleX, scale);
+            self.id.update_style_prop(ScaleY, scale);
+        });
+
+        self
+    }
+
+    pub fn opacity(self, opacity_fn: impl Fn() -> f64 + 'static) -> Self {
+        create_effect(move |_| {
+            let opacity = opacity_fn();
+
+            self.id.update_style_prop(Opacity, opacity);
+        });
+
+        self
+    }
+
+    pub fn translate_x(self, translate_fn: impl Fn() -> f64 + 'static) -> Self {
+        create_effect(move |_| {
+            let translate = translate_fn();
+
+            self.id.update_style_prop(TranslateX, PxPct::Px(translate));
+        });
+
+        self
+    }
+
+    pub fn translate_y(self, translate_fn: impl Fn() -> f64 + 'static) -> Self {
+        create_effect(move |_| {
+            let translate = translate_fn();
+
+            self.id.update_style_prop(TranslateY, PxPct::Px(translate));
+        });
+
+        self
+    }
+
+    /// Animates the rotation, in degrees.
+    pub fn rotate(self, rotate_fn: impl Fn() -> f64 + 'static) -> Self {
+        create_effect(move |_| {
+            let rotate = rotate_fn();
+
+            self.id.update_style_prop(Rotate, rotate);
+        });
+
+        self
+    }
 
     pub fn border_radius(self, border_radius_fn: impl Fn() -> f64 + 'static) -> Self {
         create_effect(move |_| {
@@ -146,7 +210,7 @@ impl Animation {
         create_effect(move |_| {
             let color = color_fn();
 
-            self.id.update_style_prop(TextColor, Some(color));
+            self.id.update_style_prop(TextColor, Some(color.into()));
         });
 
         self
@@ -156,7 +220,7 @@ impl Animation {
         create_effect(move |_| {
             let border_color = bord_color_fn();
 
-            self.id.update_style_prop(BorderColor, border_color);
+            self.id.update_style_prop(BorderColor, border_color.into());
         });
 
         self
@@ -166,12 +230,45 @@ impl Animation {
         create_effect(move |_| {
             let background = bg_fn();
 
-            self.id.update_style_prop(Background, Some(background));
+            self.id
+                .update_style_prop(Background, Some(background.into()));
+        });
+
+        self
+    }
+
+    /// Animates any style property to the value returned by `value_fn`, when the type of the
+    /// property can be interpolated
+    pub fn prop<P: StyleProp>(self, prop: P, value_fn: impl Fn() -> P::Type + 'static) -> Self {
+        create_effect(move |_| {
+            let value = value_fn();
+
+            self.id.update_style_prop(prop, value);
         });
 
         self
     }
 
+    /// Add a keyframe. The keyframes are played over the duration of the animation, and they can
+    /// set any style property.
+    pub fn keyframe(mut self, keyframe: KeyFrame) -> Self {
+        let index = self
+            .keyframes
+            .partition_point(|frame| frame.offset <= keyframe.offset);
+        self.keyframes.insert(index, keyframe);
+        self
+    }
+
+    /// Move the animated properties with a `spring` instead of an easing over the duration. The
+    /// animation lasts until the spring settles, and when a property is given a new value while
+    /// it's moving, it keeps its velocity.
+    ///
+    /// This doesn't apply to keyframes, and it can't be auto reversed.
+    pub fn spring(mut self, spring: Spring) -> Self {
+        self.spring = Some(spring);
+        self
+    }
+
     pub fn width(self, width_fn: impl Fn() -> f64 + 'static) -> Self {
         create_effect(move |_| {
             let to_width = width_fn();
@@ -254,7 +351,7 @@ impl Animation {
         );
         if let AnimState::Paused { elapsed } = &self.state {
             self.state = AnimState::PassInProgress {
-                started_on: Instant::now(),
+                started_on: clock::now(),
                 elapsed: elapsed.unwrap_or(Duration::ZERO),
             }
         }
@@ -263,7 +360,7 @@ impl Animation {
     pub fn start(&mut self) {
         self.repeat_count = 0;
         self.state = AnimState::PassInProgress {
-            started_on: Instant::now(),
+            started_on: clock::now(),
             elapsed: Duration::ZERO,
         }
     }
@@ -292,7 +389,7 @@ impl Animation {
                 started_on,
                 elapsed,
             } => {
-                let duration = Instant::now() - *started_on;
+                let duration = clock::now() - *started_on;
                 Some(*elapsed + duration)
             }
             AnimState::PassFinished { elapsed } => Some(*elapsed),
@@ -310,7 +407,7 @@ impl Animation {
                 started_on,
                 mut elapsed,
             } => {
-                let now = Instant::now();
+                let now = clock::now();
                 let duration = now - *started_on;
                 elapsed += duration;
 
@@ -321,7 +418,7 @@ impl Animation {
             AnimState::PassFinished { elapsed } => match self.repeat_mode {
                 RepeatMode::LoopForever => {
                     self.state = AnimState::PassInProgress {
-                        started_on: Instant::now(),
+                        started_on: clock::now(),
                         elapsed: Duration::ZERO,
                     }
                 }
@@ -333,7 +430,7 @@ impl Animation {
                         }
                     } else {
                         self.state = AnimState::PassInProgress {
-                            started_on: Instant::now(),
+                            started_on: clock::now(),
                             elapsed: Duration::ZERO,
                         }
                     }
@@ -353,10 +450,6 @@ impl Animation {
         &self.animated_props
     }
 
-    pub(crate) fn props_mut(&mut self) -> &mut HashMap<AnimPropKind, AnimatedProp> {
-        self.animated_props.borrow_mut()
-    }
-
     pub(crate) fn animate_prop(&self, elapsed: Duration, prop_kind: &AnimPropKind) -> AnimValue {
         let mut elapsed = elapsed;
         let prop = self.animated_props.get(prop_kind).unwrap();
@@ -373,6 +466,16 @@ impl Animation {
             elapsed = self.duration;
         }
 
+        if let Some(spring) = self.spring {
+            let time = if elapsed >= self.duration {
+                1.0
+            } else {
+                let velocity = self.velocities.get(prop_kind).copied().unwrap_or(0.0);
+                spring.position(elapsed, velocity)
+            };
+            return prop.animate(time, AnimDirection::Forward);
+        }
+
         let time = elapsed.as_secs_f64() / self.duration.as_secs_f64();
         let time = self.easing.ease(time);
         assert_valid_time(time);
@@ -387,4 +490,102 @@ impl Animation {
             prop.animate(time, AnimDirection::Forward)
         }
     }
+
+    /// The style properties set by the animation
+    pub(crate) fn style_keys(&self) -> Vec<StyleKey> {
+        let mut keys: Vec<StyleKey> = self
+            .animated_props
+            .keys()
+            .filter_map(|kind| match kind {
+                AnimPropKind::Width => Some(Width::key()),
+                AnimPropKind::Height => Some(Height::key()),
+                AnimPropKind::Prop { prop } => Some(prop.key),
+                AnimPropKind::Scale => None,
+            })
+            .collect();
+        for frame in &self.keyframes {
+            keys.extend(
+                frame
+                    .style
+                    .map
+                    .keys()
+                    .filter(|key| matches!(key.info, StyleKeyInfo::Prop(_))),
+            );
+        }
+        keys
+    }
+
+    /// Apply the keyframes at `elapsed` to `style`
+    pub(crate) fn animate_keyframes(&self, elapsed: Duration, style: &mut Style) {
+        if self.keyframes.is_empty() {
+            return;
+        }
+        let elapsed = elapsed + self.skip.unwrap_or_default();
+        let time = if self.duration == Duration::ZERO {
+            1.0
+        } else {
+            (elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+        };
+        let time = if self.auto_reverse {
+            if time > 0.5 {
+                2.0 - time * 2.0
+            } else {
+                time * 2.0
+            }
+        } else {
+            time
+        };
+        apply_keyframes(&self.keyframes, &self.easing, time, style);
+    }
+
+    /// Animate `kind` to the new value in `prop` and restart the animation.
+    ///
+    /// With a spring, the other properties continue from where they are and every property keeps
+    /// its current velocity.
+    pub(crate) fn retarget(&mut self, kind: AnimPropKind, prop: AnimatedProp) {
+        let Some(spring) = self.spring else {
+            self.animated_props.insert(kind, prop);
+            self.start();
+            return;
+        };
+
+        // The speed of each moving property, in units of the distance per second when the
+        // distance is known, or as a fraction of its whole movement otherwise
+        let mut speeds = HashMap::new();
+        if let Some(elapsed) = self.elapsed().filter(|_| self.is_in_progress()) {
+            let elapsed = elapsed.min(self.duration);
+            let kinds: Vec<AnimPropKind> = self.animated_props.keys().cloned().collect();
+            for other in kinds {
+                let velocity = self.velocities.get(&other).copied().unwrap_or(0.0);
+                let velocity = spring.velocity(elapsed, velocity);
+                let current = self.animate_prop(elapsed, &other);
+                let moving = self.animated_props.get_mut(&other).unwrap();
+                let speed = match moving.distance() {
+                    Some(distance) => (velocity * distance, true),
+                    None => (velocity, false),
+                };
+                speeds.insert(other, speed);
+                moving.set_from(current);
+            }
+        }
+        self.animated_props.insert(kind, prop);
+
+        self.velocities.clear();
+        let mut duration = Duration::ZERO;
+        for (kind, prop) in &self.animated_props {
+            let velocity = match speeds.get(kind) {
+                Some((speed, true)) => prop
+                    .distance()
+                    .filter(|distance| *distance > f64::EPSILON)
+                    .map(|distance| speed / distance)
+                    .unwrap_or(0.0),
+                Some((velocity, false)) => *velocity,
+                None => 0.0,
+            };
+            duration = duration.max(spring.settle_duration(velocity));
+            self.velocities.insert(kind.clone(), velocity);
+        }
+        self.duration = duration;
+        self.start();
+    }
 }
@@ -34,14 +34,29 @@ pub enum EasingFn {
     Quintic,
     /// Creates an animation that accelerates and/or decelerates using a sine formula.
     Sine,
-    //TODO:
-    // /// Retracts the motion of an animation slightly before it begins to animate in the path indicated.
-    // Back,
-    // /// Creates a bouncing effect.
-    // Bounce,
-    // /// Creates an animation that accelerates and/or
-    // /// decelerates using the formula `f(t) = tp` where p is equal to the Power property.
-    // Power,
+    /// Retracts the motion of an animation slightly before it begins to animate in the path indicated.
+    Back,
+    /// Creates a bouncing effect.
+    Bounce,
+    /// Creates an animation that accelerates and/or
+    /// decelerates using the formula `f(t) = tp` where p is equal to the Power property.
+    Power(f64),
+    /// A cubic Bézier curve from `(0, 0)` to `(1, 1)` with the control points `(x1, y1)` and
+    /// `(x2, y2)`, like the CSS `cubic-bezier()` function.
+    CubicBezier { x1: f64, y1: f64, x2: f64, y2: f64 },
+    /// Jumps between `count` equal steps instead of moving smoothly, like the CSS `steps()`
+    /// function.
+    Steps { count: u32, position: StepPosition },
+}
+
+/// When the jumps of [`EasingFn::Steps`] happen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StepPosition {
+    /// The first jump happens at the start of the animation
+    Start,
+    /// The last jump happens at the end of the animation
+    #[default]
+    End,
 }
 
 // See https://easings.net/ and
@@ -63,7 +78,107 @@ fn elastic_easing(time: f64) -> f64 {
     }
 }
 
+fn back_easing(time: f64) -> f64 {
+    const C1: f64 = 1.70158;
+    const C3: f64 = C1 + 1.0;
+    C3 * time.powi(3) - C1 * time.powi(2)
+}
+
+fn bounce_easing(time: f64) -> f64 {
+    // The bounce hits the ground at the end, so it's defined as the `Out` curve and flipped
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+    let time = 1.0 - time;
+    let out = if time < 1.0 / D1 {
+        N1 * time * time
+    } else if time < 2.0 / D1 {
+        let time = time - 1.5 / D1;
+        N1 * time * time + 0.75
+    } else if time < 2.5 / D1 {
+        let time = time - 2.25 / D1;
+        N1 * time * time + 0.9375
+    } else {
+        let time = time - 2.625 / D1;
+        N1 * time * time + 0.984375
+    };
+    1.0 - out
+}
+
+fn cubic_bezier_easing(time: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    let bezier = |t: f64, p1: f64, p2: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    };
+    let derivative = |t: f64, p1: f64, p2: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * p1 + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2)
+    };
+
+    // Find the `t` where the curve is at `x = time` with Newton's method, falling back to
+    // bisection where the slope is too flat
+    let mut t = time;
+    for _ in 0..8 {
+        let error = bezier(t, x1, x2) - time;
+        if error.abs() < 1e-7 {
+            return bezier(t, y1, y2);
+        }
+        let slope = derivative(t, x1, x2);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        t -= error / slope;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    t = time;
+    for _ in 0..32 {
+        let x = bezier(t, x1, x2);
+        if (x - time).abs() < 1e-7 {
+            break;
+        }
+        if x < time {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    bezier(t, y1, y2)
+}
+
+fn steps_easing(time: f64, count: u32, position: StepPosition) -> f64 {
+    let count = count.max(1) as f64;
+    let step = match position {
+        StepPosition::Start => (time * count).ceil(),
+        StepPosition::End => (time * count).floor(),
+    };
+    (step / count).clamp(0.0, 1.0)
+}
+
+impl From<EasingFn> for Easing {
+    fn from(func: EasingFn) -> Self {
+        Easing {
+            mode: EasingMode::In,
+            func,
+        }
+    }
+}
+
 impl Easing {
+    pub fn new(func: EasingFn, mode: EasingMode) -> Self {
+        Easing { mode, func }
+    }
+
+    /// A cubic Bézier easing, like the CSS `cubic-bezier()` function
+    pub fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        EasingFn::CubicBezier { x1, y1, x2, y2 }.into()
+    }
+
+    /// An easing that jumps between `count` steps, like the CSS `steps()` function
+    pub fn steps(count: u32, position: StepPosition) -> Self {
+        EasingFn::Steps { count, position }.into()
+    }
+
     pub(crate) fn apply_easing_fn(&self, time: f64) -> f64 {
         assert_valid_time(time);
         match self.func {
@@ -82,9 +197,11 @@ impl Easing {
             EasingFn::Quartic => time.powf(4.0),
             EasingFn::Quintic => time.powf(5.0),
             EasingFn::Sine => 1.0 - ((time * PI) / 2.0).cos(),
-            // EasingFn::Power => todo!(),
-            // EasingFn::Back => todo!(),
-            // EasingFn::Bounce => todo!(),
+            EasingFn::Back => back_easing(time),
+            EasingFn::Bounce => bounce_easing(time),
+            EasingFn::Power(power) => time.powf(power),
+            EasingFn::CubicBezier { x1, y1, x2, y2 } => cubic_bezier_easing(time, x1, y1, x2, y2),
+            EasingFn::Steps { count, position } => steps_easing(time, count, position),
         }
     }
 
@@ -103,3 +220,50 @@ impl Easing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_and_end_at_the_ends() {
+        for func in [
+            EasingFn::Back,
+            EasingFn::Bounce,
+            EasingFn::Power(2.5),
+            EasingFn::CubicBezier {
+                x1: 0.25,
+                y1: 0.1,
+                x2: 0.25,
+                y2: 1.0,
+            },
+        ] {
+            for mode in [EasingMode::In, EasingMode::Out, EasingMode::InOut] {
+                let easing = Easing::new(func, mode);
+                assert!(easing.ease(0.0).abs() < 1e-6, "{easing:?}");
+                assert!((easing.ease(1.0) - 1.0).abs() < 1e-6, "{easing:?}");
+            }
+        }
+        // Back moves backwards first
+        assert!(Easing::from(EasingFn::Back).ease(0.2) < 0.0);
+    }
+
+    #[test]
+    fn cubic_bezier() {
+        // CSS `ease`
+        let ease = Easing::cubic_bezier(0.25, 0.1, 0.25, 1.0);
+        assert!((ease.ease(0.5) - 0.8024).abs() < 0.001);
+        let linear = Easing::cubic_bezier(0.0, 0.0, 1.0, 1.0);
+        assert!((linear.ease(0.3) - 0.3).abs() < 1e-4);
+    }
+
+    #[test]
+    fn steps() {
+        let end = Easing::steps(4, StepPosition::End);
+        assert_eq!(end.ease(0.0), 0.0);
+        assert_eq!(end.ease(0.3), 0.25);
+        assert_eq!(end.ease(1.0), 1.0);
+        let start = Easing::steps(4, StepPosition::Start);
+        assert_eq!(start.ease(0.3), 0.5);
+    }
+}
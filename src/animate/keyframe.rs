@@ -0,0 +1,127 @@
+use std::{any::Any, rc::Rc};
+
+use crate::style::{Style, StyleKey, StyleKeyInfo};
+
+use super::Easing;
+
+/// The style of a view at a point in an [`Animation`](super::Animation), added with
+/// [`Animation::keyframe`](super::Animation::keyframe).
+///
+/// Any style property with a value that can be interpolated is animated between the keyframes
+/// which set it. Properties missing from the first or last keyframe animate from or to the
+/// view's own style.
+///
+/// ## Example
+/// ```
+/// use floem::animate::{animation, EasingFn, KeyFrame};
+/// use floem::peniko::Color;
+///
+/// let pulse = animation()
+///     .keyframe(KeyFrame::new(0.3).style(|s| s.width(120.0).background(Color::RED)))
+///     // The easing is used from this keyframe to the next
+///     .keyframe(
+///         KeyFrame::new(0.6)
+///             .style(|s| s.width(80.0))
+///             .easing(EasingFn::Bounce),
+///     )
+///     .keyframe(KeyFrame::new(1.0).style(|s| s.width(100.0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyFrame {
+    pub(crate) offset: f64,
+    pub(crate) style: Style,
+    pub(crate) easing: Option<Easing>,
+}
+
+impl KeyFrame {
+    /// A keyframe at `offset` through the animation, from 0.0 at the start to 1.0 at the end
+    pub fn new(offset: f64) -> Self {
+        Self {
+            offset: offset.clamp(0.0, 1.0),
+            style: Style::new(),
+            easing: None,
+        }
+    }
+
+    pub fn style(mut self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.style = style(self.style);
+        self
+    }
+
+    /// The easing from this keyframe to the next one. The easing of the animation is used if
+    /// this isn't set.
+    pub fn easing(mut self, easing: impl Into<Easing>) -> Self {
+        self.easing = Some(easing.into());
+        self
+    }
+}
+
+/// Apply the values of the keyframes at `time` through the animation to `style`
+pub(crate) fn apply_keyframes(
+    keyframes: &[KeyFrame],
+    default_easing: &Easing,
+    time: f64,
+    style: &mut Style,
+) {
+    let mut keys: Vec<StyleKey> = Vec::new();
+    for frame in keyframes {
+        for key in frame.style.map.keys() {
+            if matches!(key.info, StyleKeyInfo::Prop(_)) && !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+    }
+
+    for key in keys {
+        let StyleKeyInfo::Prop(info) = key.info else {
+            continue;
+        };
+        let base = style
+            .map
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| (info.default_as_any)());
+
+        // The keyframes setting this property, starting and ending with the base value if the
+        // first and last keyframes don't set it
+        let mut frames: Vec<(f64, Rc<dyn Any>, &Easing)> = keyframes
+            .iter()
+            .filter_map(|frame| {
+                let value = frame.style.map.get(&key)?.clone();
+                let easing = frame.easing.as_ref().unwrap_or(default_easing);
+                Some((frame.offset, value, easing))
+            })
+            .collect();
+        if frames.first().map(|frame| frame.0 > 0.0).unwrap_or(true) {
+            frames.insert(0, (0.0, base.clone(), default_easing));
+        }
+        if frames.last().map(|frame| frame.0 < 1.0).unwrap_or(true) {
+            frames.push((1.0, base, default_easing));
+        }
+
+        let next = frames
+            .iter()
+            .position(|frame| frame.0 > time)
+            .unwrap_or(frames.len() - 1)
+            .max(1);
+        let (from_offset, from, easing) = &frames[next - 1];
+        let (to_offset, to, _) = &frames[next];
+
+        let span = to_offset - from_offset;
+        let local = if span > 0.0 {
+            ((time - from_offset) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let local = easing.ease(local);
+        let value = (info.interpolate)(&**from, &**to, local).unwrap_or_else(|| {
+            // Values which can't be interpolated switch halfway through
+            if local < 0.5 {
+                from.clone()
+            } else {
+                to.clone()
+            }
+        });
+        style.map.insert(key, value);
+    }
+}
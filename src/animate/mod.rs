@@ -13,5 +13,15 @@ pub use anim_val::*;
 mod easing;
 pub use easing::*;
 
+mod keyframe;
+pub use keyframe::*;
+
 mod prop;
 pub use prop::*;
+
+mod spring;
+pub use spring::*;
+
+mod timeline;
+pub(crate) use timeline::driven_elapsed;
+pub use timeline::{delay, parallel, sequence, stagger, Timeline, TimelineItem};
@@ -1,12 +1,6 @@
 use std::{any::Any, rc::Rc};
 
-use floem_peniko::Color;
-
-use crate::{
-    animate::AnimDirection,
-    style::{StyleMapValue, StylePropRef},
-    unit::Px,
-};
+use crate::{animate::AnimDirection, style::StylePropRef};
 
 use super::{anim_val::AnimValue, assert_valid_time, SizeUnit};
 
@@ -47,6 +41,28 @@ impl AnimatedProp {
         }
     }
 
+    /// Start from `value` instead, which is used to continue from the current value when the
+    /// animation is restarted
+    pub(crate) fn set_from(&mut self, value: AnimValue) {
+        match self {
+            AnimatedProp::Prop { from, .. } => *from = value.get_any(),
+            AnimatedProp::Width { from, .. }
+            | AnimatedProp::Height { from, .. }
+            | AnimatedProp::Scale { from, .. } => *from = value.get_f64(),
+        }
+    }
+
+    /// How far apart the start and end values are, if that can be measured for the type of the
+    /// values
+    pub(crate) fn distance(&self) -> Option<f64> {
+        match self {
+            AnimatedProp::Prop { prop, from, to } => (prop.info().distance)(&**from, &**to),
+            AnimatedProp::Width { from, to, .. }
+            | AnimatedProp::Height { from, to, .. }
+            | AnimatedProp::Scale { from, to } => Some((to - from).abs()),
+        }
+    }
+
     pub(crate) fn animate_float(
         &self,
         from: f64,
@@ -72,93 +88,22 @@ impl AnimatedProp {
         from * (1.0 - time) + to * time
     }
 
-    pub(crate) fn animate_usize(
-        &self,
-        from: u8,
-        to: u8,
-        time: f64,
-        direction: AnimDirection,
-    ) -> u8 {
-        assert_valid_time(time);
-        let (from, to) = match direction {
-            AnimDirection::Forward => (from, to),
-            AnimDirection::Backward => (to, from),
-        };
-
-        if time == 0.0 {
-            return from;
-        }
-        if (1.0 - time).abs() < f64::EPSILON {
-            return to;
-        }
-        if from == to {
-            return from;
-        }
-
-        let from = from as f64;
-        let to = to as f64;
-
-        let val = from * (1.0 - time) + to * time;
-        if to >= from {
-            (val + 0.5) as u8
-        } else {
-            (val - 0.5) as u8
-        }
-    }
-
-    pub(crate) fn animate_color(
-        &self,
-        from: Color,
-        to: Color,
-        time: f64,
-        direction: AnimDirection,
-    ) -> Color {
-        let r = self.animate_usize(from.r, to.r, time, direction);
-        let g = self.animate_usize(from.g, to.g, time, direction);
-        let b = self.animate_usize(from.b, to.b, time, direction);
-        let a = self.animate_usize(from.a, to.a, time, direction);
-        Color { r, g, b, a }
-    }
-
     pub(crate) fn animate(&self, time: f64, direction: AnimDirection) -> AnimValue {
         match self {
             AnimatedProp::Prop { prop, from, to } => {
-                if let Some(from) = from.downcast_ref::<StyleMapValue<Px>>() {
-                    let from = from.as_ref().unwrap();
-                    let to = to.downcast_ref::<StyleMapValue<Px>>().unwrap();
-                    let to = to.as_ref().unwrap();
-                    return AnimValue::Prop(Rc::new(StyleMapValue::Val(Px(
-                        self.animate_float(from.0, to.0, time, direction)
-                    ))));
-                }
-                if let Some(from) = from.downcast_ref::<StyleMapValue<f64>>() {
-                    let from = from.as_ref().unwrap();
-                    let to = to.downcast_ref::<StyleMapValue<f64>>().unwrap();
-                    let to = to.as_ref().unwrap();
-                    return AnimValue::Prop(Rc::new(StyleMapValue::Val(
-                        self.animate_float(*from, *to, time, direction),
-                    )));
-                }
-                if let Some(from) = from.downcast_ref::<StyleMapValue<Color>>() {
-                    let from = from.as_ref().unwrap();
-                    let to = to.downcast_ref::<StyleMapValue<Color>>().unwrap();
-                    let to = to.as_ref().unwrap();
-                    return AnimValue::Prop(Rc::new(StyleMapValue::Val(
-                        self.animate_color(*from, *to, time, direction),
-                    )));
-                }
-                if let Some(from) = from.downcast_ref::<StyleMapValue<Option<Color>>>() {
-                    let from = from.as_ref().unwrap();
-                    let to = to.downcast_ref::<StyleMapValue<Option<Color>>>().unwrap();
-                    let to = to.as_ref().unwrap();
-                    //TODO: get rid of this requirement
-                    let from = from.expect("Color must be set in the styles to be animated");
-                    let to = to.unwrap();
-                    return AnimValue::Prop(Rc::new(StyleMapValue::Val(Some(
-                        self.animate_color(from, to, time, direction),
-                    ))));
-                }
-                panic!("unknown type for {prop:?}")
+                let (from, to) = match direction {
+                    AnimDirection::Forward => (from, to),
+                    AnimDirection::Backward => (to, from),
+                };
+                let value = (prop.info().interpolate)(&**from, &**to, time).unwrap_or_else(|| {
+                    // Values which can't be interpolated switch halfway through
+                    if time < 0.5 {
+                        from.clone()
+                    } else {
+                        to.clone()
+                    }
+                });
+                AnimValue::Prop(value)
             }
             AnimatedProp::Width { from, to, unit: _ }
             | AnimatedProp::Height { from, to, unit: _ } => {
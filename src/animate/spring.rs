@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+/// A damped spring, which animates a value by pulling it towards its target instead of following
+/// a curve over a fixed duration.
+///
+/// Because a spring keeps its velocity when its target changes, an animation using one can be
+/// retargeted mid-flight without a sudden change in speed. See
+/// [`Animation::spring`](super::Animation::spring).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub mass: f64,
+    pub stiffness: f64,
+    pub damping: f64,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self::new(1.0, 170.0, 26.0)
+    }
+}
+
+/// The spring is at rest once it's this close to the target, as a fraction of the distance it
+/// started from
+const REST_DISTANCE: f64 = 0.001;
+const REST_VELOCITY: f64 = 0.001;
+/// Springs that would take longer than this to settle are stopped at their target
+const MAX_DURATION: Duration = Duration::from_secs(10);
+
+impl Spring {
+    pub fn new(mass: f64, stiffness: f64, damping: f64) -> Self {
+        Self {
+            mass,
+            stiffness,
+            damping,
+        }
+    }
+
+    /// A slow spring without overshoot
+    pub fn gentle() -> Self {
+        Self::new(1.0, 120.0, 14.0)
+    }
+
+    /// A spring which overshoots and settles back
+    pub fn wobbly() -> Self {
+        Self::new(1.0, 180.0, 12.0)
+    }
+
+    /// A fast spring with little overshoot
+    pub fn stiff() -> Self {
+        Self::new(1.0, 210.0, 20.0)
+    }
+
+    fn angular_frequency(&self) -> f64 {
+        (self.stiffness / self.mass.max(f64::EPSILON)).sqrt()
+    }
+
+    fn damping_ratio(&self) -> f64 {
+        self.damping / (2.0 * (self.stiffness * self.mass).sqrt().max(f64::EPSILON))
+    }
+
+    /// How far the spring has moved from its start (0.0) towards its target (1.0) after `time`,
+    /// given its starting `velocity` in the same units per second. This overshoots past 1.0 when
+    /// the spring is underdamped.
+    pub fn position(&self, time: Duration, velocity: f64) -> f64 {
+        let t = time.as_secs_f64();
+        let w0 = self.angular_frequency();
+        let zeta = self.damping_ratio();
+
+        // The displacement from the target starts at -1
+        let displacement = if zeta < 1.0 {
+            let wd = w0 * (1.0 - zeta * zeta).sqrt();
+            let b = (velocity - zeta * w0) / wd;
+            (-zeta * w0 * t).exp() * (-(wd * t).cos() + b * (wd * t).sin())
+        } else if zeta == 1.0 {
+            (-w0 * t).exp() * (-1.0 + (velocity - w0) * t)
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -w0 * (zeta - root);
+            let r2 = -w0 * (zeta + root);
+            let c2 = (velocity + r1) / (r2 - r1);
+            let c1 = -1.0 - c2;
+            c1 * (r1 * t).exp() + c2 * (r2 * t).exp()
+        };
+        1.0 + displacement
+    }
+
+    /// The velocity of the spring after `time`, given its starting `velocity`
+    pub fn velocity(&self, time: Duration, velocity: f64) -> f64 {
+        let h = Duration::from_micros(500);
+        let before = time.saturating_sub(h);
+        let after = time + h;
+        (self.position(after, velocity) - self.position(before, velocity))
+            / (after - before).as_secs_f64()
+    }
+
+    /// How long the spring takes to come to rest at its target, given its starting `velocity`
+    pub fn settle_duration(&self, velocity: f64) -> Duration {
+        let step = Duration::from_millis(4);
+        let mut time = Duration::ZERO;
+        while time < MAX_DURATION {
+            let at_rest = (1.0 - self.position(time, velocity)).abs() < REST_DISTANCE
+                && self.velocity(time, velocity).abs() < REST_VELOCITY;
+            if at_rest {
+                return time;
+            }
+            time += step;
+        }
+        MAX_DURATION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Spring;
+
+    #[test]
+    fn spring_settles_at_the_target() {
+        for spring in [Spring::gentle(), Spring::wobbly(), Spring::stiff()] {
+            assert_eq!(spring.position(Duration::ZERO, 0.0), 0.0);
+            let settled = spring.settle_duration(0.0);
+            assert!(
+                settled < Duration::from_secs(3),
+                "{spring:?} took {settled:?}"
+            );
+            assert!((spring.position(settled, 0.0) - 1.0).abs() < 0.001);
+        }
+        // Critically and over damped springs
+        for damping in [20.0, 40.0] {
+            let spring = Spring::new(1.0, 100.0, damping);
+            let settled = spring.settle_duration(0.0);
+            assert!((spring.position(settled, 0.0) - 1.0).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn wobbly_spring_overshoots() {
+        let spring = Spring::wobbly();
+        let max = (0..100)
+            .map(|i| spring.position(Duration::from_millis(i * 10), 0.0))
+            .fold(0.0, f64::max);
+        assert!(max > 1.0);
+    }
+
+    #[test]
+    fn initial_velocity_is_kept() {
+        let spring = Spring::default();
+        assert!((spring.velocity(Duration::ZERO, 5.0) - 5.0).abs() < 0.1);
+        assert!(
+            spring.position(Duration::from_millis(10), 5.0)
+                > spring.position(Duration::from_millis(10), 0.0)
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use floem_reactive::{create_rw_signal, ReadSignal, RwSignal};
+
+use crate::{action::exec_after, clock, update::ANIM_UPDATE_MESSAGES};
+
+use super::{AnimId, AnimUpdateMsg, Animation};
+
+/// How often a playing timeline moves its animations
+const TICK_INTERVAL: Duration = Duration::from_nanos(16_666_667);
+
+thread_local! {
+    /// The elapsed time of the animations driven by a timeline
+    static DRIVEN: RefCell<HashMap<AnimId, Duration>> = RefCell::new(HashMap::new());
+}
+
+/// The elapsed time set by the timeline driving the animation `id`, if there is one. Animations
+/// driven by a timeline don't advance on their own.
+pub(crate) fn driven_elapsed(id: AnimId) -> Option<Duration> {
+    DRIVEN.with(|driven| driven.borrow().get(&id).copied())
+}
+
+/// A part of a [`Timeline`], built with [`sequence`], [`parallel`], [`stagger`] and [`delay`], or
+/// from an [`Animation`].
+#[derive(Debug, Clone)]
+pub enum TimelineItem {
+    Animation {
+        id: AnimId,
+        duration: Duration,
+    },
+    Delay(Duration),
+    /// Each item starts when the one before it ends
+    Sequence(Vec<TimelineItem>),
+    /// All items start at the same time
+    Parallel(Vec<TimelineItem>),
+    /// Each item starts `interval` after the one before it started
+    Stagger {
+        items: Vec<TimelineItem>,
+        interval: Duration,
+    },
+}
+
+impl From<&Animation> for TimelineItem {
+    fn from(animation: &Animation) -> Self {
+        TimelineItem::Animation {
+            id: animation.id(),
+            duration: animation.duration,
+        }
+    }
+}
+
+impl From<Animation> for TimelineItem {
+    fn from(animation: Animation) -> Self {
+        (&animation).into()
+    }
+}
+
+impl From<Duration> for TimelineItem {
+    fn from(duration: Duration) -> Self {
+        TimelineItem::Delay(duration)
+    }
+}
+
+/// Run the items one after another
+pub fn sequence<I: Into<TimelineItem>>(items: impl IntoIterator<Item = I>) -> TimelineItem {
+    TimelineItem::Sequence(items.into_iter().map(Into::into).collect())
+}
+
+/// Run the items at the same time
+pub fn parallel<I: Into<TimelineItem>>(items: impl IntoIterator<Item = I>) -> TimelineItem {
+    TimelineItem::Parallel(items.into_iter().map(Into::into).collect())
+}
+
+/// Start each item `interval` after the one before it
+pub fn stagger<I: Into<TimelineItem>>(
+    interval: Duration,
+    items: impl IntoIterator<Item = I>,
+) -> TimelineItem {
+    TimelineItem::Stagger {
+        items: items.into_iter().map(Into::into).collect(),
+        interval,
+    }
+}
+
+/// Wait for `duration`, which is useful in a [`sequence`]
+pub fn delay(duration: Duration) -> TimelineItem {
+    TimelineItem::Delay(duration)
+}
+
+/// When an animation runs in a timeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entry {
+    id: AnimId,
+    start: Duration,
+    duration: Duration,
+}
+
+impl TimelineItem {
+    /// Add the animations in this item to `entries`, starting at `start`, and return when the
+    /// item ends
+    fn schedule(&self, start: Duration, entries: &mut Vec<Entry>) -> Duration {
+        match self {
+            TimelineItem::Animation { id, duration } => {
+                entries.push(Entry {
+                    id: *id,
+                    start,
+                    duration: *duration,
+                });
+                start + *duration
+            }
+            TimelineItem::Delay(duration) => start + *duration,
+            TimelineItem::Sequence(items) => items
+                .iter()
+                .fold(start, |end, item| item.schedule(end, entries)),
+            TimelineItem::Parallel(items) => items
+                .iter()
+                .map(|item| item.schedule(start, entries))
+                .fold(start, Duration::max),
+            TimelineItem::Stagger { items, interval } => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| item.schedule(start + *interval * i as u32, entries))
+                .fold(start, Duration::max),
+        }
+    }
+}
+
+struct TimelineState {
+    entries: Vec<Entry>,
+    total: Duration,
+    position: Cell<Duration>,
+    playing: Cell<bool>,
+    reversed: Cell<bool>,
+    rate: Cell<f64>,
+    last_tick: Cell<Instant>,
+    tick_scheduled: Cell<bool>,
+    progress: RwSignal<f64>,
+    on_complete: RefCell<Vec<Rc<dyn Fn()>>>,
+}
+
+impl Drop for TimelineState {
+    fn drop(&mut self) {
+        DRIVEN.with(|driven| {
+            let mut driven = driven.borrow_mut();
+            for entry in &self.entries {
+                driven.remove(&entry.id);
+            }
+        });
+    }
+}
+
+/// Runs several [`Animation`]s, which can be on different views, in sequence or in parallel.
+///
+/// The animations in a timeline don't start on their own. Instead the timeline sets how far
+/// through each of them is as it plays, so the whole timeline can be paused, seeked, reversed and
+/// sped up. Each animation in the timeline is played once. The timeline follows the same clock
+/// as the rest of floem, so it can be driven by a
+/// [`HeadlessWindow`](crate::headless::HeadlessWindow) in tests.
+///
+/// The timeline should be created before the views with its animations are first styled, and
+/// releases the animations when it's dropped.
+///
+/// ## Example
+/// ```
+/// use std::time::Duration;
+/// use floem::animate::{animation, delay, parallel, sequence, stagger, Timeline};
+///
+/// let fade_in = animation().opacity(|| 1.0);
+/// let slide = animation().translate_x(|| 100.0);
+/// let items: Vec<_> = (0..5).map(|_| animation().scale(|| 1.0)).collect();
+///
+/// let timeline = Timeline::new(sequence([
+///     parallel([&fade_in, &slide]),
+///     delay(Duration::from_millis(100)),
+///     stagger(Duration::from_millis(50), &items),
+/// ]))
+/// .on_complete(|| println!("done"));
+/// timeline.play();
+/// ```
+#[derive(Clone)]
+pub struct Timeline {
+    state: Rc<TimelineState>,
+}
+
+impl Timeline {
+    pub fn new(item: impl Into<TimelineItem>) -> Self {
+        let mut entries = Vec::new();
+        let total = item.into().schedule(Duration::ZERO, &mut entries);
+        let timeline = Self {
+            state: Rc::new(TimelineState {
+                entries,
+                total,
+                position: Cell::new(Duration::ZERO),
+                playing: Cell::new(false),
+                reversed: Cell::new(false),
+                rate: Cell::new(1.0),
+                last_tick: Cell::new(clock::now()),
+                tick_scheduled: Cell::new(false),
+                progress: create_rw_signal(0.0),
+                on_complete: RefCell::new(Vec::new()),
+            }),
+        };
+        timeline.apply();
+        timeline
+    }
+
+    /// Call `on_complete` whenever the timeline finishes playing, at its end or at its start when
+    /// it's reversed
+    pub fn on_complete(self, on_complete: impl Fn() + 'static) -> Self {
+        self.state
+            .on_complete
+            .borrow_mut()
+            .push(Rc::new(on_complete));
+        self
+    }
+
+    /// The length of the timeline, when played at a rate of 1.0
+    pub fn duration(&self) -> Duration {
+        self.state.total
+    }
+
+    /// How far through the timeline is, from 0.0 to 1.0
+    pub fn progress(&self) -> ReadSignal<f64> {
+        self.state.progress.read_only()
+    }
+
+    pub fn position(&self) -> Duration {
+        self.state.position.get()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state.playing.get()
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.state.reversed.get()
+    }
+
+    /// Play the timeline from its position. If it's already at the end it starts again from the
+    /// beginning, or from the end when it's reversed.
+    pub fn play(&self) {
+        let state = &self.state;
+        if state.playing.get() {
+            return;
+        }
+        if self.at_end() {
+            let restart = if state.reversed.get() {
+                state.total
+            } else {
+                Duration::ZERO
+            };
+            state.position.set(restart);
+            self.apply();
+        }
+        state.playing.set(true);
+        state.last_tick.set(clock::now());
+        self.schedule_tick();
+    }
+
+    pub fn pause(&self) {
+        self.state.playing.set(false);
+    }
+
+    /// Move to `position` in the timeline, which is kept between the start and the end
+    pub fn seek(&self, position: Duration) {
+        self.state.position.set(position.min(self.state.total));
+        self.state.last_tick.set(clock::now());
+        self.apply();
+    }
+
+    /// Move to `progress` through the timeline, from 0.0 to 1.0
+    pub fn seek_progress(&self, progress: f64) {
+        self.seek(self.state.total.mul_f64(progress.clamp(0.0, 1.0)));
+    }
+
+    /// Play the timeline in the other direction from its position
+    pub fn reverse(&self) {
+        self.state.reversed.set(!self.state.reversed.get());
+        self.state.playing.set(false);
+        self.play();
+    }
+
+    /// How fast the timeline plays, where 2.0 is twice as fast. Negative rates are treated as 0.0,
+    /// use [`reverse`](Self::reverse) to play backwards.
+    pub fn set_rate(&self, rate: f64) {
+        self.tick();
+        self.state.rate.set(rate.max(0.0));
+    }
+
+    /// Whether the position is at the end the timeline is playing towards
+    fn at_end(&self) -> bool {
+        let position = self.state.position.get();
+        if self.state.reversed.get() {
+            position.is_zero()
+        } else {
+            position >= self.state.total
+        }
+    }
+
+    fn schedule_tick(&self) {
+        if self.state.tick_scheduled.get() {
+            return;
+        }
+        self.state.tick_scheduled.set(true);
+        let timeline = self.clone();
+        exec_after(TICK_INTERVAL, move |_| {
+            timeline.state.tick_scheduled.set(false);
+            if timeline.state.playing.get() {
+                timeline.tick();
+                if timeline.state.playing.get() {
+                    timeline.schedule_tick();
+                }
+            }
+        });
+    }
+
+    /// Move the position by the time since the last tick
+    fn tick(&self) {
+        let state = &self.state;
+        let now = clock::now();
+        let elapsed = now.saturating_duration_since(state.last_tick.replace(now));
+        if !state.playing.get() {
+            return;
+        }
+
+        let step = elapsed.mul_f64(state.rate.get());
+        let position = if state.reversed.get() {
+            state.position.get().saturating_sub(step)
+        } else {
+            (state.position.get() + step).min(state.total)
+        };
+        state.position.set(position);
+        self.apply();
+
+        if self.at_end() {
+            state.playing.set(false);
+            let on_complete = state.on_complete.borrow().clone();
+            for on_complete in on_complete {
+                on_complete();
+            }
+        }
+    }
+
+    /// Set the elapsed time of each animation from the position in the timeline
+    fn apply(&self) {
+        let state = &self.state;
+        let position = state.position.get();
+        DRIVEN.with(|driven| {
+            let mut driven = driven.borrow_mut();
+            for entry in &state.entries {
+                let elapsed = position.saturating_sub(entry.start).min(entry.duration);
+                driven.insert(entry.id, elapsed);
+            }
+        });
+        ANIM_UPDATE_MESSAGES.with(|msgs| {
+            let mut msgs = msgs.borrow_mut();
+            for entry in &state.entries {
+                msgs.push(AnimUpdateMsg::Seek(entry.id));
+            }
+        });
+
+        let progress = if state.total.is_zero() {
+            1.0
+        } else {
+            position.as_secs_f64() / state.total.as_secs_f64()
+        };
+        if state.progress.get_untracked() != progress {
+            state.progress.set(progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
+    use super::{
+        delay, driven_elapsed, parallel, sequence, stagger, Entry, Timeline, TimelineItem,
+    };
+    use crate::{animate::AnimId, clock};
+
+    fn anim(id: usize, millis: u64) -> TimelineItem {
+        TimelineItem::Animation {
+            id: AnimId::from(id),
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    fn schedule(item: TimelineItem) -> (Vec<(AnimId, u64)>, u64) {
+        let mut entries = Vec::new();
+        let end = item.schedule(Duration::ZERO, &mut entries);
+        let starts = entries
+            .iter()
+            .map(|Entry { id, start, .. }| (*id, start.as_millis() as u64))
+            .collect();
+        (starts, end.as_millis() as u64)
+    }
+
+    fn starts(starts: &[(usize, u64)]) -> Vec<(AnimId, u64)> {
+        starts
+            .iter()
+            .map(|(id, start)| (AnimId::from(*id), *start))
+            .collect()
+    }
+
+    #[test]
+    fn sequence_and_parallel() {
+        let item = sequence([
+            anim(1, 100),
+            delay(Duration::from_millis(50)),
+            parallel([anim(2, 200), anim(3, 300)]),
+            anim(4, 100),
+        ]);
+        assert_eq!(
+            schedule(item),
+            (starts(&[(1, 0), (2, 150), (3, 150), (4, 450)]), 550)
+        );
+    }
+
+    #[test]
+    fn stagger_offsets_each_item() {
+        let item = stagger(
+            Duration::from_millis(30),
+            [anim(1, 100), anim(2, 100), anim(3, 10)],
+        );
+        assert_eq!(schedule(item), (starts(&[(1, 0), (2, 30), (3, 60)]), 130));
+    }
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// A timeline of two 100ms animations one after the other, counting how often it completes
+    fn two_in_sequence(first: usize) -> (Timeline, Rc<Cell<usize>>) {
+        clock::set_fake_now(Some(Instant::now()));
+        let completed = Rc::new(Cell::new(0));
+        let timeline = Timeline::new(sequence([anim(first, 100), anim(first + 1, 100)]))
+            .on_complete({
+                let completed = completed.clone();
+                move || completed.set(completed.get() + 1)
+            });
+        (timeline, completed)
+    }
+
+    /// Move the fake clock forward and let the timeline catch up, as its timer would
+    fn advance(timeline: &Timeline, duration: Duration) {
+        clock::advance(duration);
+        timeline.tick();
+    }
+
+    fn elapsed(id: usize) -> Option<Duration> {
+        driven_elapsed(AnimId::from(id))
+    }
+
+    #[test]
+    fn play_drives_the_animations() {
+        let (timeline, completed) = two_in_sequence(10);
+        assert_eq!(elapsed(10), Some(Duration::ZERO));
+
+        // Nothing moves until it's played
+        advance(&timeline, millis(50));
+        assert_eq!(timeline.position(), Duration::ZERO);
+
+        timeline.play();
+        assert!(timeline.is_playing());
+        advance(&timeline, millis(50));
+        assert_eq!(timeline.position(), millis(50));
+        assert_eq!(elapsed(10), Some(millis(50)));
+        assert_eq!(elapsed(11), Some(Duration::ZERO));
+
+        advance(&timeline, millis(120));
+        assert_eq!(elapsed(10), Some(millis(100)));
+        assert_eq!(elapsed(11), Some(millis(70)));
+
+        // It stops at the end
+        advance(&timeline, millis(100));
+        assert_eq!(timeline.position(), millis(200));
+        assert_eq!(timeline.progress().get_untracked(), 1.0);
+        assert!(!timeline.is_playing());
+        assert_eq!(completed.get(), 1);
+
+        // Playing again starts from the beginning
+        timeline.play();
+        assert_eq!(timeline.position(), Duration::ZERO);
+        assert_eq!(elapsed(11), Some(Duration::ZERO));
+
+        // A paused timeline stays where it is
+        advance(&timeline, millis(30));
+        timeline.pause();
+        advance(&timeline, millis(30));
+        assert_eq!(timeline.position(), millis(30));
+        assert_eq!(completed.get(), 1);
+        clock::set_fake_now(None);
+    }
+
+    #[test]
+    fn seek_and_reverse() {
+        let (timeline, completed) = two_in_sequence(20);
+
+        timeline.seek(millis(150));
+        assert_eq!(elapsed(20), Some(millis(100)));
+        assert_eq!(elapsed(21), Some(millis(50)));
+        timeline.seek(millis(100));
+        assert_eq!(timeline.progress().get_untracked(), 0.5);
+
+        timeline.seek(millis(500));
+        assert_eq!(timeline.position(), millis(200));
+        timeline.seek_progress(0.25);
+        assert_eq!(timeline.position(), millis(50));
+
+        // Reversing plays back towards the start
+        timeline.reverse();
+        assert!(timeline.is_reversed());
+        assert!(timeline.is_playing());
+        advance(&timeline, millis(20));
+        assert_eq!(timeline.position(), millis(30));
+        assert_eq!(elapsed(20), Some(millis(30)));
+
+        advance(&timeline, millis(50));
+        assert_eq!(timeline.position(), Duration::ZERO);
+        assert!(!timeline.is_playing());
+        assert_eq!(completed.get(), 1);
+
+        // Playing a reversed timeline at its start starts again from the end
+        timeline.play();
+        assert_eq!(timeline.position(), millis(200));
+
+        // Reversing again while playing turns around where it is
+        advance(&timeline, millis(40));
+        timeline.reverse();
+        assert!(!timeline.is_reversed());
+        advance(&timeline, millis(40));
+        assert_eq!(timeline.position(), millis(200));
+        assert_eq!(completed.get(), 2);
+        clock::set_fake_now(None);
+    }
+
+    #[test]
+    fn set_rate_changes_the_speed_from_now_on() {
+        let (timeline, completed) = two_in_sequence(30);
+        timeline.play();
+        advance(&timeline, millis(20));
+
+        // The time before the rate changes still counts at the old rate
+        clock::advance(millis(10));
+        timeline.set_rate(2.0);
+        assert_eq!(timeline.position(), millis(30));
+        advance(&timeline, millis(20));
+        assert_eq!(timeline.position(), millis(70));
+
+        timeline.set_rate(-1.0);
+        advance(&timeline, millis(20));
+        assert_eq!(timeline.position(), millis(70));
+        assert!(timeline.is_playing());
+
+        timeline.set_rate(10.0);
+        advance(&timeline, millis(20));
+        assert_eq!(timeline.position(), millis(200));
+        assert_eq!(completed.get(), 1);
+        clock::set_fake_now(None);
+    }
+}
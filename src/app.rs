@@ -10,9 +10,11 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
 use crate::{
-    action::Timer,
+    accessibility::ActionRequest,
+    action::{exec_after, Timer},
     app_handle::ApplicationHandle,
     clipboard::Clipboard,
+    ext_event::register_ext_trigger,
     inspector::Capture,
     profiler::Profile,
     view::{AnyView, View},
@@ -43,6 +45,14 @@ pub(crate) enum UserEvent {
     AppUpdate,
     Idle,
     QuitApp,
+    /// An assistive technology started using the accessibility tree of the window.
+    AccessibilityActivated {
+        window_id: WindowId,
+    },
+    AccessibilityAction {
+        window_id: WindowId,
+        request: ActionRequest,
+    },
 }
 
 pub(crate) enum AppUpdateEvent {
@@ -101,6 +111,12 @@ impl Application {
             .expect("can't start the event loop");
         let event_loop_proxy = event_loop.create_proxy();
         *EVENT_LOOP_PROXY.lock() = Some(event_loop_proxy.clone());
+        // Wake up resources through the event loop
+        floem_reactive::set_ext_trigger_handler(register_ext_trigger);
+        // Wait out the throttle of sync signals with timers on the event loop
+        floem_reactive::set_timer_handler(|delay, trigger| {
+            exec_after(delay, move |_| trigger.notify());
+        });
         unsafe {
             Clipboard::init(event_loop.raw_display_handle());
         }
@@ -152,6 +168,8 @@ impl Application {
                 floem_winit::event::Event::Resumed => {}
                 floem_winit::event::Event::AboutToWait => {}
                 floem_winit::event::Event::LoopExiting => {
+                    #[cfg(feature = "serde")]
+                    let _ = crate::persist::save_persisted();
                     if let Some(action) = self.event_listener.as_ref() {
                         action(AppEvent::WillTerminate);
                     }
@@ -8,6 +8,8 @@ use floem_winit::{
 };
 use kurbo::{Point, Size};
 
+#[cfg(feature = "serde")]
+use crate::persist::{self, WindowState};
 use crate::{
     action::{Timer, TimerToken},
     app::{AppUpdateEvent, UserEvent, APP_UPDATE_EVENTS},
@@ -22,6 +24,9 @@ use crate::{
 pub(crate) struct ApplicationHandle {
     window_handles: HashMap<floem_winit::window::WindowId, WindowHandle>,
     timers: HashMap<TimerToken, Timer>,
+    /// The key and current state of the windows created with `WindowConfig::restore_state`
+    #[cfg(feature = "serde")]
+    window_states: HashMap<WindowId, (String, WindowState)>,
 }
 
 impl ApplicationHandle {
@@ -29,6 +34,8 @@ impl ApplicationHandle {
         Self {
             window_handles: HashMap::new(),
             timers: HashMap::new(),
+            #[cfg(feature = "serde")]
+            window_states: HashMap::new(),
         }
     }
 
@@ -47,6 +54,16 @@ impl ApplicationHandle {
             UserEvent::QuitApp => {
                 event_loop.exit();
             }
+            UserEvent::AccessibilityActivated { window_id } => {
+                if let Some(window_handle) = self.window_handles.get_mut(&window_id) {
+                    window_handle.accessibility_activated();
+                }
+            }
+            UserEvent::AccessibilityAction { window_id, request } => {
+                if let Some(window_handle) = self.window_handles.get_mut(&window_id) {
+                    window_handle.accessibility_action(request);
+                }
+            }
         }
     }
 
@@ -155,11 +172,31 @@ impl ApplicationHandle {
                 let size: LogicalSize<f64> = size.to_logical(window_handle.scale);
                 let size = Size::new(size.width, size.height);
                 window_handle.size(size);
+                #[cfg(feature = "serde")]
+                if let Some((key, state)) = self.window_states.get_mut(&window_id) {
+                    let maximized = window_handle
+                        .window
+                        .as_ref()
+                        .is_some_and(|window| window.is_maximized());
+                    // Keep the size to go back to when the window is unmaximized
+                    if !maximized {
+                        state.size = size;
+                    }
+                    state.maximized = maximized;
+                    persist::store_persisted(key, &*state);
+                }
             }
             WindowEvent::Moved(position) => {
                 let position: LogicalPosition<f64> = position.to_logical(window_handle.scale);
                 let point = Point::new(position.x, position.y);
                 window_handle.position(point);
+                #[cfg(feature = "serde")]
+                if let Some((key, state)) = self.window_states.get_mut(&window_id) {
+                    if !state.maximized {
+                        state.position = Some(point);
+                        persist::store_persisted(key, &*state);
+                    }
+                }
             }
             WindowEvent::CloseRequested => {
                 self.close_window(window_id, event_loop);
@@ -167,9 +204,15 @@ impl ApplicationHandle {
             WindowEvent::Destroyed => {
                 self.close_window(window_id, event_loop);
             }
-            WindowEvent::DroppedFile(_) => {}
-            WindowEvent::HoveredFile(_) => {}
-            WindowEvent::HoveredFileCancelled => {}
+            WindowEvent::DroppedFile(path) => {
+                window_handle.file_drop(path);
+            }
+            WindowEvent::HoveredFile(path) => {
+                window_handle.file_hover(path);
+            }
+            WindowEvent::HoveredFileCancelled => {
+                window_handle.file_hover_cancelled();
+            }
             WindowEvent::Focused(focused) => {
                 window_handle.focused(focused);
             }
@@ -249,6 +292,17 @@ impl ApplicationHandle {
         config: Option<WindowConfig>,
     ) {
         let mut window_builder = floem_winit::window::WindowBuilder::new();
+        #[cfg(feature = "serde")]
+        let state_key = config.as_ref().and_then(|c| c.restore_state.clone());
+        #[cfg(feature = "serde")]
+        let restored = state_key
+            .as_deref()
+            .and_then(persist::load_persisted::<WindowState>);
+        #[cfg(feature = "serde")]
+        let config = config.map(|config| match restored {
+            Some(state) => config.with_state(state),
+            None => config,
+        });
         let transparent = config.as_ref().and_then(|c| c.transparent).unwrap_or(false);
         let apply_default_theme = if let Some(config) = config {
             if let Some(size) = config.size {
@@ -295,13 +349,42 @@ impl ApplicationHandle {
         } else {
             true
         };
-        let result = window_builder.build(event_loop);
+        // The accessibility adapter has to be created before the window is shown.
+        let result = window_builder.with_visible(false).build(event_loop);
         let window = match result {
             Ok(window) => window,
             Err(_) => return,
         };
         let window_id = window.id();
+        #[cfg(feature = "serde")]
+        if let Some(key) = state_key {
+            let state = match restored {
+                Some(state) => {
+                    if state.maximized {
+                        window.set_maximized(true);
+                    }
+                    state
+                }
+                None => {
+                    let scale = window.scale_factor();
+                    let size: LogicalSize<f64> = window.inner_size().to_logical(scale);
+                    let position = window.outer_position().ok().map(|position| {
+                        let position: LogicalPosition<f64> = position.to_logical(scale);
+                        Point::new(position.x, position.y)
+                    });
+                    WindowState {
+                        size: Size::new(size.width, size.height),
+                        position,
+                        maximized: window.is_maximized(),
+                    }
+                }
+            };
+            self.window_states.insert(window_id, (key, state));
+        }
         let window_handle = WindowHandle::new(window, view_fn, transparent, apply_default_theme);
+        if let Some(window) = window_handle.window.as_ref() {
+            window.set_visible(true);
+        }
         self.window_handles.insert(window_id, window_handle);
     }
 
@@ -312,10 +395,14 @@ impl ApplicationHandle {
         #[cfg(not(target_os = "macos"))] event_loop: &EventLoopWindowTarget<UserEvent>,
     ) {
         if let Some(handle) = self.window_handles.get_mut(&window_id) {
+            // The accessibility adapter refers to the window, so drop it first.
+            handle.accessibility_adapter = None;
             handle.window = None;
             handle.destroy();
         }
         self.window_handles.remove(&window_id);
+        #[cfg(feature = "serde")]
+        self.window_states.remove(&window_id);
         #[cfg(not(target_os = "macos"))]
         if self.window_handles.is_empty() {
             event_loop.exit();
@@ -0,0 +1,462 @@
+//! # Brushes
+//!
+//! A [`Brush`] is what the `background`, `border_color` and `color` style properties are painted
+//! with. It's either a solid [`Color`] or a [`Gradient`].
+//!
+//! Gradients are defined relative to the box they're painted into, similar to CSS gradients,
+//! and are resolved to a [`floem_peniko::Gradient`] in absolute coordinates at paint time.
+//!
+//! The tiny-skia renderer paints every gradient exactly. The vger renderer only has linear
+//! gradients between two colors: it paints linear gradients with more stops between their first
+//! and last stop, and radial and sweep gradients with the average color of their stops. Set the
+//! `FLOEM_FORCE_TINY_SKIA` environment variable to use the tiny-skia renderer when gradients
+//! need to be exact.
+//!
+//! A [`Color`] converts into a solid [`Brush`], so the color style properties still accept
+//! colors directly.
+//!
+//! ```
+//! use floem::brush::Gradient;
+//! use floem::peniko::Color;
+//! use floem::style::Style;
+//!
+//! let style = Style::new().background(
+//!     Gradient::linear(90.0)
+//!         .stop(0.0, Color::RED)
+//!         .stop(1.0, Color::BLUE),
+//! );
+//! ```
+
+use std::f64::consts::{PI, TAU};
+
+use floem_peniko::{Color, Extend};
+use kurbo::{Point, Rect, Vec2};
+
+use crate::style::StylePropValue;
+
+/// A solid color or a gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Brush {
+    Solid(Color),
+    Gradient(Gradient),
+}
+
+impl Brush {
+    /// Resolves the brush for painting into `rect`.
+    pub fn resolve(&self, rect: Rect) -> floem_peniko::Brush {
+        match self {
+            Brush::Solid(color) => floem_peniko::Brush::Solid(*color),
+            Brush::Gradient(gradient) => floem_peniko::Brush::Gradient(gradient.resolve(rect)),
+        }
+    }
+
+    /// The solid color of the brush, or `None` for gradients.
+    pub fn as_color(&self) -> Option<Color> {
+        match self {
+            Brush::Solid(color) => Some(*color),
+            Brush::Gradient(_) => None,
+        }
+    }
+
+    /// A single color representing the brush. For gradients this is the color halfway
+    /// through the gradient, which is used where only solid colors can be painted.
+    pub fn to_color(&self) -> Color {
+        match self {
+            Brush::Solid(color) => *color,
+            Brush::Gradient(gradient) => gradient.color_at(0.5),
+        }
+    }
+
+    pub fn is_gradient(&self) -> bool {
+        matches!(self, Brush::Gradient(_))
+    }
+
+    /// Interpolates between two brushes. A solid color transitions into a gradient by
+    /// treating it as a gradient of the same kind where every stop has that color.
+    pub fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Brush::Solid(from), Brush::Solid(to)) => from.interpolate(to, value).map(Brush::Solid),
+            (Brush::Gradient(from), Brush::Gradient(to)) => {
+                from.interpolate(to, value).map(Brush::Gradient)
+            }
+            (Brush::Solid(from), Brush::Gradient(to)) => to
+                .with_color(*from)
+                .interpolate(to, value)
+                .map(Brush::Gradient),
+            (Brush::Gradient(from), Brush::Solid(to)) => from
+                .interpolate(&from.with_color(*to), value)
+                .map(Brush::Gradient),
+        }
+    }
+}
+
+impl From<Color> for Brush {
+    fn from(color: Color) -> Self {
+        Brush::Solid(color)
+    }
+}
+
+impl From<Gradient> for Brush {
+    fn from(gradient: Gradient) -> Self {
+        Brush::Gradient(gradient)
+    }
+}
+
+/// A color at an offset along a [`Gradient`], where `0.0` is the start and `1.0` the end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// The shape of a [`Gradient`]. Positions are in unit coordinates of the painted box, so
+/// `(0.5, 0.5)` is the center. Angles are in degrees and go clockwise, with `0` pointing up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientKind {
+    /// A gradient along a line through the center of the box at `angle`. The line is long
+    /// enough for the start and end to reach the corners, like a CSS `linear-gradient`.
+    Linear { angle: f64 },
+    /// A gradient of circles around `center`. A `radius` of `1.0` reaches the farthest corner.
+    Radial { center: Point, radius: f64 },
+    /// A gradient around `center`, starting at `start_angle`.
+    Sweep { center: Point, start_angle: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// A linear gradient at `angle` degrees. `0` goes from bottom to top and `90` from left to
+    /// right.
+    pub fn linear(angle: f64) -> Self {
+        Self {
+            kind: GradientKind::Linear { angle },
+            stops: Vec::new(),
+        }
+    }
+
+    /// A radial gradient centered in the box, reaching the corners.
+    pub fn radial() -> Self {
+        Self {
+            kind: GradientKind::Radial {
+                center: Point::new(0.5, 0.5),
+                radius: 1.0,
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    /// A sweep (conic) gradient centered in the box, starting at the top.
+    pub fn sweep() -> Self {
+        Self {
+            kind: GradientKind::Sweep {
+                center: Point::new(0.5, 0.5),
+                start_angle: 0.0,
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    /// Sets the angle of a linear gradient or the start angle of a sweep gradient.
+    pub fn angle(mut self, degrees: f64) -> Self {
+        match &mut self.kind {
+            GradientKind::Linear { angle } => *angle = degrees,
+            GradientKind::Sweep { start_angle, .. } => *start_angle = degrees,
+            GradientKind::Radial { .. } => {}
+        }
+        self
+    }
+
+    /// Sets the center of a radial or sweep gradient in unit coordinates of the box.
+    pub fn center(mut self, x: f64, y: f64) -> Self {
+        match &mut self.kind {
+            GradientKind::Radial { center, .. } | GradientKind::Sweep { center, .. } => {
+                *center = Point::new(x, y)
+            }
+            GradientKind::Linear { .. } => {}
+        }
+        self
+    }
+
+    /// Sets the radius of a radial gradient relative to the distance to the farthest corner.
+    pub fn radius(mut self, value: f64) -> Self {
+        if let GradientKind::Radial { radius, .. } = &mut self.kind {
+            *radius = value;
+        }
+        self
+    }
+
+    /// Adds a color stop. Stops must be added in increasing order of `offset`.
+    pub fn stop(mut self, offset: f32, color: Color) -> Self {
+        self.stops.push(ColorStop { offset, color });
+        self
+    }
+
+    /// Adds colors as evenly spaced stops, replacing existing stops.
+    pub fn colors(mut self, colors: impl IntoIterator<Item = Color>) -> Self {
+        let colors: Vec<Color> = colors.into_iter().collect();
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        self.stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| ColorStop {
+                offset: i as f32 / last,
+                color,
+            })
+            .collect();
+        self
+    }
+
+    /// The color at `offset`, interpolated between the surrounding stops.
+    pub fn color_at(&self, offset: f32) -> Color {
+        let Some(first) = self.stops.first() else {
+            return Color::TRANSPARENT;
+        };
+        if offset <= first.offset {
+            return first.color;
+        }
+        for stops in self.stops.windows(2) {
+            let (a, b) = (stops[0], stops[1]);
+            if offset <= b.offset {
+                let span = b.offset - a.offset;
+                if span <= f32::EPSILON {
+                    return b.color;
+                }
+                let t = ((offset - a.offset) / span) as f64;
+                return a.color.interpolate(&b.color, t).unwrap_or(b.color);
+            }
+        }
+        self.stops[self.stops.len() - 1].color
+    }
+
+    /// Resolves the gradient in absolute coordinates for painting into `rect`.
+    pub fn resolve(&self, rect: Rect) -> floem_peniko::Gradient {
+        let size = rect.size();
+        let unit_point =
+            |p: Point| Point::new(rect.x0 + p.x * size.width, rect.y0 + p.y * size.height);
+
+        let kind = match self.kind {
+            GradientKind::Linear { angle } => {
+                let angle = angle.to_radians();
+                let dir = Vec2::new(angle.sin(), -angle.cos());
+                let half = (size.width * dir.x.abs() + size.height * dir.y.abs()) / 2.0;
+                let center = rect.center();
+                floem_peniko::GradientKind::Linear {
+                    start: center - dir * half,
+                    end: center + dir * half,
+                }
+            }
+            GradientKind::Radial { center, radius } => {
+                let center = unit_point(center);
+                let farthest = [
+                    rect.origin(),
+                    Point::new(rect.x1, rect.y0),
+                    Point::new(rect.x0, rect.y1),
+                    Point::new(rect.x1, rect.y1),
+                ]
+                .into_iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0, f64::max);
+                floem_peniko::GradientKind::Radial {
+                    start_center: center,
+                    start_radius: 0.0,
+                    end_center: center,
+                    end_radius: (radius * farthest) as f32,
+                }
+            }
+            GradientKind::Sweep {
+                center,
+                start_angle,
+            } => {
+                // Renderers measure sweep angles in radians from the positive x axis, going
+                // clockwise as y points down.
+                let start = start_angle.to_radians() - PI / 2.0;
+                floem_peniko::GradientKind::Sweep {
+                    center: unit_point(center),
+                    start_angle: start as f32,
+                    end_angle: (start + TAU) as f32,
+                }
+            }
+        };
+
+        floem_peniko::Gradient {
+            kind,
+            extend: Extend::Pad,
+            stops: self
+                .stops
+                .iter()
+                .map(|stop| floem_peniko::ColorStop {
+                    offset: stop.offset,
+                    color: stop.color,
+                })
+                .collect(),
+        }
+    }
+
+    /// A gradient of the same kind and stop offsets where every stop has `color`.
+    fn with_color(&self, color: Color) -> Self {
+        let mut stops: Vec<ColorStop> = self
+            .stops
+            .iter()
+            .map(|stop| ColorStop {
+                offset: stop.offset,
+                color,
+            })
+            .collect();
+        if stops.is_empty() {
+            stops.push(ColorStop { offset: 0.0, color });
+        }
+        Self {
+            kind: self.kind,
+            stops,
+        }
+    }
+
+    /// Interpolates two gradients of the same kind. Gradients with different stop offsets are
+    /// sampled at the offsets of both before interpolating.
+    pub fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        let kind = match (self.kind, other.kind) {
+            (GradientKind::Linear { angle: a }, GradientKind::Linear { angle: b }) => {
+                GradientKind::Linear {
+                    angle: a.interpolate(&b, value)?,
+                }
+            }
+            (
+                GradientKind::Radial {
+                    center: c1,
+                    radius: r1,
+                },
+                GradientKind::Radial {
+                    center: c2,
+                    radius: r2,
+                },
+            ) => GradientKind::Radial {
+                center: c1.lerp(c2, value),
+                radius: r1.interpolate(&r2, value)?,
+            },
+            (
+                GradientKind::Sweep {
+                    center: c1,
+                    start_angle: a1,
+                },
+                GradientKind::Sweep {
+                    center: c2,
+                    start_angle: a2,
+                },
+            ) => GradientKind::Sweep {
+                center: c1.lerp(c2, value),
+                start_angle: a1.interpolate(&a2, value)?,
+            },
+            _ => return None,
+        };
+
+        let same_offsets = self.stops.len() == other.stops.len()
+            && self
+                .stops
+                .iter()
+                .zip(&other.stops)
+                .all(|(a, b)| a.offset == b.offset);
+        let offsets: Vec<f32> = if same_offsets {
+            self.stops.iter().map(|stop| stop.offset).collect()
+        } else {
+            let mut offsets: Vec<f32> = self
+                .stops
+                .iter()
+                .chain(&other.stops)
+                .map(|stop| stop.offset)
+                .collect();
+            offsets.sort_by(f32::total_cmp);
+            offsets.dedup();
+            offsets
+        };
+
+        let stops = offsets
+            .into_iter()
+            .map(|offset| {
+                let from = self.color_at(offset);
+                let to = other.color_at(offset);
+                Some(ColorStop {
+                    offset,
+                    color: from.interpolate(&to, value)?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { kind, stops })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_peniko::Color;
+    use kurbo::{Point, Rect};
+
+    use super::{Brush, Gradient, GradientKind};
+    use crate::style::{Background, BorderColor, Style, StyleValue, TextColor};
+
+    #[test]
+    fn linear_resolves_to_corners() {
+        let gradient = Gradient::linear(90.0)
+            .stop(0.0, Color::RED)
+            .stop(1.0, Color::BLUE)
+            .resolve(Rect::new(0.0, 0.0, 100.0, 50.0));
+        match gradient.kind {
+            floem_peniko::GradientKind::Linear { start, end } => {
+                assert!(start.distance(Point::new(0.0, 25.0)) < 1e-9);
+                assert!(end.distance(Point::new(100.0, 25.0)) < 1e-9);
+            }
+            _ => panic!("expected a linear gradient"),
+        }
+    }
+
+    #[test]
+    fn colors_convert_to_solid_brushes() {
+        assert_eq!(Brush::from(Color::RED), Brush::Solid(Color::RED));
+        assert!(matches!(
+            StyleValue::<Brush>::from(Color::RED),
+            StyleValue::Val(Brush::Solid(Color::RED))
+        ));
+
+        let style = Style::new()
+            .color(Color::RED)
+            .background(Color::GREEN)
+            .border_color(Color::BLUE);
+        assert_eq!(style.get(TextColor), Some(Brush::Solid(Color::RED)));
+        assert_eq!(style.get(Background), Some(Brush::Solid(Color::GREEN)));
+        assert_eq!(style.get(BorderColor), Brush::Solid(Color::BLUE));
+        assert_eq!(style.get(BorderColor).as_color(), Some(Color::BLUE));
+
+        let style = Style::new().set(Background, Some(Color::GREEN.into()));
+        assert_eq!(style.get(Background), Some(Brush::Solid(Color::GREEN)));
+    }
+
+    #[test]
+    fn solid_to_gradient_interpolates() {
+        let from = Brush::Solid(Color::RED);
+        let to = Brush::Gradient(Gradient::linear(0.0).colors([Color::BLUE, Color::GREEN]));
+        let Some(Brush::Gradient(mid)) = from.interpolate(&to, 1.0) else {
+            panic!("expected a gradient");
+        };
+        assert_eq!(mid.kind, GradientKind::Linear { angle: 0.0 });
+        assert_eq!(mid.color_at(0.0), Color::BLUE);
+        assert_eq!(mid.color_at(1.0), Color::GREEN);
+
+        let Some(Brush::Gradient(start)) = from.interpolate(&to, 0.0) else {
+            panic!("expected a gradient");
+        };
+        assert_eq!(start.color_at(0.5), Color::RED);
+    }
+
+    #[test]
+    fn mismatched_stops_are_resampled() {
+        let a = Gradient::linear(0.0).colors([Color::BLACK, Color::WHITE]);
+        let b = Gradient::linear(180.0).colors([Color::BLACK, Color::BLACK, Color::BLACK]);
+        let mid = a.interpolate(&b, 0.5).unwrap();
+        assert_eq!(mid.stops.len(), 3);
+        assert_eq!(mid.kind, GradientKind::Linear { angle: 90.0 });
+        assert!(a.interpolate(&Gradient::radial(), 0.5).is_none());
+    }
+}
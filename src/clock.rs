@@ -0,0 +1,31 @@
+//! The time source used for timers, animations and transitions.
+//!
+//! Normally this is the system clock, but a [`HeadlessWindow`](crate::headless::HeadlessWindow)
+//! replaces it with a manually advanced clock so tests are deterministic.
+
+use std::{cell::Cell, time::Instant};
+
+#[cfg(test)]
+use std::time::Duration;
+
+thread_local! {
+    static FAKE_NOW: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Returns the current time, or the fake time if one is set on this thread.
+pub(crate) fn now() -> Instant {
+    FAKE_NOW.with(|now| now.get()).unwrap_or_else(Instant::now)
+}
+
+/// Overrides the time returned by [`now`] on this thread, returning the previous override. `None`
+/// restores the system clock.
+pub(crate) fn set_fake_now(now: Option<Instant>) -> Option<Instant> {
+    FAKE_NOW.with(|fake| fake.replace(now))
+}
+
+/// Moves the time returned by [`now`] on this thread forward by `duration`, starting the fake
+/// clock at the current time if it isn't set.
+#[cfg(test)]
+pub(crate) fn advance(duration: Duration) {
+    set_fake_now(Some(now() + duration));
+}
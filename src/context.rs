@@ -16,6 +16,7 @@ use taffy::{
 use crate::{
     action::{exec_after, show_context_menu},
     animate::AnimId,
+    clock,
     event::{Event, EventListener},
     id::Id,
     inspector::CaptureState,
@@ -98,6 +99,7 @@ pub struct AppState {
     pub(crate) request_compute_layout: bool,
     pub(crate) request_paint: bool,
     pub(crate) disabled: HashSet<Id>,
+    pub(crate) invalid: HashSet<Id>,
     pub(crate) keyboard_navigable: HashSet<Id>,
     pub(crate) draggable: HashSet<Id>,
     pub(crate) dragging: Option<DragState>,
@@ -107,6 +109,8 @@ pub struct AppState {
     pub(crate) grid_bps: GridBreakpoints,
     pub(crate) clicking: HashSet<Id>,
     pub(crate) hovered: HashSet<Id>,
+    /// The views under the pointer while files are dragged over the window.
+    pub(crate) file_hovered: HashSet<Id>,
     /// This keeps track of all views that have an animation,
     /// regardless of the status of the animation
     pub(crate) cursor: Option<CursorStyle>,
@@ -143,6 +147,7 @@ impl AppState {
             request_paint: false,
             request_compute_layout: false,
             disabled: HashSet::new(),
+            invalid: HashSet::new(),
             keyboard_navigable: HashSet::new(),
             draggable: HashSet::new(),
             dragging: None,
@@ -150,6 +155,7 @@ impl AppState {
             dragging_over: HashSet::new(),
             clicking: HashSet::new(),
             hovered: HashSet::new(),
+            file_hovered: HashSet::new(),
             cursor: None,
             last_cursor: CursorIcon::Default,
             keyboard_navigation: false,
@@ -192,12 +198,14 @@ impl AppState {
         id.remove_id_path();
         self.view_states.remove(&id);
         self.disabled.remove(&id);
+        self.invalid.remove(&id);
         self.keyboard_navigable.remove(&id);
         self.draggable.remove(&id);
         self.dragging_over.remove(&id);
         self.clicking.remove(&id);
         self.hovered.remove(&id);
         self.clicking.remove(&id);
+        crate::views::selectable_text::remove(id);
         if self.focus == Some(id) {
             self.focus = None;
         }
@@ -232,10 +240,30 @@ impl AppState {
         self.hovered.contains(id)
     }
 
+    pub fn is_file_hovered(&self, id: &Id) -> bool {
+        self.file_hovered.contains(id)
+    }
+
     pub fn is_disabled(&self, id: &Id) -> bool {
         self.disabled.contains(id)
     }
 
+    pub fn is_invalid(&self, id: &Id) -> bool {
+        self.invalid.contains(id)
+    }
+
+    /// Mark the value of a view as invalid, which applies its [`invalid`](Style::invalid) style
+    pub fn set_invalid(&mut self, id: Id, invalid: bool) {
+        let changed = if invalid {
+            self.invalid.insert(id)
+        } else {
+            self.invalid.remove(&id)
+        };
+        if changed {
+            self.request_style(id);
+        }
+    }
+
     pub fn is_focused(&self, id: &Id) -> bool {
         self.focus.map(|f| &f == id).unwrap_or(false)
     }
@@ -422,6 +450,19 @@ impl AppState {
         self.view_state(id).layout_rect
     }
 
+    /// Returns the transform from the `translate`, `scale` and `rotate` style properties.
+    /// This is relative to the layout position of the view.
+    pub(crate) fn get_transform(&self, id: Id) -> Affine {
+        let Some(view_state) = self.view_states.get(&id) else {
+            return Affine::IDENTITY;
+        };
+        let size = self
+            .get_layout(id)
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+        view_state.view_style_props.transform(size)
+    }
+
     pub(crate) fn update_active(&mut self, id: Id) {
         if self.active.is_some() {
             // the first update_active wins, so if there's active set,
@@ -479,8 +520,11 @@ impl AppState {
     // TODO: animated should be a HashMap<Id, AnimId>
     // so we don't have to loop through all view states
     pub(crate) fn get_view_id_by_anim_id(&self, anim_id: AnimId) -> Id {
-        *self
-            .view_states
+        self.find_view_id_by_anim_id(anim_id).unwrap()
+    }
+
+    pub(crate) fn find_view_id_by_anim_id(&self, anim_id: AnimId) -> Option<Id> {
+        self.view_states
             .iter()
             .find(|(_, vs)| {
                 vs.animation
@@ -488,8 +532,7 @@ impl AppState {
                     .map(|a| a.id() == anim_id)
                     .unwrap_or(false)
             })
-            .unwrap()
-            .0
+            .map(|(id, _)| *id)
     }
 
     pub(crate) fn update_context_menu(&mut self, menu: &mut Menu) {
@@ -838,7 +881,7 @@ impl<'a> EventCx<'a> {
                         self.app_state.dragging.as_mut().filter(|d| d.id == id)
                     {
                         let dragging_id = dragging.id;
-                        dragging.released_at = Some(std::time::Instant::now());
+                        dragging.released_at = Some(clock::now());
                         id.request_paint();
                         self.apply_event(dragging_id, &EventListener::DragEnd, &event);
                     }
@@ -909,6 +952,12 @@ impl<'a> EventCx<'a> {
                     }
                 }
             }
+            Event::FileHover(file_event) => {
+                let rect = self.get_size(id).unwrap_or_default().to_rect();
+                if rect.contains(file_event.pos) {
+                    self.app_state.file_hovered.insert(id);
+                }
+            }
             Event::KeyDown(_) => {
                 if self.app_state.is_focused(&id) && event.is_keyboard_trigger() {
                     self.apply_event(id, &EventListener::Click, &event);
@@ -995,9 +1044,16 @@ impl<'a> EventCx<'a> {
             .and_then(|view| view.viewport);
 
         if let Some(layout) = self.get_layout(id) {
+            let event = event.offset((layout.location.x as f64, layout.location.y as f64));
+            let transform = self.app_state.get_transform(id);
+            let event = if transform != Affine::IDENTITY && transform.determinant() != 0.0 {
+                event.transform(transform.inverse())
+            } else {
+                event
+            };
             event.offset((
-                layout.location.x as f64 - viewport.map(|rect| rect.x0).unwrap_or(0.0),
-                layout.location.y as f64 - viewport.map(|rect| rect.y0).unwrap_or(0.0),
+                -viewport.map(|rect| rect.x0).unwrap_or(0.0),
+                -viewport.map(|rect| rect.y0).unwrap_or(0.0),
             ))
         } else {
             event
@@ -1015,12 +1071,16 @@ impl<'a> EventCx<'a> {
         if let Some(point) = event.point() {
             let layout_rect = self.app_state.get_layout_rect(id);
             if let Some(layout) = self.get_layout(id) {
+                // Hit-test in the coordinates of the view so that
+                // scaled and rotated views receive the right events.
+                let point = point - Vec2::new(layout.location.x as f64, layout.location.y as f64);
+                let transform = self.app_state.get_transform(id);
+                if transform.determinant() == 0.0 {
+                    return false;
+                }
                 if layout_rect
-                    .with_origin(Point::new(
-                        layout.location.x as f64,
-                        layout.location.y as f64,
-                    ))
-                    .contains(point)
+                    .with_origin(Point::ZERO)
+                    .contains(transform.inverse() * point)
                 {
                     return true;
                 }
@@ -1035,8 +1095,10 @@ impl<'a> EventCx<'a> {
 #[derive(Default)]
 pub struct InteractionState {
     pub(crate) is_hovered: bool,
+    pub(crate) is_file_hovered: bool,
     pub(crate) is_selected: bool,
     pub(crate) is_disabled: bool,
+    pub(crate) is_invalid: bool,
     pub(crate) is_focused: bool,
     pub(crate) is_clicking: bool,
     pub(crate) using_keyboard_navigation: bool,
@@ -1063,7 +1125,7 @@ impl<'a> StyleCx<'a> {
             current: Default::default(),
             direct: Default::default(),
             saved: Default::default(),
-            now: Instant::now(),
+            now: clock::now(),
             saved_disabled: Default::default(),
             saved_selected: Default::default(),
             disabled: false,
@@ -1080,7 +1142,9 @@ impl<'a> StyleCx<'a> {
         InteractionState {
             is_selected: self.selected,
             is_hovered: self.app_state.is_hovered(id),
+            is_file_hovered: self.app_state.is_file_hovered(id),
             is_disabled: self.app_state.is_disabled(id),
+            is_invalid: self.app_state.is_invalid(id),
             is_focused: self.app_state.is_focused(id),
             is_clicking: self.app_state.is_clicking(id),
             using_keyboard_navigation: self.app_state.keyboard_navigation,
@@ -1115,7 +1179,7 @@ impl<'a> StyleCx<'a> {
         let mut view_interact_state = self.get_interact_state(&id);
         view_interact_state.is_disabled |= self.disabled;
         self.disabled = view_interact_state.is_disabled;
-        let mut new_frame = self.app_state.compute_style(
+        let new_frame = self.app_state.compute_style(
             id,
             view.view_data_mut(),
             view_style,
@@ -1145,19 +1209,12 @@ impl<'a> StyleCx<'a> {
 
         // Extract the relevant layout properties so the content rect can be calculated
         // when painting.
-        view_state.layout_props.read_explicit(
-            &self.direct,
-            &self.current,
-            &self.now,
-            &mut new_frame,
-        );
-
-        view_state.view_style_props.read_explicit(
-            &self.direct,
-            &self.current,
-            &self.now,
-            &mut new_frame,
-        );
+        view_state
+            .layout_props
+            .read_explicit(&self.direct, &self.current);
+        view_state
+            .view_style_props
+            .read_explicit(&self.direct, &self.current);
         if new_frame {
             self.app_state.schedule_style(id);
         }
@@ -1478,11 +1535,23 @@ impl<'a> PaintCx<'a> {
                 self.set_z_index(z_index);
             }
 
-            paint_bg(self, &style, &view_style_props, size);
+            // Paint the view and its children into a layer so they're faded as a group.
+            let opacity = view_style_props.opacity().clamp(0.0, 1.0) as f32;
+            if opacity > 0.0 {
+                if opacity < 1.0 {
+                    self.paint_state.renderer.push_layer(opacity);
+                }
+
+                paint_bg(self, &style, &view_style_props, size);
+
+                view.paint(self);
+                paint_border(self, &view_style_props, size);
+                paint_outline(self, &view_style_props, size);
 
-            view.paint(self);
-            paint_border(self, &view_style_props, size);
-            paint_outline(self, &view_style_props, size)
+                if opacity < 1.0 {
+                    self.paint_state.renderer.pop_layer();
+                }
+            }
         }
 
         let mut drag_set_to_none = false;
@@ -1492,7 +1561,9 @@ impl<'a> PaintCx<'a> {
                 let mut offset_scale = None;
                 if let Some(released_at) = dragging.released_at {
                     const LIMIT: f64 = 300.0;
-                    let elapsed = released_at.elapsed().as_millis() as f64;
+                    let elapsed = clock::now()
+                        .saturating_duration_since(released_at)
+                        .as_millis() as f64;
                     if elapsed < LIMIT {
                         offset_scale = Some(1.0 - elapsed / LIMIT);
                         exec_after(std::time::Duration::from_millis(8), move |_| {
@@ -1522,13 +1593,7 @@ impl<'a> PaintCx<'a> {
                     let mut view_style_props = view_state.view_style_props.clone();
                     let style = if let Some(dragging_style) = view_state.dragging_style.clone() {
                         let style = style.apply(dragging_style);
-                        let mut _new_frame = false;
-                        view_style_props.read_explicit(
-                            &style,
-                            &style,
-                            &Instant::now(),
-                            &mut _new_frame,
-                        );
+                        view_style_props.read_explicit(&style, &style);
                         style
                     } else {
                         style
@@ -1550,6 +1615,51 @@ impl<'a> PaintCx<'a> {
         self.restore();
     }
 
+    /// Paint a view like [`paint_view`](Self::paint_view), with an extra `transform` relative to
+    /// its layout position and faded by `opacity`. This is used for enter and exit transitions.
+    pub(crate) fn paint_view_with(
+        &mut self,
+        view: &mut dyn Widget,
+        transform: Affine,
+        opacity: f64,
+    ) {
+        let opacity = opacity.clamp(0.0, 1.0) as f32;
+        if opacity <= 0.0 {
+            return;
+        }
+
+        self.save();
+        if transform != Affine::IDENTITY {
+            let offset = self
+                .get_layout(view.view_data().id())
+                .map(|layout| Vec2::new(layout.location.x as f64, layout.location.y as f64))
+                .unwrap_or_default();
+            let transform = Affine::translate(offset) * transform * Affine::translate(-offset);
+            self.transform = self.transform * transform;
+            if let Some(rect) = self.clip.as_mut() {
+                let raidus = rect.radii();
+                *rect = if transform.determinant() != 0.0 {
+                    transform
+                        .inverse()
+                        .transform_rect_bbox(rect.rect())
+                        .to_rounded_rect(raidus)
+                } else {
+                    Rect::ZERO.to_rounded_rect(raidus)
+                };
+            }
+            self.paint_state.renderer.transform(self.transform);
+        }
+
+        if opacity < 1.0 {
+            self.paint_state.renderer.push_layer(opacity);
+        }
+        self.paint_view(view);
+        if opacity < 1.0 {
+            self.paint_state.renderer.pop_layer();
+        }
+        self.restore();
+    }
+
     pub fn layout(&self, node: NodeId) -> Option<Layout> {
         self.app_state.taffy.layout(node).ok().copied()
     }
@@ -1615,6 +1725,8 @@ impl<'a> PaintCx<'a> {
         }
     }
 
+    /// Moves the drawing origin to the layout position of the view and applies
+    /// the `translate`, `scale` and `rotate` style properties of the view.
     pub fn transform(&mut self, id: Id) -> Size {
         if let Some(layout) = self.get_layout(id) {
             let offset = layout.location;
@@ -1622,7 +1734,6 @@ impl<'a> PaintCx<'a> {
             new[4] += offset.x as f64;
             new[5] += offset.y as f64;
             self.transform = Affine::new(new);
-            self.paint_state.renderer.transform(self.transform);
 
             if let Some(rect) = self.clip.as_mut() {
                 let raidus = rect.radii();
@@ -1632,6 +1743,25 @@ impl<'a> PaintCx<'a> {
                     .to_rounded_rect(raidus);
             }
 
+            let view_transform = self.app_state.get_transform(id);
+            if view_transform != Affine::IDENTITY {
+                self.transform = self.transform * view_transform;
+                if let Some(rect) = self.clip.as_mut() {
+                    // The clip is tracked as a rect in view coordinates, so for rotations
+                    // this is the bounding box of the actual clip.
+                    let raidus = rect.radii();
+                    *rect = if view_transform.determinant() != 0.0 {
+                        view_transform
+                            .inverse()
+                            .transform_rect_bbox(rect.rect())
+                            .to_rounded_rect(raidus)
+                    } else {
+                        Rect::ZERO.to_rounded_rect(raidus)
+                    };
+                }
+            }
+            self.paint_state.renderer.transform(self.transform);
+
             Size::new(layout.size.width as f64, layout.size.height as f64)
         } else {
             Size::ZERO
@@ -1663,6 +1793,13 @@ impl PaintState {
         }
     }
 
+    /// Creates a paint state with an offscreen renderer which isn't tied to a window.
+    pub fn new_headless(scale: f64, size: Size) -> Self {
+        Self {
+            renderer: crate::renderer::Renderer::new_headless(scale, size),
+        }
+    }
+
     pub(crate) fn resize(&mut self, scale: f64, size: Size) {
         self.renderer.resize(scale, size);
     }
@@ -2,7 +2,8 @@ use floem_winit::{
     keyboard::{KeyCode, PhysicalKey},
     window::Theme,
 };
-use kurbo::{Point, Size};
+use kurbo::{Affine, Point, Size};
+use std::path::PathBuf;
 
 use crate::{
     keyboard::KeyEvent,
@@ -72,6 +73,20 @@ pub enum EventListener {
     WindowLostFocus,
     /// Receives [`Event::WindowMaximizeChanged`]
     WindowMaximizeChanged,
+    /// Receives [`Event::FileHover`]
+    FileHover,
+    /// Receives [`Event::FileDrop`]
+    FileDrop,
+    /// Receives [`Event::FileHoverCancelled`]
+    FileHoverCancelled,
+}
+
+/// A file being dragged over or dropped onto the window from outside the application.
+#[derive(Debug, Clone)]
+pub struct FileDragEvent {
+    pub path: PathBuf,
+    /// The pointer position when the file was hovered or dropped.
+    pub pos: Point,
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +114,14 @@ pub enum Event {
     ThemeChanged(Theme),
     FocusGained,
     FocusLost,
+    /// A file is dragged over the window. When multiple files are dragged,
+    /// this is sent once for each file.
+    FileHover(FileDragEvent),
+    /// A file is dropped onto the window. When multiple files are dropped,
+    /// this is sent once for each file.
+    FileDrop(FileDragEvent),
+    /// The files dragged over the window were moved out of it or the drag was cancelled.
+    FileHoverCancelled,
 }
 
 impl Event {
@@ -121,7 +144,10 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => false,
+            | Event::WindowLostFocus
+            | Event::FileHover(_)
+            | Event::FileDrop(_)
+            | Event::FileHoverCancelled => false,
             Event::KeyDown(_) | Event::KeyUp(_) => true,
         }
     }
@@ -147,7 +173,10 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => false,
+            | Event::WindowLostFocus
+            | Event::FileHover(_)
+            | Event::FileDrop(_)
+            | Event::FileHoverCancelled => false,
         }
     }
 
@@ -178,7 +207,9 @@ impl Event {
             | Event::ImePreedit { .. }
             | Event::ImeCommit(_)
             | Event::KeyDown(_)
-            | Event::KeyUp(_) => false,
+            | Event::KeyUp(_)
+            | Event::FileHover(_)
+            | Event::FileDrop(_) => false,
             Event::PointerLeave
             | Event::PointerMove(_)
             | Event::ThemeChanged(_)
@@ -187,7 +218,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowGotFocus
             | Event::WindowMaximizeChanged(_)
-            | Event::WindowLostFocus => true,
+            | Event::WindowLostFocus
+            | Event::FileHoverCancelled => true,
         }
     }
 
@@ -198,6 +230,7 @@ impl Event {
             }
             Event::PointerMove(pointer_event) => Some(pointer_event.pos),
             Event::PointerWheel(pointer_event) => Some(pointer_event.pos),
+            Event::FileHover(file_event) | Event::FileDrop(file_event) => Some(file_event.pos),
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -213,7 +246,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => None,
+            | Event::WindowLostFocus
+            | Event::FileHoverCancelled => None,
         }
     }
 
@@ -231,6 +265,10 @@ impl Event {
                 pointer_event.pos.x /= scale;
                 pointer_event.pos.y /= scale;
             }
+            Event::FileHover(file_event) | Event::FileDrop(file_event) => {
+                file_event.pos.x /= scale;
+                file_event.pos.y /= scale;
+            }
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -246,7 +284,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => {}
+            | Event::WindowLostFocus
+            | Event::FileHoverCancelled => {}
         }
         self
     }
@@ -262,6 +301,44 @@ impl Event {
             Event::PointerWheel(pointer_event) => {
                 pointer_event.pos -= offset;
             }
+            Event::FileHover(file_event) | Event::FileDrop(file_event) => {
+                file_event.pos -= offset;
+            }
+            Event::PointerLeave
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::ImeEnabled
+            | Event::ImeDisabled
+            | Event::ImePreedit { .. }
+            | Event::ThemeChanged(_)
+            | Event::ImeCommit(_)
+            | Event::WindowClosed
+            | Event::WindowResized(_)
+            | Event::WindowMoved(_)
+            | Event::WindowMaximizeChanged(_)
+            | Event::WindowGotFocus
+            | Event::WindowLostFocus
+            | Event::FileHoverCancelled => {}
+        }
+        self
+    }
+
+    pub fn transform(mut self, transform: Affine) -> Event {
+        match &mut self {
+            Event::PointerDown(pointer_event) | Event::PointerUp(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerMove(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::PointerWheel(pointer_event) => {
+                pointer_event.pos = transform * pointer_event.pos;
+            }
+            Event::FileHover(file_event) | Event::FileDrop(file_event) => {
+                file_event.pos = transform * file_event.pos;
+            }
             Event::PointerLeave
             | Event::KeyDown(_)
             | Event::KeyUp(_)
@@ -277,7 +354,8 @@ impl Event {
             | Event::WindowMoved(_)
             | Event::WindowMaximizeChanged(_)
             | Event::WindowGotFocus
-            | Event::WindowLostFocus => {}
+            | Event::WindowLostFocus
+            | Event::FileHoverCancelled => {}
         }
         self
     }
@@ -304,6 +382,9 @@ impl Event {
             Event::FocusLost => Some(EventListener::FocusLost),
             Event::FocusGained => Some(EventListener::FocusGained),
             Event::ThemeChanged(_) => Some(EventListener::ThemeChanged),
+            Event::FileHover(_) => Some(EventListener::FileHover),
+            Event::FileDrop(_) => Some(EventListener::FileDrop),
+            Event::FileHoverCancelled => Some(EventListener::FileHoverCancelled),
         }
     }
 }
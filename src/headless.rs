@@ -0,0 +1,751 @@
+//! # Headless windows
+//!
+//! A [`HeadlessWindow`] runs a view through the same event, style, layout and paint passes as a
+//! regular window, but without creating a platform window. Frames are painted with the
+//! tiny-skia renderer into an offscreen pixmap and returned as a [`DynamicImage`], which
+//! makes it useful for snapshot tests and for rendering views into images.
+//!
+//! ```no_run
+//! use floem::headless::HeadlessWindow;
+//! use floem::views::label;
+//!
+//! let mut window = HeadlessWindow::new(|| label(|| "Hello world"), (200.0, 100.0), 2.0);
+//! let image = window.render();
+//! assert_eq!(image.width(), 400);
+//! ```
+//!
+//! ## Testing interactions
+//!
+//! Synthetic events can be sent to the window with [`HeadlessWindow::event`] or one of the
+//! helpers like [`click`](HeadlessWindow::click) and [`key_press`](HeadlessWindow::key_press).
+//! Updates caused by the events are processed before the helpers return, so signals and layout
+//! can be inspected right away.
+//!
+//! While a headless window exists it drives a fake clock for its thread. Time only moves
+//! when [`advance`](HeadlessWindow::advance) is called, which fires the timers created with
+//! [`exec_after`](crate::action::exec_after) and steps animations and style transitions frame
+//! by frame.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use floem::headless::HeadlessWindow;
+//! use floem::reactive::create_rw_signal;
+//! use floem::views::{label, Decorators};
+//!
+//! let clicked = create_rw_signal(false);
+//! let mut window = HeadlessWindow::new(
+//!     move || {
+//!         label(|| "Click me")
+//!             .on_click_stop(move |_| clicked.set(true))
+//!             .debug_name("target")
+//!     },
+//!     (200.0, 100.0),
+//!     1.0,
+//! );
+//! let target = window.find_by_debug_name("target")[0];
+//! window.click_view(target);
+//! window.advance(Duration::from_millis(100));
+//! assert!(clicked.get());
+//! ```
+
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use floem_winit::event::{ElementState, MouseButton};
+use image::DynamicImage;
+use kurbo::{Point, Rect, Size, Vec2};
+
+use crate::{
+    accessibility::{ActionRequest, TreeUpdate},
+    action::Timer,
+    app::{AppUpdateEvent, APP_UPDATE_EVENTS},
+    clock,
+    context::AppState,
+    event::Event,
+    id::Id,
+    keyboard::{Key, KeyEvent, KeyInfo, ModifiersState},
+    pointer::PointerWheelEvent,
+    style::{StyleClass, StyleClassRef},
+    view::{View, Widget},
+    window_handle::WindowHandle,
+};
+
+/// The interval between the frames run by [`HeadlessWindow::advance`].
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// A window which isn't backed by a platform window and paints into an offscreen pixmap.
+pub struct HeadlessWindow {
+    pub(crate) handle: WindowHandle,
+    now: Instant,
+    /// The fake time which was set before this window's, restored when it's dropped
+    previous_now: Option<Instant>,
+    timers: Vec<Timer>,
+}
+
+impl HeadlessWindow {
+    /// Creates a headless window with the default theme applied.
+    ///
+    /// `size` is in logical pixels, the rendered image will be `size * scale` physical pixels.
+    pub fn new<V: View + 'static>(
+        app_view: impl FnOnce() -> V + 'static,
+        size: impl Into<Size>,
+        scale: f64,
+    ) -> Self {
+        Self::new_inner(app_view, size.into(), scale, true)
+    }
+
+    /// Creates a headless window without applying the default theme.
+    pub fn new_unthemed<V: View + 'static>(
+        app_view: impl FnOnce() -> V + 'static,
+        size: impl Into<Size>,
+        scale: f64,
+    ) -> Self {
+        Self::new_inner(app_view, size.into(), scale, false)
+    }
+
+    fn new_inner<V: View + 'static>(
+        app_view: impl FnOnce() -> V + 'static,
+        size: Size,
+        scale: f64,
+        apply_default_theme: bool,
+    ) -> Self {
+        let now = Instant::now();
+        let previous_now = clock::set_fake_now(Some(now));
+
+        let handle =
+            WindowHandle::new_headless(move |_| app_view().any(), size, scale, apply_default_theme);
+        let mut window = Self {
+            handle,
+            now,
+            previous_now,
+            timers: Vec::new(),
+        };
+        window.process_update();
+        window
+    }
+
+    /// The logical size of the window.
+    pub fn size(&self) -> Size {
+        self.handle.size_untracked()
+    }
+
+    /// The scale factor used to map logical pixels to physical pixels.
+    pub fn scale(&self) -> f64 {
+        self.handle.scale
+    }
+
+    /// Resizes the window, sending [`Event::WindowResized`] to the views.
+    pub fn set_size(&mut self, size: impl Into<Size>) {
+        self.handle.size(size.into());
+        self.collect_timers();
+    }
+
+    /// Changes the scale factor of the window.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.handle.scale(scale);
+        // Resize the pixmap to match the new physical size.
+        let size = self.size();
+        self.handle.size(size);
+        self.collect_timers();
+    }
+
+    /// Runs any pending style and layout passes and paints a frame.
+    pub fn render(&mut self) -> DynamicImage {
+        let img = self
+            .handle
+            .render_frame()
+            .expect("headless renderer should return the painted frame");
+        self.collect_timers();
+        img
+    }
+
+    /// Runs any pending updates and style and layout passes without painting.
+    pub fn process_update(&mut self) {
+        self.handle.process_scheduled_updates();
+        self.handle.process_update_no_paint();
+        self.collect_timers();
+    }
+
+    /// The current time of the fake clock.
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    /// Moves the fake clock forward by `duration`.
+    ///
+    /// Timers which are due are fired in order of their deadlines, and a frame is run every
+    /// 1/60th of a second so animations and transitions progress as they would on screen.
+    pub fn advance(&mut self, duration: Duration) {
+        let target = self.now + duration;
+        loop {
+            let frame_end = (self.now + FRAME_INTERVAL).min(target);
+            while let Some(timer) = self.take_timer_due(frame_end) {
+                self.set_now(timer.deadline.max(self.now));
+                (timer.action)(timer.token);
+                self.handle.process_update_no_paint();
+                self.collect_timers();
+            }
+            self.set_now(frame_end);
+            self.process_update();
+            if self.now >= target {
+                break;
+            }
+        }
+    }
+
+    /// The number of timers which haven't fired yet.
+    pub fn pending_timers(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Sends an event to the views and processes the resulting updates.
+    ///
+    /// Positions are in logical pixels relative to the window.
+    pub fn event(&mut self, event: Event) {
+        self.handle.event(event);
+        self.process_update();
+    }
+
+    /// Sets the modifiers used by the pointer and key helpers.
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.handle.modifiers = modifiers;
+    }
+
+    /// Moves the pointer to `pos`.
+    pub fn pointer_move(&mut self, pos: impl Into<Point>) {
+        self.handle.pointer_move(pos.into());
+        self.process_update();
+    }
+
+    /// Presses the primary pointer button at `pos`. Presses in quick succession at the
+    /// same position increase the click count like a platform window would.
+    pub fn pointer_down(&mut self, pos: impl Into<Point>) {
+        self.handle.pointer_move(pos.into());
+        self.handle
+            .mouse_input(MouseButton::Left, ElementState::Pressed);
+        self.process_update();
+    }
+
+    /// Releases the primary pointer button at `pos`.
+    pub fn pointer_up(&mut self, pos: impl Into<Point>) {
+        self.handle.pointer_move(pos.into());
+        self.handle
+            .mouse_input(MouseButton::Left, ElementState::Released);
+        self.process_update();
+    }
+
+    /// Drags a file from outside the application over the window at `pos`.
+    pub fn file_hover(&mut self, path: impl Into<PathBuf>, pos: impl Into<Point>) {
+        self.handle.cursor_position = pos.into();
+        self.handle.file_hover(path.into());
+        self.process_update();
+    }
+
+    /// Drops a file from outside the application onto the window at `pos`.
+    pub fn file_drop(&mut self, path: impl Into<PathBuf>, pos: impl Into<Point>) {
+        self.handle.cursor_position = pos.into();
+        self.handle.file_drop(path.into());
+        self.process_update();
+    }
+
+    /// Clicks the primary pointer button at `pos`.
+    pub fn click(&mut self, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.pointer_down(pos);
+        self.pointer_up(pos);
+    }
+
+    /// Clicks the center of the view.
+    pub fn click_view(&mut self, id: Id) {
+        let center = self.layout_rect(id).center();
+        self.click(center);
+    }
+
+    /// Drags the primary pointer button from `from` to `to` in `steps` pointer moves.
+    pub fn drag(&mut self, from: impl Into<Point>, to: impl Into<Point>, steps: usize) {
+        let (from, to) = (from.into(), to.into());
+        self.pointer_down(from);
+        let steps = steps.max(1);
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            self.pointer_move(from.lerp(to, t));
+        }
+        self.pointer_up(to);
+    }
+
+    /// Scrolls by `delta` at the current pointer position.
+    pub fn scroll(&mut self, delta: Vec2) {
+        let event = PointerWheelEvent {
+            pos: self.handle.cursor_position,
+            delta,
+            modifiers: self.handle.modifiers,
+        };
+        self.event(Event::PointerWheel(event));
+    }
+
+    /// Presses and releases a key.
+    pub fn key_press(&mut self, key: Key) {
+        let key = KeyInfo::pressed(key);
+        let modifiers = self.handle.modifiers;
+        self.event(Event::KeyDown(KeyEvent {
+            key: key.clone(),
+            modifiers,
+        }));
+        self.event(Event::KeyUp(KeyEvent {
+            key: key.released(),
+            modifiers,
+        }));
+    }
+
+    /// Types `text` into the focused view, one key press per character.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key_press(Key::Character(c.to_string().into()));
+        }
+    }
+
+    /// Builds the accessibility tree of the window. The nodes can be targeted with
+    /// [`accessibility_action`](Self::accessibility_action).
+    pub fn accessibility_tree(&mut self) -> TreeUpdate {
+        self.handle.accessibility_tree()
+    }
+
+    /// Performs an action requested by an assistive technology and processes the resulting
+    /// updates.
+    pub fn accessibility_action(&mut self, request: ActionRequest) {
+        self.handle.accessibility_action(request);
+        self.collect_timers();
+    }
+
+    /// The state of the views in the window.
+    pub fn app_state(&self) -> &AppState {
+        self.handle.app_state()
+    }
+
+    /// The id of the root view created by the `app_view` function.
+    pub fn root_id(&self) -> Id {
+        let mut root = None;
+        self.handle.root_widget().for_each_child(&mut |main| {
+            root = Some(main.view_data().id());
+            true
+        });
+        root.expect("window view should have a main view")
+    }
+
+    /// The layout rect of the view in window coordinates, including its children.
+    pub fn layout_rect(&mut self, id: Id) -> Rect {
+        self.handle.app_state_mut().get_layout_rect(id)
+    }
+
+    pub fn is_focused(&self, id: Id) -> bool {
+        self.app_state().is_focused(&id)
+    }
+
+    pub fn is_hovered(&self, id: Id) -> bool {
+        self.app_state().is_hovered(&id)
+    }
+
+    pub fn is_active(&self, id: Id) -> bool {
+        self.app_state().is_active(&id)
+    }
+
+    pub fn is_disabled(&self, id: Id) -> bool {
+        self.app_state().is_disabled(&id)
+    }
+
+    /// Returns the views matching `predicate`, in tree order.
+    pub fn find(&self, mut predicate: impl FnMut(&dyn Widget) -> bool) -> Vec<Id> {
+        fn walk(
+            view: &dyn Widget,
+            predicate: &mut dyn FnMut(&dyn Widget) -> bool,
+            found: &mut Vec<Id>,
+        ) {
+            if predicate(view) {
+                found.push(view.view_data().id());
+            }
+            view.for_each_child(&mut |child| {
+                walk(child, predicate, found);
+                false
+            });
+        }
+
+        let mut found = Vec::new();
+        walk(self.handle.root_widget(), &mut predicate, &mut found);
+        found
+    }
+
+    /// Returns the views with the given [`debug_name`](crate::views::Decorators::debug_name)
+    /// or widget debug name.
+    pub fn find_by_debug_name(&self, name: &str) -> Vec<Id> {
+        let app_state = self.app_state();
+        self.find(|view| {
+            view.debug_name() == name
+                || app_state
+                    .view_states
+                    .get(&view.view_data().id())
+                    .is_some_and(|state| state.debug_name.iter().any(|n| n == name))
+        })
+    }
+
+    /// Returns the views which have the style class `C`.
+    pub fn find_by_class<C: StyleClass>(&self) -> Vec<Id> {
+        let class = C::class_ref();
+        let has_class = |classes: &[StyleClassRef]| classes.iter().any(|c| c.key == class.key);
+        let app_state = self.app_state();
+        self.find(|view| {
+            view.view_class().is_some_and(|c| c.key == class.key)
+                || app_state
+                    .view_states
+                    .get(&view.view_data().id())
+                    .is_some_and(|state| has_class(&state.classes))
+        })
+    }
+
+    fn set_now(&mut self, now: Instant) {
+        self.now = now;
+        clock::set_fake_now(Some(now));
+    }
+
+    /// Takes the timer requests made with `exec_after`. Other app update events like
+    /// opening new windows aren't supported headlessly and are dropped.
+    fn collect_timers(&mut self) {
+        let events = APP_UPDATE_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()));
+        for event in events {
+            if let AppUpdateEvent::RequestTimer { timer } = event {
+                self.timers.push(timer);
+            }
+        }
+    }
+
+    fn take_timer_due(&mut self, deadline: Instant) -> Option<Timer> {
+        self.collect_timers();
+        let (idx, _) = self
+            .timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| timer.deadline <= deadline)
+            .min_by_key(|(_, timer)| (timer.deadline, timer.token.into_raw()))?;
+        Some(self.timers.remove(idx))
+    }
+}
+
+impl Drop for HeadlessWindow {
+    fn drop(&mut self) {
+        self.handle.destroy();
+        clock::set_fake_now(self.previous_now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use floem_peniko::Color;
+    use floem_reactive::create_rw_signal;
+
+    use super::HeadlessWindow;
+    use crate::{
+        accessibility::{Action, ActionRequest, Checked, Role, TreeUpdate},
+        action::exec_after,
+        clock,
+        style::Transition,
+        views::{clip, container, empty, h_stack, stack, v_stack, Decorators},
+        widgets::{button, checkbox},
+    };
+
+    #[test]
+    fn render_snapshot() {
+        let mut window = HeadlessWindow::new_unthemed(
+            || {
+                h_stack((
+                    empty().style(|s| s.size(50.0, 50.0).background(Color::RED)),
+                    empty().style(|s| s.size(50.0, 50.0).background(Color::BLUE)),
+                ))
+                .style(|s| s.size_full().background(Color::WHITE))
+            },
+            (100.0, 100.0),
+            2.0,
+        );
+
+        let img = window.render().into_rgba8();
+        assert_eq!((img.width(), img.height()), (200, 200));
+        assert_eq!(img.get_pixel(50, 50).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(150, 50).0, [0, 0, 255, 255]);
+        assert_eq!(img.get_pixel(100, 150).0, [255, 255, 255, 255]);
+
+        window.set_size((60.0, 40.0));
+        window.set_scale(1.0);
+        let img = window.render().into_rgba8();
+        assert_eq!((img.width(), img.height()), (60, 40));
+        assert_eq!(img.get_pixel(25, 25).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(55, 25).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn click_and_timers() {
+        let clicks = create_rw_signal(0);
+        let fired = create_rw_signal(false);
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                empty()
+                    .style(|s| s.size(50.0, 50.0))
+                    .on_click_stop(move |_| {
+                        clicks.update(|c| *c += 1);
+                        exec_after(Duration::from_millis(100), move |_| fired.set(true));
+                    })
+                    .debug_name("target")
+            },
+            (100.0, 100.0),
+            1.0,
+        );
+
+        let target = window.find_by_debug_name("target");
+        assert_eq!(target.len(), 1);
+        window.click_view(target[0]);
+        assert_eq!(clicks.get_untracked(), 1);
+        assert_eq!(window.pending_timers(), 1);
+
+        window.advance(Duration::from_millis(50));
+        assert!(!fired.get_untracked());
+        window.advance(Duration::from_millis(50));
+        assert!(fired.get_untracked());
+        assert_eq!(window.pending_timers(), 0);
+
+        let img = window.render();
+        assert_eq!((img.width(), img.height()), (100, 100));
+    }
+
+    #[test]
+    fn transformed_hit_testing() {
+        let clicks = create_rw_signal(0);
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                container(
+                    empty()
+                        .style(|s| {
+                            s.size(20.0, 20.0)
+                                .background(Color::RED)
+                                .translate(50.0, 50.0)
+                                .scale(2.0)
+                        })
+                        .on_click_stop(move |_| clicks.update(|c| *c += 1)),
+                )
+                .style(|s| s.size_full())
+            },
+            (100.0, 100.0),
+            1.0,
+        );
+
+        // Scaled around its center the view covers (40, 40) to (80, 80).
+        window.click((10.0, 10.0));
+        assert_eq!(clicks.get_untracked(), 0);
+        window.click((45.0, 45.0));
+        assert_eq!(clicks.get_untracked(), 1);
+        window.click((75.0, 75.0));
+        assert_eq!(clicks.get_untracked(), 2);
+
+        let img = window.render().into_rgba8();
+        assert_ne!(img.get_pixel(10, 10).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(60, 60).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn opacity_fades_subtree() {
+        let mut window = HeadlessWindow::new_unthemed(
+            || {
+                container(empty().style(|s| s.size_full().background(Color::RED)))
+                    .style(|s| s.size_full().opacity(0.5))
+            },
+            (20.0, 20.0),
+            1.0,
+        );
+
+        let img = window.render().into_rgba8();
+        let [r, g, b, a] = img.get_pixel(10, 10).0;
+        assert_eq!((r, a), (255, 255));
+        assert!((126..=129).contains(&g) && g == b, "{:?}", (g, b));
+    }
+
+    #[test]
+    fn opacity_composites_overlapping_views_once() {
+        let mut window = HeadlessWindow::new_unthemed(
+            || {
+                stack((
+                    empty().style(|s| s.absolute().size_full().background(Color::RED)),
+                    empty().style(|s| s.absolute().size_full().background(Color::RED)),
+                ))
+                .style(|s| s.size_full().opacity(0.5))
+            },
+            (20.0, 20.0),
+            1.0,
+        );
+
+        // Blending each view on its own would make the overlap darker
+        let img = window.render().into_rgba8();
+        let [r, g, _, _] = img.get_pixel(10, 10).0;
+        assert_eq!(r, 255);
+        assert!((126..=129).contains(&g), "{g}");
+    }
+
+    #[test]
+    fn opacity_layer_inside_clip() {
+        let mut window = HeadlessWindow::new_unthemed(
+            || {
+                container(clip(
+                    container(empty().style(|s| s.size_full().background(Color::RED)))
+                        .style(|s| s.size(20.0, 20.0).opacity(0.5)),
+                ))
+                .style(|s| s.size_full().padding(10.0))
+            },
+            (40.0, 40.0),
+            2.0,
+        );
+
+        let img = window.render().into_rgba8();
+        let [r, g, _, _] = img.get_pixel(40, 40).0;
+        assert_eq!(r, 255);
+        assert!((126..=129).contains(&g), "{g}");
+        assert_eq!(img.get_pixel(10, 10).0, [255, 255, 255, 255]);
+        assert_eq!(img.get_pixel(70, 70).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn size_transitions_through_layout() {
+        let wide = create_rw_signal(false);
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                empty()
+                    .style(move |s| {
+                        s.height(10.0)
+                            .width(if wide.get() { 100.0 } else { 0.0 })
+                            .transition_all(Transition::linear(0.1))
+                    })
+                    .debug_name("target")
+            },
+            (200.0, 50.0),
+            1.0,
+        );
+        let target = window.find_by_debug_name("target")[0];
+        assert_eq!(window.layout_rect(target).width(), 0.0);
+
+        wide.set(true);
+        window.advance(Duration::from_millis(50));
+        let width = window.layout_rect(target).width();
+        assert!(width > 20.0 && width < 80.0, "{width}");
+
+        window.advance(Duration::from_millis(100));
+        assert_eq!(window.layout_rect(target).width(), 100.0);
+    }
+
+    #[test]
+    fn file_drop_routed_to_view_under_pointer() {
+        let dropped = create_rw_signal(Vec::new());
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                h_stack((
+                    empty()
+                        .style(|s| s.size(50.0, 50.0).file_hover(|s| s.background(Color::RED)))
+                        .on_file_drop(move |e| dropped.update(|d| d.push(e.path.clone())))
+                        .debug_name("target"),
+                    empty().style(|s| s.size(50.0, 50.0)),
+                ))
+            },
+            (100.0, 50.0),
+            1.0,
+        );
+        let target = window.find_by_debug_name("target")[0];
+
+        window.file_hover("image.png", (75.0, 25.0));
+        assert!(!window.app_state().is_file_hovered(&target));
+        window.file_hover("image.png", (25.0, 25.0));
+        assert!(window.app_state().is_file_hovered(&target));
+        let img = window.render().into_rgba8();
+        assert_eq!(img.get_pixel(25, 25).0, [255, 0, 0, 255]);
+
+        window.file_drop("image.png", (25.0, 25.0));
+        assert!(!window.app_state().is_file_hovered(&target));
+        assert_eq!(
+            dropped.get_untracked(),
+            vec![std::path::PathBuf::from("image.png")]
+        );
+    }
+
+    #[test]
+    fn accessibility_tree_and_actions() {
+        let clicks = create_rw_signal(0);
+        let checked = create_rw_signal(false);
+        let mut window = HeadlessWindow::new(
+            move || {
+                v_stack((
+                    button(|| "Save").on_click_stop(move |_| clicks.update(|c| *c += 1)),
+                    checkbox(move || checked.get()).on_update(move |c| checked.set(c)),
+                ))
+            },
+            (200.0, 100.0),
+            1.0,
+        );
+
+        let find = |tree: &TreeUpdate, role: Role| {
+            tree.nodes
+                .iter()
+                .find(|(_, node)| node.role() == role)
+                .map(|(id, node)| (*id, node.clone()))
+                .unwrap()
+        };
+
+        let tree = window.accessibility_tree();
+        let root = tree.tree.as_ref().unwrap().root;
+        let (_, window_node) = tree.nodes.iter().find(|(id, _)| *id == root).unwrap();
+        assert_eq!(window_node.role(), Role::Window);
+        assert_eq!(window_node.children().len(), 2);
+        assert_eq!(tree.focus, root);
+
+        let (button_id, button_node) = find(&tree, Role::Button);
+        assert_eq!(button_node.name(), Some("Save"));
+        let (checkbox_id, checkbox_node) = find(&tree, Role::CheckBox);
+        assert_eq!(checkbox_node.checked(), Some(Checked::False));
+
+        window.accessibility_action(ActionRequest {
+            action: Action::Default,
+            target: button_id,
+            data: None,
+        });
+        assert_eq!(clicks.get_untracked(), 1);
+
+        window.accessibility_action(ActionRequest {
+            action: Action::Default,
+            target: checkbox_id,
+            data: None,
+        });
+        window.accessibility_action(ActionRequest {
+            action: Action::Focus,
+            target: checkbox_id,
+            data: None,
+        });
+        assert!(checked.get_untracked());
+
+        let tree = window.accessibility_tree();
+        assert_eq!(tree.focus, checkbox_id);
+        let (_, checkbox_node) = find(&tree, Role::CheckBox);
+        assert_eq!(checkbox_node.checked(), Some(Checked::True));
+    }
+
+    #[test]
+    fn dropping_a_window_restores_the_clock_of_the_others() {
+        let outer = HeadlessWindow::new_unthemed(empty, (10.0, 10.0), 1.0);
+        let start = outer.now();
+        {
+            let mut inner = HeadlessWindow::new_unthemed(empty, (10.0, 10.0), 1.0);
+            inner.advance(Duration::from_secs(1));
+            assert_eq!(clock::now(), inner.now());
+        }
+        assert_eq!(clock::now(), start);
+
+        drop(outer);
+        let before = std::time::Instant::now();
+        assert!(clock::now() >= before);
+    }
+}
@@ -9,6 +9,7 @@ use std::{any::Any, cell::RefCell, collections::HashMap, sync::atomic::AtomicU64
 use kurbo::{Point, Rect};
 
 use crate::{
+    accessibility::Accessibility,
     animate::Animation,
     context::{EventCallback, MenuCallback, ResizeCallback},
     event::EventListener,
@@ -158,6 +159,10 @@ impl Id {
         self.add_update_message(UpdateMessage::AddClass { id: *self, class });
     }
 
+    pub fn add_debug_name(&self, name: String) {
+        self.add_update_message(UpdateMessage::DebugName { id: *self, name });
+    }
+
     pub(crate) fn update_style_selector(&self, style: Style, selector: StyleSelector) {
         self.add_update_message(UpdateMessage::StyleSelector {
             id: *self,
@@ -201,6 +206,13 @@ impl Id {
         });
     }
 
+    pub fn update_accessibility(&self, accessibility: Accessibility) {
+        self.add_update_message(UpdateMessage::Accessibility {
+            id: *self,
+            accessibility,
+        });
+    }
+
     pub fn clear_focus(&self) {
         self.add_update_message(UpdateMessage::ClearFocus(*self));
     }
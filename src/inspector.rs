@@ -3,6 +3,7 @@ use crate::context::{AppState, StyleCx};
 use crate::event::{Event, EventListener};
 use crate::id::Id;
 use crate::profiler::profiler;
+use crate::reactive_inspector::reactive_inspector;
 use crate::style::{Style, StylePropRef, Transition};
 use crate::view::{view_children, AnyView, View, Widget};
 use crate::view_data::ChangeFlags;
@@ -887,12 +888,16 @@ pub fn capture(window_id: WindowId) {
                         })
                 };
 
-                let tabs = h_stack((tab_item("Views", 0), tab_item("Profiler", 1)))
-                    .style(|s| s.background(Color::WHITE));
+                let tabs = h_stack((
+                    tab_item("Views", 0),
+                    tab_item("Profiler", 1),
+                    tab_item("Reactive", 2),
+                ))
+                .style(|s| s.background(Color::WHITE));
 
                 let tab = tab(
                     move || selected.get(),
-                    move || [0, 1].into_iter(),
+                    move || [0, 1, 2].into_iter(),
                     |it| *it,
                     move |it| match it {
                         0 => dyn_container(
@@ -902,6 +907,7 @@ pub fn capture(window_id: WindowId) {
                         .style(|s| s.width_full().height_full())
                         .any(),
                         1 => profiler(window_id).any(),
+                        2 => reactive_inspector().any(),
                         _ => panic!(),
                     },
                 )
@@ -1,7 +1,70 @@
-pub use floem_winit::keyboard::{Key, KeyCode, ModifiersState, NamedKey, NativeKey, PhysicalKey};
+pub use floem_winit::event::ElementState;
+pub use floem_winit::keyboard::{
+    Key, KeyCode, KeyLocation, ModifiersState, NamedKey, NativeKey, PhysicalKey, SmolStr,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEvent {
-    pub key: floem_winit::event::KeyEvent,
+    pub key: KeyInfo,
     pub modifiers: ModifiersState,
 }
+
+/// The key of a [`KeyEvent`].
+///
+/// This mirrors the fields of winit's key event, but unlike it can be constructed outside of
+/// the platform event loop, e.g. to send synthetic key presses to a
+/// [`HeadlessWindow`](crate::headless::HeadlessWindow).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeyInfo {
+    pub physical_key: PhysicalKey,
+    pub logical_key: Key,
+    pub text: Option<SmolStr>,
+    pub location: KeyLocation,
+    pub state: ElementState,
+    pub repeat: bool,
+}
+
+impl KeyInfo {
+    /// Creates a pressed key without a physical key code.
+    /// Characters keys will also carry their text.
+    pub fn pressed(logical_key: Key) -> Self {
+        let text = match &logical_key {
+            Key::Character(c) => Some(c.clone()),
+            Key::Named(NamedKey::Space) => Some(SmolStr::new(" ")),
+            _ => None,
+        };
+        let physical_key = match &logical_key {
+            Key::Named(NamedKey::Enter) => PhysicalKey::Code(KeyCode::Enter),
+            Key::Named(NamedKey::Space) => PhysicalKey::Code(KeyCode::Space),
+            _ => PhysicalKey::Unidentified(floem_winit::keyboard::NativeKeyCode::Unidentified),
+        };
+        Self {
+            physical_key,
+            logical_key,
+            text,
+            location: KeyLocation::Standard,
+            state: ElementState::Pressed,
+            repeat: false,
+        }
+    }
+
+    /// Returns the same key in the released state.
+    pub fn released(mut self) -> Self {
+        self.state = ElementState::Released;
+        self.repeat = false;
+        self
+    }
+}
+
+impl From<floem_winit::event::KeyEvent> for KeyInfo {
+    fn from(key: floem_winit::event::KeyEvent) -> Self {
+        Self {
+            physical_key: key.physical_key,
+            logical_key: key.logical_key,
+            text: key.text,
+            location: key.location,
+            state: key.state,
+            repeat: key.repeat,
+        }
+    }
+}
@@ -75,6 +75,8 @@
 //!
 //! Global state can be implemented using [provide_context](floem_reactive::provide_context) and
 //! [use_context](floem_reactive::use_context).
+//! Contexts are attached to the current [`Scope`](floem_reactive::Scope) and are visible to its child
+//! scopes, so a subtree created in a child scope can be given its own values.
 //!
 //! ## Customizing appearance
 //!
@@ -149,22 +151,29 @@
 //! - [Understanding Ids](crate::id)
 //! - [How the update lifecycle works](crate::renderer)
 //!
+pub mod accessibility;
 pub mod action;
 pub mod animate;
 mod app;
 mod app_handle;
+pub mod brush;
 mod clipboard;
+mod clock;
 pub mod context;
 pub mod event;
 pub mod ext_event;
 pub mod file;
+pub mod headless;
 pub mod id;
 mod inspector;
 pub mod keyboard;
 pub mod menu;
 mod nav;
+#[cfg(feature = "serde")]
+pub mod persist;
 pub mod pointer;
 mod profiler;
+mod reactive_inspector;
 pub mod renderer;
 pub mod responsive;
 pub mod style;
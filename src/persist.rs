@@ -0,0 +1,436 @@
+//! # Persisted signals
+//!
+//! Signals created with [`create_persisted_signal`] save their value whenever it changes and
+//! start with the saved value the next time the app runs. Values are kept as JSON in a
+//! [`PersistStorage`], which is a file in the user's config directory unless a different
+//! [`Persistence`] is installed.
+//!
+//! ```no_run
+//! use floem::persist::{create_persisted_signal, Persistence};
+//!
+//! let installed = Persistence::json_file("state.json")
+//!     .version(2)
+//!     // Version 1 saved the sidebar width under a different key
+//!     .migration(1, |values| {
+//!         if let Some(width) = values.remove("sidebar") {
+//!             values.insert("sidebar_width".to_string(), width);
+//!         }
+//!     })
+//!     .install();
+//! if let Err(err) = installed {
+//!     // The signals start with their defaults, and the unreadable file isn't saved over
+//!     eprintln!("can't load the saved state: {err}");
+//! }
+//!
+//! let sidebar_width = create_persisted_signal("sidebar_width", 250.0);
+//! ```
+//!
+//! Windows can restore their size, position and maximized state from the same storage with
+//! [`WindowConfig::restore_state`](crate::window::WindowConfig::restore_state).
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Size};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::action::exec_after;
+
+/// Everything saved by the persisted signals, along with the version of its layout.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersistedState {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub values: Map<String, Value>,
+}
+
+/// Where the [`PersistedState`] is loaded from and saved to.
+pub trait PersistStorage {
+    /// Load the saved state, or `None` if nothing was saved yet
+    fn load(&self) -> io::Result<Option<PersistedState>>;
+
+    fn save(&self, state: &PersistedState) -> io::Result<()>;
+}
+
+/// Saves the state in a JSON file.
+pub struct JsonFileStorage {
+    path: PathBuf,
+}
+
+impl JsonFileStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl PersistStorage for JsonFileStorage {
+    fn load(&self) -> io::Result<Option<PersistedState>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn save(&self, state: &PersistedState) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(state)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // Write to a temporary file first so that a crash doesn't leave a truncated file
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+/// Changes the saved values from the layout of one version to the next
+type Migration = Box<dyn Fn(&mut Map<String, Value>)>;
+
+/// How persisted signals are stored, set up with [`Persistence::install`].
+pub struct Persistence {
+    storage: Box<dyn PersistStorage>,
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+    debounce: Duration,
+}
+
+impl Persistence {
+    pub fn new(storage: impl PersistStorage + 'static) -> Self {
+        Self {
+            storage: Box::new(storage),
+            version: 0,
+            migrations: BTreeMap::new(),
+            debounce: Duration::from_millis(500),
+        }
+    }
+
+    /// Store the values in a JSON file at `path`
+    pub fn json_file(path: impl Into<PathBuf>) -> Self {
+        Self::new(JsonFileStorage::new(path))
+    }
+
+    /// The current version of the layout of the saved values. Saved values from an older version
+    /// are updated by the [migrations](Self::migration) when they are loaded, values from a newer
+    /// version are loaded without changes.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Add a migration which updates the saved values from version `from` to `from + 1`
+    pub fn migration(
+        mut self,
+        from: u32,
+        migrate: impl Fn(&mut Map<String, Value>) + 'static,
+    ) -> Self {
+        self.migrations.insert(from, Box::new(migrate));
+        self
+    }
+
+    /// How long to wait after a change before saving, so that a burst of changes is only saved
+    /// once. The default is 500ms.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Load the saved values and use this for the persisted signals created after this on the
+    /// current thread.
+    ///
+    /// If the saved values can't be loaded, the error is returned and the signals start with their
+    /// defaults, but nothing is saved so the values which couldn't be read aren't replaced. The
+    /// app can move the saved values out of the way and install the persistence again.
+    pub fn install(self) -> io::Result<()> {
+        let (mut state, result) = match self.storage.load() {
+            Ok(state) => (state.unwrap_or_default(), Ok(())),
+            Err(e) => (PersistedState::default(), Err(e)),
+        };
+        self.migrate(&mut state);
+
+        let persistence = Rc::new(PersistenceState {
+            storage: self.storage,
+            debounce: self.debounce,
+            state: RefCell::new(state),
+            loaded: result.is_ok(),
+            save_scheduled: Cell::new(false),
+        });
+        PERSISTENCE.with(|p| *p.borrow_mut() = Some(persistence));
+        result
+    }
+
+    fn migrate(&self, state: &mut PersistedState) {
+        if state.values.is_empty() {
+            state.version = self.version;
+            return;
+        }
+        // Values saved by a newer version of the app are left as they are
+        if state.version < self.version {
+            for (_, migrate) in self.migrations.range(state.version..self.version) {
+                migrate(&mut state.values);
+            }
+            state.version = self.version;
+        }
+    }
+}
+
+struct PersistenceState {
+    storage: Box<dyn PersistStorage>,
+    debounce: Duration,
+    state: RefCell<PersistedState>,
+    /// Whether the saved state was loaded, without which saving would replace the values which
+    /// couldn't be read
+    loaded: bool,
+    save_scheduled: Cell<bool>,
+}
+
+impl PersistenceState {
+    fn save(&self) -> io::Result<()> {
+        self.save_scheduled.set(false);
+        self.storage.save(&self.state.borrow())
+    }
+}
+
+thread_local! {
+    static PERSISTENCE: RefCell<Option<Rc<PersistenceState>>> = const { RefCell::new(None) };
+}
+
+/// The installed persistence, or a JSON file in the config directory if none is installed
+fn persistence() -> Rc<PersistenceState> {
+    if let Some(persistence) = PERSISTENCE.with(|p| p.borrow().clone()) {
+        return persistence;
+    }
+    // Nothing is saved if this fails, and the signals use their defaults
+    let _ = Persistence::json_file(default_path()).install();
+    PERSISTENCE.with(|p| p.borrow().clone().unwrap())
+}
+
+/// `<config dir>/<executable name>/state.json`
+fn default_path() -> PathBuf {
+    let app_name = std::env::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "floem".to_string());
+
+    let config_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    config_dir
+        .unwrap_or_else(std::env::temp_dir)
+        .join(app_name)
+        .join("state.json")
+}
+
+/// Get the saved value for `key`, if there is one and it can be deserialized as `T`
+pub fn load_persisted<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let persistence = persistence();
+    let state = persistence.state.borrow();
+    let value = state.values.get(key)?;
+    T::deserialize(value).ok()
+}
+
+/// Save the value for `key`, after the debounce delay of the installed [`Persistence`]
+pub fn store_persisted<T: Serialize>(key: &str, value: &T) {
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    let persistence = persistence();
+    persistence
+        .state
+        .borrow_mut()
+        .values
+        .insert(key.to_string(), value);
+
+    if persistence.loaded && !persistence.save_scheduled.get() {
+        persistence.save_scheduled.set(true);
+        let persistence = persistence.clone();
+        exec_after(persistence.debounce, move |_| {
+            let _ = persistence.save();
+        });
+    }
+}
+
+/// Save all persisted values now, rather than waiting for the debounce delay. This is done
+/// automatically when the app exits.
+pub fn save_persisted() -> io::Result<()> {
+    match PERSISTENCE.with(|p| p.borrow().clone()) {
+        Some(persistence) if persistence.save_scheduled.get() => persistence.save(),
+        _ => Ok(()),
+    }
+}
+
+/// Create a signal which starts with the value saved for `key`, or `default` if there is none,
+/// and saves its value whenever it changes.
+pub fn create_persisted_signal<T>(key: impl Into<String>, default: T) -> RwSignal<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    let key = key.into();
+    let signal = create_rw_signal(load_persisted(&key).unwrap_or(default));
+    create_effect(move |prev: Option<()>| {
+        signal.with(|value| {
+            // The first run only subscribes, since the value was just loaded
+            if prev.is_some() {
+                store_persisted(&key, value);
+            }
+        });
+    });
+    signal
+}
+
+/// The size, position and maximized state of a window saved by
+/// [`WindowConfig::restore_state`](crate::window::WindowConfig::restore_state).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    /// The size of the window when it isn't maximized
+    pub size: Size,
+    /// The position of the window when it isn't maximized
+    pub position: Option<Point>,
+    pub maximized: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStorage(Rc<RefCell<Option<PersistedState>>>);
+
+    impl PersistStorage for MemoryStorage {
+        fn load(&self) -> io::Result<Option<PersistedState>> {
+            Ok(self.0.borrow().clone())
+        }
+
+        fn save(&self, state: &PersistedState) -> io::Result<()> {
+            *self.0.borrow_mut() = Some(state.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn migrations_run_from_the_saved_version() {
+        let saved = Rc::new(RefCell::new(Some(PersistedState {
+            version: 1,
+            values: json!({ "a": 1 }).as_object().unwrap().clone(),
+        })));
+
+        Persistence::new(MemoryStorage(saved.clone()))
+            .version(3)
+            .migration(0, |values| {
+                values.insert("from_0".to_string(), json!(true));
+            })
+            .migration(1, |values| {
+                let a = values.remove("a").unwrap();
+                values.insert("b".to_string(), a);
+            })
+            .migration(2, |values| {
+                values.insert("c".to_string(), json!("new"));
+            })
+            .install()
+            .unwrap();
+
+        assert_eq!(load_persisted::<u32>("b"), Some(1));
+        assert_eq!(load_persisted::<String>("c").as_deref(), Some("new"));
+        assert_eq!(load_persisted::<bool>("from_0"), None);
+        assert_eq!(load_persisted::<u32>("a"), None);
+        // Values of the wrong type are ignored
+        assert_eq!(load_persisted::<String>("b"), None);
+    }
+
+    #[test]
+    fn newer_saved_version_is_kept() {
+        let saved = Rc::new(RefCell::new(Some(PersistedState {
+            version: 5,
+            values: json!({ "a": 1 }).as_object().unwrap().clone(),
+        })));
+
+        Persistence::new(MemoryStorage(saved.clone()))
+            .version(2)
+            .migration(1, |values| {
+                values.insert("b".to_string(), json!(2));
+            })
+            .install()
+            .unwrap();
+
+        assert_eq!(load_persisted::<u32>("a"), Some(1));
+        assert_eq!(load_persisted::<u32>("b"), None);
+        assert_eq!(persistence().state.borrow().version, 5);
+    }
+
+    #[test]
+    fn json_file_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("floem-persist-{}", std::process::id()))
+            .join("state.json");
+        let storage = JsonFileStorage::new(&path);
+        assert_eq!(storage.load().unwrap(), None);
+
+        let state = PersistedState {
+            version: 2,
+            values: json!({ "size": [1.0, 2.0] }).as_object().unwrap().clone(),
+        };
+        storage.save(&state).unwrap();
+        assert_eq!(storage.load().unwrap(), Some(state));
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    struct UnreadableStorage(Rc<Cell<usize>>);
+
+    impl PersistStorage for UnreadableStorage {
+        fn load(&self) -> io::Result<Option<PersistedState>> {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "unreadable"))
+        }
+
+        fn save(&self, _state: &PersistedState) -> io::Result<()> {
+            self.0.set(self.0.get() + 1);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn unreadable_state_is_not_saved_over() {
+        let saves = Rc::new(Cell::new(0));
+        let result = Persistence::new(UnreadableStorage(saves.clone())).install();
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let signal = create_persisted_signal("a", 1);
+        assert_eq!(signal.get_untracked(), 1);
+        signal.set(2);
+        save_persisted().unwrap();
+        assert_eq!(saves.get(), 0);
+    }
+}
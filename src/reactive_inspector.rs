@@ -0,0 +1,207 @@
+use crate::inspector::header;
+use crate::view::View;
+use crate::views::{
+    dyn_container, empty, h_stack, label, scroll, stack, static_label, text, v_stack,
+    v_stack_from_iter, Decorators,
+};
+use crate::widgets::button;
+use crate::Clipboard;
+use floem_peniko::Color;
+use floem_reactive::{create_rw_signal, reactive_graph, GraphNode, NodeKind, ReactiveGraph};
+use std::rc::Rc;
+use taffy::style::FlexDirection;
+
+fn info_row(name: &'static str, view: impl View + 'static) -> impl View {
+    stack((
+        stack((static_label(name).style(|s| {
+            s.margin_right(5.0)
+                .color(Color::BLACK.with_alpha_factor(0.6))
+        }),))
+        .style(|s| s.min_width(120.0).flex_direction(FlexDirection::RowReverse)),
+        view,
+    ))
+    .style(|s| {
+        s.padding(5.0)
+            .hover(|s| s.background(Color::rgba8(228, 237, 216, 160)))
+    })
+}
+
+fn node_name(node: &GraphNode) -> String {
+    let name = node.kind.name();
+    match node.type_name {
+        Some(type_name) => format!("{name} #{}: {type_name}", node.id),
+        None => format!("{name} #{}", node.id),
+    }
+}
+
+/// A list of the given nodes with where they were created
+fn node_list(graph: &ReactiveGraph, ids: &[u64]) -> impl View {
+    let nodes: Vec<_> = ids
+        .iter()
+        .filter_map(|id| graph.node(*id))
+        .map(|node| {
+            v_stack((
+                static_label(node_name(node)),
+                static_label(node.location.to_string())
+                    .style(|s| s.color(Color::BLACK.with_alpha_factor(0.6))),
+            ))
+            .style(|s| s.padding(5.0))
+        })
+        .collect();
+    if nodes.is_empty() {
+        text("None").style(|s| s.padding(5.0)).any()
+    } else {
+        v_stack_from_iter(nodes).any()
+    }
+}
+
+fn effect_view(graph: &Rc<ReactiveGraph>, effect: &GraphNode) -> impl View {
+    let owner = effect
+        .owner
+        .and_then(|owner| graph.node(owner))
+        .map(node_name)
+        .unwrap_or_else(|| "None".to_string());
+    v_stack((
+        info_row("Effect", static_label(effect.id.to_string())),
+        info_row("Created At", static_label(effect.location.to_string())),
+        info_row("Runs", static_label(effect.run_count.to_string())),
+        info_row("Owner", static_label(owner)),
+        header("Caused Last Run"),
+        node_list(graph, &effect.caused_by),
+        header("Dependencies"),
+        node_list(graph, &effect.dependencies),
+    ))
+    .style(|s| s.width_full())
+}
+
+fn graph_view(graph: &Rc<ReactiveGraph>) -> impl View {
+    let mut effects: Vec<&GraphNode> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Effect)
+        .collect();
+    effects.sort_by(|a, b| b.run_count.cmp(&a.run_count));
+
+    let selected = create_rw_signal(None::<u64>);
+
+    let effect_list: Vec<_> = effects
+        .iter()
+        .map(|effect| {
+            let id = effect.id;
+            v_stack((
+                h_stack((
+                    static_label(format!("Effect #{id}")).style(|s| s.flex_grow(1.0)),
+                    static_label(format!("{} runs", effect.run_count))
+                        .style(|s| s.margin_right(16)),
+                )),
+                static_label(effect.location.to_string())
+                    .style(|s| s.color(Color::BLACK.with_alpha_factor(0.6))),
+            ))
+            .on_click_stop(move |_| selected.set(Some(id)))
+            .style(move |s| {
+                let selected = selected.get() == Some(id);
+                s.padding(5.0)
+                    .width_full()
+                    .apply_if(selected, |s| s.background(Color::rgb8(213, 208, 216)))
+                    .hover(move |s| {
+                        s.background(Color::rgba8(228, 237, 216, 160))
+                            .apply_if(selected, |s| s.background(Color::rgb8(186, 180, 216)))
+                    })
+            })
+        })
+        .collect();
+
+    let count = |kind| graph.nodes.iter().filter(|node| node.kind == kind).count();
+    let summary = static_label(format!(
+        "{} signals, {} memos, {} effects, {} scopes",
+        count(NodeKind::Signal),
+        count(NodeKind::Memo),
+        count(NodeKind::Effect),
+        count(NodeKind::Scope),
+    ))
+    .style(|s| s.padding(5.0));
+
+    let effects = v_stack((
+        header("Effects by Runs"),
+        summary,
+        scroll(v_stack_from_iter(effect_list).style(|s| s.width_full())).style(|s| {
+            s.background(Color::WHITE)
+                .flex_basis(0)
+                .min_height(0)
+                .flex_grow(1.0)
+        }),
+    ))
+    .style(|s| s.max_width_pct(60.0).min_width(200.0));
+
+    let separator = empty().style(move |s| {
+        s.height_full()
+            .min_width(1.0)
+            .background(Color::BLACK.with_alpha_factor(0.2))
+    });
+
+    let graph = graph.clone();
+    let details = dyn_container(
+        move || selected.get(),
+        move |id| {
+            if let Some(effect) = id.and_then(|id| graph.node(id)) {
+                scroll(effect_view(&graph, effect))
+                    .style(|s| s.height_full().width_full())
+                    .any()
+            } else {
+                text("No selected effect").style(|s| s.padding(5.0)).any()
+            }
+        },
+    )
+    .style(|s| {
+        s.width_full()
+            .min_height(0)
+            .flex_basis(0)
+            .flex_grow(1.0)
+            .background(Color::WHITE)
+    });
+
+    let details = v_stack((header("Selected Effect"), details))
+        .style(|s| s.min_width(0).flex_basis(0).flex_grow(1.0));
+
+    h_stack((effects, separator, details)).style(|s| s.height_full().width_full().max_width_full())
+}
+
+/// A panel showing the Signals, Effects and Scopes of the app, and what caused Effects to run
+pub fn reactive_inspector() -> impl View {
+    let graph = create_rw_signal(Rc::new(reactive_graph()));
+
+    let buttons = h_stack((
+        button(|| "Refresh")
+            .on_click_stop(move |_| graph.set(Rc::new(reactive_graph())))
+            .style(|s| s.margin(5.0)),
+        button(|| "Copy DOT")
+            .on_click_stop(move |_| {
+                let _ = Clipboard::set_contents(graph.with_untracked(|graph| graph.to_dot()));
+            })
+            .style(|s| s.margin(5.0)),
+        button(|| "Copy JSON")
+            .on_click_stop(move |_| {
+                let _ = Clipboard::set_contents(graph.with_untracked(|graph| graph.to_json()));
+            })
+            .style(|s| s.margin(5.0)),
+        label(move || {
+            if graph.with(|graph| graph.nodes.is_empty()) {
+                "Enable tracking with `floem::reactive::set_debug_tracking`"
+            } else {
+                ""
+            }
+        }),
+    ))
+    .style(|s| s.items_center());
+
+    let separator = empty().style(move |s| {
+        s.width_full()
+            .min_height(1.0)
+            .background(Color::BLACK.with_alpha_factor(0.2))
+    });
+
+    let lower = dyn_container(move || graph.get(), |graph| graph_view(&graph).any())
+        .style(|s| s.width_full().min_height(0).flex_basis(0).flex_grow(1.0));
+
+    v_stack((buttons, separator, lower)).style(|s| s.flex_col().width_full().height_full())
+}
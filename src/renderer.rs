@@ -99,6 +99,17 @@ impl Renderer {
         }
     }
 
+    /// Creates a renderer which paints into an offscreen pixmap instead of a window.
+    /// The painted frame is returned by [`finish`](floem_renderer::Renderer::finish).
+    pub fn new_headless(scale: f64, size: Size) -> Self {
+        let size = Size::new(size.width.max(1.0), size.height.max(1.0));
+
+        match TinySkiaRenderer::new_headless(size.width as u32, size.height as u32, scale) {
+            Ok(tiny_skia) => Self::TinySkia(tiny_skia),
+            Err(err) => panic!("Failed to create headless TinySkiaRenderer: {err}"),
+        }
+    }
+
     pub fn resize(&mut self, scale: f64, size: Size) {
         let size = Size::new(size.width.max(1.0), size.height.max(1.0));
         match self {
@@ -149,6 +160,28 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 
+    fn push_layer(&mut self, opacity: f32) {
+        match self {
+            Renderer::Vger(v) => {
+                v.push_layer(opacity);
+            }
+            Renderer::TinySkia(v) => {
+                v.push_layer(opacity);
+            }
+        }
+    }
+
+    fn pop_layer(&mut self) {
+        match self {
+            Renderer::Vger(v) => {
+                v.pop_layer();
+            }
+            Renderer::TinySkia(v) => {
+                v.pop_layer();
+            }
+        }
+    }
+
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
         match self {
             Renderer::Vger(v) => {
@@ -187,6 +220,22 @@ impl floem_renderer::Renderer for Renderer {
         }
     }
 
+    fn draw_text_with_brush<'b>(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<kurbo::Point>,
+        brush: impl Into<BrushRef<'b>>,
+    ) {
+        match self {
+            Renderer::Vger(v) => {
+                v.draw_text_with_brush(layout, pos, brush);
+            }
+            Renderer::TinySkia(v) => {
+                v.draw_text_with_brush(layout, pos, brush);
+            }
+        }
+    }
+
     fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
         match self {
             Renderer::Vger(v) => {
@@ -27,6 +27,8 @@ use taffy::{
     },
 };
 
+use crate::animate::{Easing, EasingFn, EasingMode};
+use crate::brush::{Brush, Gradient};
 use crate::context::InteractionState;
 use crate::responsive::{ScreenSize, ScreenSizeBp};
 use crate::unit::{Px, PxPct, PxPctAuto, UnitExt};
@@ -41,15 +43,33 @@ pub trait StylePropValue: Clone + PartialEq + Debug {
     fn interpolate(&self, _other: &Self, _value: f64) -> Option<Self> {
         None
     }
+
+    /// How far apart two values are, used to keep the speed of a spring animation when it's
+    /// given a new target
+    fn distance(&self, _other: &Self) -> Option<f64> {
+        None
+    }
 }
 
 impl StylePropValue for i32 {}
 impl StylePropValue for bool {}
-impl StylePropValue for f32 {}
+impl StylePropValue for f32 {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        Some(*self * (1.0 - value as f32) + *other * value as f32)
+    }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        Some((other - self).abs() as f64)
+    }
+}
 impl StylePropValue for f64 {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         Some(*self * (1.0 - value) + *other * value)
     }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        Some((other - self).abs())
+    }
 }
 impl StylePropValue for Display {}
 impl StylePropValue for Position {}
@@ -64,13 +84,47 @@ impl<T: StylePropValue, M: StylePropValue> StylePropValue for MinMax<T, M> {}
 impl<T: StylePropValue> StylePropValue for Line<T> {}
 impl StylePropValue for GridPlacement {}
 impl StylePropValue for CursorStyle {}
-impl StylePropValue for BoxShadow {}
+impl StylePropValue for BoxShadow {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        Some(Self {
+            blur_radius: self.blur_radius.interpolate(&other.blur_radius, value)?,
+            color: self.color.interpolate(&other.color, value)?,
+            spread: self.spread.interpolate(&other.spread, value)?,
+            h_offset: self.h_offset.interpolate(&other.h_offset, value)?,
+            v_offset: self.v_offset.interpolate(&other.v_offset, value)?,
+        })
+    }
+}
 impl StylePropValue for String {}
 impl StylePropValue for Weight {}
 impl StylePropValue for cosmic_text::Style {}
 impl StylePropValue for TextOverflow {}
-impl StylePropValue for LineHeightValue {}
-impl StylePropValue for Size<LengthPercentage> {}
+impl StylePropValue for LineHeightValue {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Normal(a), Self::Normal(b)) => a.interpolate(b, value).map(Self::Normal),
+            (Self::Px(a), Self::Px(b)) => a.interpolate(b, value).map(Self::Px),
+            _ => None,
+        }
+    }
+}
+impl StylePropValue for LengthPercentage {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Length(a), Self::Length(b)) => a.interpolate(b, value).map(Self::Length),
+            (Self::Percent(a), Self::Percent(b)) => a.interpolate(b, value).map(Self::Percent),
+            _ => None,
+        }
+    }
+}
+impl StylePropValue for Size<LengthPercentage> {
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        Some(Size {
+            width: self.width.interpolate(&other.width, value)?,
+            height: self.height.interpolate(&other.height, value)?,
+        })
+    }
+}
 
 impl<T: StylePropValue> StylePropValue for Option<T> {
     fn debug_view(&self) -> Option<AnyView> {
@@ -84,6 +138,10 @@ impl<T: StylePropValue> StylePropValue for Option<T> {
                 .and_then(|other| this.interpolate(other, value).map(Some))
         })
     }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        self.as_ref()?.distance(other.as_ref()?)
+    }
 }
 impl<T: StylePropValue> StylePropValue for Vec<T> {
     fn debug_view(&self) -> Option<AnyView> {
@@ -101,6 +159,10 @@ impl StylePropValue for Px {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         self.0.interpolate(&other.0, value).map(Px)
     }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        self.0.distance(&other.0)
+    }
 }
 impl StylePropValue for PxPctAuto {
     fn debug_view(&self) -> Option<AnyView> {
@@ -111,6 +173,22 @@ impl StylePropValue for PxPctAuto {
         };
         Some(text(label).any())
     }
+
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Px(a), Self::Px(b)) => a.interpolate(b, value).map(Self::Px),
+            (Self::Pct(a), Self::Pct(b)) => a.interpolate(b, value).map(Self::Pct),
+            (Self::Auto, Self::Auto) => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        match (self, other) {
+            (Self::Px(a), Self::Px(b)) | (Self::Pct(a), Self::Pct(b)) => a.distance(b),
+            _ => None,
+        }
+    }
 }
 impl StylePropValue for PxPct {
     fn debug_view(&self) -> Option<AnyView> {
@@ -120,6 +198,21 @@ impl StylePropValue for PxPct {
         };
         Some(text(label).any())
     }
+
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        match (self, other) {
+            (Self::Px(a), Self::Px(b)) => a.interpolate(b, value).map(Self::Px),
+            (Self::Pct(a), Self::Pct(b)) => a.interpolate(b, value).map(Self::Pct),
+            _ => None,
+        }
+    }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        match (self, other) {
+            (Self::Px(a), Self::Px(b)) | (Self::Pct(a), Self::Pct(b)) => a.distance(b),
+            _ => None,
+        }
+    }
 }
 impl StylePropValue for Color {
     fn debug_view(&self) -> Option<AnyView> {
@@ -162,6 +255,32 @@ impl StylePropValue for Color {
     }
 }
 
+impl StylePropValue for Brush {
+    fn debug_view(&self) -> Option<AnyView> {
+        match self {
+            Brush::Solid(color) => color.debug_view(),
+            Brush::Gradient(gradient) => {
+                let brush = self.clone();
+                let preview = empty().style(move |s| {
+                    s.background(brush.clone())
+                        .width(22.0)
+                        .height(14.0)
+                        .border_radius(5.0)
+                });
+                Some(
+                    stack((text(format!("{:?}", gradient.kind)), preview))
+                        .style(|s| s.items_center().gap(6.0, 0.0))
+                        .any(),
+                )
+            }
+        }
+    }
+
+    fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
+        Brush::interpolate(self, other, value)
+    }
+}
+
 pub trait StyleClass: Default + Copy + 'static {
     fn key() -> StyleKey;
     fn class_ref() -> StyleClassRef {
@@ -228,6 +347,9 @@ pub struct StylePropInfo {
     pub(crate) default_as_any: fn() -> Rc<dyn Any>,
     pub(crate) debug_any: fn(val: &dyn Any) -> String,
     pub(crate) debug_view: fn(val: &dyn Any) -> Option<AnyView>,
+    pub(crate) interpolate: fn(from: &dyn Any, to: &dyn Any, value: f64) -> Option<Rc<dyn Any>>,
+    pub(crate) distance: fn(from: &dyn Any, to: &dyn Any) -> Option<f64>,
+    pub(crate) eq: fn(a: &dyn Any, b: &dyn Any) -> bool,
     pub(crate) transition_key: StyleKey,
 }
 
@@ -270,6 +392,18 @@ impl StylePropInfo {
                     )
                 }
             },
+            interpolate: |from, to, value| {
+                let from = from.downcast_ref::<StyleMapValue<T>>()?.as_ref()?;
+                let to = to.downcast_ref::<StyleMapValue<T>>()?.as_ref()?;
+                let value = from.interpolate(to, value)?;
+                Some(Rc::new(StyleMapValue::Val(value)))
+            },
+            distance: |from, to| {
+                let from = from.downcast_ref::<StyleMapValue<T>>()?.as_ref()?;
+                let to = to.downcast_ref::<StyleMapValue<T>>()?.as_ref()?;
+                from.distance(to)
+            },
+            eq: |a, b| a.downcast_ref::<StyleMapValue<T>>() == b.downcast_ref::<StyleMapValue<T>>(),
             transition_key,
         }
     }
@@ -296,62 +430,36 @@ pub trait StylePropReader {
 
     /// Reads the property from the style.
     /// Returns true if the property changed.
-    fn read(
-        state: &mut Self::State,
-        style: &Style,
-        fallback: &Style,
-        now: &Instant,
-        request_transition: &mut bool,
-    ) -> bool;
+    fn read(state: &mut Self::State, style: &Style, fallback: &Style) -> bool;
 
     fn get(state: &Self::State) -> Self::Type;
     fn new() -> Self::State;
 }
 
 impl<P: StyleProp> StylePropReader for P {
-    type State = (P::Type, TransitionState<P::Type>);
+    type State = P::Type;
     type Type = P::Type;
-    fn read(
-        state: &mut Self::State,
-        style: &Style,
-        fallback: &Style,
-        now: &Instant,
-        request_transition: &mut bool,
-    ) -> bool {
+    fn read(state: &mut Self::State, style: &Style, fallback: &Style) -> bool {
         let new = style
             .get_prop::<P>()
             .or_else(|| fallback.get_prop::<P>())
             .unwrap_or_else(|| P::default_value());
-        state.1.read(
-            style
-                .get_transition::<P>()
-                .or_else(|| fallback.get_transition::<P>()),
-        );
-        let changed = new != state.0;
-        if changed {
-            state.1.transition(&Self::get(state), &new);
-            state.0 = new;
-        }
-        changed | state.1.step(now, request_transition)
+        let changed = new != *state;
+        *state = new;
+        changed
     }
     fn get(state: &Self::State) -> Self::Type {
-        state.1.get(&state.0)
+        state.clone()
     }
     fn new() -> Self::State {
-        (P::default_value(), TransitionState::default())
+        P::default_value()
     }
 }
 
 impl<P: StyleProp> StylePropReader for Option<P> {
     type State = Option<P::Type>;
     type Type = Option<P::Type>;
-    fn read(
-        state: &mut Self::State,
-        style: &Style,
-        fallback: &Style,
-        _now: &Instant,
-        _transition: &mut bool,
-    ) -> bool {
+    fn read(state: &mut Self::State, style: &Style, fallback: &Style) -> bool {
         let new = style.get_prop::<P>().or_else(|| fallback.get_prop::<P>());
         let changed = new != *state;
         *state = new;
@@ -377,14 +485,8 @@ impl<R: StylePropReader> Debug for ExtratorField<R> {
 }
 
 impl<R: StylePropReader> ExtratorField<R> {
-    pub fn read(
-        &mut self,
-        style: &Style,
-        fallback: &Style,
-        now: &Instant,
-        request_transition: &mut bool,
-    ) -> bool {
-        R::read(&mut self.state, style, fallback, now, request_transition)
+    pub fn read(&mut self, style: &Style, fallback: &Style) -> bool {
+        R::read(&mut self.state, style, fallback)
     }
     pub fn get(&self) -> R::Type {
         R::get(&self.state)
@@ -438,27 +540,25 @@ macro_rules! prop_extractor {
             $(
                 $prop_vis $prop: $crate::style::ExtratorField<$reader>,
             )*
+            /// The transitions of styles read with `read_style`, which aren't the computed style
+            /// of a view and so haven't had their transitions applied
+            prop_transitions: $crate::style::PropTransitions,
         }
 
         impl $name {
             #[allow(dead_code)]
             $vis fn read_style(&mut self, cx: &mut $crate::context::StyleCx, style: &$crate::style::Style) -> bool {
-                let mut transition = false;
-                let changed = false $(| self.$prop.read(style, style, &cx.now(), &mut transition))*;
-                if transition {
+                let mut style = style.clone();
+                if self.prop_transitions.apply(&mut style, &$crate::style::Style::new(), cx.now(), &[]) {
                     cx.request_transition();
                 }
-                changed
+                self.read_explicit(&style, &style)
             }
 
+            /// Read the computed style of the view being styled, which has its transitions applied
             #[allow(dead_code)]
             $vis fn read(&mut self, cx: &mut $crate::context::StyleCx) -> bool {
-                let mut transition = false;
-                let changed = self.read_explicit(&cx.direct_style(), &cx.indirect_style(), &cx.now(), &mut transition);
-                if transition {
-                    cx.request_transition();
-                }
-                changed
+                self.read_explicit(&cx.direct_style(), &cx.indirect_style())
             }
 
             #[allow(dead_code)]
@@ -466,10 +566,8 @@ macro_rules! prop_extractor {
                 &mut self,
                 style: &$crate::style::Style,
                 fallback: &$crate::style::Style,
-                now: &std::time::Instant,
-                request_transition: &mut bool
             ) -> bool {
-                false $(| self.$prop.read(style, fallback, now, request_transition))*
+                false $(| self.$prop.read(style, fallback))*
             }
 
             $($prop_vis fn $prop(&self) -> <$reader as $crate::style::StylePropReader>::Type
@@ -484,6 +582,7 @@ macro_rules! prop_extractor {
                     $(
                         $prop: $crate::style::ExtratorField::new(),
                     )*
+                    prop_transitions: Default::default(),
                 }
             }
         }
@@ -506,94 +605,214 @@ impl<T> StyleMapValue<T> {
     }
 }
 
-#[derive(Clone, Debug)]
-pub(crate) struct ActiveTransition<T: StylePropValue> {
+/// A transition of a property in the computed style of a view, which isn't tied to the type of
+/// the property
+#[derive(Clone)]
+struct ActivePropTransition {
     start: Instant,
-    before: T,
-    current: T,
-    after: T,
-}
-
-#[derive(Clone, Debug)]
-pub struct TransitionState<T: StylePropValue> {
-    transition: Option<Transition>,
-    active: Option<ActiveTransition<T>>,
-    initial: bool,
-}
-
-impl<T: StylePropValue> TransitionState<T> {
-    fn read(&mut self, transition: Option<Transition>) {
-        self.transition = transition;
-    }
-
-    fn transition(&mut self, before: &T, after: &T) {
-        if !self.initial {
-            return;
-        }
-        if self.transition.is_some() {
-            self.active = Some(ActiveTransition {
-                start: Instant::now(),
-                before: before.clone(),
-                current: before.clone(),
-                after: after.clone(),
-            });
+    transition: Transition,
+    before: Rc<dyn Any>,
+    current: Rc<dyn Any>,
+    after: Rc<dyn Any>,
+}
+
+/// The transitions of the properties of a view. These are applied to the computed style of the
+/// view, so properties used for layout like sizes, margins and gaps transition too, and to the
+/// styles read by a [`prop_extractor`](crate::prop_extractor) with `read_style`.
+#[derive(Clone, Default)]
+pub struct PropTransitions {
+    /// The value each property with a transition was last styled with
+    targets: HashMap<StyleKey, Rc<dyn Any>>,
+    active: HashMap<StyleKey, ActivePropTransition>,
+    /// Whether the view has been styled before, after which a property which is set for the first
+    /// time transitions from its default
+    styled: bool,
+}
+
+impl PropTransitions {
+    /// Start transitions for the properties in `style` which changed and replace the values of
+    /// the transitioning properties with their current value. Inherited properties are read from
+    /// `context` when the view doesn't set them. The properties in `skip` are animated, so they
+    /// are left as they are.
+    ///
+    /// Returns true while any transition is running.
+    pub fn apply(
+        &mut self,
+        style: &mut Style,
+        context: &Style,
+        now: Instant,
+        skip: &[StyleKey],
+    ) -> bool {
+        let transition_all = style.get_transition_all();
+        let is_prop = |key: &&StyleKey| matches!(key.info, StyleKeyInfo::Prop(_));
+        let mut keys: Vec<StyleKey> = style.map.keys().filter(is_prop).copied().collect();
+        keys.extend(
+            context
+                .map
+                .keys()
+                .filter(is_prop)
+                .filter(|key| key.inherited() && !style.map.contains_key(*key))
+                .copied(),
+        );
+        for key in self.targets.keys() {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
         }
-    }
 
-    fn step(&mut self, now: &Instant, request_transition: &mut bool) -> bool {
-        if !self.initial {
-            // We have observed the initial value. Any further changes may trigger animations.
-            self.initial = true;
+        for key in keys {
+            let StyleKeyInfo::Prop(info) = key.info else {
+                continue;
+            };
+            let transition = style
+                .map
+                .get(&info.transition_key)
+                .map(|v| v.downcast_ref::<Transition>().unwrap().clone())
+                .or_else(|| transition_all.clone());
+            let Some(transition) = transition.filter(|_| !skip.contains(&key)) else {
+                self.targets.remove(&key);
+                self.active.remove(&key);
+                continue;
+            };
+
+            let target = style
+                .map
+                .get(&key)
+                .or_else(|| context.map.get(&key).filter(|_| key.inherited()))
+                .cloned()
+                .unwrap_or_else(|| (info.default_as_any)());
+            let before = self
+                .targets
+                .insert(key, target.clone())
+                .or_else(|| self.styled.then(|| (info.default_as_any)()));
+            let Some(before) = before else {
+                continue;
+            };
+            if (info.eq)(&*before, &*target) {
+                continue;
+            }
+            // An interrupted transition continues from where it got to
+            let before = self
+                .active
+                .get(&key)
+                .map(|active| active.current.clone())
+                .unwrap_or(before);
+            self.active.insert(
+                key,
+                ActivePropTransition {
+                    start: now,
+                    transition,
+                    current: before.clone(),
+                    before,
+                    after: target,
+                },
+            );
         }
-        if let Some(active) = &mut self.active {
-            if let Some(transition) = &self.transition {
-                let time = now.saturating_duration_since(active.start).as_secs_f64();
-                if time < transition.duration {
-                    if let Some(i) =
-                        T::interpolate(&active.before, &active.after, time / transition.duration)
-                    {
-                        active.current = i;
-                        *request_transition = true;
-                        return true;
-                    }
+        self.styled = true;
+
+        self.active.retain(|key, active| {
+            let StyleKeyInfo::Prop(info) = key.info else {
+                return false;
+            };
+            let time = now.saturating_duration_since(active.start).as_secs_f64();
+            let current = active
+                .transition
+                .progress(time)
+                .and_then(|progress| (info.interpolate)(&*active.before, &*active.after, progress));
+            match current {
+                Some(current) => {
+                    active.current = current.clone();
+                    style.map.insert(*key, current);
+                    true
                 }
+                None => false,
             }
-            self.active = None;
+        });
 
-            true
-        } else {
-            false
-        }
+        // The transitions are done here, so the property extractors reading this style shouldn't
+        // run them again
+        style
+            .map
+            .retain(|key, _| !matches!(key.info, StyleKeyInfo::Transition));
+
+        !self.active.is_empty()
     }
 
-    fn get(&self, value: &T) -> T {
-        if let Some(active) = &self.active {
-            active.current.clone()
-        } else {
-            value.clone()
-        }
+    /// Whether an inherited property is transitioning, so the children of the view need to be
+    /// styled again on each frame
+    pub(crate) fn has_inherited(&self) -> bool {
+        self.active.keys().any(|key| key.inherited())
     }
 }
 
-impl<T: StylePropValue> Default for TransitionState<T> {
-    fn default() -> Self {
-        Self {
-            transition: None,
-            active: None,
-            initial: false,
-        }
+impl Debug for PropTransitions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropTransitions")
+            .field("active", &self.active.keys().collect::<Vec<_>>())
+            .finish()
     }
 }
 
+/// How a style property changes from one value to the next, set with [`Style::transition`] or
+/// [`Style::transition_all`]. The duration and delay are in seconds.
+///
+/// ## Example
+/// ```
+/// use floem::animate::EasingFn;
+/// use floem::style::{Background, Style, Transition};
+///
+/// let style = Style::new()
+///     .transition(Background, Transition::linear(0.2).easing(EasingFn::Cubic).delay(0.05));
+/// ```
 #[derive(Clone, Debug)]
 pub struct Transition {
     duration: f64,
+    delay: f64,
+    easing: Easing,
 }
 
 impl Transition {
     pub fn linear(duration: f64) -> Self {
-        Self { duration }
+        Self {
+            duration,
+            delay: 0.0,
+            easing: Easing::default(),
+        }
     }
+
+    /// A transition with an ease in and out curve
+    pub fn ease_in_out(duration: f64) -> Self {
+        Self::linear(duration).easing(Easing::new(EasingFn::Cubic, EasingMode::InOut))
+    }
+
+    pub fn easing(mut self, easing: impl Into<Easing>) -> Self {
+        self.easing = easing.into();
+        self
+    }
+
+    /// Wait for `delay` seconds after the property changes before starting the transition
+    pub fn delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The eased progress of the transition `time` seconds after it started, or `None` once it's
+    /// finished
+    fn progress(&self, time: f64) -> Option<f64> {
+        let time = time - self.delay;
+        if time < 0.0 {
+            Some(0.0)
+        } else if time < self.duration {
+            Some(self.easing.ease(time / self.duration))
+        } else {
+            None
+        }
+    }
+}
+
+fn transition_all_key() -> StyleKey {
+    static INFO: StyleKeyInfo = StyleKeyInfo::Transition;
+    StyleKey { info: &INFO }
 }
 
 #[derive(Debug)]
@@ -676,9 +895,9 @@ impl Style {
         Self::default()
     }
 
-    pub(crate) fn get_transition<P: StyleProp>(&self) -> Option<Transition> {
+    fn get_transition_all(&self) -> Option<Transition> {
         self.map
-            .get(&P::prop_ref().info().transition_key)
+            .get(&transition_all_key())
             .map(|v| v.downcast_ref::<Transition>().unwrap().clone())
     }
 
@@ -782,6 +1001,18 @@ impl Style {
                 self.apply_mut(map);
             }
         }
+        if interact_state.is_file_hovered && !interact_state.is_disabled {
+            if let Some(mut map) = self.get_nested_map(StyleSelector::FileHover.to_key()) {
+                map.apply_interact_state(interact_state, screen_size_bp);
+                self.apply_mut(map);
+            }
+        }
+        if interact_state.is_invalid {
+            if let Some(mut map) = self.get_nested_map(StyleSelector::Invalid.to_key()) {
+                map.apply_interact_state(interact_state, screen_size_bp);
+                self.apply_mut(map);
+            }
+        }
         if interact_state.is_disabled {
             if let Some(mut map) = self.get_nested_map(StyleSelector::Disabled.to_key()) {
                 map.apply_interact_state(interact_state, screen_size_bp);
@@ -934,6 +1165,10 @@ pub enum StyleSelector {
     Active,
     Dragging,
     Selected,
+    FileHover,
+    /// The view's value failed validation, such as a [`text_input`](crate::views::text_input)
+    /// with a [`validator`](crate::views::TextInput::validator)
+    Invalid,
 }
 
 style_key_selector!(hover, StyleSelectors::new().set(StyleSelector::Hover, true));
@@ -958,6 +1193,14 @@ style_key_selector!(
     selected,
     StyleSelectors::new().set(StyleSelector::Selected, true)
 );
+style_key_selector!(
+    file_hover,
+    StyleSelectors::new().set(StyleSelector::FileHover, true)
+);
+style_key_selector!(
+    invalid,
+    StyleSelectors::new().set(StyleSelector::Invalid, true)
+);
 
 impl StyleSelector {
     fn to_key(self) -> StyleKey {
@@ -969,13 +1212,15 @@ impl StyleSelector {
             StyleSelector::Active => active(),
             StyleSelector::Dragging => dragging(),
             StyleSelector::Selected => selected(),
+            StyleSelector::FileHover => file_hover(),
+            StyleSelector::Invalid => invalid(),
         }
     }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
 pub struct StyleSelectors {
-    selectors: u8,
+    selectors: u16,
     responsive: bool,
 }
 
@@ -987,14 +1232,14 @@ impl StyleSelectors {
         }
     }
     pub(crate) const fn set(mut self, selector: StyleSelector, value: bool) -> Self {
-        let v = selector as isize as u8;
+        let v = selector as isize as u16;
         let bit = 1 << v;
-        self.selectors = (self.selectors & !bit) | ((value as u8) << v);
+        self.selectors = (self.selectors & !bit) | ((value as u16) << v);
         self
     }
     pub(crate) fn has(self, selector: StyleSelector) -> bool {
         let v = (selector as isize).try_into().unwrap();
-        let bit = 1_u8.checked_shl(v).unwrap();
+        let bit = 1_u16.checked_shl(v).unwrap();
         self.selectors & bit != 0
     }
     pub(crate) fn union(self, other: StyleSelectors) -> StyleSelectors {
@@ -1117,6 +1362,18 @@ impl<T> From<T> for StyleValue<T> {
     }
 }
 
+impl From<Color> for StyleValue<Brush> {
+    fn from(color: Color) -> Self {
+        Self::Val(Brush::Solid(color))
+    }
+}
+
+impl From<Gradient> for StyleValue<Brush> {
+    fn from(gradient: Gradient) -> Self {
+        Self::Val(Brush::Gradient(gradient))
+    }
+}
+
 macro_rules! define_builtin_props {
     (
         $($type_name:ident $name:ident $($opt:ident)?:
@@ -1184,7 +1441,7 @@ define_builtin_props!(
     BorderRadius border_radius: PxPct {} = PxPct::Px(0.0),
     OutlineColor outline_color: Color {} = Color::TRANSPARENT,
     Outline outline: Px {} = Px(0.0),
-    BorderColor border_color: Color {} = Color::BLACK,
+    BorderColor border_color: Brush {} = Brush::Solid(Color::BLACK),
     PaddingLeft padding_left: PxPct {} = PxPct::Px(0.0),
     PaddingTop padding_top: PxPct {} = PxPct::Px(0.0),
     PaddingRight padding_right: PxPct {} = PxPct::Px(0.0),
@@ -1199,8 +1456,8 @@ define_builtin_props!(
     InsetBottom inset_bottom: PxPctAuto {} = PxPctAuto::Auto,
     ZIndex z_index nocb: Option<i32> {} = None,
     Cursor cursor nocb: Option<CursorStyle> {} = None,
-    TextColor color nocb: Option<Color> { inherited } = None,
-    Background background nocb: Option<Color> {} = None,
+    TextColor color nocb: Option<Brush> { inherited } = None,
+    Background background nocb: Option<Brush> {} = None,
     Foreground foreground nocb: Option<Color> {} = None,
     BoxShadowProp box_shadow nocb: Option<BoxShadow> {} = None,
     FontSize font_size nocb: Option<f32> { inherited } = None,
@@ -1212,6 +1469,14 @@ define_builtin_props!(
     LineHeight line_height nocb: Option<LineHeightValue> { inherited } = None,
     AspectRatio aspect_ratio: Option<f32> {} = None,
     Gap gap nocb: Size<LengthPercentage> {} = Size::zero(),
+    TranslateX translate_x: PxPct {} = PxPct::Px(0.0),
+    TranslateY translate_y: PxPct {} = PxPct::Px(0.0),
+    ScaleX scale_x: f64 {} = 1.0,
+    ScaleY scale_y: f64 {} = 1.0,
+    Rotate rotate: f64 {} = 0.0,
+    TransformOriginX transform_origin_x: PxPct {} = PxPct::Pct(50.0),
+    TransformOriginY transform_origin_y: PxPct {} = PxPct::Pct(50.0),
+    Opacity opacity: f64 {} = 1.0,
 );
 
 prop_extractor! {
@@ -1268,6 +1533,13 @@ impl Style {
         self
     }
 
+    /// Use `transition` for every property which doesn't have its own transition. Properties
+    /// whose values can't be interpolated change straight away.
+    pub fn transition_all(mut self, transition: Transition) -> Self {
+        self.map.insert(transition_all_key(), Rc::new(transition));
+        self
+    }
+
     fn selector(mut self, selector: StyleSelector, style: impl FnOnce(Style) -> Style) -> Self {
         let over = style(Style::default());
         self.set_selector(selector, over);
@@ -1296,10 +1568,20 @@ impl Style {
         self.selector(StyleSelector::Disabled, style)
     }
 
+    /// The style to apply when the view's value is invalid
+    pub fn invalid(self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(StyleSelector::Invalid, style)
+    }
+
     pub fn active(self, style: impl FnOnce(Style) -> Style) -> Self {
         self.selector(StyleSelector::Active, style)
     }
 
+    /// The style to apply while files from outside the application are dragged over the view.
+    pub fn file_hover(self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(StyleSelector::FileHover, style)
+    }
+
     pub fn responsive(mut self, size: ScreenSize, style: impl FnOnce(Style) -> Style) -> Self {
         let over = style(Style::default());
         for breakpoint in size.breakpoints() {
@@ -1583,11 +1865,11 @@ impl Style {
         self.set_style_value(Cursor, cursor.into().map(Some))
     }
 
-    pub fn color(self, color: impl Into<StyleValue<Color>>) -> Self {
+    pub fn color(self, color: impl Into<StyleValue<Brush>>) -> Self {
         self.set_style_value(TextColor, color.into().map(Some))
     }
 
-    pub fn background(self, color: impl Into<StyleValue<Color>>) -> Self {
+    pub fn background(self, color: impl Into<StyleValue<Brush>>) -> Self {
         self.set_style_value(Background, color.into().map(Some))
     }
 
@@ -1715,6 +1997,27 @@ impl Style {
         self.set(ZIndex, Some(z_index))
     }
 
+    /// Sets `translate_x` and `translate_y`. Percentages are relative to the size of the view.
+    pub fn translate(self, x: impl Into<PxPct>, y: impl Into<PxPct>) -> Self {
+        self.translate_x(x).translate_y(y)
+    }
+
+    /// Sets `scale_x` and `scale_y` to `scale`
+    pub fn scale(self, scale: impl Into<f64>) -> Self {
+        let scale = scale.into();
+        self.scale_x(scale).scale_y(scale)
+    }
+
+    /// Sets the point that `scale` and `rotate` are applied around. Percentages are relative to
+    /// the size of the view and the default is the center.
+    ///
+    /// The tiny-skia renderer applies the full transform. The vger renderer only translates
+    /// text, images and svgs without scaling or rotating them, and paints rotated backgrounds
+    /// and borders as their axis aligned bounding box.
+    pub fn transform_origin(self, x: impl Into<PxPct>, y: impl Into<PxPct>) -> Self {
+        self.transform_origin_x(x).transform_origin_y(y)
+    }
+
     /// Allow the application of a function if the option exists.  
     /// This is useful for chaining together a bunch of optional style changes.  
     /// ```rust
@@ -4,6 +4,7 @@ use floem_winit::window::ResizeDirection;
 use kurbo::{Point, Rect, Size, Vec2};
 
 use crate::{
+    accessibility::Accessibility,
     animate::{AnimUpdateMsg, Animation},
     context::{EventCallback, ResizeCallback},
     event::EventListener,
@@ -57,6 +58,10 @@ pub(crate) enum UpdateMessage {
         id: Id,
         class: StyleClassRef,
     },
+    DebugName {
+        id: Id,
+        name: String,
+    },
     StyleSelector {
         id: Id,
         selector: StyleSelector,
@@ -68,6 +73,10 @@ pub(crate) enum UpdateMessage {
     Draggable {
         id: Id,
     },
+    Accessibility {
+        id: Id,
+        accessibility: Accessibility,
+    },
     EventListener {
         id: Id,
         listener: EventListener,
@@ -365,26 +365,26 @@ pub(crate) fn paint_bg(
             let radius = width.max(height) / 2.0;
             let circle = Circle::new(rect.center(), radius);
             let bg = match style.background() {
-                Some(color) => color,
+                Some(brush) => brush.resolve(rect),
                 None => return,
             };
-            cx.fill(&circle, bg, 0.0);
+            cx.fill(&circle, &bg, 0.0);
         } else {
             paint_box_shadow(cx, computed_style, rect, Some(radius));
             let bg = match style.background() {
-                Some(color) => color,
+                Some(brush) => brush.resolve(rect),
                 None => return,
             };
             let rounded_rect = rect.to_rounded_rect(radius);
-            cx.fill(&rounded_rect, bg, 0.0);
+            cx.fill(&rounded_rect, &bg, 0.0);
         }
     } else {
         paint_box_shadow(cx, computed_style, size.to_rect(), None);
         let bg = match style.background() {
-            Some(color) => color,
+            Some(brush) => brush.resolve(size.to_rect()),
             None => return,
         };
-        cx.fill(&size.to_rect(), bg, 0.0);
+        cx.fill(&size.to_rect(), &bg, 0.0);
     }
 }
 
@@ -448,7 +448,7 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
     let right = style.border_right().0;
     let bottom = style.border_bottom().0;
 
-    let border_color = style.border_color();
+    let border_color = style.border_color().resolve(size.to_rect());
     if left == top && top == right && right == bottom && bottom == left && left > 0.0 {
         let half = left / 2.0;
         let rect = size.to_rect().inflate(-half, -half);
@@ -457,16 +457,16 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
             crate::unit::PxPct::Pct(pct) => size.min_side() * (pct / 100.),
         };
         if radius > 0.0 {
-            cx.stroke(&rect.to_rounded_rect(radius), border_color, left);
+            cx.stroke(&rect.to_rounded_rect(radius), &border_color, left);
         } else {
-            cx.stroke(&rect, border_color, left);
+            cx.stroke(&rect, &border_color, left);
         }
     } else {
         if left > 0.0 {
             let half = left / 2.0;
             cx.stroke(
                 &Line::new(Point::new(half, 0.0), Point::new(half, size.height)),
-                border_color,
+                &border_color,
                 left,
             );
         }
@@ -477,7 +477,7 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
                     Point::new(size.width - half, 0.0),
                     Point::new(size.width - half, size.height),
                 ),
-                border_color,
+                &border_color,
                 right,
             );
         }
@@ -485,7 +485,7 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
             let half = top / 2.0;
             cx.stroke(
                 &Line::new(Point::new(0.0, half), Point::new(size.width, half)),
-                border_color,
+                &border_color,
                 top,
             );
         }
@@ -496,7 +496,7 @@ pub(crate) fn paint_border(cx: &mut PaintCx, style: &ViewStyleProps, size: Size)
                     Point::new(0.0, size.height - half),
                     Point::new(size.width, size.height - half),
                 ),
-                border_color,
+                &border_color,
                 bottom,
             );
         }
@@ -1,5 +1,7 @@
 use crate::{
-    animate::{AnimPropKind, Animation},
+    accessibility::Accessibility,
+    animate::{driven_elapsed, AnimPropKind, Animation},
+    clock,
     context::{EventCallback, InteractionState, MenuCallback, MoveListener, ResizeListener},
     event::EventListener,
     id::{Id, ID_PATHS},
@@ -8,13 +10,16 @@ use crate::{
     responsive::ScreenSizeBp,
     style::{
         Background, BorderBottom, BorderColor, BorderLeft, BorderRadius, BorderRight, BorderTop,
-        LayoutProps, Outline, OutlineColor, Style, StyleClassRef, StyleSelectors,
+        LayoutProps, Opacity, Outline, OutlineColor, PropTransitions, Rotate, ScaleX, ScaleY,
+        Style, StyleClassRef, StyleSelectors, TransformOriginX, TransformOriginY, TranslateX,
+        TranslateY,
     },
+    unit::PxPct,
     view::Widget,
     EventPropagation,
 };
 use bitflags::bitflags;
-use kurbo::Rect;
+use kurbo::{Affine, Rect, Size, Vec2};
 use smallvec::SmallVec;
 use std::{collections::HashMap, marker::PhantomData, time::Duration};
 use taffy::tree::NodeId;
@@ -124,6 +129,43 @@ prop_extractor! {
         pub outline_color: OutlineColor,
         pub border_color: BorderColor,
         pub background: Background,
+
+        pub translate_x: TranslateX,
+        pub translate_y: TranslateY,
+        pub scale_x: ScaleX,
+        pub scale_y: ScaleY,
+        pub rotate: Rotate,
+        pub transform_origin_x: TransformOriginX,
+        pub transform_origin_y: TransformOriginY,
+
+        pub opacity: Opacity,
+    }
+}
+
+impl ViewStyleProps {
+    /// The transform from the style properties `translate`, `scale` and `rotate` for a view of
+    /// the given size. It maps from the view's coordinates to its untransformed layout position.
+    pub(crate) fn transform(&self, size: Size) -> Affine {
+        let pixels = |px_pct, abs: f64| match px_pct {
+            PxPct::Px(v) => v,
+            PxPct::Pct(pct) => pct / 100.0 * abs,
+        };
+        let (scale_x, scale_y, rotate) = (self.scale_x(), self.scale_y(), self.rotate());
+        let translate = Vec2::new(
+            pixels(self.translate_x(), size.width),
+            pixels(self.translate_y(), size.height),
+        );
+        if scale_x == 1.0 && scale_y == 1.0 && rotate == 0.0 {
+            return Affine::translate(translate);
+        }
+        let origin = Vec2::new(
+            pixels(self.transform_origin_x(), size.width),
+            pixels(self.transform_origin_y(), size.height),
+        );
+        Affine::translate(translate + origin)
+            * Affine::rotate(rotate.to_radians())
+            * Affine::scale_non_uniform(scale_x, scale_y)
+            * Affine::translate(-origin)
     }
 }
 
@@ -148,7 +190,9 @@ pub struct ViewState {
     pub(crate) layout_props: LayoutProps,
     pub(crate) view_style_props: ViewStyleProps,
     pub(crate) animation: Option<Animation>,
+    pub(crate) transitions: PropTransitions,
     pub(crate) classes: Vec<StyleClassRef>,
+    pub(crate) debug_name: Vec<String>,
     pub(crate) dragging_style: Option<Style>,
     pub(crate) combined_style: Style,
     pub(crate) taffy_style: taffy::style::Style,
@@ -159,6 +203,7 @@ pub struct ViewState {
     pub(crate) move_listener: Option<MoveListener>,
     pub(crate) cleanup_listener: Option<Box<dyn Fn()>>,
     pub(crate) last_pointer_down: Option<PointerInputEvent>,
+    pub(crate) accessibility: Option<Accessibility>,
 }
 
 impl ViewState {
@@ -173,7 +218,9 @@ impl ViewState {
             request_style_recursive: false,
             has_style_selectors: StyleSelectors::default(),
             animation: None,
+            transitions: PropTransitions::default(),
             classes: Vec::new(),
+            debug_name: Vec::new(),
             combined_style: Style::new(),
             taffy_style: taffy::style::Style::DEFAULT,
             dragging_style: None,
@@ -184,6 +231,7 @@ impl ViewState {
             move_listener: None,
             cleanup_listener: None,
             last_pointer_down: None,
+            accessibility: None,
         }
     }
 
@@ -231,20 +279,26 @@ impl ViewState {
             .apply_classes_from_context(classes, context)
             .apply(view_data.style());
 
+        let mut animated = Vec::new();
         'anim: {
             if let Some(animation) = self.animation.as_mut() {
                 // Means effectively no changes should be applied - bail out
                 if animation.is_completed() && animation.is_auto_reverse() {
                     break 'anim;
                 }
+                animated = animation.style_keys();
 
-                new_frame = true;
+                // Animations driven by a timeline only move when the timeline does
+                let driven = driven_elapsed(animation.id());
+                new_frame = driven.is_none();
+                let elapsed = driven
+                    .or_else(|| animation.elapsed())
+                    .unwrap_or(Duration::ZERO);
 
                 let props = animation.props();
 
                 for kind in props.keys() {
-                    let val =
-                        animation.animate_prop(animation.elapsed().unwrap_or(Duration::ZERO), kind);
+                    let val = animation.animate_prop(elapsed, kind);
                     match kind {
                         AnimPropKind::Width => {
                             computed_style = computed_style.width(val.get_f32());
@@ -258,8 +312,9 @@ impl ViewState {
                         AnimPropKind::Scale => todo!(),
                     }
                 }
+                animation.animate_keyframes(elapsed, &mut computed_style);
 
-                if animation.can_advance() {
+                if driven.is_none() && animation.can_advance() {
                     animation.advance();
                     debug_assert!(!animation.is_idle());
                 }
@@ -270,6 +325,16 @@ impl ViewState {
 
         computed_style.apply_interact_state(&interact_state, screen_size_bp);
 
+        if self
+            .transitions
+            .apply(&mut computed_style, context, clock::now(), &animated)
+        {
+            new_frame = true;
+            if self.transitions.has_inherited() {
+                self.request_style_recursive = true;
+            }
+        }
+
         self.combined_style = computed_style;
 
         new_frame
@@ -7,9 +7,10 @@ use floem_winit::keyboard::{Key, ModifiersState};
 use kurbo::{Point, Rect};
 
 use crate::{
+    accessibility::Accessibility,
     action::{set_window_menu, set_window_title, update_window_scale},
     animate::Animation,
-    event::{Event, EventListener},
+    event::{Event, EventListener, FileDragEvent},
     menu::Menu,
     style::{Style, StyleClass, StyleSelector},
     view::View,
@@ -62,6 +63,12 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Adds a name to the view which can be used to find it in tests and the inspector.
+    fn debug_name(self, name: impl Into<String>) -> Self {
+        self.id().add_debug_name(name.into());
+        self
+    }
+
     /// Allows the element to be navigated to with the keyboard. Similar to setting tabindex="0" in html.
     fn keyboard_navigatable(self) -> Self {
         let id = self.id();
@@ -75,6 +82,27 @@ pub trait Decorators: View + Sized {
         self
     }
 
+    /// Describes the view to assistive technologies. The closure is rerun when the signals it
+    /// reads change.
+    /// ```rust
+    /// # use floem::accessibility::Role;
+    /// # use floem::reactive::create_rw_signal;
+    /// # use floem::views::{label, Decorators};
+    /// let count = create_rw_signal(0);
+    /// label(move || count.get().to_string())
+    ///     .accessibility(move |a| a.role(Role::Label).value(count.get().to_string()));
+    /// ```
+    fn accessibility(
+        self,
+        accessibility: impl Fn(Accessibility) -> Accessibility + 'static,
+    ) -> Self {
+        let id = self.id();
+        create_effect(move |_| {
+            id.update_accessibility(accessibility(Accessibility::new()));
+        });
+        self
+    }
+
     fn disabled(self, disabled_fn: impl Fn() -> bool + 'static) -> Self {
         let id = self.id();
 
@@ -232,6 +260,18 @@ pub trait Decorators: View + Sized {
         })
     }
 
+    /// Add an event handler for [EventListener::FileDrop], which is called for each file
+    /// dropped onto the view. This event will be handled with the given handler and the
+    /// event will stop propagating.
+    fn on_file_drop(self, action: impl Fn(&FileDragEvent) + 'static) -> Self {
+        self.on_event(EventListener::FileDrop, move |e| {
+            if let Event::FileDrop(file_event) = e {
+                action(file_event);
+            }
+            EventPropagation::Stop
+        })
+    }
+
     fn on_resize(self, action: impl Fn(Rect) + 'static) -> Self {
         let id = self.id();
         id.update_resize_listener(Box::new(action));
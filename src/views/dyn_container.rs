@@ -1,10 +1,14 @@
 use floem_reactive::{as_child_of_current_scope, create_updater, Scope};
+use taffy::prelude::NodeId;
 
 use crate::{
+    context::{LayoutCx, PaintCx, StyleCx},
     id::Id,
     view::{view_children_set_parent_id, AnyView, View, ViewData, Widget},
 };
 
+use super::{transition::Transitions, ViewTransition};
+
 type ChildFn<T> = dyn Fn(T) -> (Box<dyn Widget>, Scope);
 
 /// A container for a dynamically updating View. See [`dyn_container`]
@@ -13,6 +17,7 @@ pub struct DynamicContainer<T: 'static> {
     child: Box<dyn Widget>,
     child_scope: Scope,
     child_fn: Box<ChildFn<T>>,
+    transitions: Transitions,
 }
 
 /// A container for a dynamically updating View
@@ -78,6 +83,29 @@ pub fn dyn_container<CF: Fn(T) -> AnyView + 'static, T: 'static>(
         child,
         child_scope,
         child_fn,
+        transitions: Transitions::default(),
+    }
+}
+
+impl<T: 'static> DynamicContainer<T> {
+    /// Use `transition` when the child is replaced, for both the new child entering and the old
+    /// child exiting
+    pub fn transition(self, transition: ViewTransition) -> Self {
+        self.enter_transition(transition.clone())
+            .exit_transition(transition)
+    }
+
+    /// Animate the new child in with `transition` when the child is replaced
+    pub fn enter_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.enter = Some(transition);
+        self
+    }
+
+    /// Animate the old child out with `transition` when the child is replaced. The old child and
+    /// its [`Scope`] are kept until the transition is finished.
+    pub fn exit_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.exit = Some(transition);
+        self
     }
 }
 
@@ -125,13 +153,36 @@ impl<T: 'static> Widget for DynamicContainer<T> {
 
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
         if let Ok(val) = state.downcast::<T>() {
-            let old_child_scope = self.child_scope;
-            cx.app_state_mut().remove_view(&mut self.child);
-            (self.child, self.child_scope) = (self.child_fn)(*val);
-            old_child_scope.dispose();
+            let (child, child_scope) = (self.child_fn)(*val);
+            let old_child = std::mem::replace(&mut self.child, child);
+            let old_child_scope = std::mem::replace(&mut self.child_scope, child_scope);
+            // The old child is kept until its exit transition is done, if there is one
+            self.transitions
+                .exit_child(cx.app_state_mut(), old_child, old_child_scope);
+            self.transitions.enter(self.child.view_data().id());
             self.child.view_data().id().set_parent(self.id());
             view_children_set_parent_id(&*self.child);
             cx.request_all(self.id());
         }
     }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        cx.style_view(&mut self.child);
+        let id = self.id();
+        self.transitions.style_exiting(cx, id);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> NodeId {
+        cx.layout_node(self.id(), true, |cx| {
+            let mut nodes = vec![cx.layout_view(&mut self.child)];
+            nodes.extend(self.transitions.layout_exiting(cx));
+            nodes
+        })
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        self.transitions.paint_exiting(cx);
+        self.transitions.paint_child(cx, &mut self.child);
+        self.transitions.finish(cx, self.id());
+    }
 }
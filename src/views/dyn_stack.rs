@@ -1,18 +1,22 @@
 use std::{
+    collections::HashSet,
     hash::{BuildHasherDefault, Hash},
     marker::PhantomData,
 };
 
-use floem_reactive::{as_child_of_current_scope, create_effect, Scope};
+use floem_reactive::{as_child_of_current_scope, create_effect, Scope, Store, StoreDiff, StoreVec};
 use rustc_hash::FxHasher;
 use smallvec::SmallVec;
+use taffy::prelude::NodeId;
 
 use crate::{
-    context::{AppState, UpdateCx},
+    context::{AppState, LayoutCx, PaintCx, StyleCx, UpdateCx},
     id::Id,
     view::{view_children_set_parent_id, AnyWidget, View, ViewData, Widget},
 };
 
+use super::{transition::Transitions, ViewTransition};
+
 pub(crate) type FxIndexSet<T> = indexmap::IndexSet<T, BuildHasherDefault<FxHasher>>;
 
 #[derive(educe::Educe)]
@@ -26,6 +30,9 @@ where
     data: ViewData,
     children: Vec<Option<(AnyWidget, Scope)>>,
     view_fn: Box<dyn Fn(T) -> (AnyWidget, Scope)>,
+    transitions: Transitions,
+    /// Items added before the stack is first painted don't use the enter transition
+    painted: bool,
     phantom: PhantomData<T>,
 }
 
@@ -118,10 +125,144 @@ where
         data: ViewData::new(id),
         children: Vec::new(),
         view_fn,
+        transitions: Transitions::default(),
+        painted: false,
+        phantom: PhantomData,
+    }
+}
+
+/// A [`dyn_stack`] over a [`StoreVec`], which applies the changes recorded by the store instead of
+/// diffing the keys of every item whenever the list changes.
+///
+/// The view function is given the signals of each item, so changing an item only updates the
+/// views that read it.
+///
+/// ## Example
+/// ```
+/// use floem::reactive::*;
+/// use floem::views::*;
+///
+/// let items = create_store_vec([(1, String::from("a")), (2, String::from("b"))]);
+///
+/// dyn_stack_store(items, move |item| label(move || item.get()));
+///
+/// items.push(3, String::from("c"));
+/// ```
+pub fn dyn_stack_store<K, T, VF, V>(store: StoreVec<K, T>, view_fn: VF) -> DynStack<T::Handle>
+where
+    K: Clone + PartialEq + 'static,
+    T: Store,
+    VF: Fn(T::Handle) -> V + 'static,
+    V: View + 'static,
+{
+    let id = Id::next();
+    create_effect(move |prev: Option<(u64, usize)>| {
+        let version = store.version();
+        let len = store.with_untracked(|items| items.len());
+
+        let diffs = prev
+            .and_then(|(prev_version, prev_len)| {
+                let diffs = store.diffs_since(prev_version)?;
+                Some(store_diffs(diffs, prev_len))
+            })
+            .unwrap_or_else(|| {
+                // Rebuild from the current items, either on the first run or when the store no
+                // longer has all of the changes since the last run
+                let mut diff = Diff {
+                    clear: prev.is_some(),
+                    ..Default::default()
+                };
+                store.with_untracked(|items| {
+                    for (i, item) in items.iter().enumerate() {
+                        diff.added.push(DiffOpAdd {
+                            at: i,
+                            view: Some(item.handle),
+                        });
+                    }
+                });
+                vec![diff]
+            });
+
+        if !diffs.is_empty() {
+            id.update_state(diffs);
+        }
+        (version, len)
+    });
+    let view_fn = Box::new(as_child_of_current_scope(move |e| view_fn(e).build()));
+    DynStack {
+        data: ViewData::new(id),
+        children: Vec::new(),
+        view_fn,
+        transitions: Transitions::default(),
+        painted: false,
         phantom: PhantomData,
     }
 }
 
+/// Convert the changes of a store into a diff for each change, given the length of the list
+/// before them.
+fn store_diffs<K, H>(store_diffs: Vec<StoreDiff<K, H>>, mut len: usize) -> Vec<Diff<H>> {
+    store_diffs
+        .into_iter()
+        .map(|store_diff| {
+            let mut diff = Diff::default();
+            match store_diff {
+                StoreDiff::Insert { at, handle, .. } => {
+                    // Shift the items after the inserted one
+                    diff.moved
+                        .extend((at..len).rev().map(|i| DiffOpMove { from: i, to: i + 1 }));
+                    diff.added.push(DiffOpAdd {
+                        at,
+                        view: Some(handle),
+                    });
+                    len += 1;
+                }
+                StoreDiff::Remove { at, .. } => {
+                    diff.removed.push(DiffOpRemove { at });
+                    len = len.saturating_sub(1);
+                }
+                StoreDiff::Move { from, to, .. } => {
+                    diff.moved.push(DiffOpMove { from, to });
+                    if from < to {
+                        diff.moved
+                            .extend((from + 1..=to).map(|i| DiffOpMove { from: i, to: i - 1 }));
+                    } else {
+                        diff.moved
+                            .extend((to..from).map(|i| DiffOpMove { from: i, to: i + 1 }));
+                    }
+                }
+                StoreDiff::Clear => {
+                    diff.clear = true;
+                    len = 0;
+                }
+            }
+            diff
+        })
+        .collect()
+}
+
+impl<T> DynStack<T> {
+    /// Use `transition` for both the items which are added and the items which are removed
+    pub fn transition(self, transition: ViewTransition) -> Self {
+        self.enter_transition(transition.clone())
+            .exit_transition(transition)
+    }
+
+    /// Animate the views of items which are added with `transition`. The items present when the
+    /// stack is first shown appear straight away.
+    pub fn enter_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.enter = Some(transition);
+        self
+    }
+
+    /// Animate the views of items which are removed with `transition`. They're kept where they
+    /// were, along with their [`Scope`], until the transition is finished.
+    pub fn exit_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.exit = Some(transition);
+        self
+    }
+}
+
 impl<T> View for DynStack<T> {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -182,16 +323,71 @@ impl<T> Widget for DynStack<T> {
     }
 
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
-        if let Ok(diff) = state.downcast() {
-            apply_diff(
-                self.id(),
+        let diffs = match state.downcast::<Diff<T>>() {
+            Ok(diff) => vec![*diff],
+            // A `dyn_stack_store` sends a diff for each change to the store
+            Err(state) => match state.downcast::<Vec<Diff<T>>>() {
+                Ok(diffs) => *diffs,
+                Err(_) => return,
+            },
+        };
+        let existing: HashSet<Id> = self
+            .children
+            .iter()
+            .flatten()
+            .map(|(child, _)| child.view_data().id())
+            .collect();
+        let transitions = &mut self.transitions;
+        for diff in diffs {
+            apply_diff_with(
+                self.data.id(),
                 cx.app_state,
-                *diff,
+                diff,
                 &mut self.children,
                 &self.view_fn,
+                |app_state, child, scope| transitions.exit_child(app_state, child, scope),
             );
-            cx.request_all(self.id());
         }
+        if self.painted {
+            for (child, _) in self.children.iter().flatten() {
+                let id = child.view_data().id();
+                if !existing.contains(&id) {
+                    self.transitions.enter(id);
+                }
+            }
+        }
+        cx.request_all(self.id());
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        for (child, _) in self.children.iter_mut().flatten() {
+            cx.style_view(child);
+        }
+        let id = self.id();
+        self.transitions.style_exiting(cx, id);
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> NodeId {
+        cx.layout_node(self.id(), true, |cx| {
+            let mut nodes = self
+                .children
+                .iter_mut()
+                .flatten()
+                .map(|(child, _)| cx.layout_view(child))
+                .collect::<Vec<_>>();
+            // Exiting children stay in the layout so they keep their place until they're removed
+            nodes.extend(self.transitions.layout_exiting(cx));
+            nodes
+        })
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        self.transitions.paint_exiting(cx);
+        for (child, _) in self.children.iter_mut().flatten() {
+            self.transitions.paint_child(cx, child);
+        }
+        self.transitions.finish(cx, self.id());
+        self.painted = true;
     }
 }
 
@@ -324,19 +520,45 @@ fn remove_index<V: Widget>(
     app_state: &mut AppState,
     children: &mut [Option<(V, Scope)>],
     index: usize,
+    remove: &mut impl FnMut(&mut AppState, V, Scope),
 ) -> Option<()> {
-    let (mut view, scope) = std::mem::take(&mut children[index])?;
-    app_state.remove_view(&mut view);
-    scope.dispose();
+    let (view, scope) = std::mem::take(&mut children[index])?;
+    remove(app_state, view, scope);
     Some(())
 }
 
 pub(super) fn apply_diff<T, V, VF>(
+    view_id: Id,
+    app_state: &mut AppState,
+    diff: Diff<T>,
+    children: &mut Vec<Option<(V, Scope)>>,
+    view_fn: &VF,
+) where
+    V: Widget,
+    VF: Fn(T) -> (V, Scope),
+{
+    apply_diff_with(
+        view_id,
+        app_state,
+        diff,
+        children,
+        view_fn,
+        |app_state, mut view, scope| {
+            app_state.remove_view(&mut view);
+            scope.dispose();
+        },
+    );
+}
+
+/// [`apply_diff`], with `remove` responsible for removing the children which are removed from the
+/// list
+pub(super) fn apply_diff_with<T, V, VF>(
     view_id: Id,
     app_state: &mut AppState,
     mut diff: Diff<T>,
     children: &mut Vec<Option<(V, Scope)>>,
     view_fn: &VF,
+    mut remove: impl FnMut(&mut AppState, V, Scope),
 ) where
     V: Widget,
     VF: Fn(T) -> (V, Scope),
@@ -361,13 +583,13 @@ pub(super) fn apply_diff<T, V, VF>(
     // 4. Add
     if diff.clear {
         for i in 0..children.len() {
-            remove_index(app_state, children, i);
+            remove_index(app_state, children, i, &mut remove);
         }
         diff.removed.clear();
     }
 
     for DiffOpRemove { at } in diff.removed {
-        remove_index(app_state, children, at);
+        remove_index(app_state, children, at, &mut remove);
     }
 
     for DiffOpMove { from, to } in diff.moved {
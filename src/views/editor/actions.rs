@@ -2,11 +2,12 @@ use std::ops::Range;
 
 use crate::keyboard::ModifiersState;
 use floem_editor_core::{
-    command::{EditCommand, MotionModeCommand, MultiSelectionCommand, ScrollCommand},
-    cursor::Cursor,
+    command::{EditCommand, FocusCommand, MotionModeCommand, MultiSelectionCommand, ScrollCommand},
+    cursor::{Cursor, CursorMode},
     mode::MotionMode,
     movement::Movement,
     register::Register,
+    selection::Selection,
 };
 
 use super::{
@@ -30,6 +31,7 @@ pub fn handle_command_default(
         Command::Scroll(cmd) => handle_scroll_command_default(ed, cmd, count, modifiers),
         Command::MotionMode(cmd) => handle_motion_mode_command_default(ed, action, cmd, count),
         Command::MultiSelection(cmd) => handle_multi_selection_command_default(ed, cmd),
+        Command::Focus(cmd) => handle_focus_command_default(ed, cmd),
     }
 }
 fn handle_edit_command_default(
@@ -158,6 +160,65 @@ fn handle_multi_selection_command_default(
     CommandExecuted::Yes
 }
 
+fn handle_focus_command_default(ed: &Editor, cmd: &FocusCommand) -> CommandExecuted {
+    match cmd {
+        FocusCommand::Search => {
+            // Search for the selected text, if there is any
+            let pattern = ed.cursor.with_untracked(|cursor| match &cursor.mode {
+                CursorMode::Insert(selection) => selection
+                    .last_inserted()
+                    .filter(|region| !region.is_caret())
+                    .map(|region| {
+                        ed.text()
+                            .slice_to_cow(region.min()..region.max())
+                            .to_string()
+                    }),
+                _ => None,
+            });
+            if let Some(pattern) = pattern {
+                ed.update_find(|find| {
+                    find.set_pattern(pattern);
+                    find.set_whole_words(false);
+                });
+            }
+        }
+        FocusCommand::SearchForward => {
+            ed.search(true);
+        }
+        FocusCommand::SearchBackward => {
+            ed.search(false);
+        }
+        FocusCommand::SearchWholeWordForward => {
+            let offset = ed.cursor.with_untracked(|cursor| cursor.offset());
+            let (start, end) = ed.select_word(offset);
+            if start == end {
+                return CommandExecuted::Yes;
+            }
+            let word = ed.text().slice_to_cow(start..end).to_string();
+            ed.update_find(|find| {
+                find.set_pattern(word);
+                find.set_regex(false);
+                find.set_whole_words(true);
+            });
+            // Move past the word under the cursor
+            ed.cursor.update(|cursor| match cursor.mode {
+                CursorMode::Insert(_) => cursor.set_insert(Selection::caret(end)),
+                _ => cursor.mode = CursorMode::Normal(start),
+            });
+            ed.search(true);
+        }
+        FocusCommand::ToggleCaseSensitive => {
+            ed.update_find(|find| find.set_case_sensitive(!find.case_sensitive()));
+        }
+        FocusCommand::ClearSearch => {
+            ed.update_find(|find| find.set_pattern(""));
+        }
+        _ => return CommandExecuted::No,
+    }
+
+    CommandExecuted::Yes
+}
+
 /// Trait for common actions needed for the default implementation of the
 /// operations.
 pub trait CommonAction {
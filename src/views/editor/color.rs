@@ -30,4 +30,6 @@ pub enum EditorColor {
     StickyHeaderBackground,
     #[strum(serialize = "editor.preedit.underline")]
     PreeditUnderline,
+    #[strum(serialize = "editor.find_match")]
+    FindMatch,
 }
@@ -1,5 +1,5 @@
 use floem_editor_core::command::{
-    EditCommand, MotionModeCommand, MoveCommand, MultiSelectionCommand, ScrollCommand,
+    EditCommand, FocusCommand, MotionModeCommand, MoveCommand, MultiSelectionCommand, ScrollCommand,
 };
 use strum::EnumMessage;
 
@@ -10,6 +10,7 @@ pub enum Command {
     Scroll(ScrollCommand),
     MotionMode(MotionModeCommand),
     MultiSelection(MultiSelectionCommand),
+    Focus(FocusCommand),
 }
 
 impl Command {
@@ -20,6 +21,7 @@ impl Command {
             Command::Scroll(cmd) => cmd.get_message(),
             Command::MotionMode(cmd) => cmd.get_message(),
             Command::MultiSelection(cmd) => cmd.get_message(),
+            Command::Focus(cmd) => cmd.get_message(),
         }
     }
 
@@ -30,6 +32,7 @@ impl Command {
             Command::Scroll(cmd) => cmd.into(),
             Command::MotionMode(cmd) => cmd.into(),
             Command::MultiSelection(cmd) => cmd.into(),
+            Command::Focus(cmd) => cmd.into(),
         }
     }
 }
@@ -1,8 +1,11 @@
 use crate::{
-    context::PaintCx,
+    context::{EventCx, EventPropagation, PaintCx},
     cosmic_text::{Attrs, AttrsList, TextLayout},
+    event::Event,
     id::Id,
-    peniko::kurbo::Point,
+    peniko::kurbo::{BezPath, Point},
+    peniko::Color,
+    pointer::{PointerButton, PointerInputEvent},
     style::Style,
     view::{AnyWidget, View, ViewData, Widget},
     Renderer,
@@ -129,6 +132,9 @@ impl Widget for EditorGutterView {
 
         self.text_width = self.compute_widest_text_width(&attrs_list);
 
+        let folding = editor.folding();
+        let fold_marker_color = style.color(edid, EditorColor::Dim);
+
         editor.screen_lines.with_untracked(|screen_lines| {
             for (line, y) in screen_lines.iter_lines_y() {
                 // If it ends up outside the bounds of the file, stop trying to display line numbers
@@ -164,12 +170,85 @@ impl Widget for EditorGutterView {
                 );
 
                 cx.draw_text(&text_layout, pos);
+
+                let fold = folding.and_then(|folding| {
+                    folding.with_untracked(|folding| {
+                        folding
+                            .range_at(line)
+                            .map(|range| folding.is_folded(range.start_line))
+                    })
+                });
+                if let Some(is_folded) = fold {
+                    let center = Point::new(
+                        self.full_width - self.padding_right / 2.0,
+                        y + line_height / 2.0 - viewport.y0,
+                    );
+                    Self::paint_fold_marker(cx, center, is_folded, fold_marker_color);
+                }
             }
         });
     }
+
+    fn event(
+        &mut self,
+        _cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if let Event::PointerDown(PointerInputEvent {
+            button: PointerButton::Primary,
+            pos,
+            ..
+        }) = event
+        {
+            // Fold markers are drawn in the right padding of the gutter
+            if pos.x < self.full_width - self.padding_right {
+                return EventPropagation::Continue;
+            }
+
+            let editor = self.editor.get_untracked();
+            if editor.folding().is_none() {
+                return EventPropagation::Continue;
+            }
+
+            let viewport = editor.viewport.get_untracked();
+            let style = editor.style.get_untracked();
+            let y = pos.y + viewport.y0;
+            let line = editor.screen_lines.with_untracked(|screen_lines| {
+                screen_lines.iter_lines_y().find_map(|(line, line_y)| {
+                    let line_height = f64::from(style.line_height(editor.id(), line));
+                    (line_y..line_y + line_height).contains(&y).then_some(line)
+                })
+            });
+
+            if let Some(line) = line {
+                editor.toggle_fold(line);
+                return EventPropagation::Stop;
+            }
+        }
+
+        EventPropagation::Continue
+    }
 }
 
 impl EditorGutterView {
+    /// Paint a triangle pointing right for a folded region, or down for an unfolded one.
+    fn paint_fold_marker(cx: &mut PaintCx, center: Point, is_folded: bool, color: Color) {
+        let size = 4.0;
+        let mut path = BezPath::new();
+        if is_folded {
+            path.move_to(center + (-size / 2.0, -size));
+            path.line_to(center + (size, 0.0));
+            path.line_to(center + (-size / 2.0, size));
+        } else {
+            path.move_to(center + (-size, -size / 2.0));
+            path.line_to(center + (size, -size / 2.0));
+            path.line_to(center + (0.0, size));
+        }
+        path.close_path();
+        cx.fill(&path, color, 0.0);
+    }
+
     fn compute_widest_text_width(&mut self, attrs_list: &AttrsList) -> f64 {
         let last_line = self.editor.get_untracked().last_line() + 1;
         let mut text = TextLayout::new();
@@ -0,0 +1,241 @@
+use std::{fmt::Display, str::FromStr};
+
+use floem_editor_core::{
+    command::{
+        EditCommand, FocusCommand, MotionModeCommand, MoveCommand, MultiSelectionCommand,
+        ScrollCommand,
+    },
+    mode::Mode,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{super::command::Command, press::KeyPress};
+
+/// A condition on the state of the editor which must hold for a keybinding to apply.
+/// Written as the atoms `modal`, `normal_mode`, `insert_mode`, `visual_mode` and `terminal_mode`,
+/// combined with `!`, `&&` and `||`. For example, `modal && !insert_mode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapCondition {
+    /// Whether modal editing is enabled
+    Modal,
+    /// Whether the cursor is in the given mode. Any visual mode matches `Mode::Visual`.
+    Mode(Mode),
+    Not(Box<KeymapCondition>),
+    And(Box<KeymapCondition>, Box<KeymapCondition>),
+    Or(Box<KeymapCondition>, Box<KeymapCondition>),
+}
+
+impl KeymapCondition {
+    pub fn check(&self, modal: bool, mode: Mode) -> bool {
+        match self {
+            KeymapCondition::Modal => modal,
+            KeymapCondition::Mode(Mode::Visual(_)) => matches!(mode, Mode::Visual(_)),
+            KeymapCondition::Mode(m) => *m == mode,
+            KeymapCondition::Not(cond) => !cond.check(modal, mode),
+            KeymapCondition::And(a, b) => a.check(modal, mode) && b.check(modal, mode),
+            KeymapCondition::Or(a, b) => a.check(modal, mode) || b.check(modal, mode),
+        }
+    }
+}
+
+impl FromStr for KeymapCondition {
+    type Err = KeymapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || KeymapError::InvalidCondition(s.to_string());
+
+        if let Some((a, b)) = s.split_once("||") {
+            return Ok(KeymapCondition::Or(
+                Box::new(a.parse().map_err(|_| invalid())?),
+                Box::new(b.parse().map_err(|_| invalid())?),
+            ));
+        }
+        if let Some((a, b)) = s.split_once("&&") {
+            return Ok(KeymapCondition::And(
+                Box::new(a.parse().map_err(|_| invalid())?),
+                Box::new(b.parse().map_err(|_| invalid())?),
+            ));
+        }
+
+        let s = s.trim();
+        if let Some(cond) = s.strip_prefix('!') {
+            return Ok(KeymapCondition::Not(Box::new(
+                cond.parse().map_err(|_| invalid())?,
+            )));
+        }
+
+        Ok(match s {
+            "modal" => KeymapCondition::Modal,
+            "normal_mode" => KeymapCondition::Mode(Mode::Normal),
+            "insert_mode" => KeymapCondition::Mode(Mode::Insert),
+            "visual_mode" => KeymapCondition::Mode(Mode::Visual(Default::default())),
+            "terminal_mode" => KeymapCondition::Mode(Mode::Terminal),
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+impl Display for KeymapCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeymapCondition::Modal => f.write_str("modal"),
+            KeymapCondition::Mode(Mode::Normal) => f.write_str("normal_mode"),
+            KeymapCondition::Mode(Mode::Insert) => f.write_str("insert_mode"),
+            KeymapCondition::Mode(Mode::Visual(_)) => f.write_str("visual_mode"),
+            KeymapCondition::Mode(Mode::Terminal) => f.write_str("terminal_mode"),
+            KeymapCondition::Not(cond) => write!(f, "!{cond}"),
+            KeymapCondition::And(a, b) => write!(f, "{a} && {b}"),
+            KeymapCondition::Or(a, b) => write!(f, "{a} || {b}"),
+        }
+    }
+}
+
+/// A command bound to a sequence of keypresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    /// The keypresses which must be pressed one after another, such as `ctrl+k ctrl+c`
+    pub keys: Vec<KeyPress>,
+    pub command: Command,
+    pub when: Option<KeymapCondition>,
+}
+
+impl Keymap {
+    pub fn new(keys: Vec<KeyPress>, command: Command) -> Self {
+        Self {
+            keys,
+            command,
+            when: None,
+        }
+    }
+
+    pub fn when(mut self, when: KeymapCondition) -> Self {
+        self.when = Some(when);
+        self
+    }
+
+    pub fn applies(&self, modal: bool, mode: Mode) -> bool {
+        self.when
+            .as_ref()
+            .map(|when| when.check(modal, mode))
+            .unwrap_or(true)
+    }
+
+    /// Parse an entry of a keymap file. Entries which remove a binding are rejected, use
+    /// [`KeymapEntry::is_unbind`] to check for those.
+    pub fn from_entry(entry: &KeymapEntry) -> Result<Self, KeymapError> {
+        let keys = parse_keys(&entry.key)?;
+        let command = parse_command(&entry.command)?;
+        let when = entry.when.as_deref().map(str::parse).transpose()?;
+
+        Ok(Self {
+            keys,
+            command,
+            when,
+        })
+    }
+
+    pub fn to_entry(&self) -> KeymapEntry {
+        KeymapEntry {
+            key: keys_to_string(&self.keys),
+            command: self.command.str().to_string(),
+            when: self.when.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// An entry in a keymap file, such as
+/// ```toml
+/// [[keymaps]]
+/// key = "ctrl+k ctrl+c"
+/// command = "toggle_line_comment"
+/// when = "!modal || insert_mode"
+/// ```
+/// Prefixing the command with `-` removes the binding of the command to the key instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeymapEntry {
+    pub key: String,
+    pub command: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub when: Option<String>,
+}
+
+impl KeymapEntry {
+    /// Whether the entry removes a binding rather than adding one.
+    pub fn is_unbind(&self) -> bool {
+        self.command.starts_with('-')
+    }
+}
+
+/// The contents of a keymap file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeymapFile {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keymaps: Vec<KeymapEntry>,
+}
+
+#[derive(Debug)]
+pub enum KeymapError {
+    InvalidKey(String),
+    UnknownCommand(String),
+    InvalidCondition(String),
+    #[cfg(feature = "serde")]
+    TomlDe(toml::de::Error),
+    #[cfg(feature = "serde")]
+    TomlSer(toml::ser::Error),
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+}
+
+impl Display for KeymapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeymapError::InvalidKey(key) => write!(f, "invalid key: {key}"),
+            KeymapError::UnknownCommand(cmd) => write!(f, "unknown command: {cmd}"),
+            KeymapError::InvalidCondition(cond) => write!(f, "invalid condition: {cond}"),
+            #[cfg(feature = "serde")]
+            KeymapError::TomlDe(err) => write!(f, "failed to parse keymap: {err}"),
+            #[cfg(feature = "serde")]
+            KeymapError::TomlSer(err) => write!(f, "failed to serialize keymap: {err}"),
+            #[cfg(feature = "serde")]
+            KeymapError::Json(err) => write!(f, "failed to parse keymap: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// Parse a sequence of keypresses separated by spaces, such as `ctrl+k ctrl+c`.
+pub fn parse_keys(key: &str) -> Result<Vec<KeyPress>, KeymapError> {
+    let keys = KeyPress::parse(key);
+    // Unrecognized keys are skipped by `KeyPress::parse`
+    if keys.is_empty() || keys.len() != key.split_whitespace().count() {
+        return Err(KeymapError::InvalidKey(key.to_string()));
+    }
+
+    Ok(keys)
+}
+
+pub fn keys_to_string(keys: &[KeyPress]) -> String {
+    keys.iter()
+        .map(|key| key.to_string().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a command from its name, as given by [`Command::str`].
+pub fn parse_command(name: &str) -> Result<Command, KeymapError> {
+    EditCommand::from_str(name)
+        .map(Command::Edit)
+        .or_else(|_| MoveCommand::from_str(name).map(Command::Move))
+        .or_else(|_| ScrollCommand::from_str(name).map(Command::Scroll))
+        .or_else(|_| MotionModeCommand::from_str(name).map(Command::MotionMode))
+        .or_else(|_| MultiSelectionCommand::from_str(name).map(Command::MultiSelection))
+        .or_else(|_| FocusCommand::from_str(name).map(Command::Focus))
+        .map_err(|_| KeymapError::UnknownCommand(name.to_string()))
+}
@@ -1,11 +1,12 @@
 pub mod key;
+pub mod keymap;
 pub mod press;
 
-use std::{collections::HashMap, str::FromStr};
+use std::{cell::RefCell, collections::HashMap, str::FromStr};
 
 use crate::{keyboard::ModifiersState, reactive::RwSignal};
 use floem_editor_core::{
-    command::{EditCommand, MoveCommand, MultiSelectionCommand, ScrollCommand},
+    command::{EditCommand, FocusCommand, MoveCommand, MultiSelectionCommand, ScrollCommand},
     mode::Mode,
 };
 
@@ -14,34 +15,186 @@ use super::{
     Editor,
 };
 
-use self::{key::KeyInput, press::KeyPress};
+use self::{
+    key::KeyInput,
+    keymap::{parse_command, parse_keys, Keymap, KeymapCondition, KeymapEntry, KeymapError},
+    press::KeyPress,
+};
+
+/// The result of looking up a sequence of keypresses in a [`KeypressMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeymapMatch<'a> {
+    /// The keypresses are bound to the command
+    Full(&'a Command),
+    /// The keypresses are the start of a longer sequence, so more keypresses are needed
+    Prefix,
+    None,
+}
 
 /// The default keymap handler does not have modal-mode specific
 /// keybindings.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KeypressMap {
-    pub keymaps: HashMap<KeyPress, Command>,
+    /// The bindings for each sequence of keypresses. Later bindings take priority over earlier
+    /// ones.
+    pub keymaps: HashMap<Vec<KeyPress>, Vec<Keymap>>,
 }
 impl KeypressMap {
+    /// A keymap without any bindings, unlike [`KeypressMap::default`] which has the bindings of
+    /// the current platform
+    pub fn empty() -> Self {
+        Self {
+            keymaps: HashMap::new(),
+        }
+    }
+
     pub fn default_windows() -> Self {
         let mut keymaps = HashMap::new();
         add_default_common(&mut keymaps);
         add_default_windows(&mut keymaps);
-        Self { keymaps }
+        Self::from_keypresses(keymaps)
     }
 
     pub fn default_macos() -> Self {
         let mut keymaps = HashMap::new();
         add_default_common(&mut keymaps);
         add_default_macos(&mut keymaps);
-        Self { keymaps }
+        Self::from_keypresses(keymaps)
     }
 
     pub fn default_linux() -> Self {
         let mut keymaps = HashMap::new();
         add_default_common(&mut keymaps);
         add_default_linux(&mut keymaps);
-        Self { keymaps }
+        Self::from_keypresses(keymaps)
+    }
+
+    fn from_keypresses(keypresses: HashMap<KeyPress, Command>) -> Self {
+        let mut keymaps = Self::empty();
+        for (keypress, command) in keypresses {
+            keymaps.insert(Keymap::new(vec![keypress], command));
+        }
+        keymaps
+    }
+
+    /// Add a binding, which takes priority over any existing bindings for the same keypresses.
+    pub fn insert(&mut self, keymap: Keymap) {
+        self.keymaps
+            .entry(keymap.keys.clone())
+            .or_default()
+            .push(keymap);
+    }
+
+    /// Remove the bindings of `command` to the keypresses.  
+    /// If `when` is given then only the bindings with that condition are removed.
+    pub fn unbind(&mut self, keys: &[KeyPress], command: &Command, when: Option<&KeymapCondition>) {
+        let Some(keymaps) = self.keymaps.get_mut(keys) else {
+            return;
+        };
+        keymaps.retain(|keymap| {
+            keymap.command != *command
+                || when.is_some_and(|when| keymap.when.as_ref() != Some(when))
+        });
+        if keymaps.is_empty() {
+            self.keymaps.remove(keys);
+        }
+    }
+
+    /// Find the command bound to the keypresses, for the given modal state and mode.
+    pub fn lookup(&self, keys: &[KeyPress], modal: bool, mode: Mode) -> KeymapMatch<'_> {
+        let command = self.keymaps.get(keys).and_then(|keymaps| {
+            keymaps
+                .iter()
+                .rev()
+                .find(|keymap| keymap.applies(modal, mode))
+        });
+        if let Some(keymap) = command {
+            return KeymapMatch::Full(&keymap.command);
+        }
+
+        let is_prefix = self.keymaps.iter().any(|(seq, keymaps)| {
+            seq.len() > keys.len()
+                && seq.starts_with(keys)
+                && keymaps.iter().any(|keymap| keymap.applies(modal, mode))
+        });
+        if is_prefix {
+            KeymapMatch::Prefix
+        } else {
+            KeymapMatch::None
+        }
+    }
+
+    /// Merge the entries of a keymap file into the bindings, with the entries taking priority.  
+    /// Entries whose command starts with `-` remove the binding instead.  
+    /// Nothing is changed if any of the entries are invalid.
+    pub fn merge(&mut self, entries: &[KeymapEntry]) -> Result<(), KeymapError> {
+        enum Change {
+            Bind(Keymap),
+            Unbind(Keymap),
+        }
+
+        let changes = entries
+            .iter()
+            .map(|entry| match entry.command.strip_prefix('-') {
+                Some(command) => Ok(Change::Unbind(Keymap {
+                    keys: parse_keys(&entry.key)?,
+                    command: parse_command(command)?,
+                    when: entry.when.as_deref().map(str::parse).transpose()?,
+                })),
+                None => Keymap::from_entry(entry).map(Change::Bind),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for change in changes {
+            match change {
+                Change::Bind(keymap) => self.insert(keymap),
+                Change::Unbind(keymap) => {
+                    self.unbind(&keymap.keys, &keymap.command, keymap.when.as_ref())
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The bindings as keymap file entries, sorted by key so that the output is stable.
+    pub fn entries(&self) -> Vec<KeymapEntry> {
+        let mut keys: Vec<_> = self.keymaps.keys().collect();
+        keys.sort_by_cached_key(|keys| keymap::keys_to_string(keys));
+
+        keys.into_iter()
+            .flat_map(|keys| self.keymaps[keys].iter().map(Keymap::to_entry))
+            .collect()
+    }
+
+    /// Merge a TOML keymap file, which has a `[[keymaps]]` table for each entry.
+    #[cfg(feature = "serde")]
+    pub fn merge_toml(&mut self, s: &str) -> Result<(), KeymapError> {
+        let file: keymap::KeymapFile = toml::from_str(s).map_err(KeymapError::TomlDe)?;
+        self.merge(&file.keymaps)
+    }
+
+    /// Merge a JSON keymap file, which is an object with a `keymaps` array of entries.
+    #[cfg(feature = "serde")]
+    pub fn merge_json(&mut self, s: &str) -> Result<(), KeymapError> {
+        let file: keymap::KeymapFile = serde_json::from_str(s).map_err(KeymapError::Json)?;
+        self.merge(&file.keymaps)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_toml(&self) -> Result<String, KeymapError> {
+        let file = keymap::KeymapFile {
+            keymaps: self.entries(),
+        };
+        toml::to_string_pretty(&file).map_err(KeymapError::TomlSer)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, KeymapError> {
+        let file = keymap::KeymapFile {
+            keymaps: self.entries(),
+        };
+        serde_json::to_string_pretty(&file).map_err(KeymapError::Json)
     }
 }
 impl Default for KeypressMap {
@@ -108,6 +261,14 @@ fn add_default_common(c: &mut HashMap<KeyPress, Command>) {
         Command::MultiSelection(MultiSelectionCommand::InsertCursorEndOfLine),
     );
 
+    // --- Find ---
+
+    c.insert(key_d("f3"), Command::Focus(FocusCommand::SearchForward));
+    c.insert(
+        key("f3", ModifiersState::SHIFT),
+        Command::Focus(FocusCommand::SearchBackward),
+    );
+
     // TODO: should we have jump location backward/forward?
 
     // TODO: jump to snippet positions?
@@ -382,21 +543,49 @@ fn add_default_nonmacos(c: &mut HashMap<KeyPress, Command>) {
 pub fn default_key_handler(
     editor: RwSignal<Editor>,
 ) -> impl Fn(&KeyPress, ModifiersState) -> CommandExecuted + 'static {
-    let keypress_map = KeypressMap::default();
+    keymap_key_handler(editor, KeypressMap::default())
+}
+
+/// A key handler which runs the commands bound in `keypress_map`.  
+/// Keypresses that are the start of a chord are held onto until the chord is finished.
+pub fn keymap_key_handler(
+    editor: RwSignal<Editor>,
+    keypress_map: KeypressMap,
+) -> impl Fn(&KeyPress, ModifiersState) -> CommandExecuted + 'static {
+    let pending = RefCell::new(Vec::new());
     move |keypress, modifiers| {
-        let command = keypress_map.keymaps.get(keypress).or_else(|| {
-            let mode = editor.get_untracked().cursor.get_untracked().get_mode();
-            if mode == Mode::Insert {
-                let mut keypress = keypress.clone();
-                keypress.mods.set(ModifiersState::SHIFT, false);
-                keypress_map.keymaps.get(&keypress)
-            } else {
-                None
-            }
+        // Pressing a modifier on its own should not interrupt a chord
+        if keypress.is_modifiers() {
+            return CommandExecuted::No;
+        }
+
+        let (modal, mode) = editor.with_untracked(|editor| {
+            (
+                editor.modal.get_untracked(),
+                editor.cursor.with_untracked(|c| c.get_mode()),
+            )
         });
 
-        let Some(command) = command else {
-            return CommandExecuted::No;
+        let mut keys = pending.take();
+        let had_pending = !keys.is_empty();
+        keys.push(keypress.clone());
+
+        let mut found = keypress_map.lookup(&keys, modal, mode);
+        if found == KeymapMatch::None && mode == Mode::Insert {
+            let last = keys.last_mut().unwrap();
+            last.mods.set(ModifiersState::SHIFT, false);
+            found = keypress_map.lookup(&keys, modal, mode);
+        }
+
+        let command = match found {
+            KeymapMatch::Full(command) => command,
+            KeymapMatch::Prefix => {
+                *pending.borrow_mut() = keys;
+                return CommandExecuted::Yes;
+            }
+            // An unfinished chord swallows the keypress that doesn't continue it
+            KeymapMatch::None if had_pending => return CommandExecuted::Yes,
+            KeymapMatch::None => return CommandExecuted::No,
         };
 
         editor.with_untracked(|editor| {
@@ -406,3 +595,96 @@ pub fn default_key_handler(
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::{
+        command::{EditCommand, MoveCommand},
+        mode::Mode,
+    };
+
+    use super::{
+        keymap::{parse_keys, KeymapEntry},
+        Command, KeymapMatch, KeypressMap,
+    };
+
+    fn entry(key: &str, command: &str, when: Option<&str>) -> KeymapEntry {
+        KeymapEntry {
+            key: key.to_string(),
+            command: command.to_string(),
+            when: when.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn defaults_round_trip() {
+        for keymaps in [
+            KeypressMap::default_linux(),
+            KeypressMap::default_macos(),
+            KeypressMap::default_windows(),
+        ] {
+            let mut loaded = KeypressMap::empty();
+            loaded.merge(&keymaps.entries()).unwrap();
+            assert_eq!(loaded, keymaps);
+            assert_eq!(loaded.entries(), keymaps.entries());
+        }
+    }
+
+    #[test]
+    fn chords_and_conditions() {
+        let mut keymaps = KeypressMap::empty();
+        keymaps
+            .merge(&[
+                entry("ctrl+k ctrl+c", "toggle_line_comment", None),
+                entry("j", "down", Some("modal && normal_mode")),
+            ])
+            .unwrap();
+
+        let ctrl_k = parse_keys("ctrl+k").unwrap();
+        assert_eq!(
+            keymaps.lookup(&ctrl_k, false, Mode::Insert),
+            KeymapMatch::Prefix
+        );
+        let chord = parse_keys("ctrl+k ctrl+c").unwrap();
+        assert_eq!(
+            keymaps.lookup(&chord, false, Mode::Insert),
+            KeymapMatch::Full(&Command::Edit(EditCommand::ToggleLineComment))
+        );
+
+        let j = parse_keys("j").unwrap();
+        assert_eq!(
+            keymaps.lookup(&j, true, Mode::Normal),
+            KeymapMatch::Full(&Command::Move(MoveCommand::Down))
+        );
+        assert_eq!(keymaps.lookup(&j, true, Mode::Insert), KeymapMatch::None);
+        assert_eq!(keymaps.lookup(&j, false, Mode::Normal), KeymapMatch::None);
+    }
+
+    #[test]
+    fn unbind() {
+        let mut keymaps = KeypressMap::default_linux();
+        let home = parse_keys("home").unwrap();
+        assert!(matches!(
+            keymaps.lookup(&home, false, Mode::Insert),
+            KeymapMatch::Full(_)
+        ));
+
+        keymaps
+            .merge(&[entry("home", "-line_start_non_blank", None)])
+            .unwrap();
+        assert_eq!(
+            keymaps.lookup(&home, false, Mode::Insert),
+            KeymapMatch::None
+        );
+
+        // Invalid entries leave the keymap unchanged
+        let before = keymaps.clone();
+        assert!(keymaps
+            .merge(&[
+                entry("end", "-line_end", None),
+                entry("ctrl+q", "not_a_command", None),
+            ])
+            .is_err());
+        assert_eq!(keymaps, before);
+    }
+}
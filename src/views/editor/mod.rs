@@ -3,6 +3,7 @@ use std::{
     cmp::Ordering,
     collections::{hash_map::DefaultHasher, HashMap},
     hash::{Hash, Hasher},
+    ops::Range,
     rc::Rc,
     sync::Arc,
     time::Duration,
@@ -21,6 +22,9 @@ use floem_editor_core::{
     buffer::rope_text::{RopeText, RopeTextVal},
     command::MoveCommand,
     cursor::{ColPosition, Cursor, CursorAffinity, CursorMode},
+    editor::EditType,
+    find::Find,
+    folding::{Folding, FoldingRange},
     mode::Mode,
     movement::Movement,
     register::Register,
@@ -117,6 +121,10 @@ pub struct Editor {
 
     pub last_movement: RwSignal<Movement>,
 
+    /// The search query and its matches in the document.  
+    /// Use [`Editor::update_find`] to change the query so that the matches are found again.
+    pub find: RwSignal<Find>,
+
     /// Whether ime input is allowed.  
     /// Should not be set manually outside of the specific handling for ime.
     pub ime_allowed: RwSignal<bool>,
@@ -217,6 +225,7 @@ impl Editor {
             register: cx.create_rw_signal(Register::default()),
             cursor_info: CursorInfo::new(cx),
             last_movement: cx.create_rw_signal(Movement::Left),
+            find: cx.create_rw_signal(Find::new()),
             ime_allowed: cx.create_rw_signal(false),
         };
 
@@ -335,6 +344,7 @@ impl Editor {
             editor.register.set(self.register.get_untracked());
             editor.cursor_info = self.cursor_info.clone();
             editor.last_movement.set(self.last_movement.get_untracked());
+            editor.find.set(self.find.get_untracked());
             // ?
             // editor.ime_allowed.set(self.ime_allowed.get_untracked());
         });
@@ -693,6 +703,113 @@ impl Editor {
         self.rope_text().select_word(offset)
     }
 
+    // ==== Find ====
+
+    /// Change the search query, finding the matches in the document again.
+    pub fn update_find(&self, f: impl FnOnce(&mut Find)) {
+        let text = self.text();
+        self.find.update(|find| {
+            f(find);
+            find.update_text(&text);
+        });
+    }
+
+    /// Select the next match after the cursor, or the previous match before it when `forward`
+    /// is false. Wraps around the ends of the document.  
+    /// Returns the selected match, if there was one.
+    pub fn search(&self, forward: bool) -> Option<Range<usize>> {
+        let (start, end, is_insert) = self.cursor.with_untracked(|cursor| {
+            let is_insert = matches!(cursor.mode, CursorMode::Insert(_));
+            (cursor.start_offset(), cursor.offset(), is_insert)
+        });
+        let offset = if !forward {
+            start.min(end)
+        } else if is_insert {
+            start.max(end)
+        } else {
+            // The normal mode cursor sits on the start of the current match
+            end + 1
+        };
+
+        let m = self
+            .find
+            .with_untracked(|find| find.next_match(offset, forward))?;
+        self.select_match(m.clone());
+        Some(m)
+    }
+
+    fn select_match(&self, m: Range<usize>) {
+        // Reveal the match if it is within a folded region
+        let line = self.line_of_offset(m.start);
+        self.unfold(line);
+        self.cursor.update(|cursor| match cursor.mode {
+            CursorMode::Insert(_) => cursor.set_insert(Selection::region(m.start, m.end)),
+            _ => cursor.mode = CursorMode::Normal(m.start),
+        });
+    }
+
+    /// Replace the selected match with `replacement`, or select the next match if the cursor
+    /// is not on one. Moves on to the following match afterwards.
+    pub fn replace(&self, replacement: &str) {
+        let range = self.cursor.with_untracked(|cursor| match &cursor.mode {
+            CursorMode::Insert(selection) => selection
+                .last_inserted()
+                .map(|region| region.min()..region.max()),
+            CursorMode::Normal(offset) => self
+                .find
+                .with_untracked(|find| find.next_match(*offset, true))
+                .filter(|m| m.start == *offset),
+            CursorMode::Visual { .. } => None,
+        });
+        let text = self.text();
+        let Some((range, replacement)) = range.and_then(|range| {
+            self.find.with_untracked(|find| {
+                find.match_index(range.clone())?;
+                let replacement = find.replacement(&text, range.clone(), replacement)?;
+                Some((range, replacement))
+            })
+        }) else {
+            self.search(true);
+            return;
+        };
+
+        self.doc().edit_single(
+            Selection::region(range.start, range.end),
+            &replacement,
+            EditType::Other,
+        );
+        let end = range.start + replacement.len();
+        let text = self.text();
+        self.find.update(|find| find.update_text(&text));
+        match self.find.with_untracked(|find| find.next_match(end, true)) {
+            Some(m) => self.select_match(m),
+            None => self.cursor.update(|cursor| match cursor.mode {
+                CursorMode::Insert(_) => cursor.set_insert(Selection::caret(end)),
+                _ => cursor.mode = CursorMode::Normal(end),
+            }),
+        }
+    }
+
+    /// Replace every match with `replacement`, as a single undoable edit.
+    pub fn replace_all(&self, replacement: &str) {
+        let text = self.text();
+        let edits: Vec<(Selection, String)> = self.find.with_untracked(|find| {
+            find.matches()
+                .iter()
+                .filter_map(|m| {
+                    let replacement = find.replacement(&text, m.clone(), replacement)?;
+                    Some((Selection::region(m.start, m.end), replacement))
+                })
+                .collect()
+        });
+        if edits.is_empty() {
+            return;
+        }
+
+        let mut iter = edits.iter().map(|(sel, repl)| (sel.clone(), repl.as_str()));
+        self.doc().edit(&mut iter, EditType::Other);
+    }
+
     /// `affinity` decides whether an offset at a soft line break is considered to be on the
     /// previous line or the next line.  
     /// If `affinity` is `CursorAffinity::Forward` and is at the very end of the wrapped line, then
@@ -778,6 +895,88 @@ impl Editor {
         info.last_col(&self.text_prov(), caret)
     }
 
+    // ==== Folding ====
+
+    /// The foldable regions of the document, if it supports folding.
+    pub fn folding(&self) -> Option<RwSignal<Folding>> {
+        self.doc().folding()
+    }
+
+    /// Fold the region that starts on `line`, or otherwise the smallest region containing it.  
+    /// Returns the region that was folded.
+    pub fn fold(&self, line: usize) -> Option<FoldingRange> {
+        let folding = self.folding()?;
+        let mut range = None;
+        folding.update(|folding| range = folding.fold(line));
+        if range.is_some() {
+            self.move_cursor_out_of_folds();
+        }
+        range
+    }
+
+    /// Unfold the region that starts on `line`, as well as any folded regions hiding it.  
+    /// Returns whether anything was unfolded.
+    pub fn unfold(&self, line: usize) -> bool {
+        let Some(folding) = self.folding() else {
+            return false;
+        };
+        // Avoid notifying the subscribers when nothing is folded there
+        let is_folded =
+            folding.with_untracked(|folding| folding.is_folded(line) || folding.is_hidden(line));
+        if !is_folded {
+            return false;
+        }
+
+        let mut changed = false;
+        folding.update(|folding| changed = folding.unfold(line));
+        changed
+    }
+
+    /// Unfold the region starting on `line` if it is folded, otherwise fold it.
+    pub fn toggle_fold(&self, line: usize) {
+        let is_folded = self
+            .folding()
+            .map(|folding| folding.with_untracked(|folding| folding.is_folded(line)))
+            .unwrap_or(false);
+        if is_folded {
+            self.unfold(line);
+        } else {
+            self.fold(line);
+        }
+    }
+
+    pub fn fold_all(&self) {
+        if let Some(folding) = self.folding() {
+            folding.update(Folding::fold_all);
+            self.move_cursor_out_of_folds();
+        }
+    }
+
+    pub fn unfold_all(&self) {
+        if let Some(folding) = self.folding() {
+            folding.update(Folding::unfold_all);
+        }
+    }
+
+    /// Move the cursor to the end of the first line of the folded region that hides it.
+    fn move_cursor_out_of_folds(&self) {
+        let Some(folding) = self.folding() else {
+            return;
+        };
+        let line = self.line_of_offset(self.cursor.with_untracked(|c| c.offset()));
+        let Some(hidden) = folding.with_untracked(|folding| folding.hidden_range(line)) else {
+            return;
+        };
+        let Some(fold_line) = hidden.start.checked_sub(1) else {
+            return;
+        };
+
+        let is_normal = self.cursor.with_untracked(|c| c.get_mode() == Mode::Normal);
+        let offset = self.rope_text().line_end_offset(fold_line, !is_normal);
+        self.cursor
+            .update(|cursor| cursor.set_offset(offset, false, false));
+    }
+
     // ==== Points of locations ====
 
     pub fn max_line_width(&self) -> f64 {
@@ -1193,6 +1392,18 @@ impl TextLayoutProvider for Editor {
         self.doc()
             .has_multiline_phantom(self.id(), self.style().as_ref())
     }
+
+    fn is_line_hidden(&self, line: usize) -> bool {
+        self.folding()
+            .map(|folding| folding.with_untracked(|folding| folding.is_hidden(line)))
+            .unwrap_or(false)
+    }
+
+    fn has_hidden_lines(&self) -> bool {
+        self.folding()
+            .map(|folding| folding.with_untracked(Folding::has_hidden))
+            .unwrap_or(false)
+    }
 }
 
 struct EditorFontSizes {
@@ -1242,6 +1453,21 @@ fn create_view_effects(cx: Scope, ed: &Editor) {
         });
     }
 
+    // Keep the find matches up to date with the text of the document
+    {
+        let doc = ed.doc;
+        let find = ed.find;
+        cx.create_effect(move |_| {
+            let doc = doc.get();
+            doc.cache_rev().track();
+            if find.with_untracked(|find| find.pattern().is_empty()) {
+                return;
+            }
+            let text = doc.text();
+            find.update(|find| find.update_text(&text));
+        });
+    }
+
     let update_screen_lines = |ed: &Editor| {
         // This function should not depend on the viewport signal directly.
 
@@ -97,7 +97,7 @@ pub fn move_offset(
     movement: &Movement,
     mode: Mode,
 ) -> (usize, Option<ColPosition>) {
-    match movement {
+    let (new_offset, horiz) = match movement {
         Movement::Left => {
             let new_offset = move_left(view, offset, affinity, mode, count);
 
@@ -192,6 +192,28 @@ pub fn move_offset(
 
             (new_offset, None)
         }
+    };
+
+    (skip_folded(view, offset, new_offset, mode), horiz)
+}
+
+/// Move an offset that landed within a folded region out of it, continuing in the direction of
+/// the movement.
+fn skip_folded(ed: &Editor, old_offset: usize, new_offset: usize, mode: Mode) -> usize {
+    let Some(folding) = ed.doc().folding() else {
+        return new_offset;
+    };
+    let line = ed.line_of_offset(new_offset);
+    let Some(hidden) = folding.with_untracked(|folding| folding.hidden_range(line)) else {
+        return new_offset;
+    };
+
+    if new_offset > old_offset && hidden.end <= ed.last_line() {
+        ed.offset_of_line(hidden.end)
+    } else {
+        // The line before the hidden lines is the start of the folded region
+        ed.rope_text()
+            .line_end_offset(hidden.start - 1, mode != Mode::Normal)
     }
 }
 
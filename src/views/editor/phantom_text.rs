@@ -33,6 +33,8 @@ pub enum PhantomTextKind {
     InlayHint,
     /// Error lens
     Diagnostic,
+    /// Placeholder for the hidden lines of a folded region
+    Fold,
 }
 
 /// Information about the phantom text on a specific line.  
@@ -12,6 +12,7 @@ use floem_editor_core::{
     command::EditCommand,
     cursor::Cursor,
     editor::EditType,
+    folding::Folding,
     indent::IndentStyle,
     mode::MotionMode,
     register::{Clipboard, Register},
@@ -143,6 +144,31 @@ pub trait Document: DocumentPhantom + Downcast {
         })
     }
 
+    /// The foldable regions of the document and which of them are folded, or `None` if the
+    /// document does not support folding.  
+    /// Lines within folded regions are hidden from the editor view.
+    fn folding(&self) -> Option<RwSignal<Folding>> {
+        None
+    }
+
+    /// The placeholder shown at the end of the first line of a folded region.
+    fn fold_phantom(&self, fg: Option<Color>, line: usize) -> Option<PhantomText> {
+        let folding = self.folding()?;
+        folding.with_untracked(|folding| folding.folded_range_at(line))?;
+
+        let col = self.rope_text().line_end_col(line, true);
+
+        Some(PhantomText {
+            kind: PhantomTextKind::Fold,
+            text: " ⋯ ".to_string(),
+            col,
+            font_size: None,
+            fg,
+            bg: None,
+            under_line: None,
+        })
+    }
+
     /// Compute the visible screen lines.  
     /// Note: you should typically *not* need to implement this, unless you have some custom
     /// behavior. Unfortunately this needs an `&self` to be a trait object. So don't call `.update`
@@ -379,6 +405,7 @@ pub fn default_light_color(color: EditorColor) -> Color {
         EditorColor::IndentGuide => grey,
         EditorColor::StickyHeaderBackground => bg,
         EditorColor::PreeditUnderline => fg,
+        EditorColor::FindMatch => Color::rgba8(0xFF, 0xC8, 0x3D, 0x80),
     }
 }
 
@@ -402,6 +429,7 @@ pub fn default_dark_color(color: EditorColor) -> Color {
         EditorColor::IndentGuide => grey,
         EditorColor::StickyHeaderBackground => bg,
         EditorColor::PreeditUnderline => fg,
+        EditorColor::FindMatch => Color::rgba8(0xE5, 0xB0, 0x3A, 0x60),
     }
 }
 
@@ -459,6 +487,14 @@ where
         self.doc.preedit_phantom(under_line, line)
     }
 
+    fn folding(&self) -> Option<RwSignal<Folding>> {
+        self.doc.folding()
+    }
+
+    fn fold_phantom(&self, fg: Option<Color>, line: usize) -> Option<PhantomText> {
+        self.doc.fold_phantom(fg, line)
+    }
+
     fn compute_screen_lines(
         &self,
         editor: &Editor,
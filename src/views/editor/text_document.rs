@@ -10,6 +10,8 @@ use floem_editor_core::{
     command::EditCommand,
     cursor::Cursor,
     editor::{Action, EditConf, EditType},
+    folding::{indent_folding_ranges, Folding, FoldingRange},
+    indent::IndentStyle,
     mode::{Mode, MotionMode},
     register::Register,
     selection::Selection,
@@ -67,6 +69,11 @@ pub struct TextDocument {
 
     pub placeholders: RwSignal<HashMap<EditorId, String>>,
 
+    folding: RwSignal<Folding>,
+    /// Whether the folding ranges were provided with [`TextDocument::set_folding_ranges`] rather
+    /// than found from the indentation of the text
+    host_folding_ranges: Cell<bool>,
+
     // (cmd: &Command, count: Option<usize>, modifiers: ModifierState)
     /// Ran before a command is executed. If it says that it executed the command, then handlers
     /// after it will not be called.
@@ -93,6 +100,21 @@ impl TextDocument {
             });
         });
 
+        let mut folding = Folding::new();
+        folding.set_ranges(indent_folding_ranges(
+            buffer.text(),
+            indent_tab_width(buffer.indent_style()),
+        ));
+        let folding = cx.create_rw_signal(folding);
+
+        // Folding changes which lines are hidden and where the fold placeholders are
+        create_effect(move |_| {
+            folding.track();
+            cache_rev.try_update(|cache_rev| {
+                *cache_rev += 1;
+            });
+        });
+
         TextDocument {
             buffer: cx.create_rw_signal(buffer),
             cache_rev,
@@ -100,6 +122,8 @@ impl TextDocument {
             keep_indent: Cell::new(true),
             auto_indent: Cell::new(false),
             placeholders,
+            folding,
+            host_folding_ranges: Cell::new(false),
             pre_command: Rc::new(RefCell::new(HashMap::new())),
             on_updates: Rc::new(RefCell::new(SmallVec::new())),
         }
@@ -112,6 +136,8 @@ impl TextDocument {
     }
 
     fn on_update(&self, ed: Option<&Editor>, deltas: &[(Rope, RopeDelta, InvalLines)]) {
+        self.update_folding(deltas);
+
         let on_updates = self.on_updates.borrow();
         let data = OnUpdate { editor: ed, deltas };
         for on_update in on_updates.iter() {
@@ -119,6 +145,44 @@ impl TextDocument {
         }
     }
 
+    fn update_folding(&self, deltas: &[(Rope, RopeDelta, InvalLines)]) {
+        if deltas.is_empty() {
+            return;
+        }
+
+        let ranges = if self.host_folding_ranges.get() {
+            None
+        } else {
+            Some(self.buffer.with_untracked(|buffer| {
+                indent_folding_ranges(buffer.text(), indent_tab_width(buffer.indent_style()))
+            }))
+        };
+
+        self.folding.update(|folding| {
+            // Move the folded regions along with the text before replacing the ranges, so that
+            // they stay folded.
+            for (_, _, inval) in deltas {
+                folding.update_lines(inval.start_line, inval.inval_count, inval.new_count);
+            }
+            if let Some(ranges) = ranges {
+                folding.set_ranges(ranges);
+            }
+        });
+    }
+
+    /// Set the foldable ranges, such as ones provided by a language server.  
+    /// If `None` then the ranges are found from the indentation of the text, which is the
+    /// default.
+    pub fn set_folding_ranges(&self, ranges: Option<Vec<FoldingRange>>) {
+        self.host_folding_ranges.set(ranges.is_some());
+        let ranges = ranges.unwrap_or_else(|| {
+            self.buffer.with_untracked(|buffer| {
+                indent_folding_ranges(buffer.text(), indent_tab_width(buffer.indent_style()))
+            })
+        });
+        self.folding.update(|folding| folding.set_ranges(ranges));
+    }
+
     pub fn add_pre_command(
         &self,
         id: EditorId,
@@ -166,6 +230,10 @@ impl Document for TextDocument {
         self.preedit.clone()
     }
 
+    fn folding(&self) -> Option<RwSignal<Folding>> {
+        Some(self.folding)
+    }
+
     fn run_command(
         &self,
         ed: &Editor,
@@ -259,6 +327,10 @@ impl DocumentPhantom for TextDocument {
             }
         }
 
+        if let Some(fold) = self.fold_phantom(Some(styling.color(edid, EditorColor::Dim)), line) {
+            text.push(fold);
+        }
+
         if let Some(preedit) = self.preedit_phantom(
             Some(styling.color(edid, EditorColor::PreeditUnderline)),
             line,
@@ -360,6 +432,14 @@ impl CommonAction for TextDocument {
     }
 }
 
+/// The width of a tab when finding folding ranges from the indentation.
+fn indent_tab_width(indent_style: IndentStyle) -> usize {
+    match indent_style {
+        IndentStyle::Tabs => 4,
+        IndentStyle::Spaces(width) => usize::from(width),
+    }
+}
+
 impl std::fmt::Debug for TextDocument {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = f.debug_struct("TextDocument");
@@ -410,6 +410,79 @@ impl EditorView {
         }
     }
 
+    /// Highlight the matches of the editor's find query that are within the screen lines.
+    pub fn paint_find_matches(
+        cx: &mut PaintCx,
+        ed: &Editor,
+        viewport: Rect,
+        screen_lines: &ScreenLines,
+    ) {
+        let (Some(first), Some(last)) = (screen_lines.lines.first(), screen_lines.lines.last())
+        else {
+            return;
+        };
+        let start = ed.offset_of_line(first.line);
+        let end = ed.offset_of_line(last.line + 1);
+        let color = ed.color(EditorColor::FindMatch);
+
+        ed.find.with_untracked(|find| {
+            for m in find.matches_in(start..end) {
+                let (start_rvline, start_col) =
+                    ed.rvline_col_of_offset(m.start, CursorAffinity::Forward);
+                let (end_rvline, end_col) =
+                    ed.rvline_col_of_offset(m.end, CursorAffinity::Backward);
+
+                for LineInfo {
+                    vline_y,
+                    vline_info: info,
+                    ..
+                } in screen_lines.iter_line_info_r(start_rvline..=end_rvline)
+                {
+                    let rvline = info.rvline;
+                    let line = rvline.line;
+
+                    let phantom_text = ed.phantom_text(line);
+                    let left_col = if rvline == start_rvline {
+                        start_col
+                    } else {
+                        ed.first_col(info)
+                    };
+                    let right_col = if rvline == end_rvline {
+                        end_col
+                    } else {
+                        ed.last_col(info, true)
+                    };
+                    let left_col = phantom_text.col_after(left_col, false);
+                    let right_col = phantom_text.col_after(right_col, false);
+
+                    let x0 = ed
+                        .line_point_of_line_col(line, left_col, CursorAffinity::Forward)
+                        .x;
+                    let x1 = ed
+                        .line_point_of_line_col(line, right_col, CursorAffinity::Backward)
+                        .x;
+                    // Show the newline of matches spanning multiple lines
+                    let x1 = if rvline != end_rvline {
+                        x1 + CHAR_WIDTH
+                    } else {
+                        x1
+                    };
+
+                    let style = LineExtraStyle {
+                        x: x0,
+                        y: 0.0,
+                        width: Some(x1 - x0),
+                        height: f64::from(ed.line_height(line)),
+                        bg_color: Some(color),
+                        under_line: None,
+                        wave_line: None,
+                    };
+                    EditorView::paint_extra_style(cx, &[style], vline_y, viewport);
+                }
+            }
+        });
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn paint_linewise_selection(
         cx: &mut PaintCx,
@@ -630,6 +703,7 @@ impl EditorView {
         screen_lines: &ScreenLines,
     ) {
         let cursor = ed.cursor;
+
         let hide_cursor = ed.cursor_info.hidden;
         let caret_color = ed.color(EditorColor::Caret);
 
@@ -894,6 +968,8 @@ impl Widget for EditorView {
         let screen_lines = ed.screen_lines.get_untracked();
         EditorView::paint_cursor(cx, &ed, self.is_active.get_untracked(), &screen_lines);
         let screen_lines = ed.screen_lines.get_untracked();
+        EditorView::paint_find_matches(cx, &ed, viewport, &screen_lines);
+        let screen_lines = ed.screen_lines.get_untracked();
         EditorView::paint_text(cx, &ed, viewport, &screen_lines);
         EditorView::paint_scroll_bar(cx, &ed, viewport);
     }
@@ -926,6 +1002,12 @@ pub fn editor_view(
         id.request_paint();
     });
 
+    let find = ed.find;
+    create_effect(move |_| {
+        find.track();
+        id.request_paint();
+    });
+
     let editor_window_origin = ed.window_origin;
     let cursor = ed.cursor;
     let ime_allowed = ed.ime_allowed;
@@ -1199,7 +1281,10 @@ fn editor_content(
                     return;
                 };
 
-                handle_key_event(&keypress, key_event.modifiers);
+                if handle_key_event(&keypress, key_event.modifiers) == CommandExecuted::Yes {
+                    // The key was bound to a command, or is part of an unfinished chord
+                    return;
+                }
 
                 let mut mods = key_event.modifiers;
                 mods.set(ModifiersState::SHIFT, false);
@@ -218,6 +218,18 @@ pub trait TextLayoutProvider {
     /// This should be a conservative estimate, so if you aren't bothering to check all of your
     /// phantom text then just return true.
     fn has_multiline_phantom(&self) -> bool;
+
+    /// Whether the buffer line is hidden, such as by being inside a folded region.  
+    /// Hidden lines have no visual lines, so they are skipped over when iterating.
+    fn is_line_hidden(&self, _line: usize) -> bool {
+        false
+    }
+
+    /// Whether *any* buffer line is hidden.  
+    /// The lines can't be treated as linear if there are hidden lines.
+    fn has_hidden_lines(&self) -> bool {
+        false
+    }
 }
 impl<T: TextLayoutProvider> TextLayoutProvider for &T {
     fn text(&self) -> Rope {
@@ -240,6 +252,14 @@ impl<T: TextLayoutProvider> TextLayoutProvider for &T {
     fn has_multiline_phantom(&self) -> bool {
         (**self).has_multiline_phantom()
     }
+
+    fn is_line_hidden(&self, line: usize) -> bool {
+        (**self).is_line_hidden(line)
+    }
+
+    fn has_hidden_lines(&self) -> bool {
+        (**self).has_hidden_lines()
+    }
 }
 
 pub type FontSizeCacheId = u64;
@@ -321,7 +341,8 @@ impl Lines {
     /// Check if the lines can be modelled as a purely linear file.  
     /// If `true` this makes various operations simpler because there is a one-to-one
     /// correspondence between visual lines and buffer lines.  
-    /// However, if there is wrapping or any multiline phantom text, then we can't rely on that.  
+    /// However, if there is wrapping, any multiline phantom text, or hidden lines, then we can't
+    /// rely on that.  
     ///   
     /// TODO:?
     /// We could be smarter about various pieces.  
@@ -331,7 +352,9 @@ impl Lines {
     /// - `is_linear` could be up to some line, which allows us to make at least the earliest parts
     ///    before any wrapping were faster. However, early lines are faster to calculate anyways.
     pub fn is_linear(&self, text_prov: impl TextLayoutProvider) -> bool {
-        self.wrap.get() == ResolvedWrap::None && !text_prov.has_multiline_phantom()
+        self.wrap.get() == ResolvedWrap::None
+            && !text_prov.has_multiline_phantom()
+            && !text_prov.has_hidden_lines()
     }
 
     /// Get the font size that [`Self::font_sizes`] provides
@@ -356,13 +379,17 @@ impl Lines {
             let rope_text = text_prov.rope_text();
             let hard_line_count = rope_text.num_lines();
 
-            let line_count = if self.is_linear(text_prov) {
+            let line_count = if self.is_linear(&text_prov) {
                 hard_line_count
             } else {
                 let mut soft_line_count = 0;
 
                 let layouts = self.text_layouts.borrow();
                 for i in 0..hard_line_count {
+                    if text_prov.is_line_hidden(i) {
+                        continue;
+                    }
+
                     let font_size = self.font_size(i);
                     if let Some(text_layout) = layouts.get(font_size, i) {
                         let line_count = text_layout.line_count();
@@ -390,7 +417,7 @@ impl Lines {
     /// Cheap, so not cached
     pub fn last_rvline(&self, text_prov: impl TextLayoutProvider) -> RVLine {
         let rope_text = text_prov.rope_text();
-        let last_line = rope_text.last_line();
+        let last_line = prev_visible_line(&text_prov, rope_text.last_line()).unwrap_or(0);
         let layouts = self.text_layouts.borrow();
         let font_size = self.font_size(last_line);
 
@@ -576,7 +603,7 @@ impl Lines {
                 if v.is_first() {
                     // For every (first) vline we initialize the next buffer line's text layout
                     // This ensures it is ready for when re reach it.
-                    let next_line = v.rvline.line + 1;
+                    let next_line = next_visible_line(&text_prov, v.rvline.line + 1);
                     let font_size = font_sizes.borrow().font_size(next_line);
                     // `init_iter_vlines` is the reason `get_init_text_layout` is split out.
                     // Being split out lets us avoid attaching lifetimes to the iterator, since it
@@ -648,7 +675,7 @@ impl Lines {
                 if v.is_first() {
                     // For every (first) vline we initialize the next buffer line's text layout
                     // This ensures it is ready for when re reach it.
-                    let next_line = v.rvline.line + 1;
+                    let next_line = next_visible_line(&text_prov, v.rvline.line + 1);
                     let font_size = font_sizes.borrow().font_size(next_line);
                     // `init_iter_lines` is the reason `get_init_text_layout` is split out.
                     // Being split out lets us avoid attaching lifetimes to the iterator, since it
@@ -925,7 +952,7 @@ fn get_init_text_layout(
         // Update last vline
         if let Some(vline) = last_vline.get() {
             let last_line = text_prov.rope_text().last_line();
-            if line <= last_line {
+            if line <= last_line && !text_prov.is_line_hidden(line) {
                 // We can get rid of the old line count and add our new count.
                 // This lets us typically avoid having to calculate the last visual line.
                 let vline = vline.get();
@@ -1118,9 +1145,9 @@ fn find_vline_of_line(
         let last_vline = lines.last_vline(text_prov);
         let last_rvline = lines.last_rvline(text_prov);
         let last_start_vline = VLine(last_vline.get() - last_rvline.line_index);
-        find_vline_of_line_backwards(lines, (last_start_vline, last_line), line)
+        find_vline_of_line_backwards(lines, text_prov, (last_start_vline, last_rvline.line), line)
     } else {
-        find_vline_of_line_forwards(lines, (VLine(0), 0), line)
+        find_vline_of_line_forwards(lines, text_prov, (VLine(0), 0), line)
     }
 }
 
@@ -1129,6 +1156,7 @@ fn find_vline_of_line(
 /// This requires that the `pivot` is the first line index of the line it is for.
 fn find_vline_of_line_backwards(
     lines: &Lines,
+    text_prov: &impl TextLayoutProvider,
     (start, s_line): (VLine, usize),
     line: usize,
 ) -> Option<VLine> {
@@ -1145,6 +1173,10 @@ fn find_vline_of_line_backwards(
     let mut cur_vline = start.get();
 
     for cur_line in line..s_line {
+        if text_prov.is_line_hidden(cur_line) {
+            continue;
+        }
+
         let font_size = lines.font_size(cur_line);
 
         let Some(text_layout) = layouts.get(font_size, cur_line) else {
@@ -1163,6 +1195,7 @@ fn find_vline_of_line_backwards(
 
 fn find_vline_of_line_forwards(
     lines: &Lines,
+    text_prov: &impl TextLayoutProvider,
     (start, s_line): (VLine, usize),
     line: usize,
 ) -> Option<VLine> {
@@ -1177,6 +1210,10 @@ fn find_vline_of_line_forwards(
     let mut cur_vline = start.get();
 
     for cur_line in s_line..line {
+        if text_prov.is_line_hidden(cur_line) {
+            continue;
+        }
+
         let font_size = lines.font_size(cur_line);
 
         let Some(text_layout) = layouts.get(font_size, cur_line) else {
@@ -1258,6 +1295,12 @@ fn find_vline_init_info_forward(
 
     let layouts = lines.text_layouts.borrow();
     while cur_vline < vline.get() {
+        if text_prov.is_line_hidden(cur_line) {
+            // Hidden lines have no visual lines
+            cur_line += 1;
+            continue;
+        }
+
         let font_size = lines.font_size(cur_line);
         let line_count = if let Some(text_layout) = layouts.get(font_size, cur_line) {
             let line_count = text_layout.line_count();
@@ -1294,6 +1337,7 @@ fn find_vline_init_info_forward(
     // We've reached the visual line we're looking for, we can return the offset.
     // This also handles the case where the vline is past the end of the text.
     if cur_vline == vline.get() {
+        let cur_line = next_visible_line(text_prov, cur_line);
         if cur_line > rope_text.last_line() {
             return None;
         }
@@ -1367,7 +1411,7 @@ fn find_vline_init_info_backward(
     vline: VLine,
 ) -> Option<(usize, RVLine)> {
     loop {
-        let (prev_vline, prev_line) = prev_line_start(lines, start, start_line)?;
+        let (prev_vline, prev_line) = prev_line_start(lines, text_prov, start, start_line)?;
 
         match prev_vline.cmp(&vline) {
             // We found the target, and it was at the start
@@ -1403,14 +1447,19 @@ fn find_vline_init_info_backward(
 }
 
 /// Get the previous (line, start visual line) from a (line, start visual line).
-fn prev_line_start(lines: &Lines, vline: VLine, line: usize) -> Option<(VLine, usize)> {
+fn prev_line_start(
+    lines: &Lines,
+    text_prov: &impl TextLayoutProvider,
+    vline: VLine,
+    line: usize,
+) -> Option<(VLine, usize)> {
     if line == 0 {
         return None;
     }
 
     let layouts = lines.text_layouts.borrow();
 
-    let prev_line = line - 1;
+    let prev_line = prev_visible_line(text_prov, line - 1)?;
     let font_size = lines.font_size(line);
     if let Some(layout) = layouts.get(font_size, prev_line) {
         let line_count = layout.line_count();
@@ -1833,14 +1882,23 @@ fn next_rvline(
         } else {
             // There was no next layout/vline on this buffer line.
             // So we can simply move to the start of the next buffer line.
+            let next_line = next_visible_line(text_prov, line + 1);
 
-            (RVLine::new(line + 1, 0), rope_text.offset_of_line(line + 1))
+            (
+                RVLine::new(next_line, 0),
+                rope_text.offset_of_line(next_line),
+            )
         }
     } else {
         // There was no text layout line, so this is a normal line.
         debug_assert_eq!(line_index, 0);
 
-        (RVLine::new(line + 1, 0), rope_text.offset_of_line(line + 1))
+        let next_line = next_visible_line(text_prov, line + 1);
+
+        (
+            RVLine::new(next_line, 0),
+            rope_text.offset_of_line(next_line),
+        )
     }
 }
 
@@ -1860,7 +1918,7 @@ fn prev_rvline(
             return None;
         }
 
-        let prev_line = line - 1;
+        let prev_line = prev_visible_line(text_prov, line - 1)?;
         let font_size = font_sizes.font_size(prev_line);
         if let Some(layout_line) = layouts.get(font_size, prev_line) {
             let line_offset = rope_text.offset_of_line(prev_line);
@@ -1913,6 +1971,26 @@ fn prev_rvline(
     }
 }
 
+/// The first line at or after `line` which is not hidden.  
+/// This may be past the last line of the text.
+fn next_visible_line(text_prov: &impl TextLayoutProvider, mut line: usize) -> usize {
+    let last_line = text_prov.rope_text().last_line();
+    while line <= last_line && text_prov.is_line_hidden(line) {
+        line += 1;
+    }
+
+    line
+}
+
+/// The last line at or before `line` which is not hidden.
+fn prev_visible_line(text_prov: &impl TextLayoutProvider, mut line: usize) -> Option<usize> {
+    while text_prov.is_line_hidden(line) {
+        line = line.checked_sub(1)?;
+    }
+
+    Some(line)
+}
+
 // FIXME: Put this in our cosmic-text fork.
 
 /// Hit position but decides wether it should go to the next line based on the `before` bool.
@@ -2026,6 +2104,7 @@ mod tests {
         font_family: Vec<FamilyOwned>,
         #[allow(dead_code)]
         wrap: Wrap,
+        hidden: Vec<usize>,
     }
     impl<'a> TestTextLayoutProvider<'a> {
         fn new(text: &'a Rope, ph: HashMap<usize, PhantomTextLine>, wrap: Wrap) -> Self {
@@ -2036,6 +2115,7 @@ mod tests {
                 // TODO(minor): Is there a more common font that we can use?
                 font_family: FamilyOwned::parse_list("Cascadia Code").collect(),
                 wrap,
+                hidden: Vec::new(),
             }
         }
     }
@@ -2143,6 +2223,14 @@ mod tests {
             // Conservatively, yes.
             true
         }
+
+        fn is_line_hidden(&self, line: usize) -> bool {
+            self.hidden.contains(&line)
+        }
+
+        fn has_hidden_lines(&self) -> bool {
+            !self.hidden.is_empty()
+        }
     }
 
     struct TestFontSize {
@@ -2669,11 +2757,16 @@ mod tests {
         for line in 0..4 {
             let vline = VLine(vline_line_data[line]);
             assert_eq!(
-                find_vline_of_line_forwards(&lines, Default::default(), line),
+                find_vline_of_line_forwards(&lines, &text_prov, Default::default(), line),
                 Some(vline)
             );
             assert_eq!(
-                find_vline_of_line_backwards(&lines, (last_start_vline, rope.last_line()), line),
+                find_vline_of_line_backwards(
+                    &lines,
+                    &text_prov,
+                    (last_start_vline, rope.last_line()),
+                    line
+                ),
                 Some(vline),
                 "line: {line}"
             );
@@ -2727,11 +2820,16 @@ mod tests {
         for line in 0..4 {
             let vline = VLine(vline_line_data[line]);
             assert_eq!(
-                find_vline_of_line_forwards(&lines, Default::default(), line),
+                find_vline_of_line_forwards(&lines, &text_prov, Default::default(), line),
                 Some(vline)
             );
             assert_eq!(
-                find_vline_of_line_backwards(&lines, (last_start_vline, rope.last_line()), line),
+                find_vline_of_line_backwards(
+                    &lines,
+                    &text_prov,
+                    (last_start_vline, rope.last_line()),
+                    line
+                ),
                 Some(vline),
                 "line: {line}"
             );
@@ -3304,6 +3402,29 @@ mod tests {
         assert_eq!(r, Vec::<&str>::new());
     }
 
+    #[test]
+    fn hidden_lines() {
+        let text: Rope = "a\nb\nc\nd\ne".into();
+        let (mut text_prov, lines) = make_lines(&text, 500., false);
+        text_prov.hidden = vec![1, 2];
+
+        assert_eq!(lines.last_vline(&text_prov), VLine(2));
+        let r: Vec<_> = lines
+            .iter_vlines(&text_prov, false, VLine(0))
+            .map(|l| (l.vline, l.rvline.line))
+            .collect();
+        assert_eq!(r, vec![(VLine(0), 0), (VLine(1), 3), (VLine(2), 4)]);
+
+        let r: Vec<_> = lines
+            .iter_rvlines(&text_prov, true, RVLine::new(4, 0))
+            .map(|l| l.rvline.line)
+            .collect();
+        assert_eq!(r, vec![4, 3, 0]);
+
+        assert_eq!(lines.vline_of_line(&text_prov, 3), VLine(1));
+        assert_eq!(lines.offset_of_vline(&text_prov, VLine(1)), 6);
+    }
+
     #[test]
     fn line_numbers() {
         let text: Rope = "aaaa\nbb bb cc\ncc dddd eeee ff\nff gggg".into();
@@ -1,10 +1,11 @@
 use std::{any::Any, fmt::Display};
 
 use crate::{
-    context::UpdateCx,
+    context::{EventCx, EventPropagation, UpdateCx},
     cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
+    event::Event,
     id::Id,
-    prop_extractor,
+    prop, prop_extractor,
     style::Style,
     style::{FontProps, LineHeight, TextColor, TextOverflow, TextOverflowProp},
     unit::PxPct,
@@ -16,11 +17,18 @@ use floem_renderer::Renderer;
 use kurbo::{Point, Rect};
 use taffy::tree::NodeId;
 
+use super::selectable_text;
+
+/// The color of the highlight behind selected text in a selectable [`Label`] or
+/// [`RichText`](super::RichText)
+prop!(pub SelectionColor: Color {} = Color::rgba8(0, 120, 215, 80));
+
 prop_extractor! {
     Extracter {
         color: TextColor,
         text_overflow: TextOverflowProp,
         line_height: LineHeight,
+        selection_color: SelectionColor,
     }
 }
 
@@ -38,7 +46,10 @@ pub struct Label {
     available_text: Option<String>,
     available_width: Option<f32>,
     available_text_layout: Option<TextLayout>,
+    /// Whether the painted text layout changed since it was last given to [`selectable_text`]
+    painted_layout_changed: bool,
     text_overflow_listener: Option<TextOverflowListener>,
+    selectable: bool,
     font: FontProps,
     style: Extracter,
 }
@@ -53,7 +64,9 @@ impl Label {
             available_text: None,
             available_width: None,
             available_text_layout: None,
+            painted_layout_changed: false,
             text_overflow_listener: None,
+            selectable: false,
             font: FontProps::default(),
             style: Default::default(),
         }
@@ -108,8 +121,25 @@ impl Label {
         self
     }
 
+    /// Allow the text to be selected with the pointer and copied.
+    ///
+    /// Dragging from one selectable label continues the selection into the selectable labels and
+    /// rich texts next to it in the same container.
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        selectable_text::selectable(self)
+    }
+
+    /// The text layout which is painted
+    fn painted_text_layout(&self) -> Option<&TextLayout> {
+        self.available_text_layout
+            .as_ref()
+            .or(self.text_layout.as_ref())
+    }
+
     fn get_attrs_list(&self) -> AttrsList {
-        let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
+        let color = self.style.color().map(|brush| brush.to_color());
+        let mut attrs = Attrs::new().color(color.unwrap_or(Color::BLACK));
         if let Some(font_size) = self.font.size() {
             attrs = attrs.font_size(font_size);
         }
@@ -143,6 +173,7 @@ impl Label {
             text_layout.set_text(new_text, attrs_list);
             self.available_text_layout = Some(text_layout);
         }
+        self.painted_layout_changed = true;
     }
 }
 
@@ -184,6 +215,19 @@ impl Widget for Label {
         }
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if self.selectable && selectable_text::event(cx, self.id(), &event) {
+            EventPropagation::Stop
+        } else {
+            EventPropagation::Continue
+        }
+    }
+
     fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
         if self.font.read(cx) | self.style.read(cx) {
             self.text_layout = None;
@@ -277,7 +321,9 @@ impl Widget for Label {
             } else {
                 self.available_text = None;
                 self.available_width = None;
-                self.available_text_layout = None;
+                if self.available_text_layout.take().is_some() {
+                    self.painted_layout_changed = true;
+                }
             }
         } else if text_overflow == TextOverflow::Wrap {
             if width > available_width {
@@ -286,15 +332,16 @@ impl Widget for Label {
                     text_layout.set_size(available_width, f32::MAX);
                     self.available_text_layout = Some(text_layout);
                     self.available_width = Some(available_width);
+                    self.painted_layout_changed = true;
                     cx.app_state_mut().request_layout(self.id());
                 }
             } else {
-                if self.available_text_layout.is_some() {
+                if self.available_text_layout.take().is_some() {
+                    self.painted_layout_changed = true;
                     cx.app_state_mut().request_layout(self.id());
                 }
                 self.available_text = None;
                 self.available_width = None;
-                self.available_text_layout = None;
             }
         }
 
@@ -307,6 +354,20 @@ impl Widget for Label {
                 listener.last_is_overflown = Some(now_overflown);
             }
         }
+
+        if self.selectable {
+            let location = cx
+                .app_state
+                .taffy
+                .layout(self.text_node.unwrap())
+                .unwrap()
+                .location;
+            let origin = cx.window_origin + (location.x as f64, location.y as f64);
+            let changed = std::mem::take(&mut self.painted_layout_changed);
+            if let Some(text_layout) = self.painted_text_layout() {
+                selectable_text::update(self.id(), text_layout, changed, origin);
+            }
+        }
         None
     }
 
@@ -318,10 +379,17 @@ impl Widget for Label {
         let text_node = self.text_node.unwrap();
         let location = cx.app_state.taffy.layout(text_node).unwrap().location;
         let point = Point::new(location.x as f64, location.y as f64);
-        if let Some(text_layout) = self.available_text_layout.as_ref() {
-            cx.draw_text(text_layout, point);
-        } else {
-            cx.draw_text(self.text_layout.as_ref().unwrap(), point);
+        let text_layout = self.painted_text_layout().unwrap();
+        if self.selectable {
+            let color = self.style.selection_color();
+            selectable_text::paint(cx, self.id(), text_layout, point, color);
+        }
+        match self.style.color() {
+            Some(brush) if brush.is_gradient() => {
+                let rect = Rect::from_origin_size(point, text_layout.size());
+                cx.draw_text_with_brush(text_layout, point, &brush.resolve(rect));
+            }
+            _ => cx.draw_text(text_layout, point),
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::{v_stack_from_iter, Decorators, Stack};
+use crate::accessibility::Role;
 use crate::context::StyleCx;
 use crate::reactive::create_effect;
 use crate::style::{Style, StyleClassRef};
@@ -82,6 +83,10 @@ where
             index,
             child: Box::new(v),
         }
+        .accessibility(move |a| {
+            a.role(Role::ListItem)
+                .selected(selection.get() == Some(index))
+        })
         .on_click_stop(move |_| {
             if selection.get_untracked() != Some(index) {
                 selection.set(Some(index));
@@ -98,6 +103,7 @@ where
         onaccept: None,
     }
     .keyboard_navigatable()
+    .accessibility(|a| a.role(Role::List))
     .on_event(EventListener::KeyDown, move |e| {
         if let Event::KeyDown(key_event) = e {
             match key_event.key.logical_key {
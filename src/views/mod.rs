@@ -46,6 +46,8 @@ pub use label::*;
 mod rich_text;
 pub use rich_text::*;
 
+pub(crate) mod selectable_text;
+
 mod dyn_stack;
 pub use dyn_stack::*;
 
@@ -61,6 +63,9 @@ pub use container::*;
 mod dyn_container;
 pub use dyn_container::*;
 
+mod transition;
+pub use transition::*;
+
 mod value_container;
 pub use value_container::*;
 
@@ -76,6 +81,14 @@ pub use virtual_list::*;
 mod virtual_stack;
 pub use virtual_stack::*;
 
+mod row_selection;
+
+mod table;
+pub use table::*;
+
+mod tree_view;
+pub use tree_view::*;
+
 pub mod scroll;
 pub use scroll::{scroll, Scroll};
 
@@ -110,3 +123,8 @@ pub mod editor;
 pub mod text_editor;
 #[cfg(feature = "editor")]
 pub use text_editor::*;
+
+#[cfg(feature = "editor")]
+mod text_area;
+#[cfg(feature = "editor")]
+pub use text_area::*;
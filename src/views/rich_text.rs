@@ -6,19 +6,33 @@ use kurbo::{Point, Rect};
 use taffy::tree::NodeId;
 
 use crate::{
-    context::UpdateCx,
+    context::{EventCx, EventPropagation, UpdateCx},
+    event::Event,
     id::Id,
+    prop_extractor,
     style::{Style, TextOverflow},
     unit::PxPct,
     view::{View, ViewData, Widget},
 };
 
+use super::{selectable_text, SelectionColor};
+
+prop_extractor! {
+    Extracter {
+        selection_color: SelectionColor,
+    }
+}
+
 pub struct RichText {
     data: ViewData,
     text_layout: TextLayout,
     text_node: Option<NodeId>,
     text_overflow: TextOverflow,
     available_width: f32,
+    selectable: bool,
+    /// Whether the text layout changed since it was last given to [`selectable_text`]
+    layout_changed: bool,
+    style: Extracter,
 }
 
 pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
@@ -34,6 +48,20 @@ pub fn rich_text(text_layout: impl Fn() -> TextLayout + 'static) -> RichText {
         text_node: None,
         text_overflow: TextOverflow::Wrap,
         available_width: 0.0,
+        selectable: false,
+        layout_changed: true,
+        style: Default::default(),
+    }
+}
+
+impl RichText {
+    /// Allow the text to be selected with the pointer and copied.
+    ///
+    /// Dragging from one selectable rich text continues the selection into the selectable labels
+    /// and rich texts next to it in the same container.
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        selectable_text::selectable(self)
     }
 }
 
@@ -80,10 +108,30 @@ impl Widget for RichText {
             }
 
             self.text_layout = text_layout;
+            self.layout_changed = true;
             cx.request_layout(self.id());
         }
     }
 
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        if self.selectable && selectable_text::event(cx, self.id(), &event) {
+            EventPropagation::Stop
+        } else {
+            EventPropagation::Continue
+        }
+    }
+
+    fn style(&mut self, cx: &mut crate::context::StyleCx<'_>) {
+        if self.style.read(cx) {
+            cx.app_state_mut().request_paint(self.id());
+        }
+    }
+
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::tree::NodeId {
         cx.layout_node(self.id(), true, |cx| {
             let size = self.text_layout.size();
@@ -123,9 +171,22 @@ impl Widget for RichText {
         if self.text_overflow == TextOverflow::Wrap && self.available_width != available_width {
             self.available_width = available_width;
             self.text_layout.set_size(self.available_width, f32::MAX);
+            self.layout_changed = true;
             cx.app_state_mut().request_layout(self.id());
         }
 
+        if self.selectable {
+            let location = cx
+                .app_state
+                .taffy
+                .layout(self.text_node.unwrap())
+                .unwrap()
+                .location;
+            let origin = cx.window_origin + (location.x as f64, location.y as f64);
+            let changed = std::mem::take(&mut self.layout_changed);
+            selectable_text::update(self.id(), &self.text_layout, changed, origin);
+        }
+
         None
     }
 
@@ -133,6 +194,10 @@ impl Widget for RichText {
         let text_node = self.text_node.unwrap();
         let location = cx.app_state.taffy.layout(text_node).unwrap().location;
         let point = Point::new(location.x as f64, location.y as f64);
+        if self.selectable {
+            let color = self.style.selection_color();
+            selectable_text::paint(cx, self.id(), &self.text_layout, point, color);
+        }
         cx.draw_text(&self.text_layout, point);
     }
 }
@@ -0,0 +1,240 @@
+//! Selecting the rows of a [`Table`](super::Table) or [`TreeView`](super::TreeView) by clicking
+//! them or with the keyboard, and rebuilding the rows whose items change.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use floem_reactive::RwSignal;
+
+use crate::{
+    context::StyleCx,
+    keyboard::ModifiersState,
+    style::Style,
+    view::{AnyWidget, View, ViewData, Widget},
+};
+
+/// The selected rows of a [`Table`](super::Table) or [`TreeView`](super::TreeView) by their key,
+/// so they stay selected when the items are refreshed
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RowSelection<R> {
+    pub(crate) selected: Vec<R>,
+    /// Where a shift-click or shift-arrow selection extends from
+    pub(crate) anchor: Option<R>,
+    /// The row moved from with the arrow keys
+    pub(crate) cursor: Option<R>,
+}
+
+impl<R> Default for RowSelection<R> {
+    fn default() -> Self {
+        Self {
+            selected: Vec::new(),
+            anchor: None,
+            cursor: None,
+        }
+    }
+}
+
+impl<R: Clone + PartialEq> RowSelection<R> {
+    /// Select `row`, given the rows in the order they're shown. `extend` selects the rows from the
+    /// anchor to `row`, and `toggle` adds or removes `row` from the selection.
+    pub(crate) fn select(&mut self, order: &[R], row: R, extend: bool, toggle: bool) {
+        let position = |row: &R| order.iter().position(|r| r == row);
+        match self.anchor.as_ref().filter(|_| extend).and_then(position) {
+            Some(anchor) => {
+                let Some(end) = position(&row) else {
+                    return;
+                };
+                self.selected = order[anchor.min(end)..=anchor.max(end)].to_vec();
+            }
+            None if toggle => {
+                match self.selected.iter().position(|selected| *selected == row) {
+                    Some(idx) => {
+                        self.selected.remove(idx);
+                    }
+                    None => self.selected.push(row.clone()),
+                }
+                self.anchor = Some(row.clone());
+            }
+            None => {
+                self.selected = vec![row.clone()];
+                self.anchor = Some(row.clone());
+            }
+        }
+        self.cursor = Some(row);
+    }
+
+    pub(crate) fn select_all(&mut self, order: &[R]) {
+        self.selected = order.to_vec();
+    }
+
+    /// Forget rows which no longer exist
+    pub(crate) fn retain(&mut self, exists: impl Fn(&R) -> bool) {
+        self.selected.retain(&exists);
+        self.anchor = self.anchor.take().filter(&exists);
+        self.cursor = self.cursor.take().filter(&exists);
+    }
+}
+
+/// The version of the item of each row by its key, which changes when the item does. Keying rows
+/// by their key and version rebuilds the ones whose item changed but whose key didn't.
+pub(crate) struct RowVersions<K, T> {
+    items: HashMap<K, (T, u64)>,
+}
+
+impl<K: Clone + Eq + Hash, T: Clone + PartialEq> RowVersions<K, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            items: HashMap::new(),
+        }
+    }
+
+    /// The version of `item`, which is the item of the row with `key`
+    pub(crate) fn version(&mut self, key: &K, item: &T) -> u64 {
+        match self.items.get_mut(key) {
+            Some((old, version)) => {
+                if old != item {
+                    *old = item.clone();
+                    *version += 1;
+                }
+                *version
+            }
+            None => {
+                self.items.insert(key.clone(), (item.clone(), 0));
+                0
+            }
+        }
+    }
+
+    /// Forget the rows which no longer exist
+    pub(crate) fn retain(&mut self, keys: &HashSet<&K>) {
+        self.items.retain(|key, _| keys.contains(key));
+    }
+}
+
+/// Whether the modifiers add a row to the selection when clicking it, which is ctrl, or cmd on
+/// macOS
+pub(crate) fn is_toggle(modifiers: ModifiersState) -> bool {
+    #[cfg(target_os = "macos")]
+    let toggle = ModifiersState::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    let toggle = ModifiersState::CONTROL;
+    modifiers.contains(toggle)
+}
+
+/// A row of a [`Table`](super::Table) or [`TreeView`](super::TreeView), which is styled as selected
+/// when it is
+pub(crate) struct Row<R: 'static> {
+    pub(crate) data: ViewData,
+    pub(crate) row: R,
+    pub(crate) selection: RwSignal<RowSelection<R>>,
+    pub(crate) child: AnyWidget,
+}
+
+impl<R: PartialEq + 'static> View for Row<R> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn build(self) -> Box<dyn Widget> {
+        Box::new(self)
+    }
+}
+
+impl<R: PartialEq + 'static> Widget for Row<R> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().flex_row())
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn Widget) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Row".into()
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let selected = self
+            .selection
+            .with_untracked(|selection| selection.selected.contains(&self.row));
+        if selected {
+            cx.save();
+            cx.selected();
+            cx.style_view(&mut self.child);
+            cx.restore();
+        } else {
+            cx.style_view(&mut self.child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{RowSelection, RowVersions};
+
+    #[test]
+    fn select_rows() {
+        let order = [3, 0, 2, 1];
+        let mut selection = RowSelection::default();
+        selection.select(&order, 0, false, false);
+        assert_eq!(selection.selected, vec![0]);
+
+        selection.select(&order, 1, true, false);
+        assert_eq!(selection.selected, vec![0, 2, 1]);
+        assert_eq!(selection.anchor, Some(0));
+        assert_eq!(selection.cursor, Some(1));
+
+        selection.select(&order, 2, false, true);
+        assert_eq!(selection.selected, vec![0, 1]);
+        selection.select(&order, 3, false, true);
+        assert_eq!(selection.selected, vec![0, 1, 3]);
+
+        selection.select(&order, 2, true, false);
+        assert_eq!(selection.selected, vec![3, 0, 2]);
+
+        selection.retain(|row| *row < 3);
+        assert_eq!(selection.selected, vec![0, 2]);
+        assert_eq!(selection.anchor, None);
+    }
+
+    #[test]
+    fn row_versions() {
+        let mut versions = RowVersions::new();
+        assert_eq!(versions.version(&1, &"a"), 0);
+        assert_eq!(versions.version(&2, &"b"), 0);
+        assert_eq!(versions.version(&1, &"a"), 0);
+        assert_eq!(versions.version(&1, &"changed"), 1);
+        assert_eq!(versions.version(&1, &"changed"), 1);
+
+        versions.retain(&HashSet::from([&2]));
+        assert_eq!(versions.version(&1, &"a"), 0);
+        assert_eq!(versions.version(&2, &"b"), 0);
+    }
+}
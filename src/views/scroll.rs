@@ -4,6 +4,7 @@ use floem_renderer::Renderer;
 use kurbo::{Point, Rect, Size, Vec2};
 
 use crate::{
+    brush::Brush,
     context::{AppState, ComputeLayoutCx, PaintCx},
     event::Event,
     id::Id,
@@ -370,18 +371,26 @@ impl Scroll {
                     &self.track_style
                 };
 
-            if let Some(color) = track_style.color() {
+            if let Some(brush) = track_style.color() {
                 let mut bounds = bounds - scroll_offset;
                 bounds.y0 = self.actual_rect.y0;
                 bounds.y1 = self.actual_rect.y1;
-                cx.fill(&bounds, color, 0.0);
+                cx.fill(&bounds, &brush.resolve(bounds), 0.0);
             }
             let edge_width = style.border().0;
             let rect = (bounds - scroll_offset).inset(-edge_width / 2.0);
             let rect = rect.to_rounded_rect(radius(style, rect, true));
-            cx.fill(&rect, style.color().unwrap_or(HANDLE_COLOR), 0.0);
+            let handle = style
+                .color()
+                .unwrap_or(Brush::Solid(HANDLE_COLOR))
+                .resolve(rect.rect());
+            cx.fill(&rect, &handle, 0.0);
             if edge_width > 0.0 {
-                cx.stroke(&rect, style.border_color(), edge_width);
+                cx.stroke(
+                    &rect,
+                    &style.border_color().resolve(rect.rect()),
+                    edge_width,
+                );
             }
         }
 
@@ -395,18 +404,26 @@ impl Scroll {
                     &self.track_style
                 };
 
-            if let Some(color) = track_style.color() {
+            if let Some(brush) = track_style.color() {
                 let mut bounds = bounds - scroll_offset;
                 bounds.x0 = self.actual_rect.x0;
                 bounds.x1 = self.actual_rect.x1;
-                cx.fill(&bounds, color, 0.0);
+                cx.fill(&bounds, &brush.resolve(bounds), 0.0);
             }
             let edge_width = style.border().0;
             let rect = (bounds - scroll_offset).inset(-edge_width / 2.0);
             let rect = rect.to_rounded_rect(radius(style, rect, false));
-            cx.fill(&rect, style.color().unwrap_or(HANDLE_COLOR), 0.0);
+            let handle = style
+                .color()
+                .unwrap_or(Brush::Solid(HANDLE_COLOR))
+                .resolve(rect.rect());
+            cx.fill(&rect, &handle, 0.0);
             if edge_width > 0.0 {
-                cx.stroke(&rect, style.border_color(), edge_width);
+                cx.stroke(
+                    &rect,
+                    &style.border_color().resolve(rect.rect()),
+                    edge_width,
+                );
             }
         }
     }
@@ -0,0 +1,427 @@
+//! Mouse selection for [`label`](super::label)s and [`rich_text`](super::rich_text) which have
+//! been made selectable. A selection starts in one text and can be dragged through the selectable
+//! texts next to it in the same container.
+
+use std::{cell::RefCell, collections::HashMap, ops::Range};
+
+use floem_peniko::Color;
+use floem_renderer::Renderer;
+use floem_winit::keyboard::{Key, ModifiersState};
+use kurbo::{Point, Rect};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    context::{EventCx, PaintCx},
+    cosmic_text::TextLayout,
+    event::{Event, EventListener},
+    id::Id,
+    menu::{Menu, MenuItem},
+    view::View,
+    Clipboard,
+};
+
+use super::Decorators;
+
+/// The laid out text of a selectable view
+struct SelectableText {
+    layout: TextLayout,
+    /// Where the text starts, in window coordinates
+    origin: Point,
+}
+
+impl SelectableText {
+    fn text(&self) -> String {
+        self.layout
+            .lines
+            .iter()
+            .map(|line| line.text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn rect(&self) -> Rect {
+        Rect::from_origin_size(self.origin, self.layout.size())
+    }
+
+    fn hit_offset(&self, window_point: Point) -> usize {
+        let hit = self.layout.hit_point(window_point - self.origin.to_vec2());
+        line_start(&self.layout, hit.line) + hit.index
+    }
+}
+
+/// A selection from an offset in one text to an offset in the same text or one of the selectable
+/// texts sharing its container
+#[derive(Clone, Copy)]
+struct Selection {
+    anchor: (Id, usize),
+    focus: (Id, usize),
+}
+
+thread_local! {
+    static TEXTS: RefCell<HashMap<Id, SelectableText>> = RefCell::new(HashMap::new());
+    static SELECTION: RefCell<Option<Selection>> = RefCell::new(None);
+}
+
+/// Make a view selectable, which also has to call [`update`], [`event`] and [`paint`]
+pub(crate) fn selectable<V: View>(view: V) -> V {
+    let id = view.id();
+    view.on_event_cont(EventListener::FocusLost, move |_| {
+        if selection().is_some_and(|selection| selection.anchor.0 == id) {
+            set_selection(None);
+        }
+    })
+    .context_menu(move || {
+        let has_selection = selection().is_some_and(|selection| {
+            selection.anchor.0 == id && selection.anchor != selection.focus
+        });
+        Menu::new("").entry(
+            MenuItem::new("Copy")
+                .enabled(has_selection)
+                .action(copy_selection),
+        )
+    })
+}
+
+/// Keep the laid out text of a selectable view, which starts at `origin` in window coordinates.
+/// The layout is only copied when `layout_changed` is set or the view has none kept yet.
+pub(crate) fn update(id: Id, layout: &TextLayout, layout_changed: bool, origin: Point) {
+    TEXTS.with(|texts| {
+        let mut texts = texts.borrow_mut();
+        match texts.get_mut(&id) {
+            Some(text) if !layout_changed => text.origin = origin,
+            _ => {
+                texts.insert(
+                    id,
+                    SelectableText {
+                        layout: layout.clone(),
+                        origin,
+                    },
+                );
+            }
+        }
+    });
+}
+
+/// Forget a view which has been removed
+pub(crate) fn remove(id: Id) {
+    TEXTS.with(|texts| texts.borrow_mut().remove(&id));
+    if selection().is_some_and(|selection| selection.anchor.0 == id || selection.focus.0 == id) {
+        set_selection(None);
+    }
+}
+
+fn selection() -> Option<Selection> {
+    SELECTION.with(|selection| *selection.borrow())
+}
+
+fn set_selection(new: Option<Selection>) {
+    let old = SELECTION.with(|selection| std::mem::replace(&mut *selection.borrow_mut(), new));
+    for selection in [old, new].into_iter().flatten() {
+        for id in group(selection.anchor.0) {
+            id.request_paint();
+        }
+    }
+}
+
+/// The selectable texts sharing the container of `id`, in reading order
+fn group(id: Id) -> Vec<Id> {
+    let parent = id.parent();
+    TEXTS.with(|texts| {
+        let texts = texts.borrow();
+        let mut group: Vec<(Id, Point)> = texts
+            .iter()
+            .filter(|(other, _)| **other == id || (parent.is_some() && other.parent() == parent))
+            .map(|(other, text)| (*other, text.origin))
+            .collect();
+        group.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        group.into_iter().map(|(id, _)| id).collect()
+    })
+}
+
+/// The offset where each line of `layout` starts, counting a line break between lines
+fn line_start(layout: &TextLayout, line: usize) -> usize {
+    layout
+        .lines
+        .iter()
+        .take(line)
+        .map(|line| line.text().len() + 1)
+        .sum()
+}
+
+/// The range of the word, or the run of spaces or punctuation, at `offset`
+fn word_range(text: &str, offset: usize) -> Range<usize> {
+    text.split_word_bound_indices()
+        .map(|(start, word)| start..start + word.len())
+        .find(|range| range.contains(&offset))
+        .unwrap_or(offset..offset)
+}
+
+/// The range of the line at `offset`, without its line break
+fn line_range(text: &str, offset: usize) -> Range<usize> {
+    let offset = offset.min(text.len());
+    let start = text[..offset].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    let end = text[offset..]
+        .find('\n')
+        .map(|idx| offset + idx)
+        .unwrap_or(text.len());
+    start..end
+}
+
+/// The part of the text of `id` which is selected
+fn selected_range(id: Id) -> Option<Range<usize>> {
+    let selection = selection()?;
+    let group = group(selection.anchor.0);
+    let position = |(id, offset): (Id, usize)| Some((group.iter().position(|i| *i == id)?, offset));
+    let anchor = position(selection.anchor)?;
+    let focus = position(selection.focus)?;
+    let (first, last) = if anchor <= focus {
+        (anchor, focus)
+    } else {
+        (focus, anchor)
+    };
+
+    let index = group.iter().position(|i| *i == id)?;
+    if index < first.0 || index > last.0 {
+        return None;
+    }
+    let len = TEXTS.with(|texts| texts.borrow().get(&id).map(|text| text.text().len()))?;
+    let start = if index == first.0 { first.1 } else { 0 };
+    let end = if index == last.0 { last.1 } else { len };
+    (start < end).then_some(start..end.min(len))
+}
+
+/// The selected text, with the texts it spans on separate lines
+fn selected_text() -> Option<String> {
+    let selection = selection()?;
+    let parts: Vec<String> = group(selection.anchor.0)
+        .into_iter()
+        .filter_map(|id| {
+            let range = selected_range(id)?;
+            let text = TEXTS.with(|texts| texts.borrow().get(&id).map(|text| text.text()))?;
+            text.get(range).map(|text| text.to_string())
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n"))
+}
+
+fn copy_selection() {
+    if let Some(text) = selected_text() {
+        let _ = Clipboard::set_contents(text);
+    }
+}
+
+fn is_copy(modifiers: ModifiersState, key: &Key) -> bool {
+    #[cfg(target_os = "macos")]
+    let command = modifiers == ModifiersState::SUPER;
+    #[cfg(not(target_os = "macos"))]
+    let command = modifiers == ModifiersState::CONTROL;
+    command && matches!(key, Key::Character(ch) if ch.eq_ignore_ascii_case("c"))
+}
+
+/// Handle the pointer and key events of a selectable view. Returns true if the event was used.
+pub(crate) fn event(cx: &mut EventCx, id: Id, event: &Event) -> bool {
+    match event {
+        Event::PointerDown(event) if event.button.is_primary() => {
+            let point = cx.app_state.get_layout_rect(id).origin() + event.pos.to_vec2();
+            let Some((offset, text)) = TEXTS.with(|texts| {
+                let texts = texts.borrow();
+                let text = texts.get(&id)?;
+                Some((text.hit_offset(point), text.text()))
+            }) else {
+                return false;
+            };
+            let range = match event.count {
+                2 => word_range(&text, offset),
+                3 => line_range(&text, offset),
+                _ => offset..offset,
+            };
+            cx.update_active(id);
+            // Focus the view so it receives the key to copy
+            cx.update_focus(id, false);
+            set_selection(Some(Selection {
+                anchor: (id, range.start),
+                focus: (id, range.end),
+            }));
+            true
+        }
+        Event::PointerDown(event) if event.button.is_secondary() => {
+            // Keep the selection for the context menu, which would be cleared by losing focus
+            if selection().is_some_and(|selection| selection.anchor.0 == id) {
+                cx.update_focus(id, false);
+            }
+            false
+        }
+        Event::PointerMove(event) if cx.is_active(id) => {
+            let Some(selection) = selection().filter(|selection| selection.anchor.0 == id) else {
+                return false;
+            };
+            let point = cx.app_state.get_layout_rect(id).origin() + event.pos.to_vec2();
+            // The text under the pointer, or the closest one
+            let focus = TEXTS.with(|texts| {
+                let texts = texts.borrow();
+                group(id)
+                    .into_iter()
+                    .filter_map(|id| Some((id, texts.get(&id)?)))
+                    .min_by(|(_, a), (_, b)| {
+                        distance(a.rect(), point).total_cmp(&distance(b.rect(), point))
+                    })
+                    .map(|(id, text)| (id, text.hit_offset(point)))
+            });
+            if let Some(focus) = focus.filter(|focus| *focus != selection.focus) {
+                set_selection(Some(Selection { focus, ..selection }));
+            }
+            true
+        }
+        Event::KeyDown(event) if is_copy(event.modifiers, &event.key.logical_key) => {
+            if selection().is_some_and(|selection| selection.anchor.0 == id) {
+                copy_selection();
+                return true;
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+fn distance(rect: Rect, point: Point) -> f64 {
+    let dx = (rect.x0 - point.x).max(point.x - rect.x1).max(0.0);
+    let dy = (rect.y0 - point.y).max(point.y - rect.y1).max(0.0);
+    dx.hypot(dy)
+}
+
+/// Paint the selected part of the text of `id`, which is laid out by `layout` at `point`
+pub(crate) fn paint(cx: &mut PaintCx, id: Id, layout: &TextLayout, point: Point, color: Color) {
+    let Some(range) = selected_range(id) else {
+        return;
+    };
+    for rect in selection_rects(layout, range) {
+        cx.fill(&(rect + point.to_vec2()), color, 0.0);
+    }
+}
+
+/// The rects covering the text in `range`, one for each row it's on
+fn selection_rects(layout: &TextLayout, range: Range<usize>) -> Vec<Rect> {
+    let starts: Vec<usize> = (0..layout.lines.len())
+        .map(|line| line_start(layout, line))
+        .collect();
+    layout
+        .layout_runs()
+        .filter_map(|run| {
+            let offset = starts.get(run.line_i).copied().unwrap_or(0);
+            let mut selected = run
+                .glyphs
+                .iter()
+                .filter(|glyph| {
+                    glyph.start + offset < range.end && glyph.end + offset > range.start
+                })
+                .map(|glyph| (glyph.x, glyph.x + glyph.w));
+            let (x0, x1) = selected.next()?;
+            let x1 = selected.last().map(|(_, x1)| x1).unwrap_or(x1);
+            Some(Rect::new(
+                x0 as f64,
+                (run.line_y - run.glyph_ascent) as f64,
+                x1 as f64,
+                (run.line_y + run.glyph_descent) as f64,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Point;
+
+    use crate::{
+        cosmic_text::{Attrs, AttrsList, TextLayout},
+        id::Id,
+    };
+
+    use super::{
+        line_range, selected_range, selected_text, set_selection, update, word_range, Selection,
+    };
+
+    fn selectable_text(parent: Id, text: &str, origin: Point) -> Id {
+        let id = Id::next();
+        id.set_parent(parent);
+        let mut layout = TextLayout::new();
+        layout.set_text(text, AttrsList::new(Attrs::new()));
+        update(id, &layout, true, origin);
+        id
+    }
+
+    #[test]
+    fn word_and_line_ranges() {
+        let text = "Hello, world\nsecond line";
+        assert_eq!(word_range(text, 1), 0..5);
+        assert_eq!(word_range(text, 5), 5..6);
+        assert_eq!(word_range(text, 9), 7..12);
+        assert_eq!(line_range(text, 3), 0..12);
+        assert_eq!(line_range(text, 15), 13..24);
+        assert_eq!(line_range(text, text.len()), 13..24);
+    }
+
+    #[test]
+    fn selection_across_texts() {
+        let parent = Id::next().new();
+        // Added out of order, the group is in reading order
+        let last = selectable_text(parent, "last text", Point::new(0.0, 60.0));
+        let first = selectable_text(parent, "first text", Point::new(0.0, 0.0));
+        let middle = selectable_text(parent, "middle\nlines", Point::new(0.0, 20.0));
+        let other = selectable_text(Id::next().new(), "other", Point::new(0.0, 40.0));
+
+        set_selection(Some(Selection {
+            anchor: (first, 6),
+            focus: (last, 4),
+        }));
+        assert_eq!(selected_range(first), Some(6..10));
+        assert_eq!(selected_range(middle), Some(0..12));
+        assert_eq!(selected_range(last), Some(0..4));
+        assert_eq!(selected_range(other), None);
+        assert_eq!(
+            selected_text().as_deref(),
+            Some("text\nmiddle\nlines\nlast")
+        );
+
+        // Selecting backwards from the middle text
+        set_selection(Some(Selection {
+            anchor: (middle, 9),
+            focus: (first, 6),
+        }));
+        assert_eq!(selected_range(first), Some(6..10));
+        assert_eq!(selected_range(middle), Some(0..9));
+        assert_eq!(selected_range(last), None);
+        assert_eq!(selected_text().as_deref(), Some("text\nmiddle\nli"));
+
+        // Within one text
+        set_selection(Some(Selection {
+            anchor: (last, 5),
+            focus: (last, 9),
+        }));
+        assert_eq!(selected_range(middle), None);
+        assert_eq!(selected_text().as_deref(), Some("text"));
+
+        set_selection(None);
+        assert_eq!(selected_range(last), None);
+        assert_eq!(selected_text(), None);
+    }
+
+    #[test]
+    fn update_keeps_the_layout_until_it_changes() {
+        let parent = Id::next().new();
+        let id = selectable_text(parent, "before", Point::ZERO);
+        let mut layout = TextLayout::new();
+        layout.set_text("after", AttrsList::new(Attrs::new()));
+
+        // Only the origin is updated while the layout is unchanged
+        update(id, &layout, false, Point::new(0.0, 10.0));
+        set_selection(Some(Selection {
+            anchor: (id, 0),
+            focus: (id, 6),
+        }));
+        assert_eq!(selected_text().as_deref(), Some("before"));
+
+        update(id, &layout, true, Point::new(0.0, 10.0));
+        assert_eq!(selected_text().as_deref(), Some("after"));
+        set_selection(None);
+    }
+}
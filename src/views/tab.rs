@@ -5,13 +5,17 @@ use smallvec::SmallVec;
 use taffy::style::Display;
 
 use crate::{
-    context::{StyleCx, UpdateCx},
+    context::{PaintCx, StyleCx, UpdateCx},
     id::Id,
     style::DisplayProp,
     view::{AnyWidget, View, ViewData, Widget},
 };
 
-use super::{apply_diff, diff, Diff, DiffOpAdd, FxIndexSet, HashRun};
+use super::{
+    apply_diff_with, diff,
+    transition::{keep_in_place, Transitions},
+    Diff, DiffOpAdd, FxIndexSet, HashRun, ViewTransition,
+};
 
 enum TabState<V> {
     Diff(Box<Diff<V>>),
@@ -26,6 +30,7 @@ where
     active: usize,
     children: Vec<Option<(AnyWidget, Scope)>>,
     view_fn: Box<dyn Fn(T) -> (AnyWidget, Scope)>,
+    transitions: Transitions,
     phatom: PhantomData<T>,
 }
 
@@ -86,10 +91,40 @@ where
         active: 0,
         children: Vec::new(),
         view_fn,
+        transitions: Transitions::default(),
         phatom: PhantomData,
     }
 }
 
+impl<T> Tab<T> {
+    /// Use `transition` when the active tab changes, for both the new tab entering and the old
+    /// tab exiting
+    pub fn transition(self, transition: ViewTransition) -> Self {
+        self.enter_transition(transition.clone())
+            .exit_transition(transition)
+    }
+
+    /// Animate the new tab in with `transition` when the active tab changes
+    pub fn enter_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.enter = Some(transition);
+        self
+    }
+
+    /// Animate the old tab out with `transition` when the active tab changes. It's shown where it
+    /// was, above the new tab, until the transition is finished.
+    pub fn exit_transition(mut self, transition: ViewTransition) -> Self {
+        self.transitions.exit = Some(transition);
+        self
+    }
+
+    fn child_id(&self, index: usize) -> Option<Id> {
+        self.children
+            .get(index)
+            .and_then(|child| child.as_ref())
+            .map(|(child, _)| child.view_data().id())
+    }
+}
+
 impl<T> View for Tab<T> {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -153,15 +188,25 @@ impl<T> Widget for Tab<T> {
         if let Ok(state) = state.downcast::<TabState<T>>() {
             match *state {
                 TabState::Diff(diff) => {
-                    apply_diff(
-                        self.id(),
+                    let transitions = &mut self.transitions;
+                    apply_diff_with(
+                        self.data.id(),
                         cx.app_state,
                         *diff,
                         &mut self.children,
                         &self.view_fn,
+                        |app_state, child, scope| transitions.remove_child(app_state, child, scope),
                     );
                 }
                 TabState::Active(active) => {
+                    if active != self.active {
+                        if let Some(id) = self.child_id(self.active) {
+                            self.transitions.exit(id);
+                        }
+                        if let Some(id) = self.child_id(active) {
+                            self.transitions.enter(id);
+                        }
+                    }
                     self.active = active;
                 }
             }
@@ -173,6 +218,7 @@ impl<T> Widget for Tab<T> {
     }
 
     fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let tab_id = self.id();
         for (i, child) in self
             .children
             .iter_mut()
@@ -180,16 +226,46 @@ impl<T> Widget for Tab<T> {
             .filter_map(|(i, child)| child.as_mut().map(|child| (i, &mut child.0)))
         {
             cx.style_view(child);
-            let child_view = cx.app_state_mut().view_state(child.view_data().id());
+            let id = child.view_data().id();
+            let exiting = i != self.active && self.transitions.is_exiting(id);
+            let layout = cx.app_state_mut().get_layout(id);
+            let child_view = cx.app_state_mut().view_state(id);
             child_view.combined_style = child_view.combined_style.clone().set(
                 DisplayProp,
-                if i != self.active {
+                if i != self.active && !exiting {
                     // set display to none for non active child
                     Display::None
                 } else {
                     Display::Flex
                 },
             );
+            // Keep an exiting child where it was, out of the way of the active child
+            if let Some(layout) = layout.filter(|_| exiting) {
+                keep_in_place(cx, tab_id, id, layout);
+            }
+        }
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let active = self.child_id(self.active);
+        // Paint the exiting tabs above the active one
+        for (child, _) in self.children.iter_mut().flatten() {
+            if Some(child.view_data().id()) == active {
+                self.transitions.paint_child(cx, child);
+            }
+        }
+        for (child, _) in self.children.iter_mut().flatten() {
+            if Some(child.view_data().id()) != active {
+                self.transitions.paint_child(cx, child);
+            }
+        }
+        if self.transitions.finish(cx, self.id()) {
+            // Restyle to hide the tabs which finished exiting
+            cx.app_state.schedule_style(self.id());
+            for (child, _) in self.children.iter().flatten() {
+                cx.app_state.schedule_style(child.view_data().id());
+            }
+            cx.app_state.schedule_layout(self.id());
         }
     }
 }
@@ -0,0 +1,803 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    rc::Rc,
+};
+
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use kurbo::{Point, Rect, Vec2};
+
+use crate::{
+    context::EventCx,
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, ModifiersState, NamedKey},
+    style::CursorStyle,
+    style_class,
+    view::{AnyView, AnyWidget, View, ViewData, Widget},
+    EventPropagation,
+};
+
+use super::{
+    clip, dyn_stack, h_stack, label,
+    row_selection::{is_toggle, Row, RowSelection, RowVersions},
+    scroll, v_stack, virtual_stack, Decorators, VirtualDirection, VirtualItemSize,
+};
+
+style_class!(pub TableClass);
+style_class!(pub TableHeaderClass);
+style_class!(pub TableHeaderCellClass);
+style_class!(pub TableRowClass);
+style_class!(pub TableCellClass);
+style_class!(pub ColumnResizeHandleClass);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    fn indicator(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+/// A column of a [`table`], which shows a cell for each row.
+///
+/// ## Example
+/// ```
+/// use floem::views::*;
+///
+/// struct Entry {
+///     level: u8,
+///     message: String,
+/// }
+///
+/// let level = Column::new("Level", |entry: &Entry| text(entry.level))
+///     .width(60.0)
+///     .sort_by_key(|entry| entry.level);
+/// let message = Column::new("Message", |entry: &Entry| text(&entry.message)).width(400.0);
+/// ```
+pub struct Column<T> {
+    title: String,
+    width: f64,
+    min_width: f64,
+    resizable: bool,
+    view_fn: Box<dyn Fn(&T) -> AnyView>,
+    compare: Option<Box<dyn Fn(&T, &T) -> Ordering>>,
+}
+
+impl<T: 'static> Column<T> {
+    pub fn new<V: View + 'static>(
+        title: impl Into<String>,
+        view_fn: impl Fn(&T) -> V + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            width: 120.0,
+            min_width: 20.0,
+            resizable: true,
+            view_fn: Box::new(move |item| view_fn(item).any()),
+            compare: None,
+        }
+    }
+
+    /// The width the column starts with
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The width the column can't be resized below
+    pub fn min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self.width = self.width.max(min_width);
+        self
+    }
+
+    /// Whether the column can be resized by dragging the edge of its header. Defaults to true.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Make the table sortable by this column by clicking its header
+    pub fn sort_by(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+
+    /// Make the table sortable by this column by clicking its header
+    pub fn sort_by_key<K: Ord>(self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.sort_by(move |a, b| key(a).cmp(&key(b)))
+    }
+}
+
+/// A row of the table, in the order the rows are shown
+#[derive(Clone)]
+struct TableRow<K, T> {
+    /// The index of the item in the items of the table
+    index: usize,
+    key: K,
+    /// Changes when the item changes but its key doesn't
+    version: u64,
+    item: T,
+}
+
+enum TableUpdate {
+    SelectionChanged,
+}
+
+/// The sort after clicking the header of `column`, which goes from ascending to descending to
+/// unsorted
+fn next_sort(
+    sort: Option<(usize, SortDirection)>,
+    column: usize,
+) -> Option<(usize, SortDirection)> {
+    match sort {
+        Some((current, SortDirection::Ascending)) if current == column => {
+            Some((column, SortDirection::Descending))
+        }
+        Some((current, SortDirection::Descending)) if current == column => None,
+        _ => Some((column, SortDirection::Ascending)),
+    }
+}
+
+/// Move the column `from` to where the column `to` is in `order`
+fn move_column(order: &mut Vec<usize>, from: usize, to: usize) {
+    let (Some(from), Some(to)) = (
+        order.iter().position(|c| *c == from),
+        order.iter().position(|c| *c == to),
+    ) else {
+        return;
+    };
+    let column = order.remove(from);
+    order.insert(to, column);
+}
+
+/// A table of rows with a cell for each of the [`Column`]s. See [`table`].
+pub struct Table {
+    data: ViewData,
+    /// The indices of the selected rows in the items of the table
+    selected: RwSignal<Vec<usize>>,
+    sort: RwSignal<Option<(usize, SortDirection)>>,
+    multi_select: RwSignal<bool>,
+    child: AnyWidget,
+}
+
+impl Table {
+    /// The column the rows are sorted by, as its index in the columns given to the table
+    pub fn sort(&self) -> RwSignal<Option<(usize, SortDirection)>> {
+        self.sort
+    }
+
+    /// Allow selecting several rows with shift-click, ctrl-click (cmd-click on macOS) and
+    /// shift with the arrow keys
+    pub fn multi_select(self, multi_select: bool) -> Self {
+        self.multi_select.set(multi_select);
+        self
+    }
+
+    /// Called with the indices of the selected rows in the items of the table when the
+    /// selection changes
+    pub fn on_select(self, on_select: impl Fn(Vec<usize>) + 'static) -> Self {
+        let selected = self.selected;
+        create_effect(move |_| {
+            on_select(selected.get());
+        });
+        self
+    }
+}
+
+/// A table which shows a row for each item with a cell for each of the `columns`, under a header
+/// which stays in place when scrolling vertically.
+///
+/// Like a [`virtual_stack`](super::virtual_stack()), only the rows in view are built, and every
+/// row has the same `row_height`. Rows are identified by the key from `key_fn`, which keeps them
+/// selected when the items change, and a row is rebuilt when its item changes.
+///
+/// Clicking the header of a column with a sort function sorts the rows by it, and clicking it
+/// again reverses or removes the sort. Columns are resized by dragging the edge of their header
+/// and reordered by dragging the header onto another. Rows are selected by clicking them or with
+/// the arrow, page up and down, home and end keys.
+///
+/// ## Example
+/// ```
+/// use floem::{reactive::*, views::*};
+///
+/// #[derive(Clone, PartialEq)]
+/// struct Entry {
+///     id: usize,
+///     message: String,
+/// }
+///
+/// let entries: im::Vector<Entry> = (0..100_000)
+///     .map(|id| Entry {
+///         id,
+///         message: format!("Message {id}"),
+///     })
+///     .collect();
+/// let (entries, _set_entries) = create_signal(entries);
+///
+/// table(
+///     24.0,
+///     vec![
+///         Column::new("Id", |entry: &Entry| text(entry.id)).sort_by_key(|entry| entry.id),
+///         Column::new("Message", |entry: &Entry| text(&entry.message)).width(300.0),
+///     ],
+///     move || entries.get(),
+///     |entry| entry.id,
+/// )
+/// .multi_select(true)
+/// .on_select(|rows| println!("selected {rows:?}"));
+/// ```
+pub fn table<T, IF, KF, K>(
+    row_height: f64,
+    columns: Vec<Column<T>>,
+    each_fn: IF,
+    key_fn: KF,
+) -> Table
+where
+    T: Clone + PartialEq + 'static,
+    IF: Fn() -> im::Vector<T> + 'static,
+    KF: Fn(&T) -> K + 'static,
+    K: Clone + Eq + Hash + 'static,
+{
+    let id = Id::next();
+    let widths: Rc<[RwSignal<f64>]> = columns
+        .iter()
+        .map(|column| create_rw_signal(column.width))
+        .collect();
+    let columns = Rc::new(columns);
+
+    let order = create_rw_signal((0..columns.len()).collect::<Vec<usize>>());
+    let sort = create_rw_signal(None);
+    let selection = create_rw_signal(RowSelection::<K>::default());
+    let selected = create_rw_signal(Vec::new());
+    let multi_select = create_rw_signal(false);
+    let rows = create_rw_signal(im::Vector::new());
+    let versions = RefCell::new(RowVersions::new());
+    let viewport = create_rw_signal(Rect::ZERO);
+    let header_delta = create_rw_signal(Vec2::ZERO);
+    let dragging_column = create_rw_signal(None);
+
+    create_effect(move |_| {
+        selection.track();
+        id.update_state(TableUpdate::SelectionChanged);
+    });
+
+    {
+        let columns = columns.clone();
+        create_effect(move |_| {
+            let items = each_fn();
+            let mut versions = versions.borrow_mut();
+            let mut sorted: Vec<TableRow<K, T>> = items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let key = key_fn(&item);
+                    let version = versions.version(&key, &item);
+                    TableRow {
+                        index,
+                        key,
+                        version,
+                        item,
+                    }
+                })
+                .collect();
+            if let Some((column, direction)) = sort.get() {
+                let compare = columns
+                    .get(column)
+                    .and_then(|column: &Column<T>| column.compare.as_ref());
+                if let Some(compare) = compare {
+                    sorted.sort_by(|a, b| match direction {
+                        SortDirection::Ascending => compare(&a.item, &b.item),
+                        SortDirection::Descending => compare(&b.item, &a.item),
+                    });
+                }
+            }
+            let new_rows = sorted.into_iter().collect::<im::Vector<_>>();
+            // Forget the selected rows whose items were removed
+            let keys: HashSet<&K> = new_rows.iter().map(|row| &row.key).collect();
+            versions.retain(&keys);
+            let exists = |key: &K| keys.contains(key);
+            if selection.with_untracked(|selection| {
+                !selection.selected.iter().all(exists)
+                    || !selection.anchor.iter().all(exists)
+                    || !selection.cursor.iter().all(exists)
+            }) {
+                selection.update(|selection| selection.retain(exists));
+            }
+            rows.set(new_rows);
+        });
+    }
+
+    create_effect(move |_| {
+        let indices = rows.with(|rows| {
+            let index: HashMap<&K, usize> = rows.iter().map(|row| (&row.key, row.index)).collect();
+            selection.with(|selection| {
+                selection
+                    .selected
+                    .iter()
+                    .filter_map(|key| index.get(key).copied())
+                    .collect::<Vec<usize>>()
+            })
+        });
+        if selected.with_untracked(|selected| *selected != indices) {
+            selected.set(indices);
+        }
+    });
+
+    let total_width = {
+        let widths = widths.clone();
+        move || {
+            order.with(|order| {
+                order
+                    .iter()
+                    .map(|column| widths[*column].get())
+                    .sum::<f64>()
+            })
+        }
+    };
+
+    let header_cells = {
+        let columns = columns.clone();
+        let widths = widths.clone();
+        dyn_stack(
+            move || order.get(),
+            |column| *column,
+            move |column| {
+                let Column {
+                    title,
+                    min_width,
+                    resizable,
+                    compare,
+                    ..
+                } = &columns[column];
+                let title = title.clone();
+                let min_width = *min_width;
+                let resizable = *resizable;
+                let sortable = compare.is_some();
+                let width = widths[column];
+                h_stack((
+                    label(move || match sort.get() {
+                        Some((sorted, direction)) if sorted == column => {
+                            format!("{title} {}", direction.indicator())
+                        }
+                        _ => title.clone(),
+                    })
+                    .style(|s| s.flex_grow(1.0).min_width(0.0).text_ellipsis()),
+                    resize_handle(width, min_width)
+                        .class(ColumnResizeHandleClass)
+                        .style(move |s| {
+                            s.width(5.0)
+                                .height_full()
+                                .cursor(CursorStyle::ColResize)
+                                .apply_if(!resizable, |s| s.hide())
+                        }),
+                ))
+                .class(TableHeaderCellClass)
+                .style(move |s| {
+                    s.width(width.get())
+                        .height_full()
+                        .flex_shrink(0.0)
+                        .items_center()
+                })
+                .draggable()
+                .on_event_cont(EventListener::DragStart, move |_| {
+                    dragging_column.set(Some(column));
+                })
+                .on_event_cont(EventListener::DragEnd, move |_| {
+                    dragging_column.set(None);
+                })
+                .on_event_stop(EventListener::Drop, move |_| {
+                    if let Some(from) = dragging_column.get_untracked() {
+                        order.update(|order| move_column(order, from, column));
+                    }
+                    dragging_column.set(None);
+                })
+                .on_click_stop(move |_| {
+                    if sortable {
+                        sort.update(|sort| *sort = next_sort(*sort, column));
+                    }
+                })
+            },
+        )
+        .class(TableHeaderClass)
+        .style({
+            let total_width = total_width.clone();
+            move |s| s.flex_row().height(row_height).width(total_width())
+        })
+    };
+
+    // The header scrolls horizontally with the body, and passes the wheel on to it
+    let header = scroll(header_cells)
+        .hide_bar(|| true)
+        .scroll_to(move || Some(Point::new(viewport.get().x0, 0.0)))
+        .on_event_stop(EventListener::PointerWheel, move |event| {
+            if let Event::PointerWheel(event) = event {
+                header_delta.set(event.delta);
+            }
+        })
+        .style(|s| s.width_full().flex_shrink(0.0));
+
+    let row_view = move |TableRow { key: row, item, .. }: TableRow<K, T>| {
+        let columns = columns.clone();
+        let widths = widths.clone();
+        let item = Rc::new(item);
+        let cells = dyn_stack(
+            move || order.get(),
+            |column| *column,
+            move |column| {
+                let width = widths[column];
+                clip((columns[column].view_fn)(&item))
+                    .class(TableCellClass)
+                    .style(move |s| {
+                        s.width(width.get())
+                            .height_full()
+                            .flex_shrink(0.0)
+                            .items_center()
+                    })
+            },
+        )
+        // The class is on the cells rather than the row, which styles them as selected
+        .class(TableRowClass)
+        .style(|s| s.flex_row().size_full());
+        Row {
+            data: ViewData::new(Id::next()),
+            row: row.clone(),
+            selection,
+            child: cells.build(),
+        }
+        .on_click_stop(move |event| {
+            let Event::PointerUp(event) = event else {
+                return;
+            };
+            let multi_select = multi_select.get_untracked();
+            let extend = multi_select && event.modifiers.contains(ModifiersState::SHIFT);
+            let toggle = multi_select && is_toggle(event.modifiers);
+            let order: Vec<K> =
+                rows.with_untracked(|rows| rows.iter().map(|row| row.key.clone()).collect());
+            selection.update(|selection| selection.select(&order, row.clone(), extend, toggle));
+        })
+        .style(move |s| s.height(row_height).width_full())
+    };
+
+    let body = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(move || row_height)),
+        move || rows.get(),
+        // The version rebuilds a row when its item changes
+        |row| (row.key.clone(), row.version),
+        row_view,
+    )
+    .style(move |s| s.flex_col().width(total_width()).min_width_full());
+
+    let body = scroll(body)
+        .on_scroll(move |rect| {
+            if viewport.get_untracked() != rect {
+                viewport.set(rect);
+            }
+        })
+        .scroll_delta(move || header_delta.get())
+        .ensure_visible(move || {
+            // Keep the row moved to with the keyboard in view
+            let viewport = viewport.get_untracked();
+            let position = selection
+                .with(|selection| selection.cursor.clone())
+                .and_then(|cursor| {
+                    rows.with_untracked(|rows| rows.iter().position(|row| row.key == cursor))
+                });
+            match position {
+                Some(position) => {
+                    let y = position as f64 * row_height;
+                    Rect::new(viewport.x0, y, viewport.x1, y + row_height)
+                }
+                None => viewport,
+            }
+        })
+        .style(|s| s.width_full().flex_grow(1.0).min_height(0.0));
+
+    Table {
+        data: ViewData::new(id),
+        selected,
+        sort,
+        multi_select,
+        child: v_stack((header, body)).style(|s| s.size_full()).build(),
+    }
+    .class(TableClass)
+    .keyboard_navigatable()
+    .on_event(EventListener::KeyDown, move |event| {
+        let Event::KeyDown(key_event) = event else {
+            return EventPropagation::Continue;
+        };
+        let order: Vec<K> =
+            rows.with_untracked(|rows| rows.iter().map(|row| row.key.clone()).collect());
+        if order.is_empty() {
+            return EventPropagation::Continue;
+        }
+        let multi_select = multi_select.get_untracked();
+        let last = order.len() - 1;
+        let page = ((viewport.get_untracked().height() / row_height) as usize).max(1);
+        let current = selection.with_untracked(|selection| {
+            selection
+                .cursor
+                .as_ref()
+                .and_then(|cursor| order.iter().position(|row| row == cursor))
+        });
+        let position = match &key_event.key.logical_key {
+            Key::Named(NamedKey::ArrowUp) => current.map(|i| i.saturating_sub(1)).unwrap_or(last),
+            Key::Named(NamedKey::ArrowDown) => current.map(|i| (i + 1).min(last)).unwrap_or(0),
+            Key::Named(NamedKey::PageUp) => current.map(|i| i.saturating_sub(page)).unwrap_or(0),
+            Key::Named(NamedKey::PageDown) => current.map(|i| (i + page).min(last)).unwrap_or(last),
+            Key::Named(NamedKey::Home) => 0,
+            Key::Named(NamedKey::End) => last,
+            Key::Character(ch)
+                if multi_select
+                    && is_toggle(key_event.modifiers)
+                    && ch.eq_ignore_ascii_case("a") =>
+            {
+                selection.update(|selection| selection.select_all(&order));
+                return EventPropagation::Stop;
+            }
+            _ => return EventPropagation::Continue,
+        };
+        let extend = multi_select && key_event.modifiers.contains(ModifiersState::SHIFT);
+        selection
+            .update(|selection| selection.select(&order, order[position].clone(), extend, false));
+        EventPropagation::Stop
+    })
+}
+
+impl View for Table {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn build(self) -> Box<dyn Widget> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Table {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn Widget) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Table".into()
+    }
+
+    fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(change) = state.downcast::<TableUpdate>() {
+            match *change {
+                TableUpdate::SelectionChanged => {
+                    cx.app_state_mut().request_style_recursive(self.id())
+                }
+            }
+        }
+    }
+}
+
+/// The handle at the edge of a column header which resizes the column when dragged
+struct ResizeHandle {
+    data: ViewData,
+    width: RwSignal<f64>,
+    min_width: f64,
+    /// The window x of the pointer and the width of the column when the drag started
+    drag_start: Option<(f64, f64)>,
+}
+
+fn resize_handle(width: RwSignal<f64>, min_width: f64) -> ResizeHandle {
+    ResizeHandle {
+        data: ViewData::new(Id::next()),
+        width,
+        min_width,
+        drag_start: None,
+    }
+}
+
+impl ResizeHandle {
+    fn window_x(&self, cx: &mut EventCx, x: f64) -> f64 {
+        cx.app_state.get_layout_rect(self.id()).x0 + x
+    }
+}
+
+impl View for ResizeHandle {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn build(self) -> Box<dyn Widget> {
+        Box::new(self)
+    }
+}
+
+impl Widget for ResizeHandle {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Column Resize Handle".into()
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        match event {
+            // Stop the pointer down so the header doesn't start dragging the column
+            Event::PointerDown(event) if event.button.is_primary() => {
+                cx.update_active(self.id());
+                self.drag_start =
+                    Some((self.window_x(cx, event.pos.x), self.width.get_untracked()));
+                EventPropagation::Stop
+            }
+            Event::PointerMove(event) => match self.drag_start {
+                Some((start_x, start_width)) => {
+                    let width = (start_width + self.window_x(cx, event.pos.x) - start_x)
+                        .max(self.min_width);
+                    if width != self.width.get_untracked() {
+                        self.width.set(width);
+                    }
+                    EventPropagation::Stop
+                }
+                None => EventPropagation::Continue,
+            },
+            Event::PointerUp(_) if self.drag_start.is_some() => {
+                self.drag_start = None;
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::create_rw_signal;
+
+    use super::{move_column, next_sort, table, Column, SortDirection};
+    use crate::{headless::HeadlessWindow, views::text};
+
+    #[test]
+    fn sort_and_reorder_columns() {
+        let sort = next_sort(None, 1);
+        assert_eq!(sort, Some((1, SortDirection::Ascending)));
+        let sort = next_sort(sort, 1);
+        assert_eq!(sort, Some((1, SortDirection::Descending)));
+        assert_eq!(next_sort(sort, 0), Some((0, SortDirection::Ascending)));
+        assert_eq!(next_sort(sort, 1), None);
+
+        let mut order = vec![0, 1, 2, 3];
+        move_column(&mut order, 0, 2);
+        assert_eq!(order, vec![1, 2, 0, 3]);
+        move_column(&mut order, 3, 1);
+        assert_eq!(order, vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn selection_follows_the_items_by_key() {
+        let items = create_rw_signal(im::vector![(1, "a"), (2, "b"), (3, "c")]);
+        let selected = create_rw_signal(Vec::new());
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                table(
+                    20.0,
+                    vec![Column::new("Name", |item: &(u32, &str)| text(item.1))],
+                    move || items.get(),
+                    |item| item.0,
+                )
+                .on_select(move |rows| selected.set(rows))
+            },
+            (200.0, 200.0),
+            1.0,
+        );
+
+        // The first row is under the header
+        window.click((50.0, 30.0));
+        assert_eq!(selected.get_untracked(), vec![0]);
+
+        items.set(im::vector![(0, "z"), (3, "c"), (1, "a")]);
+        window.process_update();
+        assert_eq!(selected.get_untracked(), vec![2]);
+
+        items.set(im::vector![(0, "z"), (3, "c")]);
+        window.process_update();
+        assert_eq!(selected.get_untracked(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn selected_rows_are_highlighted() {
+        let items = create_rw_signal(im::vector![(1, "a"), (2, "b"), (3, "c")]);
+        let mut window = HeadlessWindow::new(
+            move || {
+                table(
+                    20.0,
+                    vec![Column::new("Name", |item: &(u32, &str)| text(item.1))],
+                    move || items.get(),
+                    |item| item.0,
+                )
+                .style(|s| s.size_full())
+            },
+            (200.0, 200.0),
+            1.0,
+        );
+
+        window.click((50.0, 30.0));
+        // Move away so the row isn't hovered
+        window.pointer_move((190.0, 190.0));
+        let img = window.render().into_rgba8();
+        // The selected background of the theme, depending on whether the table has focus
+        let selected = [[213, 208, 216, 255], [212, 212, 212, 255]];
+        assert!(selected.contains(&img.get_pixel(150, 30).0));
+        assert!(!selected.contains(&img.get_pixel(150, 50).0));
+    }
+
+    #[test]
+    fn rows_are_rebuilt_when_their_item_changes() {
+        let items = create_rw_signal(im::vector![(1, "a"), (2, "b")]);
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                table(
+                    20.0,
+                    vec![Column::new("Name", |item: &(u32, &str)| text(item.1))],
+                    move || items.get(),
+                    |item| item.0,
+                )
+            },
+            (200.0, 200.0),
+            1.0,
+        );
+        let unchanged = window.find_by_debug_name("Label: \"a\"");
+        assert_eq!(unchanged.len(), 1);
+        assert_eq!(window.find_by_debug_name("Label: \"b\"").len(), 1);
+
+        items.set(im::vector![(1, "a"), (2, "changed")]);
+        window.process_update();
+        assert_eq!(window.find_by_debug_name("Label: \"b\"").len(), 0);
+        assert_eq!(window.find_by_debug_name("Label: \"changed\"").len(), 1);
+        // The row whose item didn't change is kept
+        assert_eq!(window.find_by_debug_name("Label: \"a\""), unchanged);
+    }
+}
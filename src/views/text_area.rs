@@ -0,0 +1,1013 @@
+use std::{
+    any::Any,
+    time::{Duration, Instant},
+};
+
+use floem_editor_core::{
+    buffer::{rope_text::RopeText, Buffer},
+    command::EditCommand,
+    cursor::{ColPosition, Cursor, CursorMode},
+    editor::{Action, EditConf},
+    mode::Mode,
+    register::Register,
+    selection::Selection,
+};
+use floem_reactive::{create_effect, create_rw_signal, RwSignal};
+use floem_renderer::Renderer;
+use floem_winit::keyboard::{Key, ModifiersState, NamedKey};
+use kurbo::{Point, Rect, Size};
+use lapce_xi_rope::Rope;
+use taffy::prelude::{Layout, NodeId};
+
+use crate::{
+    accessibility::{Accessibility, Role},
+    action::{exec_after, set_ime_allowed, set_ime_cursor_area},
+    clock,
+    context::{ComputeLayoutCx, EventCx, LayoutCx, PaintCx, StyleCx, UpdateCx},
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout},
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::KeyEvent,
+    peniko::Color,
+    pointer::{PointerButton, PointerInputEvent},
+    prop_extractor,
+    style::{CursorColor, FontProps, Style, TextColor},
+    unit::{PxPct, PxPctAuto},
+    view::{View, ViewData, Widget},
+    widgets::PlaceholderTextClass,
+    EventPropagation,
+};
+
+use super::{editor::text::SystemClipboard, text_input::PlaceholderStyle, Decorators};
+
+prop_extractor! {
+    Extracter {
+        color: TextColor,
+    }
+}
+
+const DEFAULT_FONT_SIZE: f32 = 14.0;
+const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
+/// Approximately how many characters wide the text area is when the width is not set in the
+/// styles
+const APPROX_VISIBLE_COLS_TARGET: f32 = 30.0;
+
+/// A multi-line plain text field. See [`text_area`].
+pub struct TextArea {
+    data: ViewData,
+    value: RwSignal<String>,
+    buffer: Buffer,
+    cursor: Cursor,
+    register: Register,
+    pub(crate) placeholder_text: Option<String>,
+    placeholder_buff: Option<TextLayout>,
+    placeholder_style: PlaceholderStyle,
+    pub(crate) min_rows: usize,
+    pub(crate) max_rows: Option<usize>,
+    lines: WrappedLines,
+    // The width the lines were wrapped to
+    wrap_width: f32,
+    width: f32,
+    // Approx max size of a glyph, which is also the height of each row of text
+    glyph_size: Size,
+    text_node: Option<NodeId>,
+    // How far the text is scrolled, when it has more rows than the max rows
+    scroll_y: f64,
+    scroll_to_cursor: bool,
+    style: Extracter,
+    font: FontProps,
+    is_focused: bool,
+    ime_cursor_area: Option<Point>,
+    last_cursor_action_on: Instant,
+}
+
+/// A multi-line plain text field editing `value`, which wraps its text to its width and grows
+/// with it between its [`min_rows`](TextArea::min_rows) and [`max_rows`](TextArea::max_rows).
+///
+/// Unlike [`text_editor`](super::text_editor) it has no syntax highlighting, gutter or modal
+/// editing, so it doesn't need a document or styling to be set up.
+///
+/// ## Example
+/// ```
+/// use floem::reactive::create_rw_signal;
+/// use floem::views::text_area;
+///
+/// let description = create_rw_signal(String::new());
+/// let field = text_area(description)
+///     .placeholder("Description")
+///     .min_rows(3)
+///     .max_rows(8);
+/// ```
+pub fn text_area(value: RwSignal<String>) -> TextArea {
+    let id = Id::next();
+    let is_focused = create_rw_signal(false);
+
+    create_effect(move |_| {
+        let text = value.get();
+        id.update_state((text, is_focused.get()));
+    });
+
+    TextArea {
+        data: ViewData::new(id),
+        value,
+        buffer: Buffer::new(value.get_untracked()),
+        cursor: Cursor::new(CursorMode::Insert(Selection::caret(0)), None, None),
+        register: Register::default(),
+        placeholder_text: None,
+        placeholder_buff: None,
+        placeholder_style: Default::default(),
+        min_rows: 2,
+        max_rows: None,
+        lines: WrappedLines::default(),
+        wrap_width: 0.0,
+        width: 0.0,
+        glyph_size: Size::ZERO,
+        text_node: None,
+        scroll_y: 0.0,
+        scroll_to_cursor: false,
+        style: Default::default(),
+        font: FontProps::default(),
+        is_focused: false,
+        ime_cursor_area: None,
+        last_cursor_action_on: clock::now(),
+    }
+    .keyboard_navigatable()
+    .on_event_stop(EventListener::FocusGained, move |_| {
+        is_focused.set(true);
+    })
+    .on_event_stop(EventListener::FocusLost, move |_| {
+        is_focused.set(false);
+    })
+}
+
+impl TextArea {
+    /// The fewest rows of text the text area is tall enough for
+    pub fn min_rows(mut self, rows: usize) -> Self {
+        self.min_rows = rows.max(1);
+        self
+    }
+
+    /// The most rows of text the text area grows to, after which it scrolls
+    pub fn max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = Some(rows.max(1));
+        self
+    }
+
+    fn text_attrs(&self) -> AttrsList {
+        let color = self.style.color().map(|brush| brush.to_color());
+        let mut attrs = Attrs::new()
+            .color(color.unwrap_or(Color::BLACK))
+            .font_size(self.font.size().unwrap_or(DEFAULT_FONT_SIZE));
+        if let Some(font_style) = self.font.style() {
+            attrs = attrs.style(font_style);
+        }
+        let font_family = self.font.family().as_ref().map(|font_family| {
+            let family: Vec<FamilyOwned> = FamilyOwned::parse_list(font_family).collect();
+            family
+        });
+        if let Some(font_family) = font_family.as_ref() {
+            attrs = attrs.family(font_family);
+        }
+        if let Some(font_weight) = self.font.weight() {
+            attrs = attrs.weight(font_weight);
+        }
+        AttrsList::new(attrs)
+    }
+
+    fn placeholder_attrs(&self) -> AttrsList {
+        let color = self.placeholder_style.color().map(|brush| brush.to_color());
+        let mut attrs = Attrs::new()
+            .color(color.unwrap_or(Color::BLACK))
+            .font_size(self.font.size().unwrap_or(DEFAULT_FONT_SIZE));
+        if let Some(font_style) = self.placeholder_style.font_style().or(self.font.style()) {
+            attrs = attrs.style(font_style);
+        }
+        if let Some(font_weight) = self.placeholder_style.font_weight().or(self.font.weight()) {
+            attrs = attrs.weight(font_weight);
+        }
+        AttrsList::new(attrs)
+    }
+
+    fn update_glyph_size(&mut self) {
+        let mut layout = TextLayout::new();
+        layout.set_text("W", self.text_attrs());
+        self.glyph_size = layout.size();
+    }
+
+    fn line_height(&self) -> f64 {
+        self.glyph_size.height
+    }
+
+    /// Lay out each line of the buffer wrapped to the width
+    fn update_lines(&mut self) {
+        self.lines = WrappedLines::new(
+            &self.buffer,
+            &self.text_attrs(),
+            self.wrap_width,
+            self.glyph_size,
+        );
+
+        if let Some(placeholder_text) = &self.placeholder_text {
+            let mut layout = TextLayout::new();
+            layout.set_text(placeholder_text, self.placeholder_attrs());
+            layout.set_size(self.wrap_width, f32::MAX);
+            self.placeholder_buff = Some(layout);
+        }
+    }
+
+    /// How many rows of text are shown
+    fn visible_rows(&self) -> usize {
+        visible_rows(
+            self.lines.height,
+            self.line_height(),
+            self.min_rows,
+            self.max_rows,
+        )
+    }
+
+    /// The top of the caret at `offset`, relative to the start of the text
+    fn offset_point(&self, offset: usize) -> Point {
+        self.lines.offset_point(&self.buffer, offset)
+    }
+
+    /// The offset closest to `point`, relative to the start of the text
+    fn point_offset(&self, point: Point) -> usize {
+        self.lines.point_offset(&self.buffer, point)
+    }
+
+    /// The offset at `x` on the row of the caret at `offset`
+    fn row_offset(&self, offset: usize, x: f64) -> usize {
+        let point = self.offset_point(offset);
+        self.point_offset(Point::new(x, point.y + self.line_height() / 2.0))
+    }
+
+    fn selection(&self) -> Option<(usize, usize)> {
+        self.cursor
+            .get_selection()
+            .filter(|(start, end)| start != end)
+            .map(|(start, end)| (start.min(end), start.max(end)))
+    }
+
+    fn move_to(&mut self, offset: usize, extend: bool) {
+        self.cursor.set_offset(offset, extend, false);
+        self.cursor.horiz = None;
+    }
+
+    /// Move the caret up or down by `rows`, keeping the column it was moved from
+    fn move_rows(&mut self, rows: f64, extend: bool) {
+        let point = self.offset_point(self.cursor.offset());
+        let x = match self.cursor.horiz {
+            Some(ColPosition::Col(x)) => x,
+            _ => point.x,
+        };
+        let y = point.y + rows * self.line_height();
+        let offset = if y < 0.0 {
+            0
+        } else if y >= self.lines.height {
+            self.buffer.len()
+        } else {
+            self.point_offset(Point::new(x, y + self.line_height() / 2.0))
+        };
+        self.cursor.set_offset(offset, extend, false);
+        self.cursor.horiz = Some(ColPosition::Col(x));
+    }
+
+    fn select_all(&mut self) {
+        self.cursor
+            .set_insert(Selection::region(0, self.buffer.len()));
+    }
+
+    fn do_edit(&mut self, cmd: &EditCommand) -> bool {
+        let old_cursor = self.cursor.mode.clone();
+        let deltas = Action::do_edit(
+            &mut self.cursor,
+            &mut self.buffer,
+            cmd,
+            &mut SystemClipboard::new(),
+            &mut self.register,
+            EditConf {
+                comment_token: "",
+                modal: false,
+                smart_tab: false,
+                keep_indent: false,
+                auto_indent: false,
+            },
+        );
+        self.after_edit(old_cursor, !deltas.is_empty())
+    }
+
+    fn insert(&mut self, text: &str) -> bool {
+        let old_cursor = self.cursor.mode.clone();
+        let deltas = Action::insert(
+            &mut self.cursor,
+            &mut self.buffer,
+            text,
+            &|_, _, _| None,
+            false,
+            false,
+        );
+        self.after_edit(old_cursor, !deltas.is_empty())
+    }
+
+    fn after_edit(&mut self, old_cursor: CursorMode, changed: bool) -> bool {
+        if changed {
+            self.buffer.set_cursor_before(old_cursor);
+            self.buffer.set_cursor_after(self.cursor.mode.clone());
+            self.lines.clear();
+            self.value
+                .set(self.buffer.slice_to_cow(0..self.buffer.len()).into_owned());
+        }
+        self.cursor.horiz = None;
+        changed
+    }
+
+    fn handle_command(&mut self, event: &KeyEvent, ch: &str) -> bool {
+        let shift = event.modifiers.contains(ModifiersState::SHIFT);
+        match ch.to_lowercase().as_str() {
+            "a" => {
+                self.select_all();
+                true
+            }
+            // Without a selection the editor commands would copy or cut the whole line
+            "c" if self.selection().is_some() => self.do_edit(&EditCommand::ClipboardCopy),
+            "x" if self.selection().is_some() => self.do_edit(&EditCommand::ClipboardCut),
+            "v" => match crate::Clipboard::get_contents() {
+                Ok(content) if !content.is_empty() => self.insert(&content),
+                _ => false,
+            },
+            "z" if shift => self.do_edit(&EditCommand::Redo),
+            "z" => self.do_edit(&EditCommand::Undo),
+            #[cfg(not(target_os = "macos"))]
+            "y" => self.do_edit(&EditCommand::Redo),
+            _ => false,
+        }
+    }
+
+    fn handle_key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
+        let shift = event.modifiers.contains(ModifiersState::SHIFT);
+        let word = is_word_motion(event.modifiers);
+        let command = is_command(event.modifiers);
+        let offset = self.cursor.offset();
+
+        match &event.key.logical_key {
+            Key::Character(ch) if command => self.handle_command(event, ch),
+            Key::Character(ch) => self.insert(ch),
+            Key::Named(NamedKey::Space) => self.insert(" "),
+            Key::Named(NamedKey::Enter) => self.do_edit(&EditCommand::InsertNewLine),
+            Key::Named(NamedKey::Backspace) if word => {
+                self.do_edit(&EditCommand::DeleteWordBackward)
+            }
+            Key::Named(NamedKey::Backspace) => self.do_edit(&EditCommand::DeleteBackward),
+            Key::Named(NamedKey::Delete) if word => self.do_edit(&EditCommand::DeleteWordForward),
+            Key::Named(NamedKey::Delete) => self.do_edit(&EditCommand::DeleteForward),
+            Key::Named(NamedKey::ArrowLeft) => {
+                let new_offset = match self.selection() {
+                    // Moving without shift collapses the selection to its start
+                    Some((start, _)) if !shift => start,
+                    // On platforms where both are Ctrl, Ctrl+Left moves by word
+                    _ if word => self.buffer.move_word_backward(offset, Mode::Insert),
+                    _ if command => self.row_offset(offset, 0.0),
+                    _ => self.buffer.move_left(offset, Mode::Insert, 1),
+                };
+                self.move_to(new_offset, shift);
+                true
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                let new_offset = match self.selection() {
+                    Some((_, end)) if !shift => end,
+                    _ if word => self.buffer.move_word_forward(offset),
+                    _ if command => self.row_offset(offset, f64::MAX),
+                    _ => self.buffer.move_right(offset, Mode::Insert, 1),
+                };
+                self.move_to(new_offset, shift);
+                true
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                self.move_rows(-1.0, shift);
+                true
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                self.move_rows(1.0, shift);
+                true
+            }
+            Key::Named(NamedKey::Home) => {
+                let new_offset = if command {
+                    0
+                } else {
+                    self.row_offset(offset, 0.0)
+                };
+                self.move_to(new_offset, shift);
+                true
+            }
+            Key::Named(NamedKey::End) => {
+                let new_offset = if command {
+                    self.buffer.len()
+                } else {
+                    self.row_offset(offset, f64::MAX)
+                };
+                self.move_to(new_offset, shift);
+                true
+            }
+            Key::Named(NamedKey::Escape) => {
+                cx.app_state.clear_focus();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The offset under the pointer at `pos`, relative to the view
+    fn pointer_offset(&self, cx: &EventCx, pos: Point) -> usize {
+        let Some(location) = self
+            .text_node
+            .and_then(|node| cx.app_state.taffy.layout(node).ok())
+            .map(|layout| layout.location)
+        else {
+            return 0;
+        };
+        self.point_offset(Point::new(
+            pos.x - location.x as f64,
+            pos.y - location.y as f64 + self.scroll_y,
+        ))
+    }
+
+    /// Scroll the text so the caret is visible in `height`
+    fn scroll_cursor_into_view(&mut self, height: f64) {
+        let caret = self.offset_point(self.cursor.offset());
+        if caret.y < self.scroll_y {
+            self.scroll_y = caret.y;
+        } else if caret.y + self.line_height() > self.scroll_y + height {
+            self.scroll_y = caret.y + self.line_height() - height;
+        }
+    }
+
+    fn clamp_scroll(&mut self, height: f64) {
+        self.scroll_y = self.scroll_y.min(self.lines.height - height).max(0.0);
+    }
+
+    fn update_accessibility(&self) {
+        let value = self.value;
+        let mut accessibility = Accessibility::new()
+            .role(Role::MultilineTextInput)
+            .value(self.buffer.slice_to_cow(0..self.buffer.len()).into_owned())
+            .on_set_value(move |text| value.set(text));
+        if let Some(placeholder) = &self.placeholder_text {
+            accessibility = accessibility.description(placeholder.clone());
+        }
+        self.id().update_accessibility(accessibility);
+    }
+}
+
+/// The lines of a buffer wrapped to a width
+#[derive(Default)]
+struct WrappedLines {
+    /// Each line of the buffer, with the y position it starts at
+    lines: Vec<(f64, TextLayout)>,
+    height: f64,
+    /// The height of each row of text
+    line_height: f64,
+    /// Approx width of a glyph, used for the width of a selected line break
+    glyph_width: f64,
+}
+
+impl WrappedLines {
+    fn new(buffer: &Buffer, attrs: &AttrsList, wrap_width: f32, glyph_size: Size) -> Self {
+        let line_height = glyph_size.height;
+        let mut y = 0.0;
+        let lines = (0..buffer.num_lines())
+            .map(|line| {
+                let mut layout = TextLayout::new();
+                let content = buffer.line_content(line);
+                layout.set_text(content.trim_end_matches(['\r', '\n']), attrs.clone());
+                layout.set_size(wrap_width, f32::MAX);
+                let line_y = y;
+                y += layout.layout_runs().count().max(1) as f64 * line_height;
+                (line_y, layout)
+            })
+            .collect();
+        Self {
+            lines,
+            height: y,
+            line_height,
+            glyph_width: glyph_size.width,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Marks the lines as needing to be laid out again
+    fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// The top of the caret at `offset`
+    fn offset_point(&self, buffer: &Buffer, offset: usize) -> Point {
+        let (line, col) = buffer.offset_to_line_col(offset);
+        let Some((y, layout)) = self.lines.get(line) else {
+            return Point::ZERO;
+        };
+        let hit = layout.hit_position(col);
+        Point::new(hit.point.x, y + hit.line as f64 * self.line_height)
+    }
+
+    /// The offset closest to `point`
+    fn point_offset(&self, buffer: &Buffer, point: Point) -> usize {
+        let Some(line) = self.lines.iter().rposition(|(y, _)| *y <= point.y) else {
+            return 0;
+        };
+        let (y, layout) = &self.lines[line];
+        let hit = layout.hit_point(Point::new(point.x, point.y - y));
+        let col = hit.index.min(buffer.line_end_col(line, true));
+        buffer.offset_of_line_col(line, col)
+    }
+
+    /// The rects covering the text from `start` to `end`, relative to the start of the text
+    fn selection_rects(&self, buffer: &Buffer, start: usize, end: usize) -> Vec<Rect> {
+        let line_height = self.line_height;
+        let mut rects = Vec::new();
+        for line in buffer.line_of_offset(start)..=buffer.line_of_offset(end) {
+            let Some((y, layout)) = self.lines.get(line) else {
+                break;
+            };
+            let line_start = buffer.offset_of_line(line);
+            let start_col = start.saturating_sub(line_start);
+            let end_col = end - line_start;
+            // A selection past the end of the line covers the line break
+            let covers_break = end > buffer.line_end_offset(line, true);
+
+            let mut runs = layout.layout_runs().peekable();
+            let mut row = 0;
+            while let Some(run) = runs.next() {
+                let is_last_row = runs.peek().is_none();
+                let mut selected = run
+                    .glyphs
+                    .iter()
+                    .filter(|glyph| glyph.start < end_col && glyph.end > start_col)
+                    .map(|glyph| (glyph.x as f64, (glyph.x + glyph.w) as f64));
+                let x = selected.next().map(|(x0, x1)| {
+                    let x1 = selected.last().map(|(_, x1)| x1).unwrap_or(x1);
+                    (x0, x1)
+                });
+                let x = match x {
+                    Some((x0, x1)) if covers_break && is_last_row => {
+                        Some((x0, x1 + self.glyph_width / 2.0))
+                    }
+                    Some(x) => Some(x),
+                    None if covers_break && is_last_row => {
+                        let x0 = run.line_w as f64;
+                        Some((x0, x0 + self.glyph_width / 2.0))
+                    }
+                    None => None,
+                };
+                if let Some((x0, x1)) = x {
+                    let top = y + row as f64 * line_height;
+                    rects.push(Rect::new(x0, top, x1, top + line_height));
+                }
+                row += 1;
+            }
+            if row == 0 && covers_break {
+                rects.push(Rect::new(0.0, *y, self.glyph_width / 2.0, y + line_height));
+            }
+        }
+        rects
+    }
+}
+
+/// How many rows of text are shown for text which is `content_height` tall
+fn visible_rows(
+    content_height: f64,
+    line_height: f64,
+    min_rows: usize,
+    max_rows: Option<usize>,
+) -> usize {
+    let rows = (content_height / line_height.max(1.0)).round() as usize;
+    let rows = rows.max(min_rows);
+    match max_rows {
+        Some(max_rows) => rows.min(max_rows.max(min_rows)),
+        None => rows,
+    }
+}
+
+/// Sets the text of `buffer` to `text` if it's different, as an edit which can be undone.
+/// Returns whether the text changed.
+fn reload_text(buffer: &mut Buffer, cursor: &mut Cursor, text: &str) -> bool {
+    if buffer.slice_to_cow(0..buffer.len()) == text {
+        return false;
+    }
+    let (_, delta, _) = buffer.reload(Rope::from(text), false);
+    cursor.apply_delta(&delta);
+    true
+}
+
+/// Whether the modifiers make the caret move a word at a time
+fn is_word_motion(modifiers: ModifiersState) -> bool {
+    #[cfg(target_os = "macos")]
+    return modifiers.contains(ModifiersState::ALT);
+    #[cfg(not(target_os = "macos"))]
+    return modifiers.contains(ModifiersState::CONTROL);
+}
+
+/// Whether the modifiers turn a key into a command like copy. On macOS they also make the left and
+/// right arrows move the caret to the end of the row.
+fn is_command(modifiers: ModifiersState) -> bool {
+    #[cfg(target_os = "macos")]
+    return modifiers.contains(ModifiersState::SUPER);
+    #[cfg(not(target_os = "macos"))]
+    return modifiers.contains(ModifiersState::CONTROL);
+}
+
+impl View for TextArea {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn build(self) -> Box<dyn Widget> {
+        Box::new(self)
+    }
+}
+
+impl Widget for TextArea {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        format!("TextArea: {:?}", self.value.get_untracked()).into()
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
+        if let Ok(state) = state.downcast::<(String, bool)>() {
+            let (text, is_focused) = *state;
+            // Only set the buffer when the value was changed from outside, so the undo history
+            // is kept while editing
+            if reload_text(&mut self.buffer, &mut self.cursor, &text) {
+                self.lines.clear();
+            }
+
+            if is_focused != self.is_focused {
+                self.is_focused = is_focused;
+                self.ime_cursor_area = None;
+                set_ime_allowed(is_focused);
+            }
+            self.update_accessibility();
+            cx.request_layout(self.id());
+        }
+    }
+
+    fn event(
+        &mut self,
+        cx: &mut EventCx,
+        _id_path: Option<&[Id]>,
+        event: Event,
+    ) -> EventPropagation {
+        let is_handled = match &event {
+            Event::PointerDown(
+                event @ PointerInputEvent {
+                    button: PointerButton::Primary,
+                    ..
+                },
+            ) => {
+                cx.update_active(self.id());
+                let offset = self.pointer_offset(cx, event.pos);
+                match event.count {
+                    2 => {
+                        let (start, end) = self.buffer.select_word(offset);
+                        self.cursor.set_insert(Selection::region(start, end));
+                    }
+                    3 => {
+                        let line = self.buffer.line_of_offset(offset);
+                        self.cursor.set_insert(Selection::region(
+                            self.buffer.offset_of_line(line),
+                            self.buffer.offset_of_line(line + 1),
+                        ));
+                    }
+                    _ => self.move_to(offset, event.modifiers.contains(ModifiersState::SHIFT)),
+                }
+                true
+            }
+            Event::PointerMove(event) if cx.is_active(self.id()) => {
+                let offset = self.pointer_offset(cx, event.pos);
+                self.move_to(offset, true);
+                true
+            }
+            Event::PointerWheel(event) => {
+                let before = self.scroll_y;
+                let height = self.visible_rows() as f64 * self.line_height();
+                self.scroll_y += event.delta.y;
+                self.clamp_scroll(height);
+                if self.scroll_y != before {
+                    cx.app_state.request_paint(self.id());
+                    return EventPropagation::Stop;
+                }
+                false
+            }
+            Event::KeyDown(event) => self.handle_key_down(cx, event),
+            Event::ImeCommit(text) if self.is_focused => self.insert(text),
+            _ => false,
+        };
+
+        if is_handled {
+            self.scroll_to_cursor = true;
+            self.last_cursor_action_on = clock::now();
+            cx.app_state.request_layout(self.id());
+        }
+
+        EventPropagation::Continue
+    }
+
+    fn style(&mut self, cx: &mut StyleCx<'_>) {
+        let style = cx.style();
+        if self.font.read(cx) | self.style.read(cx) || self.glyph_size == Size::ZERO {
+            self.update_glyph_size();
+            self.lines.clear();
+            cx.app_state_mut().request_layout(self.id());
+        }
+
+        let placeholder_style = style.apply_class(PlaceholderTextClass);
+        if self.placeholder_style.read_style(cx, &placeholder_style) {
+            self.lines.clear();
+            cx.app_state_mut().request_layout(self.id());
+        }
+    }
+
+    fn layout(&mut self, cx: &mut LayoutCx) -> NodeId {
+        cx.layout_node(self.id(), true, |cx| {
+            if self.text_node.is_none() {
+                self.text_node = Some(
+                    cx.app_state_mut()
+                        .taffy
+                        .new_leaf(taffy::style::Style::DEFAULT)
+                        .unwrap(),
+                );
+            }
+            let text_node = self.text_node.unwrap();
+
+            #[allow(clippy::unwrap_or_default)]
+            let layout = cx.app_state.get_layout(self.id()).unwrap_or(Layout::new());
+            let style = cx.app_state_mut().get_builtin_style(self.id());
+            let node_width = layout.size.width;
+
+            let style_width = style.width();
+            let padding_left = match style.padding_left() {
+                PxPct::Px(padding) => padding as f32,
+                PxPct::Pct(pct) => pct as f32 / 100.0 * node_width,
+            };
+            let padding_right = match style.padding_right() {
+                PxPct::Px(padding) => padding as f32,
+                PxPct::Pct(pct) => pct as f32 / 100.0 * node_width,
+            };
+            self.width = match style_width {
+                PxPctAuto::Px(px) => f32::max(px as f32 - padding_left - padding_right, 1.0),
+                // The percent is already applied to the view, so the text fills it
+                PxPctAuto::Pct(_) => f32::max(node_width - padding_left - padding_right, 1.0),
+                PxPctAuto::Auto => APPROX_VISIBLE_COLS_TARGET * self.glyph_size.width as f32,
+            };
+
+            if self.lines.is_empty() || self.wrap_width != self.width {
+                self.wrap_width = self.width;
+                self.update_lines();
+            }
+
+            let taffy_node_width = match style_width {
+                PxPctAuto::Px(_) | PxPctAuto::Auto => PxPctAuto::Px(self.width as f64),
+                PxPctAuto::Pct(_) => PxPctAuto::Pct(100.),
+            };
+            let style = Style::new()
+                .width(taffy_node_width)
+                .height(self.visible_rows() as f64 * self.line_height())
+                .to_taffy_style();
+            let _ = cx.app_state_mut().taffy.set_style(text_node, style);
+
+            vec![text_node]
+        })
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        let text_node = self.text_node?;
+        let node_layout = *cx.app_state.taffy.layout(text_node).unwrap();
+
+        // A width in percent is only known once the view has been laid out
+        if node_layout.size.width != self.wrap_width && node_layout.size.width > 0.0 {
+            self.wrap_width = node_layout.size.width;
+            self.update_lines();
+            cx.app_state_mut().request_layout(self.id());
+        }
+
+        let height = node_layout.size.height as f64;
+        if self.scroll_to_cursor {
+            self.scroll_to_cursor = false;
+            self.scroll_cursor_into_view(height);
+        }
+        self.clamp_scroll(height);
+
+        if self.is_focused {
+            // Tell the IME where the caret is, so it can show its candidates next to it
+            let caret = self.offset_point(self.cursor.offset());
+            let position = cx.window_origin
+                + (
+                    node_layout.location.x as f64 + caret.x,
+                    node_layout.location.y as f64 + caret.y - self.scroll_y,
+                );
+            if self.ime_cursor_area != Some(position) {
+                self.ime_cursor_area = Some(position);
+                set_ime_cursor_area(position, Size::new(1.0, self.line_height()));
+            }
+        }
+
+        None
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let Some(text_node) = self.text_node else {
+            return;
+        };
+        let node_layout = *cx.app_state.taffy.layout(text_node).unwrap();
+        let origin = Point::new(node_layout.location.x as f64, node_layout.location.y as f64);
+        let size = Size::new(
+            node_layout.size.width as f64,
+            node_layout.size.height as f64,
+        );
+        let is_focused = cx.app_state.is_focused(&self.id());
+
+        if !is_focused && self.buffer.is_empty() {
+            if let Some(placeholder_buff) = &self.placeholder_buff {
+                cx.save();
+                cx.clip(&Rect::from_origin_size(origin, size));
+                cx.draw_text(placeholder_buff, origin);
+                cx.restore();
+            }
+            return;
+        }
+
+        cx.save();
+        cx.clip(&Rect::from_origin_size(origin, size));
+        let text_origin = origin - (0.0, self.scroll_y);
+
+        if is_focused {
+            if let Some((start, end)) = self.selection() {
+                let style = cx.app_state.get_computed_style(self.id());
+                let selection_color = style.get(CursorColor).unwrap_or(Color::rgba8(0, 0, 0, 150));
+                for rect in self.lines.selection_rects(&self.buffer, start, end) {
+                    cx.fill(&(rect + text_origin.to_vec2()), selection_color, 0.0);
+                }
+            }
+        }
+
+        for (y, layout) in &self.lines.lines {
+            let top = text_origin.y + y;
+            if top > origin.y + size.height {
+                break;
+            }
+            if top + layout.size().height < origin.y {
+                continue;
+            }
+            cx.draw_text(layout, Point::new(text_origin.x, top));
+        }
+
+        let is_cursor_visible = is_focused
+            && self.selection().is_none()
+            && (clock::now()
+                .saturating_duration_since(self.last_cursor_action_on)
+                .as_millis()
+                / CURSOR_BLINK_INTERVAL_MS as u128)
+                % 2
+                == 0;
+        if is_cursor_visible {
+            let cursor_color = cx
+                .app_state
+                .get_computed_style(self.id())
+                .builtin()
+                .cursor_color();
+            let caret = self.offset_point(self.cursor.offset()) + text_origin.to_vec2();
+            let cursor_rect = Rect::from_origin_size(caret, (1.0, self.line_height()));
+            cx.fill(&cursor_rect, cursor_color.unwrap_or(Color::BLACK), 0.0);
+        }
+        cx.restore();
+
+        if is_focused {
+            let id = self.id();
+            exec_after(Duration::from_millis(CURSOR_BLINK_INTERVAL_MS), move |_| {
+                id.request_paint();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs() -> AttrsList {
+        AttrsList::new(Attrs::new().font_size(DEFAULT_FONT_SIZE))
+    }
+
+    fn text_width(text: &str) -> f32 {
+        let mut layout = TextLayout::new();
+        layout.set_text(text, attrs());
+        layout.size().width as f32
+    }
+
+    fn wrapped(text: &str, wrap_width: f32) -> (Buffer, WrappedLines) {
+        let mut glyph = TextLayout::new();
+        glyph.set_text("W", attrs());
+        let buffer = Buffer::new(text);
+        let lines = WrappedLines::new(&buffer, &attrs(), wrap_width, glyph.size());
+        (buffer, lines)
+    }
+
+    #[test]
+    fn visible_rows_between_min_and_max() {
+        assert_eq!(visible_rows(0.0, 10.0, 2, None), 2);
+        assert_eq!(visible_rows(50.0, 10.0, 2, None), 5);
+        assert_eq!(visible_rows(50.0, 10.0, 2, Some(3)), 3);
+        assert_eq!(visible_rows(20.0, 10.0, 2, Some(3)), 2);
+        // The min rows win over a smaller max
+        assert_eq!(visible_rows(50.0, 10.0, 4, Some(1)), 4);
+    }
+
+    #[test]
+    fn offsets_round_trip_on_wrapped_lines() {
+        // The first line wraps after "bbbb "
+        let (buffer, lines) = wrapped("aaaa bbbb cccc\ndd", text_width("aaaa bbbb") + 1.0);
+        let line_height = lines.line_height;
+        assert_eq!(lines.lines.len(), 2);
+        assert_eq!(lines.height, 3.0 * line_height);
+
+        assert_eq!(lines.offset_point(&buffer, 3).y, 0.0);
+        assert_eq!(lines.offset_point(&buffer, 12).y, line_height);
+        assert_eq!(lines.offset_point(&buffer, 16).y, 2.0 * line_height);
+
+        // Offsets at the soft line break could be on either row
+        for offset in (0..=9).chain(11..=buffer.len()) {
+            let point = lines.offset_point(&buffer, offset);
+            let offset_back =
+                lines.point_offset(&buffer, Point::new(point.x, point.y + line_height / 2.0));
+            assert_eq!(offset_back, offset, "{point:?}");
+        }
+
+        // Points past the end of a row stay on the line
+        assert_eq!(
+            lines.point_offset(&buffer, Point::new(1000.0, line_height * 1.5)),
+            14
+        );
+    }
+
+    #[test]
+    fn selection_covers_line_break() {
+        let (buffer, lines) = wrapped("aa\n\nbb", 1000.0);
+        let line_height = lines.line_height;
+
+        let rects = lines.selection_rects(&buffer, 1, 5);
+        assert_eq!(rects.len(), 3);
+        // The first line is selected past its last glyph
+        assert!(rects[0].x1 > lines.offset_point(&buffer, 2).x);
+        assert_eq!(rects[0].y0, 0.0);
+        // The empty line only has its line break selected
+        assert_eq!(rects[1].x0, 0.0);
+        assert_eq!(rects[1].width(), lines.glyph_width / 2.0);
+        assert_eq!(rects[1].y0, line_height);
+        // The selection ends inside the last line
+        assert_eq!(rects[2].x0, 0.0);
+        assert!((rects[2].x1 - lines.offset_point(&buffer, 5).x).abs() < 0.01);
+        assert_eq!(rects[2].y0, 2.0 * line_height);
+
+        // Without the line break only the glyphs are covered
+        let rects = lines.selection_rects(&buffer, 0, 2);
+        assert_eq!(rects.len(), 1);
+        assert!((rects[0].x1 - lines.offset_point(&buffer, 2).x).abs() < 0.01);
+    }
+
+    #[test]
+    fn reload_keeps_undo_history() {
+        let mut buffer = Buffer::new("");
+        let mut cursor = Cursor::new(CursorMode::Insert(Selection::caret(0)), None, None);
+        Action::insert(
+            &mut cursor,
+            &mut buffer,
+            "ab",
+            &|_, _, _| None,
+            false,
+            false,
+        );
+
+        assert!(reload_text(&mut buffer, &mut cursor, "xyz"));
+        assert!(!reload_text(&mut buffer, &mut cursor, "xyz"));
+        assert_eq!(buffer.to_string(), "xyz");
+        assert!(cursor.offset() <= buffer.len());
+
+        buffer.do_undo();
+        assert_eq!(buffer.to_string(), "ab");
+        buffer.do_undo();
+        assert_eq!(buffer.to_string(), "");
+    }
+}
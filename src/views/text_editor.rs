@@ -11,7 +11,7 @@ use crate::{
     views::editor::{
         command::CommandExecuted,
         id::EditorId,
-        keypress::default_key_handler,
+        keypress::{keymap_key_handler, KeypressMap},
         text::{Document, SimpleStyling, Styling},
         text_document::{OnUpdate, PreCommand, TextDocument},
         view::editor_container_view,
@@ -28,6 +28,7 @@ pub struct TextEditor {
     cx: Scope,
 
     editor: Editor,
+    keypress_map: KeypressMap,
 }
 
 pub fn text_editor(text: impl Into<Rope>) -> TextEditor {
@@ -42,6 +43,7 @@ pub fn text_editor(text: impl Into<Rope>) -> TextEditor {
         data: ViewData::new(id),
         cx,
         editor,
+        keypress_map: KeypressMap::default(),
     }
 }
 
@@ -58,8 +60,9 @@ impl View for TextEditor {
         let cx = self.cx;
 
         let editor = cx.create_rw_signal(self.editor);
+        let keypress_map = self.keypress_map;
         let view = with_scope(self.cx, || {
-            editor_container_view(editor, |_| true, default_key_handler(editor))
+            editor_container_view(editor, |_| true, keymap_key_handler(editor, keypress_map))
         });
         view.build()
     }
@@ -150,6 +153,7 @@ impl TextEditor {
             data: ViewData::new(id),
             cx: self.cx,
             editor,
+            keypress_map: self.keypress_map.clone(),
         }
     }
 
@@ -170,6 +174,18 @@ impl TextEditor {
         self
     }
 
+    /// Use different keybindings, such as the defaults merged with a user's keymap file.  
+    /// ```rust,ignore
+    /// let mut keymap = KeypressMap::default();
+    /// keymap.merge_toml(&std::fs::read_to_string("keymaps.toml")?)?;
+    /// text_editor("").keymap(keymap);
+    /// ```
+    /// Default: [`KeypressMap::default`]
+    pub fn keymap(mut self, keypress_map: KeypressMap) -> Self {
+        self.keypress_map = keypress_map;
+        self
+    }
+
     /// Set the text editor to read only.  
     /// Equivalent to setting [`Editor::read_only`]  
     /// Default: `false`
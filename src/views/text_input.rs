@@ -1,4 +1,6 @@
-use crate::action::exec_after;
+use crate::accessibility::{Accessibility, Role};
+use crate::action::{exec_after, set_ime_allowed, set_ime_cursor_area};
+use crate::clock;
 use crate::event::EventListener;
 use crate::keyboard::{self, KeyEvent};
 use crate::pointer::{PointerButton, PointerInputEvent};
@@ -21,11 +23,12 @@ use crate::{peniko::Color, style::Style, view::Widget};
 use std::{
     any::Any,
     ops::Range,
+    rc::Rc,
     time::{Duration, Instant},
 };
 
 use crate::cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout};
-use kurbo::{Point, Rect, Size};
+use kurbo::{Line, Point, Rect, Size, Vec2};
 
 use crate::{
     context::{EventCx, UpdateCx},
@@ -51,7 +54,7 @@ prop_extractor! {
 }
 
 prop_extractor! {
-    PlaceholderStyle {
+    pub(crate) PlaceholderStyle {
         pub color: TextColor,
         //TODO: pub font_size: FontSize,
         pub font_weight: FontWeight,
@@ -94,6 +97,18 @@ pub struct TextInput {
     cursor_width: f64, // TODO: make this configurable
     is_focused: bool,
     last_cursor_action_on: Instant,
+    history: EditHistory,
+    preedit: Option<Preedit>,
+    // Where the IME was last told the cursor is, in window coordinates
+    ime_cursor_area: Option<Point>,
+    ime_allowed: bool,
+    // Whether the input was focused when it was last updated, to move the cursor to the end of
+    // the text only when focus is gained
+    has_focus: bool,
+    pub(crate) obscure: Option<char>,
+    pub(crate) max_length: Option<usize>,
+    pub(crate) mask: Option<InputMask>,
+    pub(crate) validator: Option<Box<dyn Fn(&str) -> bool>>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -145,7 +160,16 @@ pub fn text_input(buffer: RwSignal<String>) -> TextInput {
         width: 0.0,
         height: 0.0,
         is_focused: false,
-        last_cursor_action_on: Instant::now(),
+        last_cursor_action_on: clock::now(),
+        history: EditHistory::default(),
+        preedit: None,
+        ime_cursor_area: None,
+        ime_allowed: false,
+        has_focus: false,
+        obscure: None,
+        max_length: None,
+        mask: None,
+        validator: None,
     }
     .keyboard_navigatable()
     .on_event_stop(EventListener::FocusGained, move |_| {
@@ -168,6 +192,8 @@ enum TextCommand {
     Copy,
     Paste,
     Cut,
+    Undo,
+    Redo,
     None,
 }
 
@@ -180,6 +206,12 @@ impl From<(&KeyEvent, &SmolStr)> for TextCommand {
             (ModifiersState::SUPER, "c") => Self::Copy,
             (ModifiersState::SUPER, "x") => Self::Cut,
             (ModifiersState::SUPER, "v") => Self::Paste,
+            (ModifiersState::SUPER, "z") => Self::Undo,
+            (modifiers, "z" | "Z")
+                if modifiers == ModifiersState::SUPER | ModifiersState::SHIFT =>
+            {
+                Self::Redo
+            }
             _ => Self::None,
         }
         #[cfg(not(target_os = "macos"))]
@@ -188,6 +220,13 @@ impl From<(&KeyEvent, &SmolStr)> for TextCommand {
             (ModifiersState::CONTROL, "c") => Self::Copy,
             (ModifiersState::CONTROL, "x") => Self::Cut,
             (ModifiersState::CONTROL, "v") => Self::Paste,
+            (ModifiersState::CONTROL, "z") => Self::Undo,
+            (ModifiersState::CONTROL, "y") => Self::Redo,
+            (modifiers, "z" | "Z")
+                if modifiers == ModifiersState::CONTROL | ModifiersState::SHIFT =>
+            {
+                Self::Redo
+            }
             _ => Self::None,
         }
     }
@@ -211,6 +250,143 @@ fn get_word_based_motion(event: &KeyEvent) -> Option<Movement> {
             .then_some(Movement::Line));
 }
 
+/// Decides which text a [`TextInput`] can hold. Edits which would leave text the mask doesn't
+/// accept are rejected, so the mask also has to accept every prefix of the text it's meant for.
+///
+/// ## Example
+/// ```
+/// use floem::views::InputMask;
+///
+/// let hex = InputMask::new(|text| text.chars().all(|c| c.is_ascii_hexdigit()));
+/// assert!(hex.accepts("ff80"));
+/// assert!(!hex.accepts("fg"));
+/// ```
+#[derive(Clone)]
+pub struct InputMask {
+    accepts: Rc<dyn Fn(&str) -> bool>,
+}
+
+impl InputMask {
+    pub fn new(accepts: impl Fn(&str) -> bool + 'static) -> Self {
+        Self {
+            accepts: Rc::new(accepts),
+        }
+    }
+
+    /// Only digits
+    pub fn numeric() -> Self {
+        Self::new(|text| text.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    /// Digits with an optional leading `-` and at most one `.`
+    pub fn decimal() -> Self {
+        Self::new(|text| {
+            let digits = text.strip_prefix('-').unwrap_or(text);
+            digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && digits.matches('.').count() <= 1
+        })
+    }
+
+    /// Text which matches `regex`. Since the text is typed a character at a time, the regex
+    /// should match every prefix of the text it's meant for, such as `^[a-z]*$`.
+    #[cfg(feature = "regex")]
+    pub fn regex(regex: regex::Regex) -> Self {
+        Self::new(move |text| regex.is_match(text))
+    }
+
+    pub fn accepts(&self, text: &str) -> bool {
+        (self.accepts)(text)
+    }
+}
+
+/// Consecutive edits of the same kind within this interval are undone together
+const UNDO_GROUP_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    /// Edits like pasting which are never grouped with others
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+/// The undo and redo stacks of a [`TextInput`]
+#[derive(Default)]
+struct EditHistory {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// The kind of the last edit, the cursor after it and when it happened
+    last_edit: Option<(EditKind, usize, Instant)>,
+    /// The text after the last edit, to notice when the buffer is changed from outside
+    text: String,
+}
+
+impl EditHistory {
+    /// Record an edit from `before` to `after`, leaving the cursor at `cursor`. The edit is
+    /// grouped with the previous one if it carries on from it.
+    fn record(
+        &mut self,
+        before: Snapshot,
+        after: &str,
+        kind: EditKind,
+        cursor: usize,
+        now: Instant,
+    ) {
+        let continues = self
+            .last_edit
+            .is_some_and(|(last_kind, last_cursor, last_time)| {
+                kind != EditKind::Other
+                    && kind == last_kind
+                    && last_cursor == before.cursor
+                    && now.saturating_duration_since(last_time) < UNDO_GROUP_INTERVAL
+            });
+        if !continues || self.undo.is_empty() {
+            self.undo.push(before);
+        }
+        self.redo.clear();
+        self.last_edit = Some((kind, cursor, now));
+        self.text = after.to_string();
+    }
+
+    fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.undo.pop()?;
+        self.redo.push(current);
+        self.last_edit = None;
+        self.text = snapshot.text.clone();
+        Some(snapshot)
+    }
+
+    fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push(current);
+        self.last_edit = None;
+        self.text = snapshot.text.clone();
+        Some(snapshot)
+    }
+
+    /// Forget the history if the buffer was set to `text` by something other than the input
+    fn sync(&mut self, text: &str) {
+        if self.text != text {
+            *self = Self {
+                text: text.to_string(),
+                ..Default::default()
+            };
+        }
+    }
+}
+
+/// Text the IME is composing, which is shown at the cursor until it's committed
+struct Preedit {
+    text: String,
+    cursor: Option<(usize, usize)>,
+}
+
 const DEFAULT_FONT_SIZE: f32 = 14.0;
 const CURSOR_BLINK_INTERVAL_MS: u64 = 500;
 /// Specifies approximately how many characters wide the input field should be
@@ -290,7 +466,7 @@ impl TextInput {
     fn clip_text(&mut self, node_layout: &Layout) {
         let virt_text = self.text_buf.as_ref().unwrap();
         let node_width = node_layout.size.width as f64;
-        let cursor_text_loc = Cursor::new(0, self.cursor_glyph_idx);
+        let cursor_text_loc = Cursor::new(0, self.display_cursor());
         let layout_cursor = virt_text.layout_cursor(&cursor_text_loc);
         let cursor_glyph_pos = virt_text.hit_position(layout_cursor.glyph);
         let cursor_x = cursor_glyph_pos.point.x;
@@ -317,12 +493,10 @@ impl TextInput {
             .index;
 
         let new_text = self
-            .buffer
-            .get()
-            .chars()
-            .skip(clip_start)
-            .take(clip_end - clip_start)
-            .collect();
+            .display_text()
+            .get(clip_start..clip_end)
+            .unwrap_or_default()
+            .to_string();
 
         self.cursor_x -= clip_start_x;
         self.clip_start_idx = clip_start;
@@ -362,7 +536,12 @@ impl TextInput {
         let clicked_glyph_idx = self.get_box_position(pos_x, pos_y, cx);
 
         self.buffer.with_untracked(|buff| {
-            let selection = get_dbl_click_selection(clicked_glyph_idx, buff);
+            // Words can't be told apart in obscured text
+            let selection = if self.obscure.is_some() {
+                0..buff.len()
+            } else {
+                get_dbl_click_selection(clicked_glyph_idx, buff)
+            };
             self.cursor_glyph_idx = selection.end;
             self.selection = Some(selection);
         })
@@ -380,7 +559,8 @@ impl TextInput {
             PxPct::Px(padding) => padding as f32,
             PxPct::Pct(pct) => pct as f32 * layout.size.width,
         };
-        self.text_buf
+        let index = self
+            .text_buf
             .as_ref()
             .unwrap()
             .hit_point(Point::new(
@@ -389,7 +569,8 @@ impl TextInput {
                 // slightly below the text
                 pos_y - padding_top as f64,
             ))
-            .index
+            .index;
+        self.buffer_offset(index)
     }
 
     fn get_selection_rect(&self, node_layout: &Layout, left_padding: f64) -> Rect {
@@ -402,12 +583,19 @@ impl TextInput {
         let virtual_text = self.text_buf.as_ref().unwrap();
         let text_height = virtual_text.size().height;
 
-        let selection_start_x =
-            virtual_text.hit_position(selection.start).point.x - self.clip_start_x;
+        let selection_start_x = virtual_text
+            .hit_position(self.display_offset(selection.start))
+            .point
+            .x
+            - self.clip_start_x;
         let selection_start_x = selection_start_x.max(node_layout.location.x as f64 - left_padding);
 
-        let selection_end_x =
-            virtual_text.hit_position(selection.end).point.x + left_padding - self.clip_start_x;
+        let selection_end_x = virtual_text
+            .hit_position(self.display_offset(selection.end))
+            .point
+            .x
+            + left_padding
+            - self.clip_start_x;
         let selection_end_x =
             selection_end_x.min(selection_start_x + self.width as f64 + left_padding);
 
@@ -424,6 +612,72 @@ impl TextInput {
         )
     }
 
+    /// The text which is laid out and painted, with the characters obscured or the IME
+    /// composition inserted at the cursor
+    fn display_text(&self) -> String {
+        self.buffer.with_untracked(|buff| {
+            if let Some(obscure) = self.obscure {
+                buff.graphemes(true).map(|_| obscure).collect()
+            } else if let Some(preedit) = &self.preedit {
+                let mut text = buff.clone();
+                text.insert_str(self.cursor_glyph_idx.min(buff.len()), &preedit.text);
+                text
+            } else {
+                buff.clone()
+            }
+        })
+    }
+
+    /// Map an index into the buffer to an index into the display text
+    fn display_offset(&self, offset: usize) -> usize {
+        if let Some(obscure) = self.obscure {
+            let graphemes = self
+                .buffer
+                .with_untracked(|buff| buff[..offset.min(buff.len())].graphemes(true).count());
+            graphemes * obscure.len_utf8()
+        } else {
+            match &self.preedit {
+                Some(preedit) if offset > self.cursor_glyph_idx => offset + preedit.text.len(),
+                _ => offset,
+            }
+        }
+    }
+
+    /// Map an index into the display text to an index into the buffer
+    fn buffer_offset(&self, offset: usize) -> usize {
+        if let Some(obscure) = self.obscure {
+            let graphemes = offset / obscure.len_utf8();
+            self.buffer.with_untracked(|buff| {
+                buff.grapheme_indices(true)
+                    .nth(graphemes)
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(buff.len())
+            })
+        } else {
+            match &self.preedit {
+                Some(preedit) if offset > self.cursor_glyph_idx => offset
+                    .saturating_sub(preedit.text.len())
+                    .max(self.cursor_glyph_idx),
+                _ => offset,
+            }
+        }
+    }
+
+    /// Where the cursor is in the display text, which is inside the IME composition while
+    /// there is one
+    fn display_cursor(&self) -> usize {
+        match &self.preedit {
+            Some(preedit) if self.obscure.is_none() => {
+                self.cursor_glyph_idx
+                    + preedit
+                        .cursor
+                        .map(|(start, _)| start)
+                        .unwrap_or(preedit.text.len())
+            }
+            _ => self.display_offset(self.cursor_glyph_idx),
+        }
+    }
+
     /// Determine approximate max size of a single glyph, given the current font weight & size
     fn get_font_glyph_max_size(&self) -> Size {
         let mut tmp = TextLayout::new();
@@ -446,12 +700,27 @@ impl TextInput {
         }
     }
 
+    fn update_accessibility(&self) {
+        let buffer = self.buffer;
+        let mut accessibility = if self.obscure.is_some() {
+            Accessibility::new().role(Role::PasswordInput)
+        } else {
+            Accessibility::new()
+                .role(Role::TextInput)
+                .value(buffer.get_untracked())
+        }
+        .on_set_value(move |value| buffer.set(value));
+        if let Some(placeholder) = &self.placeholder_text {
+            accessibility = accessibility.description(placeholder.clone());
+        }
+        self.id().update_accessibility(accessibility);
+    }
+
     fn update_text_layout(&mut self) {
         let mut text_layout = TextLayout::new();
         let attrs_list = self.get_text_attrs();
 
-        self.buffer
-            .with_untracked(|buff| text_layout.set_text(buff, attrs_list.clone()));
+        text_layout.set_text(&self.display_text(), attrs_list.clone());
 
         let glyph_max_size = self.get_font_glyph_max_size();
         self.height = glyph_max_size.height as f32;
@@ -473,7 +742,8 @@ impl TextInput {
     }
 
     pub fn get_placeholder_text_attrs(&self) -> AttrsList {
-        let mut attrs = Attrs::new().color(self.placeholder_style.color().unwrap_or(Color::BLACK));
+        let color = self.placeholder_style.color().map(|brush| brush.to_color());
+        let mut attrs = Attrs::new().color(color.unwrap_or(Color::BLACK));
 
         //TODO:
         // self.placeholder_style
@@ -496,7 +766,8 @@ impl TextInput {
     }
 
     pub fn get_text_attrs(&self) -> AttrsList {
-        let mut attrs = Attrs::new().color(self.style.color().unwrap_or(Color::BLACK));
+        let color = self.style.color().map(|brush| brush.to_color());
+        let mut attrs = Attrs::new().color(color.unwrap_or(Color::BLACK));
 
         attrs = attrs.font_size(self.font_size());
 
@@ -551,34 +822,23 @@ impl TextInput {
                 true
             }
             TextCommand::Copy => {
-                if let Some(selection) = &self.selection {
+                // The text of an obscured input shouldn't leave it
+                if let Some(selection) = self.selection.as_ref().filter(|_| self.obscure.is_none())
+                {
                     let selection_txt = self
                         .buffer
-                        .get()
-                        .chars()
-                        .skip(selection.start)
-                        .take(selection.end - selection.start)
-                        .collect();
+                        .with_untracked(|buff| buff[selection.clone()].to_string());
                     let _ = Clipboard::set_contents(selection_txt);
                 }
                 true
             }
             TextCommand::Cut => {
-                if let Some(selection) = &self.selection {
+                if let Some(selection) = self.selection.clone().filter(|_| self.obscure.is_none()) {
                     let selection_txt = self
                         .buffer
-                        .get()
-                        .chars()
-                        .skip(selection.start)
-                        .take(selection.end - selection.start)
-                        .collect();
+                        .with_untracked(|buff| buff[selection.clone()].to_string());
                     let _ = Clipboard::set_contents(selection_txt);
-
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection.clone(), None));
-
-                    self.cursor_glyph_idx = selection.start;
-                    self.selection = None;
+                    self.edit(selection, "", EditKind::Other);
                 }
 
                 true
@@ -588,26 +848,17 @@ impl TextInput {
                     Ok(content) => content,
                     Err(_) => return false,
                 };
+                // This is a single line input
+                let clipboard_content = clipboard_content.replace(['\r', '\n'], "");
                 if clipboard_content.is_empty() {
                     return false;
                 }
 
-                if let Some(selection) = &self.selection {
-                    self.buffer.update(|buf| {
-                        replace_range(buf, selection.clone(), Some(&clipboard_content))
-                    });
-
-                    self.cursor_glyph_idx +=
-                        clipboard_content.len() - selection.len().min(clipboard_content.len());
-                    self.selection = None;
-                } else {
-                    self.buffer
-                        .update(|buf| buf.insert_str(self.cursor_glyph_idx, &clipboard_content));
-                    self.cursor_glyph_idx += clipboard_content.len();
-                }
-
+                self.edit(self.edit_range(), &clipboard_content, EditKind::Other);
                 true
             }
+            TextCommand::Undo => self.undo(),
+            TextCommand::Redo => self.redo(),
             TextCommand::None => {
                 self.selection = None;
                 false
@@ -615,6 +866,91 @@ impl TextInput {
         }
     }
 
+    /// The range replaced by typing, which is the selection or the cursor
+    fn edit_range(&self) -> Range<usize> {
+        self.selection
+            .clone()
+            .unwrap_or(self.cursor_glyph_idx..self.cursor_glyph_idx)
+    }
+
+    /// Replace `range` of the buffer with `text` and record the change so it can be undone.
+    ///
+    /// The text is cut off at the max length, and the edit is rejected if the mask doesn't accept
+    /// the result. Returns true if the buffer changed.
+    fn edit(&mut self, range: Range<usize>, text: &str, kind: EditKind) -> bool {
+        let before = self.buffer.get_untracked();
+        let text = match self.max_length {
+            Some(max_length) => {
+                let kept = before.chars().count() - before[range.clone()].chars().count();
+                let allowed = max_length.saturating_sub(kept);
+                let end = text
+                    .char_indices()
+                    .nth(allowed)
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(text.len());
+                &text[..end]
+            }
+            None => text,
+        };
+
+        let mut after = before.clone();
+        replace_range(&mut after, range.clone(), Some(text));
+        if after == before || self.mask.as_ref().is_some_and(|mask| !mask.accepts(&after)) {
+            return false;
+        }
+
+        let cursor = range.start + text.len();
+        self.history.record(
+            Snapshot {
+                text: before,
+                cursor: self.cursor_glyph_idx,
+            },
+            &after,
+            kind,
+            cursor,
+            clock::now(),
+        );
+        self.buffer.set(after);
+        self.cursor_glyph_idx = cursor;
+        self.selection = None;
+        true
+    }
+
+    fn undo(&mut self) -> bool {
+        let current = self.snapshot();
+        match self.history.undo(current) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn redo(&mut self) -> bool {
+        let current = self.snapshot();
+        match self.history.redo(current) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.buffer.get_untracked(),
+            cursor: self.cursor_glyph_idx,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.cursor_glyph_idx = snapshot.cursor;
+        self.selection = None;
+        self.buffer.set(snapshot.text);
+    }
+
     fn handle_key_down(&mut self, cx: &mut EventCx, event: &KeyEvent) -> bool {
         match event.key.logical_key {
             Key::Character(ref ch) => {
@@ -622,84 +958,52 @@ impl TextInput {
                 if handled_modifier_cmd {
                     return true;
                 }
-
-                let selection = self.selection.clone();
-                if let Some(selection) = selection {
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection.clone(), None));
-                    self.cursor_glyph_idx = selection.start;
-                    self.selection = None;
+                // The IME is composing, and sends the text when it's done
+                if self.preedit.is_some() {
+                    return false;
                 }
 
-                self.buffer
-                    .update(|buf| buf.insert_str(self.cursor_glyph_idx, &ch.clone()));
-                self.move_cursor(Movement::Glyph, Direction::Right)
+                self.edit(self.edit_range(), ch, EditKind::Typing)
             }
             Key::Named(NamedKey::Space) => {
-                if let Some(selection) = &self.selection {
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection.clone(), None));
-                    self.cursor_glyph_idx = selection.start;
-                    self.selection = None;
-                } else {
-                    self.buffer
-                        .update(|buf| buf.insert(self.cursor_glyph_idx, ' '));
+                if self.preedit.is_some() {
+                    return false;
                 }
-                self.move_cursor(Movement::Glyph, Direction::Right)
+                self.edit(self.edit_range(), " ", EditKind::Typing)
             }
             Key::Named(NamedKey::Backspace) => {
-                let selection = self.selection.clone();
-                if let Some(selection) = selection {
-                    self.cursor_glyph_idx = selection.start;
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection, None));
-                    self.selection = None;
-                    true
-                } else {
-                    let prev_cursor_idx = self.cursor_glyph_idx;
-
-                    self.move_cursor(
-                        get_word_based_motion(event).unwrap_or(Movement::Glyph),
-                        Direction::Left,
-                    );
-
-                    if self.cursor_glyph_idx == prev_cursor_idx {
-                        return false;
-                    }
+                if let Some(selection) = self.selection.clone() {
+                    return self.edit(selection, "", EditKind::Deleting);
+                }
 
-                    self.buffer.update(|buf| {
-                        replace_range(buf, self.cursor_glyph_idx..prev_cursor_idx, None);
-                    });
-                    true
+                let prev_cursor_idx = self.cursor_glyph_idx;
+                self.move_cursor(
+                    get_word_based_motion(event).unwrap_or(Movement::Glyph),
+                    Direction::Left,
+                );
+                let start = std::mem::replace(&mut self.cursor_glyph_idx, prev_cursor_idx);
+                if start == prev_cursor_idx {
+                    return false;
                 }
+
+                self.edit(start..prev_cursor_idx, "", EditKind::Deleting)
             }
             Key::Named(NamedKey::Delete) => {
-                let selection = self.selection.clone();
-                if let Some(selection) = selection {
-                    self.cursor_glyph_idx = selection.start;
-                    self.buffer
-                        .update(|buf| replace_range(buf, selection, None));
-                    self.selection = None;
-                    return true;
+                if let Some(selection) = self.selection.clone() {
+                    return self.edit(selection, "", EditKind::Deleting);
                 }
 
                 let prev_cursor_idx = self.cursor_glyph_idx;
-
                 self.move_cursor(
                     get_word_based_motion(event).unwrap_or(Movement::Glyph),
                     Direction::Right,
                 );
-
-                if self.cursor_glyph_idx == prev_cursor_idx {
+                let end = std::mem::replace(&mut self.cursor_glyph_idx, prev_cursor_idx);
+                if end == prev_cursor_idx {
                     return false;
                 }
 
-                self.buffer.update(|buf| {
-                    replace_range(buf, prev_cursor_idx..self.cursor_glyph_idx, None);
-                });
-
-                self.cursor_glyph_idx = prev_cursor_idx;
-                true
+                self.edit(prev_cursor_idx..end, "", EditKind::Deleting)
             }
             Key::Named(NamedKey::Escape) => {
                 cx.app_state.clear_focus();
@@ -837,6 +1141,27 @@ impl TextInput {
         cx.draw_text(placeholder_buff, text_start_point);
     }
 
+    fn paint_preedit_underline(
+        &self,
+        preedit: &Preedit,
+        &node_layout: &Layout,
+        cx: &mut crate::context::PaintCx<'_>,
+    ) {
+        let text_buf = self.text_buf.as_ref().unwrap();
+        let start = self.cursor_glyph_idx;
+        let end = start + preedit.text.len();
+        let x_of = |idx: usize| {
+            text_buf.hit_position(idx).point.x - self.clip_start_x + node_layout.location.x as f64
+        };
+        let y = node_layout.location.y as f64 + self.height as f64;
+        let color = self.style.color().map(|brush| brush.to_color());
+        cx.stroke(
+            &Line::new(Point::new(x_of(start), y), Point::new(x_of(end), y)),
+            color.unwrap_or(Color::BLACK),
+            1.0,
+        );
+    }
+
     fn paint_selection_rect(&self, &node_layout: &Layout, cx: &mut crate::context::PaintCx<'_>) {
         let style = cx.app_state.get_computed_style(self.id());
         let cursor_color = style.get(CursorColor);
@@ -952,17 +1277,38 @@ impl Widget for TextInput {
     }
 
     fn debug_name(&self) -> std::borrow::Cow<'static, str> {
-        format!("TextInput: {:?}", self.buffer.get_untracked()).into()
+        match self.obscure {
+            Some(_) => "TextInput: <obscured>".into(),
+            None => format!("TextInput: {:?}", self.buffer.get_untracked()).into(),
+        }
     }
 
     fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn Any>) {
         if let Ok(state) = state.downcast::<(String, bool)>() {
-            let (_, is_focused) = *state;
-            if is_focused {
-                self.cursor_glyph_idx = self.buffer.with_untracked(|buff| buff.len());
+            let (text, is_focused) = *state;
+            if is_focused && !self.has_focus {
+                self.cursor_glyph_idx = text.len();
+            }
+            self.has_focus = is_focused;
+            self.history.sync(&text);
+
+            // Obscured text shouldn't be seen by the IME
+            let ime_allowed = is_focused && self.obscure.is_none();
+            if ime_allowed != self.ime_allowed {
+                self.ime_allowed = ime_allowed;
+                self.ime_cursor_area = None;
+                set_ime_allowed(ime_allowed);
+            }
+            if !ime_allowed {
+                self.preedit = None;
+            }
+
+            if let Some(validator) = &self.validator {
+                cx.app_state.set_invalid(self.id(), !validator(&text));
             }
 
             self.is_focused = is_focused;
+            self.update_accessibility();
             cx.request_layout(self.id());
         } else {
             eprintln!("downcast failed");
@@ -1010,12 +1356,24 @@ impl Widget for TextInput {
                 false
             }
             Event::KeyDown(event) => self.handle_key_down(cx, event),
+            Event::ImePreedit { text, cursor } if self.ime_allowed => {
+                self.preedit = (!text.is_empty()).then(|| Preedit {
+                    text: text.clone(),
+                    cursor: *cursor,
+                });
+                true
+            }
+            Event::ImeCommit(text) if self.ime_allowed => {
+                self.preedit = None;
+                self.edit(self.edit_range(), text, EditKind::Other);
+                true
+            }
             _ => false,
         };
 
         if is_handled {
             cx.app_state.request_layout(self.id());
-            self.last_cursor_action_on = Instant::now();
+            self.last_cursor_action_on = clock::now();
         }
 
         EventPropagation::Continue
@@ -1134,10 +1492,23 @@ impl Widget for TextInput {
                 .text_buf
                 .as_ref()
                 .unwrap()
-                .hit_position(self.cursor_glyph_idx);
+                .hit_position(self.display_cursor());
             self.cursor_x = hit_pos.point.x;
         }
 
+        if self.ime_allowed {
+            // Tell the IME where the cursor is, so it can show its candidates next to it
+            let position = cx.window_origin
+                + Vec2::new(
+                    node_layout.location.x as f64 + self.cursor_x,
+                    node_layout.location.y as f64,
+                );
+            if self.ime_cursor_area != Some(position) {
+                self.ime_cursor_area = Some(position);
+                set_ime_cursor_area(position, Size::new(self.cursor_width, self.height as f64));
+            }
+        }
+
         None
     }
 
@@ -1157,18 +1528,33 @@ impl Widget for TextInput {
         let location = node_layout.location;
         let text_start_point = Point::new(location.x as f64, location.y as f64);
 
-        if let Some(clip_txt) = self.clip_txt_buf.as_mut() {
-            cx.draw_text(
+        let (text_layout, point) = if let Some(clip_txt) = self.clip_txt_buf.as_ref() {
+            (
                 clip_txt,
                 Point::new(text_start_point.x - self.clip_offset_x, text_start_point.y),
-            );
+            )
         } else {
-            cx.draw_text(self.text_buf.as_ref().unwrap(), text_start_point);
+            (self.text_buf.as_ref().unwrap(), text_start_point)
+        };
+        match self.style.color() {
+            Some(brush) if brush.is_gradient() => {
+                let rect = Rect::from_origin_size(
+                    text_start_point,
+                    (
+                        node_layout.size.width as f64,
+                        node_layout.size.height as f64,
+                    ),
+                );
+                cx.draw_text_with_brush(text_layout, point, &brush.resolve(rect));
+            }
+            _ => cx.draw_text(text_layout, point),
         }
 
         let is_cursor_visible = cx.app_state.is_focused(&self.id())
             && self.selection.is_none()
-            && (self.last_cursor_action_on.elapsed().as_millis()
+            && (clock::now()
+                .saturating_duration_since(self.last_cursor_action_on)
+                .as_millis()
                 / CURSOR_BLINK_INTERVAL_MS as u128)
                 % 2
                 == 0;
@@ -1187,6 +1573,10 @@ impl Widget for TextInput {
             self.paint_selection_rect(&node_layout, cx);
         }
 
+        if let Some(preedit) = self.preedit.as_ref().filter(|_| self.obscure.is_none()) {
+            self.paint_preedit_underline(preedit, &node_layout, cx);
+        }
+
         let id = self.id();
         exec_after(
             Duration::from_millis(CURSOR_BLINK_INTERVAL_MS),
@@ -1199,9 +1589,11 @@ impl Widget for TextInput {
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use crate::views::text_input::get_dbl_click_selection;
 
-    use super::replace_range;
+    use super::{replace_range, EditHistory, EditKind, InputMask, Snapshot};
 
     #[test]
     fn replace_range_start() {
@@ -1373,4 +1765,98 @@ mod tests {
 
         assert_eq!(range, 0..s.len());
     }
+
+    fn type_text(history: &mut EditHistory, text: &mut String, typed: &str, now: Instant) {
+        for ch in typed.chars() {
+            let before = Snapshot {
+                text: text.clone(),
+                cursor: text.len(),
+            };
+            text.push(ch);
+            history.record(before, text, EditKind::Typing, text.len(), now);
+        }
+    }
+
+    #[test]
+    fn undo_groups_typing() {
+        let mut history = EditHistory::default();
+        let mut text = String::new();
+        let now = Instant::now();
+        type_text(&mut history, &mut text, "hello", now);
+        // A pause starts a new group
+        type_text(
+            &mut history,
+            &mut text,
+            " world",
+            now + Duration::from_secs(2),
+        );
+
+        let current = Snapshot {
+            text: text.clone(),
+            cursor: text.len(),
+        };
+        let undone = history.undo(current.clone()).unwrap();
+        assert_eq!(undone.text, "hello");
+        let undone = history.undo(undone).unwrap();
+        assert_eq!(undone.text, "");
+        assert!(history.undo(undone.clone()).is_none());
+
+        let redone = history.redo(undone).unwrap();
+        assert_eq!(redone.text, "hello");
+        assert_eq!(history.redo(redone).unwrap(), current);
+    }
+
+    #[test]
+    fn undo_doesnt_group_different_edits() {
+        let mut history = EditHistory::default();
+        let mut text = String::new();
+        let now = Instant::now();
+        type_text(&mut history, &mut text, "ab", now);
+        history.record(
+            Snapshot {
+                text: text.clone(),
+                cursor: 2,
+            },
+            "a",
+            EditKind::Deleting,
+            1,
+            now,
+        );
+
+        let undone = history
+            .undo(Snapshot {
+                text: "a".to_owned(),
+                cursor: 1,
+            })
+            .unwrap();
+        assert_eq!(undone.text, "ab");
+        // A new edit clears what could be redone
+        type_text(&mut history, &mut text, "c", now);
+        assert!(history.redo(undone).is_none());
+    }
+
+    #[test]
+    fn history_is_cleared_by_outside_changes() {
+        let mut history = EditHistory::default();
+        let mut text = String::new();
+        type_text(&mut history, &mut text, "abc", Instant::now());
+        history.sync("abc");
+        assert!(!history.undo.is_empty());
+        history.sync("replaced");
+        assert!(history.undo.is_empty());
+    }
+
+    #[test]
+    fn input_masks() {
+        let numeric = InputMask::numeric();
+        assert!(numeric.accepts(""));
+        assert!(numeric.accepts("0123"));
+        assert!(!numeric.accepts("12a"));
+
+        let decimal = InputMask::decimal();
+        assert!(decimal.accepts("-"));
+        assert!(decimal.accepts("-12.5"));
+        assert!(!decimal.accepts("1.2.3"));
+        assert!(!decimal.accepts("1-2"));
+    }
 }
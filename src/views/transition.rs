@@ -0,0 +1,508 @@
+use std::{collections::HashMap, time::Duration, time::Instant};
+
+use floem_reactive::Scope;
+use kurbo::{Affine, Size, Vec2};
+use taffy::prelude::{Layout, NodeId};
+
+use crate::{
+    action::exec_after,
+    animate::{Easing, EasingFn, EasingMode},
+    clock,
+    context::{AppState, LayoutCx, PaintCx, StyleCx},
+    id::Id,
+    style::{Height, InsetLeft, InsetTop, MarginLeft, MarginTop, PositionProp, Width},
+    unit::PxPctAuto,
+    view::{AnyWidget, Widget},
+};
+
+/// How a child of a [`dyn_container`](super::dyn_container), [`tab`](super::tab) or
+/// [`dyn_stack`](super::dyn_stack) appears or disappears.
+///
+/// The fade, slide and scale describe the child when it's hidden, and it's animated between that
+/// and how it's normally shown.
+///
+/// ## Example
+/// ```
+/// use floem::views::ViewTransition;
+///
+/// // Fade in while sliding up from 20px below
+/// let enter = ViewTransition::new().fade().slide(0.0, 20.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ViewTransition {
+    pub(crate) duration: Duration,
+    pub(crate) easing: Easing,
+    pub(crate) opacity: Option<f64>,
+    pub(crate) translate: Option<Vec2>,
+    pub(crate) scale: Option<f64>,
+}
+
+impl Default for ViewTransition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ViewTransition {
+    /// A transition of 200ms which doesn't change the child until a fade, slide or scale is added
+    pub fn new() -> Self {
+        Self {
+            duration: Duration::from_millis(200),
+            easing: Easing {
+                mode: EasingMode::Out,
+                func: EasingFn::Cubic,
+            },
+            opacity: None,
+            translate: None,
+            scale: None,
+        }
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn easing_fn(mut self, easing_fn: EasingFn) -> Self {
+        self.easing.func = easing_fn;
+        self
+    }
+
+    pub fn ease_mode(mut self, mode: EasingMode) -> Self {
+        self.easing.mode = mode;
+        self
+    }
+
+    /// Fade the child from fully transparent
+    pub fn fade(self) -> Self {
+        self.fade_from(0.0)
+    }
+
+    /// Fade the child from `opacity`
+    pub fn fade_from(mut self, opacity: f64) -> Self {
+        self.opacity = Some(opacity);
+        self
+    }
+
+    /// Slide the child from an offset of `x` and `y` from its position
+    pub fn slide(mut self, x: f64, y: f64) -> Self {
+        self.translate = Some(Vec2::new(x, y));
+        self
+    }
+
+    /// Scale the child around its center from `scale`
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// The transform and opacity of a child of `size` which is `presence` of the way from hidden
+    /// to shown
+    fn apply(&self, presence: f64, size: Size) -> (Affine, f64) {
+        let shown = self.easing.ease(presence.clamp(0.0, 1.0));
+        let hidden = 1.0 - shown;
+
+        let opacity = self
+            .opacity
+            .map(|opacity| opacity * hidden + shown)
+            .unwrap_or(1.0);
+
+        let mut transform = Affine::IDENTITY;
+        if let Some(translate) = self.translate {
+            transform = Affine::translate(translate * hidden);
+        }
+        if let Some(scale) = self.scale {
+            let center = size.to_vec2() / 2.0;
+            transform = transform
+                * Affine::translate(center)
+                * Affine::scale(scale * hidden + shown)
+                * Affine::translate(-center);
+        }
+        (transform, opacity)
+    }
+}
+
+/// How far a child is between hidden (0.0) and shown (1.0), and where it's heading
+#[derive(Debug, Clone, Copy)]
+struct Presence {
+    from: f64,
+    to: f64,
+    start: Instant,
+}
+
+impl Presence {
+    /// The progress from `from` to `to`, between 0.0 and 1.0
+    fn progress(&self, duration: Duration, now: Instant) -> f64 {
+        // Moving only part of the way, when it was interrupted, takes that part of the duration
+        let duration = duration.as_secs_f64() * (self.to - self.from).abs();
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f64();
+        if elapsed >= duration {
+            1.0
+        } else {
+            elapsed / duration
+        }
+    }
+
+    fn value(&self, duration: Duration, now: Instant) -> f64 {
+        self.from + (self.to - self.from) * self.progress(duration, now)
+    }
+
+    fn is_done(&self, duration: Duration, now: Instant) -> bool {
+        self.progress(duration, now) >= 1.0
+    }
+
+    fn is_entering(&self) -> bool {
+        self.to >= 1.0
+    }
+}
+
+/// The enter and exit transitions of the children of a view, which keeps the children that are
+/// exiting alive until their transition is finished.
+#[derive(Default)]
+pub(crate) struct Transitions {
+    pub(crate) enter: Option<ViewTransition>,
+    pub(crate) exit: Option<ViewTransition>,
+    presences: HashMap<Id, Presence>,
+    /// Children which have been removed from the view and are only painted
+    exiting: Vec<Exiting>,
+}
+
+/// A child which has been removed from its view and is running its exit transition
+struct Exiting {
+    child: AnyWidget,
+    scope: Scope,
+    /// Where the child was laid out when it was removed
+    layout: Option<Layout>,
+}
+
+impl Transitions {
+    fn transition(&self, presence: &Presence) -> Option<&ViewTransition> {
+        if presence.is_entering() {
+            self.enter.as_ref()
+        } else {
+            self.exit.as_ref()
+        }
+    }
+
+    fn current(&self, id: Id, now: Instant) -> Option<f64> {
+        let presence = self.presences.get(&id)?;
+        let transition = self.transition(presence)?;
+        Some(presence.value(transition.duration, now))
+    }
+
+    fn start(&mut self, id: Id, default_from: f64, to: f64) {
+        let now = clock::now();
+        // An interrupted transition continues from where it got to
+        let from = self.current(id, now).unwrap_or(default_from);
+        self.presences.insert(
+            id,
+            Presence {
+                from,
+                to,
+                start: now,
+            },
+        );
+    }
+
+    /// Start the enter transition of a child
+    pub(crate) fn enter(&mut self, id: Id) {
+        if self.enter.is_some() {
+            self.start(id, 0.0, 1.0);
+        } else {
+            self.presences.remove(&id);
+        }
+    }
+
+    /// Start the exit transition of a child which stays in the view. Returns false if there is no
+    /// exit transition.
+    pub(crate) fn exit(&mut self, id: Id) -> bool {
+        if self.exit.is_some() {
+            self.start(id, 1.0, 0.0);
+            true
+        } else {
+            self.presences.remove(&id);
+            false
+        }
+    }
+
+    /// Whether a child is running its exit transition
+    pub(crate) fn is_exiting(&self, id: Id) -> bool {
+        self.presences
+            .get(&id)
+            .map(|presence| !presence.is_entering())
+            .unwrap_or(false)
+    }
+
+    /// A child has been removed from the view, so keep it until its exit transition is finished
+    pub(crate) fn exit_child(&mut self, app_state: &mut AppState, child: AnyWidget, scope: Scope) {
+        let id = child.view_data().id();
+        if self.exit(id) {
+            self.exiting.push(Exiting {
+                child,
+                scope,
+                layout: app_state.get_layout(id),
+            });
+        } else {
+            self.remove_child(app_state, child, scope);
+        }
+    }
+
+    /// Remove a child straight away
+    pub(crate) fn remove_child(
+        &mut self,
+        app_state: &mut AppState,
+        mut child: AnyWidget,
+        scope: Scope,
+    ) {
+        self.presences.remove(&child.view_data().id());
+        app_state.remove_view(&mut child);
+        scope.dispose();
+    }
+
+    /// Paint a child of the view, with its transition if it has one
+    pub(crate) fn paint_child(&self, cx: &mut PaintCx, child: &mut dyn Widget) {
+        let id = child.view_data().id();
+        let now = clock::now();
+        let transition = self
+            .presences
+            .get(&id)
+            .and_then(|presence| Some((presence, self.transition(presence)?)));
+        match transition {
+            Some((presence, transition)) => {
+                let size = cx
+                    .get_layout(id)
+                    .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+                    .unwrap_or_default();
+                let (transform, opacity) =
+                    transition.apply(presence.value(transition.duration, now), size);
+                cx.paint_view_with(child, transform, opacity);
+            }
+            None => cx.paint_view(child),
+        }
+    }
+
+    /// Style the children which have been removed but are still running their exit transition,
+    /// keeping them where they were in `parent`.
+    pub(crate) fn style_exiting(&mut self, cx: &mut StyleCx, parent: Id) {
+        for exiting in &mut self.exiting {
+            cx.style_view(&mut exiting.child);
+            if let Some(layout) = exiting.layout {
+                keep_in_place(cx, parent, exiting.child.view_data().id(), layout);
+            }
+        }
+    }
+
+    /// Lay out the children which have been removed. They're positioned absolutely by
+    /// [`style_exiting`](Self::style_exiting), so they don't take up space.
+    pub(crate) fn layout_exiting(&mut self, cx: &mut LayoutCx) -> Vec<NodeId> {
+        self.exiting
+            .iter_mut()
+            .map(|exiting| cx.layout_view(&mut exiting.child))
+            .collect()
+    }
+
+    /// Paint the children which have been removed but are still running their exit transition
+    pub(crate) fn paint_exiting(&mut self, cx: &mut PaintCx) {
+        let mut exiting = std::mem::take(&mut self.exiting);
+        for exiting in &mut exiting {
+            self.paint_child(cx, &mut exiting.child);
+        }
+        self.exiting = exiting;
+    }
+
+    /// Drop the transitions which are finished, removing the children which have exited. While
+    /// any are still running the view `id` is repainted.
+    ///
+    /// Returns true if a child which is still in the view finished exiting.
+    pub(crate) fn finish(&mut self, cx: &mut PaintCx, id: Id) -> bool {
+        let now = clock::now();
+        let mut exited = Vec::new();
+        let (enter, exit) = (
+            self.enter.as_ref().map(|t| t.duration),
+            self.exit.as_ref().map(|t| t.duration),
+        );
+        self.presences.retain(|child, presence| {
+            let duration = if presence.is_entering() { enter } else { exit };
+            let done = duration
+                .map(|duration| presence.is_done(duration, now))
+                .unwrap_or(true);
+            if done && !presence.is_entering() {
+                exited.push(*child);
+            }
+            !done
+        });
+
+        let mut exited_in_view = false;
+        for exited_id in exited {
+            if let Some(index) = self
+                .exiting
+                .iter()
+                .position(|exiting| exiting.child.view_data().id() == exited_id)
+            {
+                let mut exiting = self.exiting.remove(index);
+                cx.app_state.remove_view(&mut exiting.child);
+                exiting.scope.dispose();
+                cx.app_state.schedule_layout(id);
+            } else {
+                exited_in_view = true;
+            }
+        }
+
+        if !self.presences.is_empty() {
+            exec_after(Duration::from_millis(8), move |_| {
+                id.request_paint();
+            });
+        }
+        exited_in_view
+    }
+}
+
+/// Position the child `id` of `parent` absolutely at `layout`, which it had while it was laid out
+/// normally, so it stays where it was without taking up space.
+pub(crate) fn keep_in_place(cx: &mut StyleCx, parent: Id, id: Id, layout: Layout) {
+    let border = {
+        let props = &cx.app_state_mut().view_state(parent).layout_props;
+        (props.border_left().0, props.border_top().0)
+    };
+    let child_view = cx.app_state_mut().view_state(id);
+    child_view.combined_style = child_view
+        .combined_style
+        .clone()
+        .set(PositionProp, taffy::style::Position::Absolute)
+        .set(
+            InsetLeft,
+            PxPctAuto::Px(layout.location.x as f64 - border.0),
+        )
+        .set(InsetTop, PxPctAuto::Px(layout.location.y as f64 - border.1))
+        .set(MarginLeft, PxPctAuto::Px(0.0))
+        .set(MarginTop, PxPctAuto::Px(0.0))
+        .set(Width, PxPctAuto::Px(layout.size.width as f64))
+        .set(Height, PxPctAuto::Px(layout.size.height as f64));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
+    use floem_peniko::Color;
+    use floem_reactive::{create_rw_signal, RwSignal};
+
+    use super::{Transitions, ViewTransition};
+    use crate::{
+        animate::EasingFn,
+        clock,
+        headless::HeadlessWindow,
+        id::Id,
+        views::{dyn_stack, empty, Decorators},
+    };
+
+    fn fade() -> ViewTransition {
+        ViewTransition::new()
+            .fade()
+            .easing_fn(EasingFn::Linear)
+            .duration(Duration::from_millis(100))
+    }
+
+    fn approx_eq(value: Option<f64>, expected: f64) -> bool {
+        value.is_some_and(|value| (value - expected).abs() < 1e-9)
+    }
+
+    #[test]
+    fn interrupted_transitions_continue_from_where_they_got_to() {
+        let start = Instant::now();
+        clock::set_fake_now(Some(start));
+        let mut transitions = Transitions {
+            enter: Some(fade()),
+            exit: Some(fade()),
+            ..Default::default()
+        };
+        let id = Id::next();
+
+        transitions.enter(id);
+        assert!(approx_eq(transitions.current(id, start), 0.0));
+        let half = start + Duration::from_millis(50);
+        assert!(approx_eq(transitions.current(id, half), 0.5));
+
+        // Exiting halfway through entering only has half of the way to go, so it takes half of
+        // the duration
+        clock::set_fake_now(Some(half));
+        assert!(transitions.exit(id));
+        assert!(transitions.is_exiting(id));
+        assert!(approx_eq(transitions.current(id, half), 0.5));
+        let later = half + Duration::from_millis(25);
+        assert!(approx_eq(transitions.current(id, later), 0.25));
+        let presence = transitions.presences[&id];
+        assert!(!presence.is_done(Duration::from_millis(100), later));
+        assert!(presence.is_done(Duration::from_millis(100), half + Duration::from_millis(60)));
+
+        // Without an exit transition the child is removed straight away
+        transitions.exit = None;
+        assert!(!transitions.exit(id));
+        assert!(!transitions.is_exiting(id));
+        clock::set_fake_now(None);
+    }
+
+    #[test]
+    fn children_fade_in_and_out_in_place() {
+        let items = create_rw_signal(vec![1]);
+        let second_signal: Rc<Cell<Option<RwSignal<()>>>> = Rc::new(Cell::new(None));
+        let mut window = HeadlessWindow::new_unthemed(
+            {
+                let second_signal = second_signal.clone();
+                move || {
+                    dyn_stack(
+                        move || items.get(),
+                        |item| *item,
+                        move |item| {
+                            if item == 2 {
+                                // Created in the scope of the child, to check it's disposed
+                                second_signal.set(Some(create_rw_signal(())));
+                            }
+                            empty().style(|s| s.size(20.0, 20.0).background(Color::RED))
+                        },
+                    )
+                    .transition(fade())
+                    .style(|s| s.flex_col().size_full().background(Color::WHITE))
+                }
+            },
+            (20.0, 40.0),
+            1.0,
+        );
+        // The green channel is 0 where a child is fully shown and 255 where there is none
+        let green = |window: &mut HeadlessWindow, y: u32| {
+            window.render().into_rgba8().get_pixel(10, y).0[1]
+        };
+
+        // The first children appear straight away
+        assert_eq!(green(&mut window, 10), 0);
+        assert_eq!(green(&mut window, 30), 255);
+
+        items.set(vec![1, 2]);
+        window.process_update();
+        window.advance(Duration::from_millis(50));
+        let g = green(&mut window, 30);
+        assert!((100..=160).contains(&g), "{g}");
+        window.advance(Duration::from_millis(60));
+        assert_eq!(green(&mut window, 30), 0);
+
+        // The removed child fades out where it was, without moving the others
+        items.set(vec![1]);
+        window.process_update();
+        window.advance(Duration::from_millis(50));
+        let g = green(&mut window, 30);
+        assert!((100..=160).contains(&g), "{g}");
+        assert_eq!(green(&mut window, 10), 0);
+        let signal = second_signal.get().unwrap();
+        assert!(signal.try_get_untracked().is_some());
+
+        // Its scope is disposed once it's gone
+        window.advance(Duration::from_millis(60));
+        assert_eq!(green(&mut window, 30), 255);
+        assert!(signal.try_get_untracked().is_none());
+        assert_eq!(green(&mut window, 10), 0);
+    }
+}
@@ -0,0 +1,1005 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    rc::Rc,
+};
+
+use floem_reactive::{
+    create_effect, create_rw_signal, untrack, with_scope, ReadSignal, Resource, RwSignal, Scope,
+};
+use kurbo::Rect;
+
+use crate::{
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, ModifiersState, NamedKey},
+    style_class,
+    view::{AnyWidget, View, ViewData, Widget},
+    EventPropagation,
+};
+
+use super::{
+    empty, h_stack, h_stack_from_iter, label,
+    row_selection::{is_toggle, Row, RowSelection, RowVersions},
+    scroll, virtual_stack, Decorators, VirtualDirection, VirtualItemSize,
+};
+
+style_class!(pub TreeViewClass);
+style_class!(pub TreeRowClass);
+style_class!(pub TreeGuideClass);
+style_class!(pub TreeDisclosureClass);
+
+/// The children of a node in a [`tree_view`]
+pub enum TreeChildren<T: 'static> {
+    /// The node is a leaf
+    None,
+    /// Children which are known up front
+    Ready(Vec<T>),
+    /// Children which are loaded when the node is expanded. See [`TreeChildren::lazy`] and
+    /// [`TreeChildren::resource`].
+    Lazy(Box<dyn FnOnce() -> ReadSignal<TreeLoadState<T>>>),
+}
+
+impl<T: Clone + 'static> TreeChildren<T> {
+    /// Children which are loaded by calling `load` when the node is expanded. They're kept until
+    /// the node is collapsed, so expanding it again loads them again.
+    pub fn lazy(load: impl FnOnce() -> Vec<T> + 'static) -> Self {
+        Self::Lazy(Box::new(move || {
+            create_rw_signal(TreeLoadState::Ready(load())).read_only()
+        }))
+    }
+
+    /// Children which are loaded asynchronously by the resource created by `load` when the node
+    /// is expanded. The node shows that it's loading until the resource is ready, or that it
+    /// failed if the resource gives an error, in which case collapsing and expanding the node
+    /// loads it again.
+    pub fn resource<E: 'static>(load: impl FnOnce() -> Resource<Vec<T>, E> + 'static) -> Self {
+        Self::Lazy(Box::new(move || {
+            let resource = load();
+            let state = create_rw_signal(TreeLoadState::Loading);
+            create_effect(move |_| {
+                let new_state = match resource.ready().get() {
+                    Some(children) => TreeLoadState::Ready(children),
+                    None if resource.error().with(|error| error.is_some()) => TreeLoadState::Failed,
+                    None => TreeLoadState::Loading,
+                };
+                state.set(new_state);
+            });
+            state.read_only()
+        }))
+    }
+}
+
+/// The state of the children of a node in a [`tree_view`] which are loaded lazily
+#[derive(Clone, Debug, PartialEq)]
+pub enum TreeLoadState<T> {
+    /// The children are being loaded
+    Loading,
+    /// The children have been loaded
+    Ready(Vec<T>),
+    /// Loading the children failed
+    Failed,
+}
+
+/// Where a node dropped on another in a [`TreeView`] goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropPosition {
+    /// Before the target, as its sibling
+    Before,
+    /// After the target, as its sibling
+    After,
+    /// Inside the target, as its child
+    Inside,
+}
+
+/// A visible node of the tree, in the order the nodes are shown
+#[derive(Clone)]
+struct TreeRow<T, K> {
+    item: T,
+    key: K,
+    parent: Option<K>,
+    depth: usize,
+    expandable: bool,
+    expanded: bool,
+    loading: bool,
+    failed: bool,
+    /// Changes when the item changes but its key doesn't
+    version: u64,
+}
+
+/// The lazily loaded children of an expanded node, in a scope which is disposed on collapse
+type LoadedChildren<T> = (Scope, ReadSignal<TreeLoadState<T>>);
+
+/// The rows of the nodes from `roots` down through the `expanded` nodes, in the order they're
+/// shown. Lazy children are loaded into `loaded` in children of `scope`, and the ones of nodes
+/// which are no longer expanded are disposed.
+fn flatten<T, K>(
+    roots: Vec<T>,
+    children: &impl Fn(&T) -> TreeChildren<T>,
+    key_fn: &impl Fn(&T) -> K,
+    expanded: &HashSet<K>,
+    loaded: &mut HashMap<K, LoadedChildren<T>>,
+    versions: &mut RowVersions<K, T>,
+    scope: Scope,
+) -> Vec<TreeRow<T, K>>
+where
+    T: Clone + PartialEq + 'static,
+    K: Clone + Eq + Hash + 'static,
+{
+    let mut flattened = Vec::new();
+    let mut pending: Vec<(T, usize, Option<K>)> = roots
+        .into_iter()
+        .rev()
+        .map(|item| (item, 0, None))
+        .collect();
+    while let Some((item, depth, parent)) = pending.pop() {
+        let key = key_fn(&item);
+        let is_expanded = expanded.contains(&key);
+        let (expandable, item_children, loading, failed) = match children(&item) {
+            TreeChildren::None => (false, None, false, false),
+            TreeChildren::Ready(item_children) => {
+                (!item_children.is_empty(), Some(item_children), false, false)
+            }
+            TreeChildren::Lazy(load) if is_expanded => {
+                let (_, state) = loaded.entry(key.clone()).or_insert_with(|| {
+                    let scope = scope.create_child();
+                    (scope, with_scope(scope, || untrack(load)))
+                });
+                match state.get() {
+                    TreeLoadState::Loading => (true, None, true, false),
+                    TreeLoadState::Ready(item_children) => {
+                        (!item_children.is_empty(), Some(item_children), false, false)
+                    }
+                    // Stay expandable so collapsing and expanding it loads it again
+                    TreeLoadState::Failed => (true, None, false, true),
+                }
+            }
+            TreeChildren::Lazy(_) => (true, None, false, false),
+        };
+        if is_expanded {
+            if let Some(item_children) = item_children {
+                pending.extend(
+                    item_children
+                        .into_iter()
+                        .rev()
+                        .map(|child| (child, depth + 1, Some(key.clone()))),
+                );
+            }
+        }
+        let version = versions.version(&key, &item);
+        flattened.push(TreeRow {
+            item,
+            key,
+            parent,
+            depth,
+            expandable,
+            expanded: is_expanded && expandable,
+            loading,
+            failed,
+            version,
+        });
+    }
+
+    versions.retain(&flattened.iter().map(|row| &row.key).collect());
+
+    loaded.retain(|key, (scope, _)| {
+        let keep = expanded.contains(key);
+        if !keep {
+            scope.dispose();
+        }
+        keep
+    });
+
+    flattened
+}
+
+/// Whether `ancestor` is `key` or one of its ancestors in `rows`
+fn is_ancestor<T: Clone, K: Clone + PartialEq>(
+    rows: &im::Vector<TreeRow<T, K>>,
+    ancestor: &K,
+    key: &K,
+) -> bool {
+    let mut current = Some(key.clone());
+    while let Some(key) = current {
+        if key == *ancestor {
+            return true;
+        }
+        current = rows
+            .iter()
+            .find(|row| row.key == key)
+            .and_then(|row| row.parent.clone());
+    }
+    false
+}
+
+/// Where a node dropped at `y` in a row of `row_height` goes. The top and bottom quarters of the
+/// row drop next to it, and the middle drops into it if it can have children.
+fn drop_position(y: f64, row_height: f64, expandable: bool) -> DropPosition {
+    if !expandable {
+        if y < row_height / 2.0 {
+            DropPosition::Before
+        } else {
+            DropPosition::After
+        }
+    } else if y < row_height / 4.0 {
+        DropPosition::Before
+    } else if y > row_height * 3.0 / 4.0 {
+        DropPosition::After
+    } else {
+        DropPosition::Inside
+    }
+}
+
+enum TreeUpdate {
+    SelectionChanged,
+}
+
+type MoveFn<K> = dyn Fn(&K, &K, DropPosition);
+
+/// A tree of expandable nodes. See [`tree_view`].
+pub struct TreeView<K: 'static> {
+    data: ViewData,
+    selection: RwSignal<RowSelection<K>>,
+    expanded: RwSignal<HashSet<K>>,
+    multi_select: RwSignal<bool>,
+    indent: RwSignal<f64>,
+    on_move: Rc<RefCell<Option<Box<MoveFn<K>>>>>,
+    child: AnyWidget,
+}
+
+impl<K: Clone + Eq + Hash + 'static> TreeView<K> {
+    /// The keys of the expanded nodes, which can be set to expand or collapse nodes
+    pub fn expanded(&self) -> RwSignal<HashSet<K>> {
+        self.expanded
+    }
+
+    /// Allow selecting several nodes with shift-click, ctrl-click (cmd-click on macOS) and
+    /// shift with the arrow keys
+    pub fn multi_select(self, multi_select: bool) -> Self {
+        self.multi_select.set(multi_select);
+        self
+    }
+
+    /// How far each level of the tree is indented. Defaults to 16.
+    pub fn indent(self, indent: f64) -> Self {
+        self.indent.set(indent);
+        self
+    }
+
+    /// Called with the keys of the selected nodes when the selection changes
+    pub fn on_select(self, on_select: impl Fn(Vec<K>) + 'static) -> Self {
+        let selection = self.selection;
+        create_effect(move |_| {
+            let selected = selection.with(|selection| selection.selected.clone());
+            on_select(selected);
+        });
+        self
+    }
+
+    /// Allow dragging nodes onto others. `on_move` is called with the key of the dragged node,
+    /// the key of the node it's dropped on and where it goes, and should move the node in the
+    /// data of the tree. Nodes can't be dropped into themselves.
+    pub fn on_move(self, on_move: impl Fn(&K, &K, DropPosition) + 'static) -> Self {
+        *self.on_move.borrow_mut() = Some(Box::new(on_move));
+        self
+    }
+}
+
+/// A tree of nodes which can be expanded and collapsed, such as the files in a file browser.
+///
+/// `roots` gives the nodes at the top of the tree, and `children` gives the children of each
+/// node, which can be loaded lazily when the node is expanded. Nodes are identified by the key from
+/// `key_fn`, which keeps them expanded and selected when the data is changed, and the row of a
+/// node is rebuilt when its item changes. Like a [`virtual_stack`](super::virtual_stack()), only
+/// the visible rows are built, and every row has the same `row_height`.
+///
+/// Nodes are selected by clicking them or with the arrow, page up and down, home and end keys.
+/// The right and left arrows expand and collapse nodes, and move to the first child or the parent.
+///
+/// ## Example
+/// ```
+/// use floem::{reactive::*, views::*};
+///
+/// #[derive(Clone, PartialEq)]
+/// struct Entry {
+///     path: String,
+///     is_dir: bool,
+/// }
+///
+/// fn read_dir(path: &str) -> Vec<Entry> {
+///     vec![Entry {
+///         path: format!("{path}/file.txt"),
+///         is_dir: false,
+///     }]
+/// }
+///
+/// tree_view(
+///     22.0,
+///     || {
+///         vec![Entry {
+///             path: "src".to_string(),
+///             is_dir: true,
+///         }]
+///     },
+///     |entry| {
+///         if entry.is_dir {
+///             let path = entry.path.clone();
+///             TreeChildren::lazy(move || read_dir(&path))
+///         } else {
+///             TreeChildren::None
+///         }
+///     },
+///     |entry| entry.path.clone(),
+///     |entry| text(&entry.path),
+/// )
+/// .multi_select(true)
+/// .on_select(|paths| println!("selected {paths:?}"));
+/// ```
+pub fn tree_view<T, K, RF, CF, KF, VF, V>(
+    row_height: f64,
+    roots: RF,
+    children: CF,
+    key_fn: KF,
+    view_fn: VF,
+) -> TreeView<K>
+where
+    T: Clone + PartialEq + 'static,
+    K: Clone + Eq + Hash + 'static,
+    RF: Fn() -> Vec<T> + 'static,
+    CF: Fn(&T) -> TreeChildren<T> + 'static,
+    KF: Fn(&T) -> K + 'static,
+    VF: Fn(&T) -> V + 'static,
+    V: View + 'static,
+{
+    let id = Id::next();
+    let scope = Scope::current();
+    let expanded = create_rw_signal(HashSet::new());
+    let selection = create_rw_signal(RowSelection::default());
+    let multi_select = create_rw_signal(false);
+    let indent = create_rw_signal(16.0);
+    let rows = create_rw_signal(im::Vector::new());
+    let viewport = create_rw_signal(Rect::ZERO);
+    let dragging = create_rw_signal(None::<K>);
+    let drop_target = create_rw_signal(None::<(K, DropPosition)>);
+    let on_move: Rc<RefCell<Option<Box<MoveFn<K>>>>> = Rc::new(RefCell::new(None));
+    let loaded: RefCell<HashMap<K, LoadedChildren<T>>> = RefCell::new(HashMap::new());
+    let versions = RefCell::new(RowVersions::new());
+
+    create_effect(move |_| {
+        selection.track();
+        id.update_state(TreeUpdate::SelectionChanged);
+    });
+
+    // Flatten the expanded part of the tree into rows
+    create_effect(move |_| {
+        let expanded_keys = expanded.get();
+        let flattened = flatten(
+            roots(),
+            &children,
+            &key_fn,
+            &expanded_keys,
+            &mut loaded.borrow_mut(),
+            &mut versions.borrow_mut(),
+            scope,
+        );
+
+        let visible: HashSet<&K> = flattened.iter().map(|row| &row.key).collect();
+        if selection.with_untracked(|selection| {
+            selection
+                .selected
+                .iter()
+                .chain(&selection.anchor)
+                .chain(&selection.cursor)
+                .any(|key| !visible.contains(&key))
+        }) {
+            selection.update(|selection| selection.retain(|key| visible.contains(&key)));
+        }
+        rows.set(flattened.into_iter().collect::<im::Vector<_>>());
+    });
+
+    let toggle = move |key: &K| {
+        expanded.update(|expanded| {
+            if !expanded.remove(key) {
+                expanded.insert(key.clone());
+            }
+        });
+    };
+
+    let row_view = {
+        let on_move = on_move.clone();
+        move |row: TreeRow<T, K>| {
+            let TreeRow {
+                item,
+                key,
+                depth,
+                expandable,
+                loading,
+                failed,
+                ..
+            } = row;
+            let guides = h_stack_from_iter((0..depth).map(|_| {
+                empty().class(TreeGuideClass).style(move |s| {
+                    let indent = indent.get();
+                    s.margin_left(indent / 2.0)
+                        .width(indent / 2.0)
+                        .height_full()
+                })
+            }))
+            .style(|s| s.height_full());
+            let disclosure = {
+                let key = key.clone();
+                label(move || {
+                    if !expandable {
+                        ""
+                    } else if loading {
+                        "…"
+                    } else if failed {
+                        "!"
+                    } else if expanded.with(|expanded| expanded.contains(&key)) {
+                        "▾"
+                    } else {
+                        "▸"
+                    }
+                })
+            };
+            let disclosure = {
+                let key = key.clone();
+                disclosure
+                    .class(TreeDisclosureClass)
+                    .on_click_stop(move |_| {
+                        if expandable {
+                            toggle(&key);
+                        }
+                    })
+                    .style(move |s| s.width(indent.get()).flex_shrink(0.0).justify_center())
+            };
+
+            // The class is on the content rather than the row, which styles it as selected
+            let child = h_stack((guides, disclosure, view_fn(&item)))
+                .class(TreeRowClass)
+                .style(|s| s.size_full().items_center())
+                .build();
+            let row = Row {
+                data: ViewData::new(Id::next()),
+                row: key.clone(),
+                selection,
+                child,
+            }
+            .on_click_stop({
+                let key = key.clone();
+                move |event| {
+                    let Event::PointerUp(event) = event else {
+                        return;
+                    };
+                    let multi_select = multi_select.get_untracked();
+                    let extend = multi_select && event.modifiers.contains(ModifiersState::SHIFT);
+                    let toggle = multi_select && is_toggle(event.modifiers);
+                    let order: Vec<K> = rows
+                        .with_untracked(|rows| rows.iter().map(|row| row.key.clone()).collect());
+                    selection
+                        .update(|selection| selection.select(&order, key.clone(), extend, toggle));
+                }
+            })
+            .on_double_click_stop({
+                let key = key.clone();
+                move |_| {
+                    if expandable {
+                        toggle(&key);
+                    }
+                }
+            })
+            .style({
+                let key = key.clone();
+                move |s| {
+                    let target = drop_target.with(|target| {
+                        target
+                            .as_ref()
+                            .filter(|(target, _)| *target == key)
+                            .map(|(_, position)| *position)
+                    });
+                    s.height(row_height)
+                        .width_full()
+                        .apply_if(target == Some(DropPosition::Before), |s| s.border_top(2.0))
+                        .apply_if(target == Some(DropPosition::After), |s| {
+                            s.border_bottom(2.0)
+                        })
+                        .apply_if(target == Some(DropPosition::Inside), |s| s.border(2.0))
+                }
+            });
+
+            if on_move.borrow().is_none() {
+                return row.any();
+            }
+
+            let on_move = on_move.clone();
+            let dragged = key.clone();
+            let over = key.clone();
+            let dropped = key;
+            row.draggable()
+                .on_event_cont(EventListener::DragStart, move |_| {
+                    dragging.set(Some(dragged.clone()));
+                })
+                .on_event_cont(EventListener::DragEnd, move |_| {
+                    dragging.set(None);
+                    drop_target.set(None);
+                })
+                .on_event_cont(EventListener::DragOver, move |event| {
+                    let Event::PointerMove(event) = event else {
+                        return;
+                    };
+                    // A node can't be dropped into itself
+                    let allowed = dragging.with_untracked(|dragging| {
+                        dragging.as_ref().is_some_and(|dragging| {
+                            rows.with_untracked(|rows| !is_ancestor(rows, dragging, &over))
+                        })
+                    });
+                    let target = allowed.then(|| {
+                        let position = drop_position(event.pos.y, row_height, expandable);
+                        (over.clone(), position)
+                    });
+                    if drop_target.with_untracked(|current| *current != target) {
+                        drop_target.set(target);
+                    }
+                })
+                .on_event_cont(EventListener::DragLeave, move |_| {
+                    drop_target.set(None);
+                })
+                .on_event(EventListener::Drop, move |_| {
+                    let target = drop_target.get_untracked();
+                    drop_target.set(None);
+                    let Some(((target, position), dragged)) = target
+                        .filter(|(target, _)| *target == dropped)
+                        .zip(dragging.get_untracked())
+                    else {
+                        return EventPropagation::Continue;
+                    };
+                    if let Some(on_move) = on_move.borrow().as_ref() {
+                        on_move(&dragged, &target, position);
+                    }
+                    EventPropagation::Stop
+                })
+                .any()
+        }
+    };
+
+    let stack = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(move || row_height)),
+        move || rows.get(),
+        |row| {
+            (
+                row.key.clone(),
+                row.depth,
+                row.expandable,
+                row.loading,
+                row.failed,
+                // Rebuild the row when its item changes
+                row.version,
+            )
+        },
+        row_view,
+    )
+    .style(|s| s.flex_col().min_width_full());
+
+    let child = scroll(stack)
+        .on_scroll(move |rect| {
+            if viewport.get_untracked() != rect {
+                viewport.set(rect);
+            }
+        })
+        .ensure_visible(move || {
+            // Keep the node moved to with the keyboard in view
+            let viewport = viewport.get_untracked();
+            let position = selection
+                .with(|selection| selection.cursor.clone())
+                .and_then(|cursor| {
+                    rows.with_untracked(|rows| rows.iter().position(|row| row.key == cursor))
+                });
+            match position {
+                Some(position) => {
+                    let y = position as f64 * row_height;
+                    Rect::new(viewport.x0, y, viewport.x1, y + row_height)
+                }
+                None => viewport,
+            }
+        })
+        .style(|s| s.size_full())
+        .build();
+
+    TreeView {
+        data: ViewData::new(id),
+        selection,
+        expanded,
+        multi_select,
+        indent,
+        on_move,
+        child,
+    }
+    .class(TreeViewClass)
+    .keyboard_navigatable()
+    .on_event(EventListener::KeyDown, move |event| {
+        let Event::KeyDown(key_event) = event else {
+            return EventPropagation::Continue;
+        };
+        let rows = rows.get_untracked();
+        if rows.is_empty() {
+            return EventPropagation::Continue;
+        }
+        let order: Vec<K> = rows.iter().map(|row| row.key.clone()).collect();
+        let multi_select = multi_select.get_untracked();
+        let last = order.len() - 1;
+        let page = ((viewport.get_untracked().height() / row_height) as usize).max(1);
+        let current = selection.with_untracked(|selection| {
+            selection
+                .cursor
+                .as_ref()
+                .and_then(|cursor| order.iter().position(|key| key == cursor))
+        });
+        let position = match &key_event.key.logical_key {
+            Key::Named(NamedKey::ArrowUp) => current.map(|i| i.saturating_sub(1)).unwrap_or(last),
+            Key::Named(NamedKey::ArrowDown) => current.map(|i| (i + 1).min(last)).unwrap_or(0),
+            Key::Named(NamedKey::PageUp) => current.map(|i| i.saturating_sub(page)).unwrap_or(0),
+            Key::Named(NamedKey::PageDown) => current.map(|i| (i + page).min(last)).unwrap_or(last),
+            Key::Named(NamedKey::Home) => 0,
+            Key::Named(NamedKey::End) => last,
+            Key::Named(NamedKey::ArrowRight) => {
+                let Some(current) = current else {
+                    return EventPropagation::Continue;
+                };
+                let row = &rows[current];
+                if row.expandable && !row.expanded {
+                    toggle(&row.key);
+                    return EventPropagation::Stop;
+                }
+                // Move to the first child
+                match rows.get(current + 1) {
+                    Some(child) if row.expanded && child.depth > row.depth => current + 1,
+                    _ => return EventPropagation::Stop,
+                }
+            }
+            Key::Named(NamedKey::ArrowLeft) => {
+                let Some(current) = current else {
+                    return EventPropagation::Continue;
+                };
+                let row = &rows[current];
+                if row.expanded {
+                    toggle(&row.key);
+                    return EventPropagation::Stop;
+                }
+                // Move to the parent
+                match row
+                    .parent
+                    .as_ref()
+                    .and_then(|parent| order.iter().position(|key| key == parent))
+                {
+                    Some(parent) => parent,
+                    None => return EventPropagation::Stop,
+                }
+            }
+            Key::Named(NamedKey::Enter) => {
+                if let Some(row) = current.map(|current| &rows[current]) {
+                    if row.expandable {
+                        toggle(&row.key);
+                    }
+                }
+                return EventPropagation::Stop;
+            }
+            Key::Character(ch)
+                if multi_select
+                    && is_toggle(key_event.modifiers)
+                    && ch.eq_ignore_ascii_case("a") =>
+            {
+                selection.update(|selection| selection.select_all(&order));
+                return EventPropagation::Stop;
+            }
+            _ => return EventPropagation::Continue,
+        };
+        let extend = multi_select && key_event.modifiers.contains(ModifiersState::SHIFT);
+        selection
+            .update(|selection| selection.select(&order, order[position].clone(), extend, false));
+        EventPropagation::Stop
+    })
+}
+
+impl<K: 'static> View for TreeView<K> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn build(self) -> Box<dyn Widget> {
+        Box::new(self)
+    }
+}
+
+impl<K: 'static> Widget for TreeView<K> {
+    fn view_data(&self) -> &ViewData {
+        &self.data
+    }
+
+    fn view_data_mut(&mut self) -> &mut ViewData {
+        &mut self.data
+    }
+
+    fn for_each_child<'a>(&'a self, for_each: &mut dyn FnMut(&'a dyn Widget) -> bool) {
+        for_each(&self.child);
+    }
+
+    fn for_each_child_mut<'a>(&'a mut self, for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool) {
+        for_each(&mut self.child);
+    }
+
+    fn for_each_child_rev_mut<'a>(
+        &'a mut self,
+        for_each: &mut dyn FnMut(&'a mut dyn Widget) -> bool,
+    ) {
+        for_each(&mut self.child);
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "TreeView".into()
+    }
+
+    fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(change) = state.downcast::<TreeUpdate>() {
+            match *change {
+                TreeUpdate::SelectionChanged => {
+                    cx.app_state_mut().request_style_recursive(self.id())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        collections::{HashMap, HashSet},
+        rc::Rc,
+    };
+
+    use floem_reactive::{create_rw_signal, RwSignal, Scope};
+
+    use super::{
+        drop_position, flatten, is_ancestor, tree_view, DropPosition, LoadedChildren, RowVersions,
+        TreeChildren, TreeLoadState, TreeRow,
+    };
+    use crate::{headless::HeadlessWindow, views::text};
+
+    type Item = (u32, &'static str);
+
+    /// Node 1 has children up front, node 3 loads them lazily and node 5 loads them from `state`
+    fn children(
+        loads: Rc<Cell<usize>>,
+        state: RwSignal<TreeLoadState<Item>>,
+    ) -> impl Fn(&Item) -> TreeChildren<Item> {
+        move |item| match item.0 {
+            1 => TreeChildren::Ready(vec![(2, "two"), (3, "three")]),
+            3 => {
+                let loads = loads.clone();
+                TreeChildren::lazy(move || {
+                    loads.set(loads.get() + 1);
+                    vec![(4, "four")]
+                })
+            }
+            5 => TreeChildren::Lazy(Box::new(move || state.read_only())),
+            _ => TreeChildren::None,
+        }
+    }
+
+    /// The key and depth of each row
+    fn shown(rows: &[TreeRow<Item, u32>]) -> Vec<(u32, usize)> {
+        rows.iter().map(|row| (row.key, row.depth)).collect()
+    }
+
+    fn row(key: u32, parent: Option<u32>, depth: usize) -> TreeRow<(), u32> {
+        TreeRow {
+            item: (),
+            key,
+            parent,
+            depth,
+            expandable: true,
+            expanded: true,
+            loading: false,
+            failed: false,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn ancestors() {
+        let rows = im::vector![row(1, None, 0), row(2, Some(1), 1), row(3, Some(2), 2)];
+        assert!(is_ancestor(&rows, &1, &3));
+        assert!(is_ancestor(&rows, &3, &3));
+        assert!(!is_ancestor(&rows, &3, &1));
+    }
+
+    #[test]
+    fn drop_positions() {
+        assert_eq!(drop_position(2.0, 20.0, true), DropPosition::Before);
+        assert_eq!(drop_position(10.0, 20.0, true), DropPosition::Inside);
+        assert_eq!(drop_position(18.0, 20.0, true), DropPosition::After);
+        assert_eq!(drop_position(8.0, 20.0, false), DropPosition::Before);
+        assert_eq!(drop_position(12.0, 20.0, false), DropPosition::After);
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let scope = Scope::new();
+        let loads = Rc::new(Cell::new(0));
+        let children = children(loads.clone(), create_rw_signal(TreeLoadState::Loading));
+        let roots = || vec![(1, "one"), (5, "five")];
+        let mut loaded: HashMap<u32, LoadedChildren<Item>> = HashMap::new();
+        let mut versions = RowVersions::new();
+        let mut flat = |expanded: &[u32]| {
+            let expanded: HashSet<u32> = expanded.iter().copied().collect();
+            flatten(
+                roots(),
+                &children,
+                &|item: &Item| item.0,
+                &expanded,
+                &mut loaded,
+                &mut versions,
+                scope,
+            )
+        };
+
+        let rows = flat(&[]);
+        assert_eq!(shown(&rows), vec![(1, 0), (5, 0)]);
+        assert!(rows[0].expandable && !rows[0].expanded);
+
+        let rows = flat(&[1]);
+        assert_eq!(shown(&rows), vec![(1, 0), (2, 1), (3, 1), (5, 0)]);
+        assert!(rows[0].expanded);
+        assert_eq!(rows[2].parent, Some(1));
+
+        // Expanding a lazy node loads its children once
+        let rows = flat(&[1, 3]);
+        assert_eq!(shown(&rows), vec![(1, 0), (2, 1), (3, 1), (4, 2), (5, 0)]);
+        flat(&[1, 3]);
+        assert_eq!(loads.get(), 1);
+
+        // A lazy node is loaded again after collapsing it
+        let rows = flat(&[1]);
+        assert_eq!(shown(&rows), vec![(1, 0), (2, 1), (3, 1), (5, 0)]);
+        flat(&[1, 3]);
+        assert_eq!(loads.get(), 2);
+
+        // The expanded children of a collapsed node are hidden
+        assert_eq!(shown(&flat(&[3])), vec![(1, 0), (5, 0)]);
+    }
+
+    #[test]
+    fn failed_loads() {
+        let scope = Scope::new();
+        let state = create_rw_signal(TreeLoadState::Loading);
+        let children = children(Rc::new(Cell::new(0)), state);
+        let mut loaded: HashMap<u32, LoadedChildren<Item>> = HashMap::new();
+        let mut versions = RowVersions::new();
+        let mut flat = |expanded: &[u32]| {
+            let expanded: HashSet<u32> = expanded.iter().copied().collect();
+            let roots = vec![(5, "five")];
+            flatten(
+                roots,
+                &children,
+                &|item: &Item| item.0,
+                &expanded,
+                &mut loaded,
+                &mut versions,
+                scope,
+            )
+        };
+
+        let rows = flat(&[5]);
+        assert!(rows[0].loading && !rows[0].failed);
+
+        state.set(TreeLoadState::Failed);
+        let rows = flat(&[5]);
+        assert_eq!(shown(&rows), vec![(5, 0)]);
+        assert!(rows[0].failed && !rows[0].loading);
+        // It can still be collapsed, and is loaded again when expanded
+        assert!(rows[0].expandable);
+
+        assert!(!flat(&[])[0].failed);
+        state.set(TreeLoadState::Ready(vec![(6, "six")]));
+        assert_eq!(shown(&flat(&[5])), vec![(5, 0), (6, 1)]);
+    }
+
+    #[test]
+    fn refreshed_roots_keep_their_state() {
+        let scope = Scope::new();
+        let children = children(
+            Rc::new(Cell::new(0)),
+            create_rw_signal(TreeLoadState::Loading),
+        );
+        let expanded: HashSet<u32> = [1].into_iter().collect();
+        let mut loaded: HashMap<u32, LoadedChildren<Item>> = HashMap::new();
+        let mut versions = RowVersions::new();
+        let mut flat = |roots: Vec<Item>| {
+            flatten(
+                roots,
+                &children,
+                &|item: &Item| item.0,
+                &expanded,
+                &mut loaded,
+                &mut versions,
+                scope,
+            )
+        };
+        let rows = flat(vec![(1, "one")]);
+        assert_eq!(rows[0].version, 0);
+
+        let rows = flat(vec![(1, "uno")]);
+        assert_eq!(rows[0].item, (1, "uno"));
+        assert!(rows[0].expanded);
+        assert_eq!(shown(&rows), vec![(1, 0), (2, 1), (3, 1)]);
+        // Only the changed node is rebuilt
+        assert_eq!(rows[0].version, 1);
+        assert_eq!(rows[1].version, 0);
+
+        let roots = create_rw_signal(vec![(1, "one"), (5, "five")]);
+        let selected = create_rw_signal(Vec::new());
+        let mut window = HeadlessWindow::new_unthemed(
+            move || {
+                tree_view(
+                    20.0,
+                    move || roots.get(),
+                    |_| TreeChildren::None,
+                    |item| item.0,
+                    |item| text(item.1),
+                )
+                .on_select(move |keys| selected.set(keys))
+            },
+            (200.0, 200.0),
+            1.0,
+        );
+        window.click((50.0, 30.0));
+        assert_eq!(selected.get_untracked(), vec![5]);
+
+        let unchanged = window.find_by_debug_name("Label: \"one\"");
+        assert_eq!(unchanged.len(), 1);
+
+        roots.set(vec![(1, "one"), (5, "cinco")]);
+        window.process_update();
+        assert_eq!(selected.get_untracked(), vec![5]);
+        assert_eq!(window.find_by_debug_name("Label: \"five\"").len(), 0);
+        assert_eq!(window.find_by_debug_name("Label: \"cinco\"").len(), 1);
+        assert_eq!(window.find_by_debug_name("Label: \"one\""), unchanged);
+
+        roots.set(vec![(1, "one")]);
+        window.process_update();
+        assert_eq!(selected.get_untracked(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn selected_rows_are_highlighted() {
+        let mut window = HeadlessWindow::new(
+            || {
+                tree_view(
+                    20.0,
+                    || vec![(1, "one"), (5, "five")],
+                    |_| TreeChildren::None,
+                    |item: &Item| item.0,
+                    |item| text(item.1),
+                )
+                .style(|s| s.size_full())
+            },
+            (200.0, 200.0),
+            1.0,
+        );
+
+        window.click((50.0, 30.0));
+        // Move away so the row isn't hovered
+        window.pointer_move((190.0, 190.0));
+        let img = window.render().into_rgba8();
+        // The selected background of the theme, depending on whether the tree has focus
+        let selected = [[213, 208, 216, 255], [212, 212, 212, 255]];
+        assert!(selected.contains(&img.get_pixel(150, 30).0));
+        assert!(!selected.contains(&img.get_pixel(150, 10).0));
+    }
+}
@@ -1,6 +1,9 @@
 use std::{hash::Hash, marker::PhantomData, ops::Range};
 
-use floem_reactive::{as_child_of_current_scope, create_effect, create_signal, Scope, WriteSignal};
+use floem_reactive::{
+    as_child_of_current_scope, create_effect, create_signal, Scope, Store, StoreDiff, StoreVec,
+    WriteSignal,
+};
 use kurbo::Rect;
 use smallvec::SmallVec;
 use taffy::{
@@ -11,7 +14,7 @@ use taffy::{
 use crate::{
     context::ComputeLayoutCx,
     id::Id,
-    view::{self, AnyWidget, View, ViewData, Widget},
+    view::{self, view_children_set_parent_id, AnyWidget, View, ViewData, Widget},
 };
 
 use super::{apply_diff, diff, Diff, DiffOpAdd, FxIndexSet, HashRun};
@@ -37,6 +40,21 @@ pub trait VirtualVector<T> {
 
     fn slice(&mut self, range: Range<usize>) -> impl Iterator<Item = T>;
 
+    /// The version of the items, which changes whenever items are added, removed or moved.
+    ///
+    /// Vectors which return a version also report their changes in
+    /// [`changes_since`](Self::changes_since), so the stack can update the shown items from them
+    /// instead of comparing their keys.
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    /// The changes made to the items after `version`, in order, or `None` if they aren't known
+    /// anymore, in which case the shown items are rebuilt.
+    fn changes_since(&self, _version: u64) -> Option<Vec<VirtualChange<T>>> {
+        None
+    }
+
     fn enumerate(self) -> Enumerate<Self, T>
     where
         Self: Sized,
@@ -48,6 +66,32 @@ pub trait VirtualVector<T> {
     }
 }
 
+/// A change to the items of a [`VirtualVector`], see [`VirtualVector::changes_since`].
+pub enum VirtualChange<T> {
+    Insert {
+        at: usize,
+        item: T,
+    },
+    Remove {
+        at: usize,
+    },
+    /// The item at `from` was removed and inserted again at `to`
+    Move {
+        from: usize,
+        to: usize,
+        item: T,
+    },
+    Clear,
+}
+
+/// A change to the children of a [`VirtualStack`], with indices among the shown items.
+enum ShownChange<T> {
+    Insert { at: usize, item: T },
+    Remove { at: usize },
+    Move { from: usize, to: usize },
+    Clear,
+}
+
 /// A virtual stack that is like a [`dyn_stack`](super::dyn_stack()) but also lazily loads items for performance. See [`virtual_stack`].
 pub struct VirtualStack<T>
 where
@@ -66,12 +110,44 @@ where
     content_node: Option<NodeId>,
 }
 
+enum VirtualStackUpdate<T> {
+    /// Found by comparing the keys of the shown items
+    Diff(Diff<T>),
+    /// Made from the changes reported by the [`VirtualVector`]
+    Changes(Vec<ShownChange<T>>),
+}
+
+impl<T> VirtualStackUpdate<T> {
+    fn is_empty(&self) -> bool {
+        match self {
+            VirtualStackUpdate::Diff(diff) => diff.is_empty(),
+            VirtualStackUpdate::Changes(changes) => changes.is_empty(),
+        }
+    }
+}
+
 struct VirtualStackState<T> {
-    diff: Diff<T>,
+    update: VirtualStackUpdate<T>,
     before_size: f64,
     content_size: f64,
 }
 
+/// What the effect of a [`virtual_stack`] remembers about the items it has shown.
+struct ShownItems<K> {
+    before_size: f64,
+    content_size: f64,
+    /// The indices of the items that have a view
+    range: Range<usize>,
+    kind: ShownKind<K>,
+}
+
+enum ShownKind<K> {
+    /// The keys of the shown items, which are compared with the next ones
+    Keys(FxIndexSet<K>),
+    /// The version of a vector which reports its own changes
+    Version(u64),
+}
+
 /// A View that is like a [`dyn_stack`](super::dyn_stack()) but also lazily loads the items as they appear in a [scroll view](super::scroll()) and does not support the flexbox nor grid layout algorithms.
 /// Instead, the Virtual Stack gives every element a consistent size and uses a basic layout.
 /// This is done for perfomance and allows for lists of millions of items to be used with very high performance.
@@ -123,7 +199,7 @@ where
 
     let (viewport, set_viewport) = create_signal(Rect::ZERO);
 
-    create_effect(move |prev| {
+    create_effect(move |prev: Option<ShownItems<K>>| {
         let mut items_vector = each_fn();
         let viewport = viewport.get();
         let min = match direction {
@@ -134,14 +210,13 @@ where
             VirtualDirection::Vertical => viewport.height() + viewport.y0,
             VirtualDirection::Horizontal => viewport.width() + viewport.x0,
         };
-        let mut items = Vec::new();
 
+        let total_len = items_vector.total_len();
         let mut before_size = 0.0;
         let mut content_size = 0.0;
-        match &item_size {
+        let range = match &item_size {
             VirtualItemSize::Fixed(item_size) => {
                 let item_size = item_size();
-                let total_len = items_vector.total_len();
                 let start = if item_size > 0.0 {
                     (min / item_size).floor() as usize
                 } else {
@@ -150,20 +225,17 @@ where
                 let end = if item_size > 0.0 {
                     ((max / item_size).ceil() as usize).min(total_len)
                 } else {
-                    usize::MAX
+                    total_len
                 };
                 before_size = item_size * (start.min(total_len)) as f64;
-
-                for item in items_vector.slice(start..end) {
-                    items.push(item);
-                }
-
                 content_size = item_size * total_len as f64;
+                start.min(end)..end
             }
             VirtualItemSize::Fn(size_fn) => {
                 let mut main_axis = 0.0;
-                let total_len = items_vector.total_len();
-                for item in items_vector.slice(0..total_len) {
+                let mut start = total_len;
+                let mut end = 0;
+                for (i, item) in items_vector.slice(0..total_len).enumerate() {
                     let item_size = size_fn(&item);
                     content_size += item_size;
                     if main_axis + item_size < min {
@@ -174,44 +246,93 @@ where
 
                     if main_axis <= max {
                         main_axis += item_size;
-                        items.push(item);
+                        start = start.min(i);
+                        end = i + 1;
                     }
                 }
+                start.min(end)..end
             }
         };
 
-        let hashed_items = items.iter().map(&key_fn).collect::<FxIndexSet<_>>();
-        let (prev_before_size, prev_content_size, diff) =
-            if let Some((prev_before_size, prev_content_size, HashRun(prev_hash_run))) = prev {
-                let mut diff = diff(&prev_hash_run, &hashed_items);
-                let mut items = items
-                    .into_iter()
-                    .map(|i| Some(i))
-                    .collect::<SmallVec<[Option<_>; 128]>>();
-                for added in &mut diff.added {
-                    added.view = Some(items[added.at].take().unwrap());
+        let (prev_before_size, prev_content_size) = prev
+            .as_ref()
+            .map_or((0.0, 0.0), |prev| (prev.before_size, prev.content_size));
+        let (update, kind) = if let Some(version) = items_vector.version() {
+            let mut changes = Vec::new();
+            let mut shown = match prev {
+                Some(ShownItems {
+                    range: prev_range,
+                    kind: ShownKind::Version(prev_version),
+                    ..
+                }) => {
+                    let mut shown = prev_range;
+                    if let Some(vector_changes) = items_vector.changes_since(prev_version) {
+                        shown_changes(vector_changes, &mut shown, &mut changes);
+                    } else if !shown.is_empty() {
+                        changes.push(ShownChange::Clear);
+                        shown = 0..0;
+                    }
+                    shown
                 }
-                (prev_before_size, prev_content_size, diff)
-            } else {
-                let mut diff = Diff::default();
-                for (i, item) in items.into_iter().enumerate() {
-                    diff.added.push(DiffOpAdd {
-                        at: i,
-                        view: Some(item),
-                    });
+                Some(ShownItems { range, .. }) if !range.is_empty() => {
+                    changes.push(ShownChange::Clear);
+                    0..0
+                }
+                _ => 0..0,
+            };
+            show_range(&mut items_vector, &mut shown, range.clone(), &mut changes);
+            (
+                VirtualStackUpdate::Changes(changes),
+                ShownKind::Version(version),
+            )
+        } else {
+            let items = items_vector.slice(range.clone()).collect::<Vec<_>>();
+            let hashed_items = items.iter().map(&key_fn).collect::<FxIndexSet<_>>();
+            let mut diff = match prev {
+                Some(ShownItems {
+                    kind: ShownKind::Keys(prev_hashed_items),
+                    ..
+                }) => diff(&prev_hashed_items, &hashed_items),
+                prev => {
+                    let mut diff = Diff {
+                        clear: prev.is_some(),
+                        ..Default::default()
+                    };
+                    for i in 0..items.len() {
+                        diff.added.push(DiffOpAdd { at: i, view: None });
+                    }
+                    diff
                 }
-                (0.0, 0.0, diff)
             };
+            let mut items = items
+                .into_iter()
+                .map(|i| Some(i))
+                .collect::<SmallVec<[Option<_>; 128]>>();
+            for added in &mut diff.added {
+                added.view = Some(items[added.at].take().unwrap());
+            }
+            (
+                VirtualStackUpdate::Diff(diff),
+                ShownKind::Keys(hashed_items),
+            )
+        };
 
-        if !diff.is_empty() || prev_before_size != before_size || prev_content_size != content_size
+        if !update.is_empty()
+            || prev_before_size != before_size
+            || prev_content_size != content_size
         {
             id.update_state(VirtualStackState {
-                diff,
+                update,
                 before_size,
                 content_size,
             });
         }
-        (before_size, content_size, HashRun(hashed_items))
+        ShownItems {
+            before_size,
+            content_size,
+            range,
+            kind,
+        }
     });
 
     let view_fn = Box::new(as_child_of_current_scope(move |e| view_fn(e).build()));
@@ -231,6 +352,132 @@ where
     }
 }
 
+/// Apply the `changes` of the items to the children, where `shown` is the range of items that
+/// have a view. Only changes within that range need to touch the children.
+fn shown_changes<T>(
+    changes: Vec<VirtualChange<T>>,
+    shown: &mut Range<usize>,
+    children: &mut Vec<ShownChange<T>>,
+) {
+    fn insert<T>(at: usize, item: T, shown: &mut Range<usize>, children: &mut Vec<ShownChange<T>>) {
+        if at < shown.start {
+            *shown = shown.start + 1..shown.end + 1;
+        } else if at <= shown.end {
+            children.push(ShownChange::Insert {
+                at: at - shown.start,
+                item,
+            });
+            shown.end += 1;
+        }
+    }
+
+    fn remove<T>(at: usize, shown: &mut Range<usize>, children: &mut Vec<ShownChange<T>>) {
+        if at < shown.start {
+            *shown = shown.start - 1..shown.end - 1;
+        } else if at < shown.end {
+            children.push(ShownChange::Remove {
+                at: at - shown.start,
+            });
+            shown.end -= 1;
+        }
+    }
+
+    for change in changes {
+        match change {
+            VirtualChange::Insert { at, item } => insert(at, item, shown, children),
+            VirtualChange::Remove { at } => remove(at, shown, children),
+            VirtualChange::Move { from, to, item } => {
+                if shown.contains(&from) && shown.contains(&to) {
+                    children.push(ShownChange::Move {
+                        from: from - shown.start,
+                        to: to - shown.start,
+                    });
+                } else {
+                    remove(from, shown, children);
+                    insert(to, item, shown, children);
+                }
+            }
+            VirtualChange::Clear => {
+                if !Range::is_empty(shown) {
+                    children.push(ShownChange::Clear);
+                }
+                *shown = 0..0;
+            }
+        }
+    }
+}
+
+/// Add and remove children at both ends so that the items in `range` have a view.
+fn show_range<T>(
+    items: &mut impl VirtualVector<T>,
+    shown: &mut Range<usize>,
+    range: Range<usize>,
+    children: &mut Vec<ShownChange<T>>,
+) {
+    if shown.end <= range.start || range.end <= shown.start || Range::is_empty(shown) {
+        if !Range::is_empty(shown) {
+            children.push(ShownChange::Clear);
+        }
+        for (at, item) in items.slice(range.clone()).enumerate() {
+            children.push(ShownChange::Insert { at, item });
+        }
+        *shown = range;
+        return;
+    }
+
+    if shown.start < range.start {
+        children.extend((shown.start..range.start).map(|_| ShownChange::Remove { at: 0 }));
+    } else {
+        for (at, item) in items.slice(range.start..shown.start).enumerate() {
+            children.push(ShownChange::Insert { at, item });
+        }
+    }
+
+    // The children now show `range.start..shown.end`
+    let len = shown.end - range.start;
+    if range.end < shown.end {
+        children.extend((range.end..shown.end).map(|_| ShownChange::Remove {
+            at: range.end - range.start,
+        }));
+    } else {
+        for (i, item) in items.slice(shown.end..range.end).enumerate() {
+            children.push(ShownChange::Insert { at: len + i, item });
+        }
+    }
+    *shown = range;
+}
+
+impl<T> VirtualStack<T> {
+    fn apply_changes(&mut self, cx: &mut crate::context::UpdateCx, changes: Vec<ShownChange<T>>) {
+        for change in changes {
+            match change {
+                ShownChange::Insert { at, item } => {
+                    let (child, scope) = (self.view_fn)(item);
+                    child.view_data().id().set_parent(self.id());
+                    view_children_set_parent_id(&child);
+                    self.children.insert(at, Some((child, scope)));
+                }
+                ShownChange::Remove { at } => {
+                    if let Some((mut child, scope)) = self.children.remove(at) {
+                        cx.app_state.remove_view(&mut child);
+                        scope.dispose();
+                    }
+                }
+                ShownChange::Move { from, to } => {
+                    let child = self.children.remove(from);
+                    self.children.insert(to, child);
+                }
+                ShownChange::Clear => {
+                    for (mut child, scope) in self.children.drain(..).flatten() {
+                        cx.app_state.remove_view(&mut child);
+                        scope.dispose();
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl<T> View for VirtualStack<T> {
     fn view_data(&self) -> &ViewData {
         &self.data
@@ -294,19 +541,22 @@ impl<T> Widget for VirtualStack<T> {
         if let Ok(state) = state.downcast::<VirtualStackState<T>>() {
             if self.before_size == state.before_size
                 && self.content_size == state.content_size
-                && state.diff.is_empty()
+                && state.update.is_empty()
             {
                 return;
             }
             self.before_size = state.before_size;
             self.content_size = state.content_size;
-            apply_diff(
-                self.id(),
-                cx.app_state,
-                state.diff,
-                &mut self.children,
-                &self.view_fn,
-            );
+            match state.update {
+                VirtualStackUpdate::Diff(diff) => apply_diff(
+                    self.id(),
+                    cx.app_state,
+                    diff,
+                    &mut self.children,
+                    &self.view_fn,
+                ),
+                VirtualStackUpdate::Changes(changes) => self.apply_changes(cx, changes),
+            }
             cx.request_all(self.id());
         }
     }
@@ -409,7 +659,64 @@ impl<T: Clone> VirtualVector<T> for im::Vector<T> {
     }
 
     fn slice(&mut self, range: Range<usize>) -> impl Iterator<Item = T> {
-        self.slice(range).into_iter()
+        // `im::Vector::slice` removes the range from the vector, so take it from a clone, which
+        // shares the same chunks
+        self.clone().slice(range).into_iter()
+    }
+}
+
+/// Items of a [`StoreVec`] are given to the view function as their key and their signals, so the
+/// key function would usually be `|(key, _)| key.clone()`. The stack follows the changes recorded
+/// by the store, so adding or removing an item only touches the views around it.
+impl<K, T> VirtualVector<(K, T::Handle)> for StoreVec<K, T>
+where
+    K: Clone + PartialEq + 'static,
+    T: Store,
+{
+    fn total_len(&self) -> usize {
+        self.len()
+    }
+
+    fn slice(&mut self, range: Range<usize>) -> impl Iterator<Item = (K, T::Handle)> {
+        self.with(|items| {
+            items
+                .iter()
+                .skip(range.start)
+                .take(range.len())
+                .map(|item| (item.key.clone(), item.handle))
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(StoreVec::version(self))
+    }
+
+    fn changes_since(&self, version: u64) -> Option<Vec<VirtualChange<(K, T::Handle)>>> {
+        let changes = self
+            .diffs_since(version)?
+            .into_iter()
+            .map(|diff| match diff {
+                StoreDiff::Insert { at, key, handle } => VirtualChange::Insert {
+                    at,
+                    item: (key, handle),
+                },
+                StoreDiff::Remove { at, .. } => VirtualChange::Remove { at },
+                StoreDiff::Move {
+                    from,
+                    to,
+                    key,
+                    handle,
+                } => VirtualChange::Move {
+                    from,
+                    to,
+                    item: (key, handle),
+                },
+                StoreDiff::Clear => VirtualChange::Clear,
+            })
+            .collect();
+        Some(changes)
     }
 }
 
@@ -431,3 +738,159 @@ impl<V: VirtualVector<T>, T> VirtualVector<(usize, T)> for Enumerate<V, T> {
             .map(move |(i, e)| (i + start, e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::{show_range, shown_changes, ShownChange, VirtualChange};
+
+    /// Apply `changes` to the items that have a view, like the stack does to its children.
+    fn apply(children: &mut Vec<i32>, changes: Vec<ShownChange<i32>>) -> usize {
+        let count = changes.len();
+        for change in changes {
+            match change {
+                ShownChange::Insert { at, item } => children.insert(at, item),
+                ShownChange::Remove { at } => {
+                    children.remove(at);
+                }
+                ShownChange::Move { from, to } => {
+                    let item = children.remove(from);
+                    children.insert(to, item);
+                }
+                ShownChange::Clear => children.clear(),
+            }
+        }
+        count
+    }
+
+    /// Make the `changes` to `items` and show `range`, checking that the children match. Moved
+    /// items are filled in from `items`.
+    fn update(
+        items: &mut im::Vector<i32>,
+        children: &mut Vec<i32>,
+        shown: &mut Range<usize>,
+        mut changes: Vec<VirtualChange<i32>>,
+        range: Range<usize>,
+    ) -> usize {
+        for change in &mut changes {
+            match change {
+                VirtualChange::Insert { at, item } => items.insert(*at, *item),
+                VirtualChange::Remove { at } => {
+                    items.remove(*at);
+                }
+                VirtualChange::Move { from, to, item } => {
+                    *item = items.remove(*from);
+                    items.insert(*to, *item);
+                }
+                VirtualChange::Clear => items.clear(),
+            }
+        }
+        let mut shown_items = Vec::new();
+        shown_changes(changes, shown, &mut shown_items);
+        show_range(items, shown, range.clone(), &mut shown_items);
+        let count = apply(children, shown_items);
+        assert_eq!(
+            *children,
+            items.clone().slice(range).into_iter().collect::<Vec<_>>()
+        );
+        count
+    }
+
+    #[test]
+    fn changes_outside_the_shown_items_only_shift_them() {
+        let mut items = (0..20).collect::<im::Vector<_>>();
+        let mut children = Vec::new();
+        let mut shown = 0..0;
+        update(&mut items, &mut children, &mut shown, Vec::new(), 5..10);
+
+        // Inserting above the shown items doesn't rebuild or move them
+        let count = update(
+            &mut items,
+            &mut children,
+            &mut shown,
+            vec![VirtualChange::Insert { at: 0, item: 100 }],
+            6..11,
+        );
+        assert_eq!(count, 0);
+
+        let count = update(
+            &mut items,
+            &mut children,
+            &mut shown,
+            vec![
+                VirtualChange::Remove { at: 1 },
+                VirtualChange::Insert { at: 15, item: 101 },
+            ],
+            5..10,
+        );
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn changes_inside_the_shown_items() {
+        let mut items = (0..20).collect::<im::Vector<_>>();
+        let mut children = Vec::new();
+        let mut shown = 0..0;
+        update(&mut items, &mut children, &mut shown, Vec::new(), 5..10);
+
+        update(
+            &mut items,
+            &mut children,
+            &mut shown,
+            vec![
+                VirtualChange::Insert { at: 7, item: 100 },
+                VirtualChange::Move {
+                    from: 9,
+                    to: 5,
+                    item: 0,
+                },
+                VirtualChange::Remove { at: 6 },
+            ],
+            5..10,
+        );
+
+        // Items moved into and out of the shown range
+        update(
+            &mut items,
+            &mut children,
+            &mut shown,
+            vec![
+                VirtualChange::Move {
+                    from: 15,
+                    to: 6,
+                    item: 0,
+                },
+                VirtualChange::Move {
+                    from: 5,
+                    to: 0,
+                    item: 0,
+                },
+            ],
+            5..10,
+        );
+    }
+
+    #[test]
+    fn scrolling_keeps_the_overlapping_items() {
+        let mut items = (0..20).collect::<im::Vector<_>>();
+        let mut children = Vec::new();
+        let mut shown = 0..0;
+        update(&mut items, &mut children, &mut shown, Vec::new(), 5..10);
+
+        // One item leaves at the top and one enters at the bottom
+        let count = update(&mut items, &mut children, &mut shown, Vec::new(), 6..11);
+        assert_eq!(count, 2);
+        update(&mut items, &mut children, &mut shown, Vec::new(), 3..8);
+        update(&mut items, &mut children, &mut shown, Vec::new(), 12..18);
+
+        update(
+            &mut items,
+            &mut children,
+            &mut shown,
+            vec![VirtualChange::Clear],
+            0..0,
+        );
+        assert!(children.is_empty());
+    }
+}
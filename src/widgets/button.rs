@@ -1,14 +1,18 @@
 use crate::{
+    accessibility::Role,
     style_class,
     view::View,
     views::{self, container, Decorators},
 };
-use std::fmt::Display;
+use std::{fmt::Display, rc::Rc};
 
 style_class!(pub ButtonClass);
 
 pub fn button<S: Display + 'static>(label: impl Fn() -> S + 'static) -> impl View {
-    container(views::label(label))
+    let label = Rc::new(label);
+    let name = label.clone();
+    container(views::label(move || label()))
         .keyboard_navigatable()
+        .accessibility(move |a| a.role(Role::Button).name(name().to_string()))
         .class(ButtonClass)
 }
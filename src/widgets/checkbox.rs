@@ -1,4 +1,5 @@
 use crate::{
+    accessibility::Role,
     style_class,
     view::View,
     views::{
@@ -7,7 +8,7 @@ use crate::{
     },
 };
 use floem_reactive::ReadSignal;
-use std::fmt::Display;
+use std::{fmt::Display, rc::Rc};
 
 style_class!(pub CheckboxClass);
 
@@ -26,6 +27,7 @@ pub fn checkbox(checked: impl Fn() -> bool + 'static) -> ValueContainer<bool> {
     value_container(
         checkbox_svg(inbound_signal.read_only())
             .keyboard_navigatable()
+            .accessibility(move |a| a.role(Role::CheckBox).checked(inbound_signal.get()))
             .on_click_stop(move |_| {
                 let checked = inbound_signal.get_untracked();
                 outbound_signal.set(!checked);
@@ -40,14 +42,21 @@ pub fn labeled_checkbox<S: Display + 'static>(
     label: impl Fn() -> S + 'static,
 ) -> ValueContainer<bool> {
     let (inbound_signal, outbound_signal) = create_value_container_signals(checked);
+    let label = Rc::new(label);
+    let name = label.clone();
 
     value_container(
         h_stack((
             checkbox_svg(inbound_signal.read_only()),
-            views::label(label),
+            views::label(move || label()),
         ))
         .class(LabeledCheckboxClass)
         .keyboard_navigatable()
+        .accessibility(move |a| {
+            a.role(Role::CheckBox)
+                .name(name().to_string())
+                .checked(inbound_signal.get())
+        })
         .on_click_stop(move |_| {
             let checked = inbound_signal.get_untracked();
             outbound_signal.set(!checked);
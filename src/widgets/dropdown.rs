@@ -5,6 +5,7 @@ use floem_winit::keyboard::{Key, NamedKey};
 use kurbo::{Point, Rect};
 
 use crate::{
+    accessibility::{Accessibility, Role},
     action::{add_overlay, remove_overlay},
     event::{Event, EventListener},
     id::Id,
@@ -236,6 +237,7 @@ where
     let main_fn = Box::new(as_child_of_current_scope(main_view));
 
     let (child, main_view_scope) = main_fn(initial);
+    dropdown_id.update_accessibility(dropdown_accessibility(false));
 
     DropDown {
         view_data: ViewData::new(dropdown_id),
@@ -253,6 +255,10 @@ where
     .class(DropDownClass)
 }
 
+fn dropdown_accessibility(expanded: bool) -> Accessibility {
+    Accessibility::new().role(Role::ComboBox).expanded(expanded)
+}
+
 impl<T> DropDown<T> {
     pub fn show_list(self, show: impl Fn() -> bool + 'static) -> Self {
         let id = self.id();
@@ -291,6 +297,7 @@ impl<T> DropDown<T> {
                 let point =
                     self.window_origin.unwrap_or_default() + (0., layout.size.height as f64);
                 self.create_overlay(point);
+                self.id().update_accessibility(dropdown_accessibility(true));
 
                 if let Some(on_open) = &self.on_open {
                     on_open(true);
@@ -302,6 +309,8 @@ impl<T> DropDown<T> {
     fn close_dropdown(&mut self) {
         if let Some(id) = self.overlay_id.take() {
             remove_overlay(id);
+            self.id()
+                .update_accessibility(dropdown_accessibility(false));
             if let Some(on_open) = &self.on_open {
                 on_open(false);
             }
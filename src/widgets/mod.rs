@@ -6,7 +6,7 @@
 use crate::{
     style::{Background, CursorStyle, Foreground, Style, Transition},
     unit::{PxPct, UnitExt},
-    views::scroll,
+    views::{self, scroll},
     widgets::{self, slider::SliderClass},
 };
 use floem_peniko::Color;
@@ -216,6 +216,7 @@ pub(crate) fn default_theme() -> Theme {
         .apply(focus_style.clone())
         .cursor(CursorStyle::Text)
         .padding(padding)
+        .invalid(|s| s.border_color(Color::rgb8(220, 60, 60)))
         .disabled(|s| {
             s.background(Color::rgb8(180, 188, 175).with_alpha_factor(0.3))
                 .color(Color::GRAY)
@@ -235,15 +236,38 @@ pub(crate) fn default_theme() -> Theme {
 
     let theme = Style::new()
         .class(ListClass, |s| {
-            s.focus(|s| s.class(ListItemClass, |_| item_focused_style))
-                .class(ListItemClass, |_| item_unfocused_style)
+            s.focus(|s| s.class(ListItemClass, |_| item_focused_style.clone()))
+                .class(ListItemClass, |_| item_unfocused_style.clone())
+        })
+        .class(views::TableClass, |s| {
+            s.focus(|s| s.class(views::TableRowClass, |_| item_focused_style.clone()))
+                .class(views::TableRowClass, |_| item_unfocused_style.clone())
+                .class(views::TableHeaderClass, |s| {
+                    s.background(Color::rgb8(238, 238, 238))
+                        .border_bottom(1.0)
+                        .border_color(border)
+                })
+                .class(views::TableHeaderCellClass, |s| s.padding_left(padding))
+                .class(views::TableCellClass, |s| s.padding_horiz(padding))
+                .class(views::ColumnResizeHandleClass, |s| {
+                    s.hover(|s| s.background(border))
+                        .active(|s| s.background(border))
+                })
+        })
+        .class(views::TreeViewClass, |s| {
+            s.focus(|s| s.class(views::TreeRowClass, |_| item_focused_style))
+                .class(views::TreeRowClass, |_| item_unfocused_style)
+                .class(views::TreeGuideClass, |s| {
+                    s.border_left(1.0).border_color(border)
+                })
         })
         .class(LabeledCheckboxClass, |_| labeled_checkbox_style)
         .class(CheckboxClass, |_| checkbox_style)
         .class(RadioButtonClass, |_| radio_button_style)
         .class(RadioButtonDotClass, |_| radio_button_dot_style)
         .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
-        .class(TextInputClass, |_| input_style)
+        .class(TextInputClass, |_| input_style.clone())
+        .class(TextAreaClass, |_| input_style)
         .class(ButtonClass, |_| button_style)
         .class(scroll::Handle, |s| {
             s.border_radius(4.0)
@@ -7,6 +7,8 @@ use floem_winit::keyboard::{Key, NamedKey};
 use kurbo::{Circle, Point, RoundedRect};
 
 use crate::{
+    accessibility::{Accessibility, Role},
+    brush::Brush,
     prop, prop_extractor,
     style::{Background, BorderRadius, Foreground, Height, Style, StyleValue},
     style_class,
@@ -160,6 +162,7 @@ impl Widget for Slider {
             match *update {
                 SliderUpdate::Percent(percent) => self.percent = percent,
             }
+            self.update_accessibility();
             cx.request_layout(self.id());
         }
     }
@@ -222,6 +225,7 @@ impl Widget for Slider {
         self.update_restrict_position();
 
         if pos_changed && self.percent != self.prev_percent {
+            self.update_accessibility();
             if let Some(onchangepx) = &self.onchangepx {
                 onchangepx(self.handle_center());
             }
@@ -316,17 +320,19 @@ impl Widget for Slider {
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
-        cx.fill(
-            &self.base_bar,
-            self.base_bar_style.color().unwrap_or(Color::BLACK),
-            0.,
-        );
+        let base_bar = self
+            .base_bar_style
+            .color()
+            .unwrap_or(Brush::Solid(Color::BLACK))
+            .resolve(self.base_bar.rect());
+        cx.fill(&self.base_bar, &base_bar, 0.);
         cx.clip(&self.base_bar);
-        cx.fill(
-            &self.accent_bar,
-            self.accent_bar_style.color().unwrap_or(Color::TRANSPARENT),
-            0.,
-        );
+        let accent_bar = self
+            .accent_bar_style
+            .color()
+            .unwrap_or(Brush::Solid(Color::TRANSPARENT))
+            .resolve(self.accent_bar.rect());
+        cx.fill(&self.accent_bar, &accent_bar, 0.);
 
         if let Some(color) = self.style.foreground() {
             cx.clear_clip();
@@ -339,6 +345,15 @@ impl Slider {
         self.percent = self.percent.clamp(0., 100.);
     }
 
+    fn update_accessibility(&self) {
+        self.id().update_accessibility(
+            Accessibility::new()
+                .role(Role::Slider)
+                .numeric_value(self.percent as f64, 0.0, 100.0)
+                .step(10.0),
+        );
+    }
+
     fn handle_center(&self) -> f32 {
         let width = self.size.width - self.handle.radius as f32 * 2.;
         width * (self.percent / 100.) + self.handle.radius as f32
@@ -405,8 +420,8 @@ impl SliderCustomStyle {
     /// Sets the color of the slider's bar.
     ///
     /// # Arguments
-    /// * `color` - A `StyleValue<Color>` that sets the bar's background color.
-    pub fn bar_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+    /// * `color` - A `StyleValue<Brush>` that sets the bar's background color or gradient.
+    pub fn bar_color(mut self, color: impl Into<StyleValue<Brush>>) -> Self {
         self = SliderCustomStyle(self.0.class(BarClass, |s| s.background(color)));
         self
     }
@@ -432,8 +447,8 @@ impl SliderCustomStyle {
     /// Sets the color of the slider's accent bar.
     ///
     /// # Arguments
-    /// * `color` - A `StyleValue<Color>` that sets the accent bar's background color.
-    pub fn accent_bar_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+    /// * `color` - A `StyleValue<Brush>` that sets the accent bar's background color or gradient.
+    pub fn accent_bar_color(mut self, color: impl Into<StyleValue<Brush>>) -> Self {
         self = SliderCustomStyle(self.0.class(AccentBarClass, |s| s.background(color)));
         self
     }
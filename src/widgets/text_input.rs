@@ -1,19 +1,64 @@
 use crate::{
     style_class,
-    views::{self, Decorators, TextInput},
+    views::{self, Decorators, InputMask, TextInput},
 };
 use floem_reactive::RwSignal;
 
 style_class!(pub TextInputClass);
 style_class!(pub PlaceholderTextClass);
+style_class!(pub TextAreaClass);
 
 pub fn text_input(buffer: RwSignal<String>) -> TextInput {
     views::text_input(buffer).class(TextInputClass)
 }
 
+/// A multi-line [`text_area`](views::text_area) with the theme's text input style
+#[cfg(feature = "editor")]
+pub fn text_area(value: RwSignal<String>) -> views::TextArea {
+    views::text_area(value).class(TextAreaClass)
+}
+
+#[cfg(feature = "editor")]
+impl views::TextArea {
+    pub fn placeholder(mut self, text: impl Into<String>) -> Self {
+        self.placeholder_text = Some(text.into());
+        self
+    }
+}
+
 impl TextInput {
     pub fn placeholder(mut self, text: impl Into<String>) -> Self {
         self.placeholder_text = Some(text.into());
         self
     }
+
+    /// Show every character as a bullet, and keep the text from being copied or seen by the IME
+    pub fn password(self) -> Self {
+        self.obscure_with('•')
+    }
+
+    /// Show every character as `obscure`, and keep the text from being copied or seen by the IME
+    pub fn obscure_with(mut self, obscure: char) -> Self {
+        self.obscure = Some(obscure);
+        self
+    }
+
+    /// The most characters the text can have. Typed or pasted text beyond it is cut off.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Reject edits which would leave text the `mask` doesn't accept
+    pub fn mask(mut self, mask: InputMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Check the text whenever it changes. While `validator` returns false the input is styled
+    /// with its [`invalid`](crate::style::Style::invalid) style.
+    pub fn validator(mut self, validator: impl Fn(&str) -> bool + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
 }
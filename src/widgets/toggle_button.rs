@@ -7,6 +7,7 @@ use floem_winit::keyboard::{Key, NamedKey};
 use kurbo::{Point, Size};
 
 use crate::{
+    brush::Brush,
     prop, prop_extractor,
     style::{self, Foreground, Style, StyleValue},
     style_class,
@@ -313,8 +314,8 @@ impl ToggleButtonCustomStyle {
     /// Sets the accent color of the toggle button.
     ///
     /// # Arguments
-    /// * `color` - A `StyleValue<Color>` that sets the toggle button's accent color. This is the same as the background color.
-    pub fn accent_color(mut self, color: impl Into<StyleValue<Color>>) -> Self {
+    /// * `color` - A `StyleValue<Brush>` that sets the toggle button's accent color. This is the same as the background color.
+    pub fn accent_color(mut self, color: impl Into<StyleValue<Brush>>) -> Self {
         self = Self(self.0.background(color));
         self
     }
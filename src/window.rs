@@ -7,6 +7,8 @@ pub use floem_winit::window::WindowLevel;
 use kurbo::{Point, Size};
 
 use crate::app::{add_app_update_event, AppUpdateEvent};
+#[cfg(feature = "serde")]
+use crate::persist::WindowState;
 use crate::view::View;
 
 #[derive(Default, Debug)]
@@ -22,6 +24,8 @@ pub struct WindowConfig {
     pub(crate) resizable: Option<bool>,
     pub(crate) window_level: Option<WindowLevel>,
     pub(crate) apply_default_theme: Option<bool>,
+    #[cfg(feature = "serde")]
+    pub(crate) restore_state: Option<String>,
 }
 
 impl WindowConfig {
@@ -82,6 +86,24 @@ impl WindowConfig {
         self.apply_default_theme = Some(apply_default_theme);
         self
     }
+
+    /// Restore the size, position and maximized state the window had when it was last closed,
+    /// which is saved under `key` with the [persisted signals](crate::persist). The size and
+    /// position set on this config are used when nothing was saved yet.
+    #[cfg(feature = "serde")]
+    pub fn restore_state(mut self, key: impl Into<String>) -> Self {
+        self.restore_state = Some(key.into());
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn with_state(mut self, state: WindowState) -> Self {
+        self.size = Some(state.size);
+        if let Some(position) = state.position {
+            self.position = Some(position);
+        }
+        self
+    }
 }
 
 /// create a new window. You'll need to create Application first, otherwise it
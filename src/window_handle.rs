@@ -1,5 +1,7 @@
 use std::{
+    collections::HashSet,
     mem,
+    path::PathBuf,
     rc::Rc,
     time::{Duration, Instant},
 };
@@ -21,15 +23,17 @@ use crate::unit::UnitExt;
 #[cfg(target_os = "linux")]
 use crate::views::{container, stack, Decorators};
 use crate::{
+    accessibility::{self, adapter::Adapter, Action, ActionData, ActionRequest, TreeUpdate},
     animate::{AnimPropKind, AnimUpdateMsg, AnimValue, AnimatedProp, SizeUnit},
+    clock,
     context::{
         AppState, ComputeLayoutCx, EventCx, FrameUpdate, LayoutCx, MoveListener, PaintCx,
         PaintState, ResizeListener, StyleCx, UpdateCx,
     },
-    event::{Event, EventListener},
+    event::{Event, EventListener, FileDragEvent},
     id::{Id, IdPath, ID_PATHS},
     inspector::{self, Capture, CaptureState, CapturedView},
-    keyboard::KeyEvent,
+    keyboard::{KeyEvent, KeyInfo},
     menu::Menu,
     nav::view_arrow_navigation,
     pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
@@ -71,6 +75,8 @@ pub(crate) struct WindowHandle {
     pub(crate) cursor_position: Point,
     pub(crate) window_position: Point,
     pub(crate) last_pointer_down: Option<(u8, Point, Instant)>,
+    /// Sends the accessibility tree to the platform. Headless windows don't have one.
+    pub(crate) accessibility_adapter: Option<Adapter>,
     #[cfg(target_os = "linux")]
     pub(crate) context_menu: RwSignal<Option<(Menu, Point)>>,
 }
@@ -82,15 +88,71 @@ impl WindowHandle {
         transparent: bool,
         apply_default_theme: bool,
     ) -> Self {
-        let scope = Scope::new();
         let window_id = window.id();
-        let id = Id::next();
         let scale = window.scale_factor();
         let size: LogicalSize<f64> = window.inner_size().to_logical(scale);
         let size = Size::new(size.width, size.height);
-        let size = scope.create_rw_signal(Size::new(size.width, size.height));
-        let theme = scope.create_rw_signal(window.theme());
+        let os_theme = window.theme();
         let is_maximized = window.is_maximized();
+        let paint_state = PaintState::new(&window, scale, size * scale);
+
+        Self::new_inner(
+            Some(window),
+            window_id,
+            view_fn,
+            paint_state,
+            scale,
+            size,
+            os_theme,
+            is_maximized,
+            transparent,
+            apply_default_theme,
+        )
+    }
+
+    /// Creates a window handle which isn't backed by a winit window and paints into an
+    /// offscreen pixmap. `size` is in logical pixels.
+    pub(crate) fn new_headless(
+        view_fn: impl FnOnce(floem_winit::window::WindowId) -> AnyView + 'static,
+        size: Size,
+        scale: f64,
+        apply_default_theme: bool,
+    ) -> Self {
+        // SAFETY: The dummy id is never passed to winit as there's no window to refer to.
+        let window_id = unsafe { WindowId::dummy() };
+        let paint_state = PaintState::new_headless(scale, size * scale);
+
+        Self::new_inner(
+            None,
+            window_id,
+            view_fn,
+            paint_state,
+            scale,
+            size,
+            None,
+            false,
+            false,
+            apply_default_theme,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_inner(
+        window: Option<floem_winit::window::Window>,
+        window_id: WindowId,
+        view_fn: impl FnOnce(floem_winit::window::WindowId) -> AnyView + 'static,
+        paint_state: PaintState,
+        scale: f64,
+        size: Size,
+        os_theme: Option<floem_winit::window::Theme>,
+        is_maximized: bool,
+        transparent: bool,
+        apply_default_theme: bool,
+    ) -> Self {
+        let scope = Scope::new();
+        let id = Id::next();
+        let size = scope.create_rw_signal(size);
+        let theme = scope.create_rw_signal(os_theme);
 
         set_current_view(id);
 
@@ -125,9 +187,8 @@ impl WindowHandle {
             overlays: Default::default(),
         };
 
-        let paint_state = PaintState::new(&window, scale, size.get_untracked() * scale);
         let mut window_handle = Self {
-            window: Some(window),
+            window,
             window_id,
             id,
             scope,
@@ -147,8 +208,13 @@ impl WindowHandle {
             #[cfg(target_os = "linux")]
             context_menu,
             last_pointer_down: None,
+            accessibility_adapter: None,
         };
         window_handle.app_state.set_root_size(size.get_untracked());
+        if let Some(window) = window_handle.window.as_ref() {
+            let initial = accessibility::initial_tree_update(id, Some(window.title().as_str()));
+            window_handle.accessibility_adapter = Some(Adapter::new(window, initial));
+        }
         window_handle
     }
 
@@ -171,6 +237,12 @@ impl WindowHandle {
             (None, None)
         };
 
+        let was_file_hovered = if matches!(&event, Event::FileHover(_)) {
+            Some(std::mem::take(&mut cx.app_state.file_hovered))
+        } else {
+            None
+        };
+
         let is_pointer_down = matches!(&event, Event::PointerDown(_));
         let was_focused = if is_pointer_down {
             cx.app_state.clicking.clear();
@@ -319,6 +391,23 @@ impl WindowHandle {
             }
         }
 
+        let file_hover_changed = match &event {
+            Event::FileHover(_) => was_file_hovered
+                .unwrap()
+                .symmetric_difference(&cx.app_state.file_hovered)
+                .copied()
+                .collect(),
+            Event::FileDrop(_) | Event::FileHoverCancelled => {
+                std::mem::take(&mut cx.app_state.file_hovered)
+            }
+            _ => HashSet::new(),
+        };
+        for id in file_hover_changed {
+            if cx.app_state.has_style_for_sel(id, StyleSelector::FileHover) {
+                cx.app_state.request_style_recursive(id);
+            }
+        }
+
         if let Event::PointerDown(event) = &event {
             if cx.app_state.focus.is_none() {
                 if let Some(id) = was_focused {
@@ -357,6 +446,10 @@ impl WindowHandle {
         self.process_update();
     }
 
+    pub(crate) fn size_untracked(&self) -> Size {
+        self.size.get_untracked()
+    }
+
     pub(crate) fn scale(&mut self, scale: f64) {
         self.scale = scale;
         let scale = self.scale * self.app_state.scale;
@@ -377,6 +470,9 @@ impl WindowHandle {
         self.app_state.set_root_size(size);
 
         if let Some(window) = self.window.as_ref() {
+            if let Some(adapter) = self.accessibility_adapter.as_ref() {
+                adapter.window_bounds_changed(window);
+            }
             let is_maximized = window.is_maximized();
             if is_maximized != self.is_maximized {
                 self.is_maximized = is_maximized;
@@ -392,12 +488,15 @@ impl WindowHandle {
 
     pub(crate) fn position(&mut self, point: Point) {
         self.window_position = point;
+        if let (Some(window), Some(adapter)) = (&self.window, &self.accessibility_adapter) {
+            adapter.window_bounds_changed(window);
+        }
         self.event(Event::WindowMoved(point));
     }
 
     pub(crate) fn key_event(&mut self, key_event: floem_winit::event::KeyEvent) {
         let event = KeyEvent {
-            key: key_event,
+            key: key_event.into(),
             modifiers: self.modifiers,
         };
         if event.key.state.is_pressed() {
@@ -418,6 +517,24 @@ impl WindowHandle {
         }
     }
 
+    pub(crate) fn file_hover(&mut self, path: PathBuf) {
+        self.event(Event::FileHover(FileDragEvent {
+            path,
+            pos: self.cursor_position,
+        }));
+    }
+
+    pub(crate) fn file_drop(&mut self, path: PathBuf) {
+        self.event(Event::FileDrop(FileDragEvent {
+            path,
+            pos: self.cursor_position,
+        }));
+    }
+
+    pub(crate) fn file_hover_cancelled(&mut self) {
+        self.event(Event::FileHoverCancelled);
+    }
+
     pub(crate) fn pointer_leave(&mut self) {
         set_current_view(self.id);
         let mut cx = EventCx {
@@ -466,18 +583,18 @@ impl WindowHandle {
             if let Some((count, last_pos, instant)) = self.last_pointer_down.as_mut() {
                 if *count == 4 {
                     *count = 1;
-                } else if instant.elapsed().as_millis() < 500
+                } else if clock::now().saturating_duration_since(*instant).as_millis() < 500
                     && last_pos.distance(self.cursor_position) < 4.0
                 {
                     *count += 1;
                 } else {
                     *count = 1;
                 }
-                *instant = Instant::now();
+                *instant = clock::now();
                 *last_pos = self.cursor_position;
                 *count
             } else {
-                self.last_pointer_down = Some((1, self.cursor_position, Instant::now()));
+                self.last_pointer_down = Some((1, self.cursor_position, clock::now()));
                 1
             }
         } else {
@@ -500,6 +617,9 @@ impl WindowHandle {
     }
 
     pub(crate) fn focused(&mut self, focused: bool) {
+        if let Some(adapter) = self.accessibility_adapter.as_ref() {
+            adapter.focused(focused);
+        }
         if focused {
             self.event(Event::WindowGotFocus);
         } else {
@@ -536,8 +656,21 @@ impl WindowHandle {
         cx.compute_view_layout(&mut self.view);
     }
 
-    pub fn render_frame(&mut self) {
-        // Processes updates scheduled on this frame.
+    pub fn render_frame(&mut self) -> Option<DynamicImage> {
+        self.process_scheduled_updates();
+        self.process_update_no_paint();
+        let img = self.paint();
+
+        // Request a new frame if there's any scheduled updates.
+        if !self.app_state.scheduled_updates.is_empty() {
+            self.schedule_repaint();
+        }
+
+        img
+    }
+
+    /// Processes updates scheduled on this frame.
+    pub(crate) fn process_scheduled_updates(&mut self) {
         for update in mem::take(&mut self.app_state.scheduled_updates) {
             match update {
                 FrameUpdate::Style(id) => self.app_state.request_style(id),
@@ -545,14 +678,18 @@ impl WindowHandle {
                 FrameUpdate::Paint(id) => self.app_state.request_paint(id),
             }
         }
+    }
 
-        self.process_update_no_paint();
-        self.paint();
+    pub(crate) fn root_widget(&self) -> &dyn Widget {
+        &self.view
+    }
 
-        // Request a new frame if there's any scheduled updates.
-        if !self.app_state.scheduled_updates.is_empty() {
-            self.schedule_repaint();
-        }
+    pub(crate) fn app_state(&self) -> &AppState {
+        &self.app_state
+    }
+
+    pub(crate) fn app_state_mut(&mut self) -> &mut AppState {
+        &mut self.app_state
     }
 
     pub fn paint(&mut self) -> Option<DynamicImage> {
@@ -594,7 +731,122 @@ impl WindowHandle {
                 window.pre_present_notify();
             }
         }
-        cx.paint_state.renderer.finish()
+        let img = cx.paint_state.renderer.finish();
+        if let Some(adapter) = self.accessibility_adapter.as_ref() {
+            adapter.update_if_active(|| self.build_accessibility_tree());
+        }
+        img
+    }
+
+    fn build_accessibility_tree(&self) -> TreeUpdate {
+        let title = self.window.as_ref().map(|window| window.title());
+        accessibility::build_tree_update(
+            &self.view,
+            &self.app_state,
+            self.app_state.scale * self.scale,
+            title.as_deref(),
+        )
+    }
+
+    /// Processes pending updates and returns the current accessibility tree.
+    pub(crate) fn accessibility_tree(&mut self) -> TreeUpdate {
+        self.process_update_no_paint();
+        self.build_accessibility_tree()
+    }
+
+    /// Called when an assistive technology started using the tree. The adapter only got the
+    /// window node, so paint to send it the full tree.
+    pub(crate) fn accessibility_activated(&mut self) {
+        self.schedule_repaint();
+    }
+
+    /// Performs an action requested by an assistive technology by sending the events a user
+    /// would have produced.
+    pub(crate) fn accessibility_action(&mut self, request: ActionRequest) {
+        let Some(id) = accessibility::view_for_node(&self.app_state, request.target) else {
+            return;
+        };
+        if self.app_state.is_disabled(&id) && request.action != Action::ScrollIntoView {
+            return;
+        }
+        match request.action {
+            Action::Focus => {
+                if self.app_state.can_focus(id) {
+                    id.request_focus();
+                }
+            }
+            Action::Blur => {
+                if self.app_state.is_focused(&id) {
+                    id.clear_focus();
+                }
+            }
+            Action::Default | Action::Expand | Action::Collapse => {
+                let expanded = self
+                    .app_state
+                    .view_states
+                    .get(&id)
+                    .and_then(|state| state.accessibility.as_ref())
+                    .and_then(|info| info.expanded);
+                let wanted = match request.action {
+                    Action::Expand => Some(true),
+                    Action::Collapse => Some(false),
+                    _ => None,
+                };
+                if wanted.is_some() && wanted == expanded {
+                    return;
+                }
+                // Click in the center of the view without moving the real pointer.
+                let event = PointerInputEvent {
+                    pos: self.app_state.get_layout_rect(id).center(),
+                    button: PointerButton::Primary,
+                    modifiers: self.modifiers,
+                    count: 1,
+                };
+                self.event(Event::PointerDown(event.clone()));
+                self.event(Event::PointerUp(event));
+            }
+            Action::Increment | Action::Decrement => {
+                let key = if request.action == Action::Increment {
+                    NamedKey::ArrowRight
+                } else {
+                    NamedKey::ArrowLeft
+                };
+                if self.app_state.focus != Some(id) && self.app_state.can_focus(id) {
+                    id.request_focus();
+                    self.process_update_messages();
+                }
+                let key = KeyInfo::pressed(Key::Named(key));
+                self.event(Event::KeyDown(KeyEvent {
+                    key: key.clone(),
+                    modifiers: self.modifiers,
+                }));
+                self.event(Event::KeyUp(KeyEvent {
+                    key: key.released(),
+                    modifiers: self.modifiers,
+                }));
+            }
+            Action::SetValue => {
+                let value = match request.data {
+                    Some(ActionData::Value(value)) => value.to_string(),
+                    Some(ActionData::NumericValue(value)) => value.to_string(),
+                    _ => return,
+                };
+                let on_set_value = self
+                    .app_state
+                    .view_states
+                    .get(&id)
+                    .and_then(|state| state.accessibility.as_ref())
+                    .and_then(|info| info.on_set_value.clone());
+                if let Some(on_set_value) = on_set_value {
+                    on_set_value(value);
+                }
+            }
+            Action::ScrollIntoView => {
+                id.scroll_to(None);
+            }
+            _ => {}
+        }
+        self.process_update();
     }
 
     pub(crate) fn capture(&mut self) -> Capture {
@@ -823,6 +1075,10 @@ impl WindowHandle {
                         state.classes.push(class);
                         cx.app_state.request_style_recursive(id);
                     }
+                    UpdateMessage::DebugName { id, name } => {
+                        let state = cx.app_state.view_state(id);
+                        state.debug_name.push(name);
+                    }
                     UpdateMessage::StyleSelector {
                         id,
                         style,
@@ -842,6 +1098,11 @@ impl WindowHandle {
                     UpdateMessage::Draggable { id } => {
                         cx.app_state.draggable.insert(id);
                     }
+                    UpdateMessage::Accessibility { id, accessibility } => {
+                        let state = cx.app_state.view_state(id);
+                        state.accessibility = Some(accessibility);
+                        cx.app_state.request_paint = true;
+                    }
                     UpdateMessage::DragWindow => {
                         if let Some(window) = self.window.as_ref() {
                             let _ = window.drag_window();
@@ -1069,6 +1330,13 @@ impl WindowHandle {
                         self.app_state.request_style(view_id)
                     }
                 }
+                AnimUpdateMsg::Seek(anim_id) => {
+                    // The animation may not be attached to a view yet, in which case it picks up
+                    // the position of its timeline when it's first styled
+                    if let Some(view_id) = self.app_state.find_view_id_by_anim_id(anim_id) {
+                        self.app_state.request_style(view_id)
+                    }
+                }
             }
         }
     }
@@ -1112,10 +1380,7 @@ impl WindowHandle {
         };
 
         // Overrides the old value
-        // TODO: logic based on the old val to make the animation smoother when overriding an old
-        // animation that was in progress
-        anim.props_mut().insert(kind, prop);
-        anim.start();
+        anim.retarget(kind, prop);
 
         self.app_state.request_style(view_id);
     }
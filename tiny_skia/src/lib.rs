@@ -1,19 +1,21 @@
 use anyhow::{anyhow, Result};
 use floem_peniko::kurbo::PathEl;
 use floem_peniko::{
-    kurbo::{Affine, Point, Rect, Shape},
-    BrushRef, Color, GradientKind,
+    kurbo::{Affine, Point, Rect, Shape, Vec2},
+    BrushRef, Color, ColorStop, GradientKind,
 };
 use floem_renderer::cosmic_text::{CacheKey, SubpixelBin, SwashCache, SwashContent, TextLayout};
 use floem_renderer::tiny_skia::{
     self, FillRule, FilterQuality, GradientStop, LinearGradient, Mask, MaskType, Paint, Path,
-    PathBuilder, Pattern, Pixmap, RadialGradient, Shader, SpreadMode, Stroke, Transform,
+    PathBuilder, Pattern, Pixmap, PixmapPaint, RadialGradient, Shader, SpreadMode, Stroke,
+    Transform,
 };
 use floem_renderer::Img;
 use floem_renderer::Renderer;
 use image::DynamicImage;
 use softbuffer::{Context, Surface};
 use std::collections::HashMap;
+use std::mem;
 use std::num::NonZeroU32;
 use std::rc::Rc;
 
@@ -36,15 +38,34 @@ struct Glyph {
 #[derive(PartialEq, Clone, Copy)]
 struct CacheColor(bool);
 
-pub struct TinySkiaRenderer {
+/// What's below a pushed layer, which is restored when it's popped.
+struct Layer {
+    /// The pixmap and clip mask below the layer, or `None` if the layer couldn't be created and
+    /// its content is painted directly onto the pixmap below.
+    below: Option<(Pixmap, Mask, (i32, i32))>,
+    opacity: f32,
+}
+
+/// The window surface the pixmap is presented to.
+struct WindowSurface {
     #[allow(unused)]
     context: Context,
     surface: Surface,
+}
+
+pub struct TinySkiaRenderer {
+    /// This is `None` for headless renderers, which only paint into `pixmap`.
+    window_surface: Option<WindowSurface>,
     pixmap: Pixmap,
     mask: Mask,
     scale: f64,
     transform: Affine,
     clip: Option<Rect>,
+    /// The pushed layers. While a layer is pushed, `pixmap` and `mask` are the offscreen pixmap
+    /// of that layer, which only covers the clip bounds at the time it was pushed.
+    layers: Vec<Layer>,
+    /// The position of `pixmap` in the window, in physical pixels.
+    layer_offset: (i32, i32),
 
     /// The cache color value set for cache entries accessed this frame.
     cache_color: CacheColor,
@@ -71,19 +92,28 @@ impl TinySkiaRenderer {
                 .map_err(|err| anyhow!("unable to create surface: {}", err))?
         };
 
+        let mut renderer = Self::new_headless(width, height, scale)?;
+        renderer.window_surface = Some(WindowSurface { context, surface });
+        Ok(renderer)
+    }
+
+    /// Creates a renderer which isn't attached to a window. The painted pixmap is returned
+    /// as an image from [`Renderer::finish`].
+    pub fn new_headless(width: u32, height: u32, scale: f64) -> Result<Self> {
         let pixmap =
             Pixmap::new(width, height).ok_or_else(|| anyhow!("unable to create pixmap"))?;
 
         let mask = Mask::new(width, height).ok_or_else(|| anyhow!("unable to create mask"))?;
 
         Ok(Self {
-            context,
-            surface,
+            window_surface: None,
             pixmap,
             mask,
             scale,
             transform: Affine::IDENTITY,
             clip: None,
+            layers: Vec::new(),
+            layer_offset: (0, 0),
             cache_color: CacheColor(false),
             image_cache: Default::default(),
             glyph_cache: Default::default(),
@@ -91,13 +121,16 @@ impl TinySkiaRenderer {
     }
 
     pub fn resize(&mut self, width: u32, height: u32, scale: f64) {
-        if width != self.pixmap.width() || height != self.pixmap.width() {
-            self.surface
-                .resize(
-                    NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
-                    NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
-                )
-                .expect("failed to resize surface");
+        if width != self.pixmap.width() || height != self.pixmap.height() {
+            if let Some(window_surface) = self.window_surface.as_mut() {
+                window_surface
+                    .surface
+                    .resize(
+                        NonZeroU32::new(width).unwrap_or(NonZeroU32::new(1).unwrap()),
+                        NonZeroU32::new(height).unwrap_or(NonZeroU32::new(1).unwrap()),
+                    )
+                    .expect("failed to resize surface");
+            }
             self.pixmap = Pixmap::new(width, height).expect("unable to create pixmap");
             self.mask = Mask::new(width, height).expect("unable to create mask");
         }
@@ -107,6 +140,26 @@ impl TinySkiaRenderer {
     pub fn set_scale(&mut self, scale: f64) {
         self.scale = scale;
     }
+
+    /// Returns `true` if this renderer isn't presenting to a window.
+    pub fn is_headless(&self) -> bool {
+        self.window_surface.is_none()
+    }
+
+    /// Copies the current pixmap into an image, converting from premultiplied alpha.
+    fn pixmap_image(&self) -> Option<DynamicImage> {
+        let data = self
+            .pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let pixel = pixel.demultiply();
+                [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            })
+            .collect();
+        image::RgbaImage::from_raw(self.pixmap.width(), self.pixmap.height(), data)
+            .map(DynamicImage::ImageRgba8)
+    }
 }
 
 fn to_color(color: Color) -> tiny_skia::Color {
@@ -117,6 +170,47 @@ fn to_point(point: Point) -> tiny_skia::Point {
     tiny_skia::Point::from_xy(point.x as f32, point.y as f32)
 }
 
+/// Samples the color at `t` between gradient stops.
+fn sample_stops(stops: &[ColorStop], t: f32) -> Color {
+    let Some(first) = stops.first() else {
+        return Color::TRANSPARENT;
+    };
+    if t <= first.offset {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if t <= b.offset {
+            let span = b.offset - a.offset;
+            if span <= f32::EPSILON {
+                return b.color;
+            }
+            let t = (t - a.offset) / span;
+            let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            return Color::rgba8(
+                lerp(a.color.r, b.color.r),
+                lerp(a.color.g, b.color.g),
+                lerp(a.color.b, b.color.b),
+                lerp(a.color.a, b.color.a),
+            );
+        }
+    }
+    stops[stops.len() - 1].color
+}
+
+fn pattern_paint(pixmap: &Pixmap, transform: Transform) -> Paint<'_> {
+    Paint {
+        shader: Pattern::new(
+            pixmap.as_ref(),
+            SpreadMode::Pad,
+            FilterQuality::Bilinear,
+            1.0,
+            transform,
+        ),
+        ..Default::default()
+    }
+}
+
 impl TinySkiaRenderer {
     fn shape_to_path(&self, shape: &impl Shape) -> Option<Path> {
         let mut builder = PathBuilder::new();
@@ -202,7 +296,7 @@ impl TinySkiaRenderer {
         } else {
             return Some(rect);
         };
-        let clip = self.rect(clip.scale_from_origin(self.scale))?;
+        let clip = self.rect(clip.scale_from_origin(self.scale) - self.layer_offset_vec())?;
         clip.intersect(&rect)
     }
 
@@ -241,7 +335,8 @@ impl TinySkiaRenderer {
                 Transform::from_scale(
                     rect.width() / pixmap.width() as f32,
                     rect.height() / pixmap.height() as f32,
-                ),
+                )
+                .post_translate(rect.x(), rect.y()),
             ),
             ..Default::default()
         };
@@ -285,6 +380,10 @@ impl TinySkiaRenderer {
         self.render_pixmap_rect(&fill, rect);
     }
 
+    fn layer_offset_vec(&self) -> Vec2 {
+        Vec2::new(self.layer_offset.0 as f64, self.layer_offset.1 as f64)
+    }
+
     fn current_transform(&self) -> Transform {
         let transfrom = self.transform.as_coeffs();
         let scale = self.scale as f32;
@@ -297,6 +396,204 @@ impl TinySkiaRenderer {
             transfrom[5] as f32,
         )
         .post_scale(scale, scale)
+        .post_translate(-self.layer_offset.0 as f32, -self.layer_offset.1 as f32)
+    }
+
+    /// Draws the glyphs of `layout`. If `paint` is set, it's used instead of the glyph colors.
+    fn draw_text_inner(&mut self, layout: &TextLayout, pos: Point, paint: Option<&Paint>) {
+        let [xx, yx, xy, yy, _, _] = self.transform.as_coeffs();
+        if (xx, yx, xy, yy) != (1.0, 0.0, 0.0, 1.0) {
+            return self.draw_text_transformed(layout, pos, paint);
+        }
+
+        let offset = self.transform.translation();
+        let clip = self.clip;
+        for line in layout.layout_runs() {
+            if let Some(rect) = clip {
+                let y = pos.y + offset.y + line.line_y as f64;
+                if y + (line.line_height as f64) < rect.y0 {
+                    continue;
+                }
+                if y - (line.line_height as f64) > rect.y1 {
+                    break;
+                }
+            }
+
+            'line_loop: for glyph_run in line.glyphs {
+                let x = glyph_run.x + pos.x as f32 + offset.x as f32;
+                let y = line.line_y + pos.y as f32 + offset.y as f32;
+
+                if let Some(rect) = clip {
+                    if ((x + glyph_run.w) as f64) < rect.x0 {
+                        continue;
+                    } else if x as f64 > rect.x1 {
+                        break 'line_loop;
+                    }
+                }
+
+                let glyph_x = x * self.scale as f32;
+                let (new_x, subpx_x) = SubpixelBin::new(glyph_x);
+                let glyph_x = new_x as f32;
+
+                let glyph_y = (y * self.scale as f32).round();
+                let (new_y, subpx_y) = SubpixelBin::new(glyph_y);
+                let glyph_y = new_y as f32;
+
+                let font_size = (glyph_run.font_size * self.scale as f32).round() as u32;
+
+                let mut cache_key = glyph_run.cache_key;
+                cache_key.font_size = font_size;
+                cache_key.x_bin = subpx_x;
+                cache_key.y_bin = subpx_y;
+
+                // With a paint the glyphs are cached in white so their alpha can be used as
+                // a mask.
+                let color = if paint.is_some() {
+                    Color::WHITE
+                } else {
+                    glyph_run.color
+                };
+                let glyph = self.cache_glyph(cache_key, color);
+
+                if let Some(glyph) = glyph {
+                    let x = glyph_x + glyph.left - self.layer_offset.0 as f32;
+                    let y = glyph_y - glyph.top - self.layer_offset.1 as f32;
+                    match paint {
+                        Some(paint) => {
+                            let shader_transform = self.current_transform().post_translate(-x, -y);
+                            if let Some(pixmap) = self.paint_glyph(&glyph, shader_transform, paint)
+                            {
+                                self.render_pixmap_direct(&pixmap, x, y);
+                            }
+                        }
+                        None => self.render_pixmap_direct(&glyph.pixmap, x, y),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the glyphs of `layout` when the transform scales or rotates. The glyphs are
+    /// rasterized at the window scale and then drawn with the full transform.
+    fn draw_text_transformed(&mut self, layout: &TextLayout, pos: Point, paint: Option<&Paint>) {
+        let scale = self.scale as f32;
+        let (_, subpx) = SubpixelBin::new(0.0);
+        for line in layout.layout_runs() {
+            for glyph_run in line.glyphs {
+                let mut cache_key = glyph_run.cache_key;
+                cache_key.font_size = (glyph_run.font_size * scale).round() as u32;
+                cache_key.x_bin = subpx;
+                cache_key.y_bin = subpx;
+
+                let color = if paint.is_some() {
+                    Color::WHITE
+                } else {
+                    glyph_run.color
+                };
+                let Some(glyph) = self.cache_glyph(cache_key, color) else {
+                    continue;
+                };
+
+                let x = glyph_run.x + pos.x as f32 + glyph.left / scale;
+                let y = line.line_y + pos.y as f32 - glyph.top / scale;
+                let Some(rect) = tiny_skia::Rect::from_xywh(
+                    x,
+                    y,
+                    glyph.pixmap.width() as f32 / scale,
+                    glyph.pixmap.height() as f32 / scale,
+                ) else {
+                    continue;
+                };
+                match paint {
+                    Some(paint) => {
+                        let shader_transform =
+                            Transform::from_translate(-x, -y).post_scale(scale, scale);
+                        if let Some(pixmap) = self.paint_glyph(&glyph, shader_transform, paint) {
+                            self.render_pixmap_rect(&pixmap, rect);
+                        }
+                    }
+                    None => self.render_pixmap_rect(&glyph.pixmap, rect),
+                }
+            }
+        }
+    }
+
+    /// Paints `paint` into a pixmap the size of `glyph`, using the glyph as an alpha mask.
+    /// `shader_transform` maps from user space to the pixels of the glyph.
+    fn paint_glyph(
+        &self,
+        glyph: &Glyph,
+        shader_transform: Transform,
+        paint: &Paint,
+    ) -> Option<Pixmap> {
+        let mut pixmap = Pixmap::new(glyph.pixmap.width(), glyph.pixmap.height())?;
+        let mut paint = paint.clone();
+        paint.shader.transform(shader_transform);
+        let rect =
+            tiny_skia::Rect::from_xywh(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32)?;
+        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        for (pixel, mask) in pixmap.pixels_mut().iter_mut().zip(glyph.pixmap.pixels()) {
+            let alpha = mask.alpha() as u16;
+            let scale = |c: u8| ((c as u16 * alpha + 127) / 255) as u8;
+            *pixel = tiny_skia::PremultipliedColorU8::from_rgba(
+                scale(pixel.red()),
+                scale(pixel.green()),
+                scale(pixel.blue()),
+                scale(pixel.alpha()),
+            )?;
+        }
+        Some(pixmap)
+    }
+
+    /// tiny-skia doesn't support sweep gradients, so they're rendered into a pixmap covering
+    /// `bounds` (in local coordinates) and painted as a pattern. Returns the pixmap and the
+    /// pattern transform, or `None` if `brush` isn't a sweep gradient.
+    fn sweep_pixmap(&self, brush: &BrushRef, bounds: Rect) -> Option<(Pixmap, Transform)> {
+        const MAX_SIZE: f64 = 4096.0;
+
+        let BrushRef::Gradient(gradient) = brush else {
+            return None;
+        };
+        let GradientKind::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        } = gradient.kind
+        else {
+            return None;
+        };
+
+        let scale = self
+            .scale
+            .min(MAX_SIZE / bounds.width().max(bounds.height()).max(1.0));
+        let width = (bounds.width() * scale).ceil().max(1.0) as u32;
+        let height = (bounds.height() * scale).ceil().max(1.0) as u32;
+        let mut pixmap = Pixmap::new(width, height)?;
+
+        let sweep = (end_angle - start_angle) as f64;
+        for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+            let x = bounds.x0 + ((i as u32 % width) as f64 + 0.5) / scale;
+            let y = bounds.y0 + ((i as u32 / width) as f64 + 0.5) / scale;
+            let angle = (y - center.y).atan2(x - center.x) - start_angle as f64;
+            let t = if sweep.abs() > f64::EPSILON {
+                (angle.rem_euclid(std::f64::consts::TAU) / sweep) as f32
+            } else {
+                0.0
+            };
+            *pixel = to_color(sample_stops(&gradient.stops, t))
+                .premultiply()
+                .to_color_u8();
+        }
+
+        let transform = Transform::from_row(
+            (1.0 / scale) as f32,
+            0.0,
+            0.0,
+            (1.0 / scale) as f32,
+            bounds.x0 as f32,
+            bounds.y0 as f32,
+        );
+        Some((pixmap, transform))
     }
 
     fn cache_glyph(&mut self, cache_key: CacheKey, color: Color) -> Option<Rc<Glyph>> {
@@ -338,15 +635,69 @@ impl TinySkiaRenderer {
     }
 }
 
+impl TinySkiaRenderer {
+    /// Creates the pixmap, mask and window position of a layer covering the part of `pixmap`
+    /// inside the current clip.
+    fn new_layer(&self) -> Option<(Pixmap, Mask, (i32, i32))> {
+        let (x, y) = self.layer_offset;
+        let bounds = Rect::from_origin_size(
+            (x as f64, y as f64),
+            (self.pixmap.width() as f64, self.pixmap.height() as f64),
+        );
+        let bounds = match self.clip {
+            Some(clip) => clip
+                .scale_from_origin(self.scale)
+                .expand()
+                .intersect(bounds),
+            None => bounds,
+        };
+        let (layer_x, layer_y) = (bounds.x0 as i32, bounds.y0 as i32);
+        let width = (bounds.width() as u32).max(1);
+        let height = (bounds.height() as u32).max(1);
+
+        let pixmap = Pixmap::new(width, height)?;
+        let mut mask = Mask::new(width, height)?;
+        let (dx, dy) = ((layer_x - x) as usize, (layer_y - y) as usize);
+        let copy_width = (width as usize).min((self.mask.width() as usize).saturating_sub(dx));
+        let copy_height = (height as usize).min((self.mask.height() as usize).saturating_sub(dy));
+        if self.clip.is_some() && copy_width > 0 {
+            // Copy the part of the clip mask the layer covers
+            let below_width = self.mask.width() as usize;
+            let below = self.mask.data();
+            for (row, data) in mask
+                .data_mut()
+                .chunks_exact_mut(width as usize)
+                .take(copy_height)
+                .enumerate()
+            {
+                let start = (row + dy) * below_width + dx;
+                data[..copy_width].copy_from_slice(&below[start..start + copy_width]);
+            }
+        }
+        Some((pixmap, mask, (layer_x, layer_y)))
+    }
+}
+
 impl Renderer for TinySkiaRenderer {
     fn begin(&mut self, _capture: bool) {
         self.transform = Affine::IDENTITY;
+        if let Some((pixmap, mask, _)) = self.layers.drain(..).find_map(|layer| layer.below) {
+            self.pixmap = pixmap;
+            self.mask = mask;
+        }
+        self.layer_offset = (0, 0);
         self.pixmap.fill(tiny_skia::Color::WHITE);
         self.clip = None;
     }
 
     fn stroke<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, width: f64) {
-        let paint = try_ret!(self.brush_to_paint(brush));
+        let brush = brush.into();
+        let bounds = shape.bounding_box().inflate(width / 2.0, width / 2.0);
+        let sweep = self.sweep_pixmap(&brush, bounds);
+        let paint = match &sweep {
+            Some((pixmap, transform)) => pattern_paint(pixmap, *transform),
+            None => try_ret!(self.brush_to_paint(brush)),
+        };
         let path = try_ret!(self.shape_to_path(shape));
         self.pixmap.stroke_path(
             &path,
@@ -363,7 +714,12 @@ impl Renderer for TinySkiaRenderer {
     fn fill<'b>(&mut self, shape: &impl Shape, brush: impl Into<BrushRef<'b>>, _blur_radius: f64) {
         // FIXME: Handle _blur_radius
 
-        let paint = try_ret!(self.brush_to_paint(brush));
+        let brush = brush.into();
+        let sweep = self.sweep_pixmap(&brush, shape.bounding_box());
+        let paint = match &sweep {
+            Some((pixmap, transform)) => pattern_paint(pixmap, *transform),
+            None => try_ret!(self.brush_to_paint(brush)),
+        };
         if let Some(rect) = shape.as_rect() {
             let rect = try_ret!(self.rect(rect));
             self.pixmap
@@ -381,58 +737,24 @@ impl Renderer for TinySkiaRenderer {
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
-        let offset = self.transform.translation();
-        let pos: Point = pos.into();
-        let clip = self.clip;
-        for line in layout.layout_runs() {
-            if let Some(rect) = clip {
-                let y = pos.y + offset.y + line.line_y as f64;
-                if y + (line.line_height as f64) < rect.y0 {
-                    continue;
-                }
-                if y - (line.line_height as f64) > rect.y1 {
-                    break;
-                }
-            }
-
-            'line_loop: for glyph_run in line.glyphs {
-                let x = glyph_run.x + pos.x as f32 + offset.x as f32;
-                let y = line.line_y + pos.y as f32 + offset.y as f32;
-
-                if let Some(rect) = clip {
-                    if ((x + glyph_run.w) as f64) < rect.x0 {
-                        continue;
-                    } else if x as f64 > rect.x1 {
-                        break 'line_loop;
-                    }
-                }
-
-                let glyph_x = x * self.scale as f32;
-                let (new_x, subpx_x) = SubpixelBin::new(glyph_x);
-                let glyph_x = new_x as f32;
-
-                let glyph_y = (y * self.scale as f32).round();
-                let (new_y, subpx_y) = SubpixelBin::new(glyph_y);
-                let glyph_y = new_y as f32;
-
-                let font_size = (glyph_run.font_size * self.scale as f32).round() as u32;
-
-                let mut cache_key = glyph_run.cache_key;
-                cache_key.font_size = font_size;
-                cache_key.x_bin = subpx_x;
-                cache_key.y_bin = subpx_y;
-
-                let pixmap = self.cache_glyph(cache_key, glyph_run.color);
+        self.draw_text_inner(layout, pos.into(), None);
+    }
 
-                if let Some(glyph) = pixmap {
-                    self.render_pixmap_direct(
-                        &glyph.pixmap,
-                        glyph_x + glyph.left,
-                        glyph_y - glyph.top,
-                    );
-                }
-            }
-        }
+    fn draw_text_with_brush<'b>(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<Point>,
+        brush: impl Into<BrushRef<'b>>,
+    ) {
+        let pos = pos.into();
+        let brush = brush.into();
+        let bounds = Rect::from_origin_size(pos, layout.size());
+        let sweep = self.sweep_pixmap(&brush, bounds);
+        let paint = match &sweep {
+            Some((pixmap, transform)) => pattern_paint(pixmap, *transform),
+            None => try_ret!(self.brush_to_paint(brush)),
+        };
+        self.draw_text_inner(layout, pos, Some(&paint));
     }
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
@@ -509,8 +831,7 @@ impl Renderer for TinySkiaRenderer {
             shape.bounding_box()
         };
 
-        let offset = self.transform.translation();
-        self.clip = Some(rect + offset);
+        self.clip = Some(self.transform.transform_rect_bbox(rect));
 
         self.mask.clear();
         let path = try_ret!(self.shape_to_path(shape));
@@ -522,6 +843,36 @@ impl Renderer for TinySkiaRenderer {
         self.clip = None;
     }
 
+    fn push_layer(&mut self, opacity: f32) {
+        let below = self.new_layer();
+        let below = below.map(|(pixmap, mask, offset)| {
+            let pixmap = mem::replace(&mut self.pixmap, pixmap);
+            let mask = mem::replace(&mut self.mask, mask);
+            let offset = mem::replace(&mut self.layer_offset, offset);
+            (pixmap, mask, offset)
+        });
+        self.layers.push(Layer { below, opacity });
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = try_ret!(self.layers.pop());
+        let (below, mask, offset) = try_ret!(layer.below);
+        let (x, y) = mem::replace(&mut self.layer_offset, offset);
+        let pixmap = mem::replace(&mut self.pixmap, below);
+        self.mask = mask;
+        self.pixmap.draw_pixmap(
+            x - offset.0,
+            y - offset.1,
+            pixmap.as_ref(),
+            &PixmapPaint {
+                opacity: layer.opacity,
+                ..Default::default()
+            },
+            Transform::identity(),
+            None,
+        );
+    }
+
     fn finish(&mut self) -> Option<DynamicImage> {
         // Remove cache entries which were not accessed.
         self.image_cache.retain(|_, (c, _)| *c == self.cache_color);
@@ -530,7 +881,17 @@ impl Renderer for TinySkiaRenderer {
         // Swap the cache color.
         self.cache_color = CacheColor(!self.cache_color.0);
 
-        let mut buffer = self
+        // Composite any layers which weren't popped.
+        while !self.layers.is_empty() {
+            self.pop_layer();
+        }
+
+        let window_surface = match self.window_surface.as_mut() {
+            Some(window_surface) => window_surface,
+            None => return self.pixmap_image(),
+        };
+
+        let mut buffer = window_surface
             .surface
             .buffer_mut()
             .expect("failed to get the surface buffer");
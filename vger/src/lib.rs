@@ -5,7 +5,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use floem_peniko::{
     kurbo::{Affine, Point, Rect, Shape, Vec2},
-    BrushRef, Color, GradientKind,
+    BrushRef, Color, ColorStop, GradientKind,
 };
 use floem_renderer::cosmic_text::{SubpixelBin, SwashCache, TextLayout};
 use floem_renderer::{tiny_skia, Img, Renderer};
@@ -24,8 +24,22 @@ pub struct VgerRenderer {
     alt_vger: Option<Vger>,
     config: SurfaceConfiguration,
     scale: f64,
+    /// The current transform.
+    ///
+    /// vger's primitives are positioned in device space, so only part of the transform can be
+    /// applied: text, images and svgs are only translated, and rects are drawn as the axis
+    /// aligned bounding box of the transformed rect, which is exact for translation and scaling
+    /// but not for rotation.
     transform: Affine,
     clip: Option<Rect>,
+    /// The opacity of each pushed layer.
+    ///
+    /// vger can't paint into offscreen layers, so instead the combined opacity of the
+    /// layers is applied to every color that's painted. This is only an approximation of
+    /// layer compositing: images and svgs aren't faded, and overlapping views in a layer are
+    /// blended with each other instead of being faded as a group.
+    layers: Vec<f32>,
+    opacity: f32,
     capture: bool,
 }
 
@@ -118,6 +132,8 @@ impl VgerRenderer {
             config,
             transform: Affine::IDENTITY,
             clip: None,
+            layers: Vec::new(),
+            opacity: 1.0,
             capture: false,
         })
     }
@@ -137,46 +153,122 @@ impl VgerRenderer {
 }
 
 impl VgerRenderer {
+    /// Converts a brush to a vger paint.
+    ///
+    /// vger can only paint solid colors and linear gradients between two colors. Linear
+    /// gradients with more stops are painted between their first and last stop, and radial and
+    /// sweep gradients are painted with the average color of their stops. Set
+    /// `FLOEM_FORCE_TINY_SKIA=1` to paint them exactly.
     fn brush_to_paint<'b>(&mut self, brush: impl Into<BrushRef<'b>>) -> Option<PaintIndex> {
         let paint = match brush.into() {
-            BrushRef::Solid(color) => self.vger.color_paint(vger_color(color)),
+            BrushRef::Solid(color) => self.vger.color_paint(self.vger_color(color)),
             BrushRef::Gradient(g) => match g.kind {
                 GradientKind::Linear { start, end } => {
-                    let mut stops = g.stops.iter();
-                    let inner_color = stops.next()?;
-                    let outer_color = stops.next()?;
-                    let inner_color = vger_color(inner_color.color);
-                    let outer_color = vger_color(outer_color.color);
-                    let start =
-                        floem_vger_rs::defs::LocalPoint::new(start.x as f32, start.y as f32);
-                    let end = floem_vger_rs::defs::LocalPoint::new(end.x as f32, end.y as f32);
+                    let inner_color = self.vger_color(g.stops.first()?.color);
+                    let outer_color = self.vger_color(g.stops.last()?.color);
+                    let start = self.vger_point(start);
+                    let end = self.vger_point(end);
                     self.vger
                         .linear_gradient(start, end, inner_color, outer_color, 0.0)
                 }
-                GradientKind::Radial { .. } => return None,
-                GradientKind::Sweep { .. } => return None,
+                GradientKind::Radial { .. } | GradientKind::Sweep { .. } => {
+                    let color = average_color(&g.stops)?;
+                    self.vger.color_paint(self.vger_color(color))
+                }
             },
             BrushRef::Image(_) => return None,
         };
         Some(paint)
     }
 
+    /// Draws the glyphs of `layout`. If `paint` is set, it's used instead of the glyph colors.
+    /// Only the translation of the transform is applied, the glyphs aren't scaled or rotated.
+    fn draw_text_inner(&mut self, layout: &TextLayout, pos: Point, paint: Option<PaintIndex>) {
+        let mut swash_cache = SwashCache::new();
+        let transform = self.transform.as_coeffs();
+        let offset = Vec2::new(transform[4], transform[5]);
+        let clip = self.clip;
+        for line in layout.layout_runs() {
+            if let Some(rect) = clip {
+                let y = pos.y + offset.y + line.line_y as f64;
+                if y + (line.line_height as f64) < rect.y0 {
+                    continue;
+                }
+                if y - (line.line_height as f64) > rect.y1 {
+                    break;
+                }
+            }
+            'line_loop: for glyph_run in line.glyphs {
+                let x = glyph_run.x + pos.x as f32 + offset.x as f32;
+                let y = line.line_y + pos.y as f32 + offset.y as f32;
+
+                if let Some(rect) = clip {
+                    if ((x + glyph_run.w) as f64) < rect.x0 {
+                        continue;
+                    } else if x as f64 > rect.x1 {
+                        break 'line_loop;
+                    }
+                }
+
+                if glyph_run.is_tab {
+                    continue;
+                }
+
+                if let Some(paint) = paint.or_else(|| self.brush_to_paint(glyph_run.color)) {
+                    let glyph_x = x * self.scale as f32;
+                    let (new_x, subpx_x) = SubpixelBin::new(glyph_x);
+                    let glyph_x = new_x as f32;
+
+                    let glyph_y = (y * self.scale as f32).round();
+                    let (new_y, subpx_y) = SubpixelBin::new(glyph_y);
+                    let glyph_y = new_y as f32;
+
+                    let font_size = (glyph_run.font_size * self.scale as f32).round() as u32;
+                    self.vger.render_glyph(
+                        glyph_x,
+                        glyph_y,
+                        glyph_run.cache_key.font_id,
+                        glyph_run.cache_key.glyph_id,
+                        font_size,
+                        (subpx_x, subpx_y),
+                        || {
+                            let mut cache_key = glyph_run.cache_key;
+                            cache_key.font_size = font_size;
+                            cache_key.x_bin = subpx_x;
+                            cache_key.y_bin = subpx_y;
+                            let image = swash_cache.get_image_uncached(cache_key);
+                            image.unwrap_or_default()
+                        },
+                        paint,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Converts `color` to a vger color with the opacity of the current layers applied.
+    fn vger_color(&self, color: Color) -> floem_vger_rs::Color {
+        let mut color = vger_color(color);
+        color.a *= self.opacity;
+        color
+    }
+
     fn vger_point(&self, point: Point) -> floem_vger_rs::defs::LocalPoint {
-        let coeffs = self.transform.as_coeffs();
-        let point = point + Vec2::new(coeffs[4], coeffs[5]);
+        let point = self.transform * point;
         floem_vger_rs::defs::LocalPoint::new(
             (point.x * self.scale) as f32,
             (point.y * self.scale) as f32,
         )
     }
 
+    /// vger only draws axis aligned rects, so a rotated rect is drawn as its bounding box.
     fn vger_rect(&self, rect: Rect) -> floem_vger_rs::defs::LocalRect {
-        let origin = rect.origin();
-        let origin = self.vger_point(origin);
-
-        let end = Point::new(rect.x1, rect.y1);
-        let end = self.vger_point(end);
-
+        let rect = self
+            .transform
+            .transform_rect_bbox(rect)
+            .scale_from_origin(self.scale);
+        let origin = floem_vger_rs::defs::LocalPoint::new(rect.x0 as f32, rect.y0 as f32);
+        let end = floem_vger_rs::defs::LocalPoint::new(rect.x1 as f32, rect.y1 as f32);
         let size = (end - origin).to_size();
         floem_vger_rs::defs::LocalRect::new(origin, size)
     }
@@ -288,6 +380,8 @@ impl Renderer for VgerRenderer {
         }
 
         self.transform = Affine::IDENTITY;
+        self.layers.clear();
+        self.opacity = 1.0;
         self.vger.begin(
             self.config.width as f32,
             self.config.height as f32,
@@ -411,67 +505,20 @@ impl Renderer for VgerRenderer {
     }
 
     fn draw_text(&mut self, layout: &TextLayout, pos: impl Into<Point>) {
-        let mut swash_cache = SwashCache::new();
-        let transform = self.transform.as_coeffs();
-        let offset = Vec2::new(transform[4], transform[5]);
-        let pos: Point = pos.into();
-        let clip = self.clip;
-        for line in layout.layout_runs() {
-            if let Some(rect) = clip {
-                let y = pos.y + offset.y + line.line_y as f64;
-                if y + (line.line_height as f64) < rect.y0 {
-                    continue;
-                }
-                if y - (line.line_height as f64) > rect.y1 {
-                    break;
-                }
-            }
-            'line_loop: for glyph_run in line.glyphs {
-                let x = glyph_run.x + pos.x as f32 + offset.x as f32;
-                let y = line.line_y + pos.y as f32 + offset.y as f32;
-
-                if let Some(rect) = clip {
-                    if ((x + glyph_run.w) as f64) < rect.x0 {
-                        continue;
-                    } else if x as f64 > rect.x1 {
-                        break 'line_loop;
-                    }
-                }
-
-                if glyph_run.is_tab {
-                    continue;
-                }
-
-                if let Some(paint) = self.brush_to_paint(glyph_run.color) {
-                    let glyph_x = x * self.scale as f32;
-                    let (new_x, subpx_x) = SubpixelBin::new(glyph_x);
-                    let glyph_x = new_x as f32;
-
-                    let glyph_y = (y * self.scale as f32).round();
-                    let (new_y, subpx_y) = SubpixelBin::new(glyph_y);
-                    let glyph_y = new_y as f32;
+        self.draw_text_inner(layout, pos.into(), None);
+    }
 
-                    let font_size = (glyph_run.font_size * self.scale as f32).round() as u32;
-                    self.vger.render_glyph(
-                        glyph_x,
-                        glyph_y,
-                        glyph_run.cache_key.font_id,
-                        glyph_run.cache_key.glyph_id,
-                        font_size,
-                        (subpx_x, subpx_y),
-                        || {
-                            let mut cache_key = glyph_run.cache_key;
-                            cache_key.font_size = font_size;
-                            cache_key.x_bin = subpx_x;
-                            cache_key.y_bin = subpx_y;
-                            let image = swash_cache.get_image_uncached(cache_key);
-                            image.unwrap_or_default()
-                        },
-                        paint,
-                    );
-                }
-            }
-        }
+    fn draw_text_with_brush<'b>(
+        &mut self,
+        layout: &TextLayout,
+        pos: impl Into<Point>,
+        brush: impl Into<BrushRef<'b>>,
+    ) {
+        let paint = match self.brush_to_paint(brush) {
+            Some(paint) => paint,
+            None => return,
+        };
+        self.draw_text_inner(layout, pos.into(), Some(paint));
     }
 
     fn draw_img(&mut self, img: Img<'_>, rect: Rect) {
@@ -553,9 +600,7 @@ impl Renderer for VgerRenderer {
         self.vger
             .scissor(self.vger_rect(rect), (radius * self.scale) as f32);
 
-        let transform = self.transform.as_coeffs();
-        let offset = Vec2::new(transform[4], transform[5]);
-        self.clip = Some(rect + offset);
+        self.clip = Some(self.transform.transform_rect_bbox(rect));
     }
 
     fn clear_clip(&mut self) {
@@ -563,6 +608,16 @@ impl Renderer for VgerRenderer {
         self.clip = None;
     }
 
+    fn push_layer(&mut self, opacity: f32) {
+        self.layers.push(opacity);
+        self.opacity *= opacity;
+    }
+
+    fn pop_layer(&mut self) {
+        self.layers.pop();
+        self.opacity = self.layers.iter().product();
+    }
+
     fn finish(&mut self) -> Option<DynamicImage> {
         if self.capture {
             self.render_image()
@@ -594,6 +649,22 @@ impl Renderer for VgerRenderer {
     }
 }
 
+fn average_color(stops: &[ColorStop]) -> Option<Color> {
+    if stops.is_empty() {
+        return None;
+    }
+    let len = stops.len() as u32;
+    let sum = |f: fn(&Color) -> u8| {
+        (stops.iter().map(|stop| f(&stop.color) as u32).sum::<u32>() / len) as u8
+    };
+    Some(Color::rgba8(
+        sum(|c| c.r),
+        sum(|c| c.g),
+        sum(|c| c.b),
+        sum(|c| c.a),
+    ))
+}
+
 fn vger_color(color: Color) -> floem_vger_rs::Color {
     floem_vger_rs::Color {
         r: color.r as f32 / 255.0,